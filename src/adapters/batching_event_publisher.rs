@@ -0,0 +1,338 @@
+//! Batching event publisher adapter
+//!
+//! Wraps another [`EventPublisher`] and buffers events so that they are handed
+//! to it in batches instead of one round-trip per event.
+
+use crate::ports::{EventPublisher, PublishError, QueryError};
+use crate::LocationDomainEvent;
+use async_trait::async_trait;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Event publisher that buffers events and publishes them in batches
+///
+/// The buffer is flushed when it reaches `max_batch_size` events, when the
+/// flush timer started by [`BatchingEventPublisher::start_flush_timer`] fires,
+/// or when [`BatchingEventPublisher::flush`] is called. Call `flush` on shutdown
+/// so no buffered events are lost.
+///
+/// Events are published in the order they were buffered, so the order of events
+/// within an aggregate is preserved. A failed batch is put back at the front of
+/// the buffer and retried by the next flush. Publishing only buffers, so it
+/// succeeds even when the flush it triggers fails; that failure is logged and
+/// the events go out with a later flush.
+pub struct BatchingEventPublisher<P: EventPublisher> {
+    inner: Arc<P>,
+    max_batch_size: usize,
+    flush_interval: Duration,
+    buffer: Mutex<Vec<LocationDomainEvent>>,
+    /// Serializes flushes so batches reach the inner publisher in order
+    flush_lock: Mutex<()>,
+}
+
+impl<P: EventPublisher + 'static> BatchingEventPublisher<P> {
+    /// Default number of buffered events that triggers a flush
+    pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+    /// Default time between timer flushes
+    pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Create a batching publisher with the default size and interval
+    pub fn new(inner: Arc<P>) -> Self {
+        Self::with_limits(
+            inner,
+            Self::DEFAULT_MAX_BATCH_SIZE,
+            Self::DEFAULT_FLUSH_INTERVAL,
+        )
+    }
+
+    /// Create a batching publisher with a custom size threshold and interval
+    pub fn with_limits(inner: Arc<P>, max_batch_size: usize, flush_interval: Duration) -> Self {
+        Self {
+            inner,
+            max_batch_size: max_batch_size.max(1),
+            flush_interval,
+            buffer: Mutex::new(Vec::new()),
+            flush_lock: Mutex::new(()),
+        }
+    }
+
+    /// Number of events waiting to be published
+    pub async fn pending(&self) -> usize {
+        self.buffer.lock().await.len()
+    }
+
+    /// Publish every buffered event to the inner publisher
+    pub async fn flush(&self) -> Result<(), PublishError> {
+        let _flushing = self.flush_lock.lock().await;
+
+        let events = std::mem::take(&mut *self.buffer.lock().await);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.inner.publish_batch(&events).await {
+            // Keep the failed events ahead of anything buffered meanwhile
+            let mut buffer = self.buffer.lock().await;
+            let newer = std::mem::replace(&mut *buffer, events);
+            buffer.extend(newer);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Flush the buffer every `flush_interval` in a background task
+    ///
+    /// The task stops once the publisher is dropped.
+    pub fn start_flush_timer(self: &Arc<Self>) -> JoinHandle<()> {
+        let publisher: Weak<Self> = Arc::downgrade(self);
+        let mut interval = tokio::time::interval(self.flush_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(publisher) = publisher.upgrade() else {
+                    break;
+                };
+                if let Err(e) = publisher.flush().await {
+                    tracing::warn!("Failed to flush batched location events: {e}");
+                }
+            }
+        })
+    }
+
+    async fn enqueue(&self, events: &[LocationDomainEvent]) -> Result<(), PublishError> {
+        let full = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.extend_from_slice(events);
+            buffer.len() >= self.max_batch_size
+        };
+
+        // The events are buffered either way, so a failed flush must not
+        // make the caller publish them again
+        if full {
+            if let Err(e) = self.flush().await {
+                tracing::warn!("Failed to flush batched location events: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Publish buffered events so queries see them
+    async fn flush_before_query(&self) -> Result<(), QueryError> {
+        self.flush()
+            .await
+            .map_err(|e| QueryError::QueryFailed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl<P: EventPublisher + 'static> EventPublisher for BatchingEventPublisher<P> {
+    async fn publish(&self, event: &LocationDomainEvent) -> Result<(), PublishError> {
+        self.enqueue(std::slice::from_ref(event)).await
+    }
+
+    async fn publish_batch(&self, events: &[LocationDomainEvent]) -> Result<(), PublishError> {
+        self.enqueue(events).await
+    }
+
+    async fn query_by_correlation(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+        self.flush_before_query().await?;
+        self.inner.query_by_correlation(correlation_id).await
+    }
+
+    async fn query_by_aggregate(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+        self.flush_before_query().await?;
+        self.inner.query_by_aggregate(aggregate_id).await
+    }
+
+    async fn query_by_time_range(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+        self.flush_before_query().await?;
+        self.inner.query_by_time_range(start, end).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::LocationMetadataAdded;
+    use std::collections::HashMap;
+
+    /// Publisher that records each batch it receives
+    #[derive(Default)]
+    struct RecordingPublisher {
+        batches: std::sync::Mutex<Vec<Vec<LocationDomainEvent>>>,
+        /// Reject every batch while set
+        failing: std::sync::atomic::AtomicBool,
+    }
+
+    impl RecordingPublisher {
+        fn batch_count(&self) -> usize {
+            self.batches.lock().unwrap().len()
+        }
+
+        /// Reasons of every published event for one location, in publish order
+        fn steps_for(&self, location_id: Uuid) -> Vec<String> {
+            self.batches
+                .lock()
+                .unwrap()
+                .iter()
+                .flatten()
+                .filter_map(|event| match event {
+                    LocationDomainEvent::LocationMetadataAdded(e)
+                        if e.location_id == location_id =>
+                    {
+                        Some(e.reason.clone())
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl EventPublisher for RecordingPublisher {
+        async fn publish(&self, event: &LocationDomainEvent) -> Result<(), PublishError> {
+            self.publish_batch(std::slice::from_ref(event)).await
+        }
+
+        async fn publish_batch(&self, events: &[LocationDomainEvent]) -> Result<(), PublishError> {
+            if self.failing.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(PublishError::ConnectionError("offline".to_string()));
+            }
+            self.batches.lock().unwrap().push(events.to_vec());
+            Ok(())
+        }
+
+        async fn query_by_correlation(
+            &self,
+            _correlation_id: Uuid,
+        ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+
+        async fn query_by_aggregate(
+            &self,
+            _aggregate_id: Uuid,
+        ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+
+        async fn query_by_time_range(
+            &self,
+            _start: chrono::DateTime<chrono::Utc>,
+            _end: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn step(location_id: Uuid, step: u32) -> LocationDomainEvent {
+        LocationDomainEvent::LocationMetadataAdded(LocationMetadataAdded {
+            location_id,
+            added_metadata: HashMap::new(),
+            current_metadata: HashMap::new(),
+            reason: format!("step {step}"),
+        })
+    }
+
+    fn steps(range: std::ops::RangeInclusive<u32>) -> Vec<String> {
+        range.map(|step| format!("step {step}")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_events_below_threshold_flush_on_timer() {
+        let inner = Arc::new(RecordingPublisher::default());
+        let publisher = Arc::new(BatchingEventPublisher::with_limits(
+            inner.clone(),
+            10,
+            Duration::from_millis(20),
+        ));
+        let location_id = Uuid::new_v4();
+
+        for n in 1..=3 {
+            publisher.publish(&step(location_id, n)).await.unwrap();
+        }
+        assert_eq!(inner.batch_count(), 0);
+        assert_eq!(publisher.pending().await, 3);
+
+        let timer = publisher.start_flush_timer();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(publisher.pending().await, 0);
+        assert_eq!(inner.batch_count(), 1);
+        assert_eq!(inner.steps_for(location_id), steps(1..=3));
+
+        drop(publisher);
+        tokio::time::timeout(Duration::from_secs(1), timer)
+            .await
+            .expect("flush timer stops once the publisher is dropped")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_flush_drains_buffer_preserving_aggregate_order() {
+        let inner = Arc::new(RecordingPublisher::default());
+        let publisher =
+            BatchingEventPublisher::with_limits(inner.clone(), 4, Duration::from_secs(3600));
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // Interleave two aggregates; the size threshold flushes after every fourth event
+        for n in 1..=5 {
+            publisher.publish(&step(first, n)).await.unwrap();
+            publisher.publish(&step(second, n)).await.unwrap();
+        }
+        assert_eq!(inner.batch_count(), 2);
+        assert_eq!(publisher.pending().await, 2);
+
+        publisher.flush().await.unwrap();
+
+        assert_eq!(publisher.pending().await, 0);
+        assert_eq!(inner.batch_count(), 3);
+        assert_eq!(inner.steps_for(first), steps(1..=5));
+        assert_eq!(inner.steps_for(second), steps(1..=5));
+
+        // Flushing an empty buffer publishes nothing
+        publisher.flush().await.unwrap();
+        assert_eq!(inner.batch_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_threshold_flush_keeps_events_buffered_once() {
+        use std::sync::atomic::Ordering;
+
+        let inner = Arc::new(RecordingPublisher::default());
+        let publisher =
+            BatchingEventPublisher::with_limits(inner.clone(), 2, Duration::from_secs(3600));
+        let location_id = Uuid::new_v4();
+
+        inner.failing.store(true, Ordering::SeqCst);
+        publisher.publish(&step(location_id, 1)).await.unwrap();
+        // Reaching the threshold flushes, which fails, but the event is buffered
+        publisher.publish(&step(location_id, 2)).await.unwrap();
+        assert_eq!(inner.batch_count(), 0);
+        assert_eq!(publisher.pending().await, 2);
+
+        inner.failing.store(false, Ordering::SeqCst);
+        publisher.flush().await.unwrap();
+
+        assert_eq!(publisher.pending().await, 0);
+        assert_eq!(inner.steps_for(location_id), steps(1..=2));
+    }
+}
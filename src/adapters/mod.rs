@@ -2,6 +2,8 @@
 //!
 //! Adapters implement ports using specific technologies (NATS, HTTP, etc.)
 
+pub mod batching_event_publisher;
 pub mod nats_event_publisher;
 
+pub use batching_event_publisher::*;
 pub use nats_event_publisher::*;
@@ -1,28 +1,38 @@
 //! NATS event publisher adapter
 //!
 //! This adapter implements the EventPublisher port using NATS JetStream.
+//! Payloads are encoded with a configurable [`Codec`], JSON by default.
 
-use crate::ports::{EventPublisher, PublishError, QueryError, event_to_subject};
+use crate::nats::{decode_message, subject_for_event, Codec, JsonCodec, CONTENT_TYPE_HEADER};
+use crate::ports::{EventPublisher, PublishError, QueryError};
 use crate::LocationDomainEvent;
 use async_nats::jetstream;
 use async_trait::async_trait;
 use cim_domain::DomainEvent;
 use futures::StreamExt;
-use serde_json;
 use uuid::Uuid;
 
 /// NATS-based event publisher
-pub struct NatsEventPublisher {
+pub struct NatsEventPublisher<C: Codec = JsonCodec> {
     jetstream: jetstream::Context,
     stream_name: String,
+    codec: C,
 }
 
 impl NatsEventPublisher {
-    /// Create a new NATS event publisher
+    /// Create a new NATS event publisher encoding events as JSON
     pub fn new(jetstream: jetstream::Context, stream_name: String) -> Self {
+        Self::with_codec(jetstream, stream_name, JsonCodec)
+    }
+}
+
+impl<C: Codec> NatsEventPublisher<C> {
+    /// Create a NATS event publisher encoding events with `codec`
+    pub fn with_codec(jetstream: jetstream::Context, stream_name: String, codec: C) -> Self {
         Self {
             jetstream,
             stream_name,
+            codec,
         }
     }
 
@@ -42,14 +52,17 @@ impl NatsEventPublisher {
 }
 
 #[async_trait]
-impl EventPublisher for NatsEventPublisher {
+impl<C: Codec> EventPublisher for NatsEventPublisher<C> {
     async fn publish(&self, event: &LocationDomainEvent) -> Result<(), PublishError> {
-        let subject = event_to_subject(event);
-        let payload = serde_json::to_vec(event)
+        let subject = subject_for_event(event).to_subject();
+        let payload = self
+            .codec
+            .encode(event)
             .map_err(|e| PublishError::SerializationError(e.to_string()))?;
 
         // Add event metadata as headers
         let mut headers = async_nats::HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, self.codec.content_type());
         headers.insert("event-type", event.event_type());
         headers.insert("aggregate-id", event.aggregate_id().to_string().as_str());
 
@@ -86,7 +99,8 @@ impl EventPublisher for NatsEventPublisher {
     }
 
     async fn query_by_aggregate(&self, aggregate_id: Uuid) -> Result<Vec<LocationDomainEvent>, QueryError> {
-        let subject = format!("events.location.{}.>", aggregate_id);
+        // Events are published as events.location.{aggregate}.{event_type}.{location_id}
+        let subject = format!("events.location.*.*.{}", aggregate_id);
 
         let stream = self
             .jetstream
@@ -112,7 +126,7 @@ impl EventPublisher for NatsEventPublisher {
         let mut events = Vec::new();
 
         while let Some(Ok(msg)) = messages.next().await {
-            let event: LocationDomainEvent = serde_json::from_slice(&msg.payload)
+            let event: LocationDomainEvent = decode_message(&msg)
                 .map_err(|e| QueryError::DeserializationError(e.to_string()))?;
 
             events.push(event);
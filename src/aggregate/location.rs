@@ -3,11 +3,17 @@
 //! Location is an aggregate that can represent any identifiable place through
 //! various means: addresses, geo-coordinates, virtual locations, etc.
 
+use super::LocationSnapshot;
+use crate::events::{CoordinatesUpdated, LocationCheckedIn, LocationCheckedOut, LocationMoved};
+use crate::ports::ElevationService;
 use crate::value_objects::{
-    Address, GeoCoordinates, LocationType, VirtualLocation as EnhancedVirtualLocation,
+    Address, AddressRole, GeoCoordinates, IndoorPosition, LocationType, PositionFix,
+    VirtualLocation as EnhancedVirtualLocation,
 };
+use chrono::{DateTime, Utc};
 use cim_domain::{AggregateRoot, DomainError, DomainResult, Entity, EntityId};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
 
 /// Location aggregate - represents any identifiable place
 #[derive(Debug, Clone)]
@@ -27,9 +33,17 @@ pub struct Location {
     /// Physical address if applicable
     pub address: Option<Address>,
 
+    /// Addresses for the other roles, e.g. mailing and billing
+    ///
+    /// The physical address is always held in `address`.
+    pub addresses: HashMap<AddressRole, Address>,
+
     /// Geographic coordinates if applicable
     pub coordinates: Option<GeoCoordinates>,
 
+    /// Building, floor and room for indoor locations
+    pub indoor_position: Option<IndoorPosition>,
+
     /// Virtual location details if applicable
     pub virtual_location: Option<EnhancedVirtualLocation>,
 
@@ -41,6 +55,18 @@ pub struct Location {
 
     /// Whether this location is archived (soft deleted)
     pub archived: bool,
+
+    /// Whether this location has been deleted (hard delete tombstone)
+    pub deleted: bool,
+
+    /// Recorded positions of a mobile location, oldest first
+    position_history: VecDeque<PositionFix>,
+
+    /// Maximum number of positions kept in `position_history`
+    position_history_limit: usize,
+
+    /// Users currently checked in, with their check-in time
+    checked_in: HashMap<Uuid, DateTime<Utc>>,
 }
 
 /// Marker type for Location entities
@@ -48,6 +74,9 @@ pub struct Location {
 pub struct LocationMarker;
 
 impl Location {
+    /// Default number of positions kept for mobile locations
+    pub const DEFAULT_POSITION_HISTORY_LIMIT: usize = 100;
+
     /// Create a new physical location with an address
     pub fn new_physical(
         id: EntityId<LocationMarker>,
@@ -62,14 +91,36 @@ impl Location {
             name,
             location_type: LocationType::Physical,
             address: Some(address),
+            addresses: HashMap::new(),
             coordinates: None,
+            indoor_position: None,
             virtual_location: None,
             parent_id: None,
             metadata: HashMap::new(),
             archived: false,
+            deleted: false,
+            position_history: VecDeque::new(),
+            position_history_limit: Self::DEFAULT_POSITION_HISTORY_LIMIT,
+            checked_in: HashMap::new(),
         })
     }
 
+    /// Create a new physical location with both an address and coordinates
+    ///
+    /// Typical after geocoding; both values are validated.
+    pub fn new_physical_full(
+        id: EntityId<LocationMarker>,
+        name: String,
+        address: Address,
+        coordinates: GeoCoordinates,
+    ) -> DomainResult<Self> {
+        coordinates.validate()?;
+
+        let mut location = Self::new_physical(id, name, address)?;
+        location.coordinates = Some(coordinates);
+        Ok(location)
+    }
+
     /// Create a new virtual location
     pub fn new_virtual(
         id: EntityId<LocationMarker>,
@@ -82,11 +133,39 @@ impl Location {
             name,
             location_type: LocationType::Virtual,
             address: None,
+            addresses: HashMap::new(),
             coordinates: None,
+            indoor_position: None,
             virtual_location: Some(virtual_location),
             parent_id: None,
             metadata: HashMap::new(),
             archived: false,
+            deleted: false,
+            position_history: VecDeque::new(),
+            position_history_limit: Self::DEFAULT_POSITION_HISTORY_LIMIT,
+            checked_in: HashMap::new(),
+        })
+    }
+
+    /// Create a new logical location (organizational unit with no physical presence)
+    pub fn new_logical(id: EntityId<LocationMarker>, name: String) -> DomainResult<Self> {
+        Ok(Self {
+            entity: Entity::with_id(id),
+            version: 0,
+            name,
+            location_type: LocationType::Logical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            indoor_position: None,
+            virtual_location: None,
+            parent_id: None,
+            metadata: HashMap::new(),
+            archived: false,
+            deleted: false,
+            position_history: VecDeque::new(),
+            position_history_limit: Self::DEFAULT_POSITION_HISTORY_LIMIT,
+            checked_in: HashMap::new(),
         })
     }
 
@@ -104,16 +183,177 @@ impl Location {
             name,
             location_type: LocationType::Physical,
             address: None,
+            addresses: HashMap::new(),
             coordinates: Some(coordinates),
+            indoor_position: None,
             virtual_location: None,
             parent_id: None,
             metadata: HashMap::new(),
             archived: false,
+            deleted: false,
+            position_history: VecDeque::new(),
+            position_history_limit: Self::DEFAULT_POSITION_HISTORY_LIMIT,
+            checked_in: HashMap::new(),
+        })
+    }
+
+    /// Create a new mobile location (vehicle, tracked asset) at its current position
+    pub fn new_mobile(
+        id: EntityId<LocationMarker>,
+        name: String,
+        coordinates: GeoCoordinates,
+    ) -> DomainResult<Self> {
+        let mut location = Self::new_from_coordinates(id, name, coordinates)?;
+        location.location_type = LocationType::Mobile;
+        Ok(location)
+    }
+
+    /// Set how many recorded positions a mobile location keeps
+    ///
+    /// Shrinking the limit drops the oldest positions right away.
+    pub fn set_position_history_limit(&mut self, limit: usize) {
+        self.position_history_limit = limit;
+        self.trim_position_history();
+    }
+
+    /// Record a new position of a mobile location
+    ///
+    /// Updates the current coordinates and appends to the bounded position
+    /// history, dropping the oldest entry once the limit is reached.
+    pub fn record_position(
+        &mut self,
+        coordinates: GeoCoordinates,
+        at: DateTime<Utc>,
+    ) -> DomainResult<LocationMoved> {
+        self.ensure_not_deleted()?;
+
+        if self.location_type != LocationType::Mobile {
+            return Err(DomainError::ValidationError(
+                "Only mobile locations can record positions".to_string(),
+            ));
+        }
+        if self.archived {
+            return Err(DomainError::ValidationError(
+                "Cannot record position of archived location".to_string(),
+            ));
+        }
+        coordinates.validate()?;
+
+        let previous = self
+            .coordinates
+            .replace(coordinates.clone())
+            .unwrap_or_else(|| coordinates.clone());
+
+        self.position_history.push_back(PositionFix {
+            coordinates: coordinates.clone(),
+            recorded_at: at,
+        });
+        self.trim_position_history();
+        self.entity.touch();
+
+        Ok(
+            LocationMoved::new(*self.entity.id.as_uuid(), previous, coordinates)
+                .with_recorded_at(at),
+        )
+    }
+
+    /// Recorded positions, oldest first
+    pub fn position_history(&self) -> impl Iterator<Item = &PositionFix> {
+        self.position_history.iter()
+    }
+
+    fn trim_position_history(&mut self) {
+        while self.position_history.len() > self.position_history_limit {
+            self.position_history.pop_front();
+        }
+    }
+
+    /// Check a user in at this location
+    ///
+    /// Fails if the user is already checked in.
+    pub fn check_in(&mut self, user: Uuid, at: DateTime<Utc>) -> DomainResult<LocationCheckedIn> {
+        self.ensure_not_deleted()?;
+
+        if self.archived {
+            return Err(DomainError::ValidationError(
+                "Cannot check in at archived location".to_string(),
+            ));
+        }
+        if self.checked_in.contains_key(&user) {
+            return Err(DomainError::ValidationError(format!(
+                "User {user} is already checked in"
+            )));
+        }
+
+        self.checked_in.insert(user, at);
+        self.entity.touch();
+
+        Ok(LocationCheckedIn {
+            location_id: *self.entity.id.as_uuid(),
+            user_id: user,
+            checked_in_at: at,
+            occupancy: self.checked_in.len(),
+        })
+    }
+
+    /// Check a user out of this location, closing their visit
+    ///
+    /// Fails if the user is not checked in.
+    pub fn check_out(&mut self, user: Uuid, at: DateTime<Utc>) -> DomainResult<LocationCheckedOut> {
+        self.ensure_not_deleted()?;
+
+        let checked_in_at = self.checked_in.get(&user).copied().ok_or_else(|| {
+            DomainError::ValidationError(format!("User {user} is not checked in"))
+        })?;
+        if at < checked_in_at {
+            return Err(DomainError::ValidationError(
+                "Check-out cannot precede check-in".to_string(),
+            ));
+        }
+
+        self.checked_in.remove(&user);
+        self.entity.touch();
+
+        Ok(LocationCheckedOut {
+            location_id: *self.entity.id.as_uuid(),
+            user_id: user,
+            checked_in_at,
+            checked_out_at: at,
+            occupancy: self.checked_in.len(),
         })
     }
 
+    /// Check if a user is currently checked in
+    pub fn is_checked_in(&self, user: Uuid) -> bool {
+        self.checked_in.contains_key(&user)
+    }
+
+    /// Number of users currently checked in
+    pub fn current_occupancy(&self) -> usize {
+        self.checked_in.len()
+    }
+
+    /// Great-circle distance in meters to another location
+    ///
+    /// Returns `None` if either location has no coordinates.
+    pub fn distance_to(&self, other: &Location) -> Option<f64> {
+        let here = self.coordinates.as_ref()?;
+        let there = other.coordinates.as_ref()?;
+        Some(here.distance_to(there))
+    }
+
+    /// Check whether this location lies within `radius_meters` of `center`
+    ///
+    /// Locations without coordinates are never within any radius.
+    pub fn is_within(&self, center: &GeoCoordinates, radius_meters: f64) -> bool {
+        self.coordinates
+            .as_ref()
+            .is_some_and(|coordinates| coordinates.distance_to(center) <= radius_meters)
+    }
+
     /// Set the address for this location
     pub fn set_address(&mut self, address: Address) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
         address.validate()?;
 
         if self.location_type == LocationType::Virtual {
@@ -122,13 +362,85 @@ impl Location {
             ));
         }
 
+        if self.location_type == LocationType::Logical {
+            return Err(DomainError::ValidationError(
+                "Cannot set physical address on logical location".to_string(),
+            ));
+        }
+
         self.address = Some(address);
         self.entity.touch();
         Ok(())
     }
 
+    /// Set the address for a given role
+    ///
+    /// The physical address follows the rules of [`Location::set_address`];
+    /// mailing and billing addresses can be set on any active location.
+    pub fn set_address_for(&mut self, role: AddressRole, address: Address) -> DomainResult<()> {
+        if role == AddressRole::Physical {
+            return self.set_address(address);
+        }
+
+        self.ensure_not_deleted()?;
+        if self.archived {
+            return Err(DomainError::ValidationError(
+                "Cannot modify archived location".to_string(),
+            ));
+        }
+
+        address.validate()?;
+        self.addresses.insert(role, address);
+        self.entity.touch();
+        Ok(())
+    }
+
+    /// Get the address for a given role, if one is set
+    pub fn address_for(&self, role: AddressRole) -> Option<&Address> {
+        match role {
+            AddressRole::Physical => self.address.as_ref(),
+            _ => self.addresses.get(&role),
+        }
+    }
+
+    /// Set the building, floor and room of an indoor location
+    ///
+    /// Rooms and floors without coordinates of their own are logical
+    /// locations, so any type but virtual can be placed inside a building.
+    pub fn set_indoor_position(&mut self, position: IndoorPosition) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+        position.validate()?;
+
+        if self.archived {
+            return Err(DomainError::ValidationError(
+                "Cannot modify archived location".to_string(),
+            ));
+        }
+
+        if self.location_type == LocationType::Virtual {
+            return Err(DomainError::ValidationError(
+                "Cannot set indoor position on virtual location".to_string(),
+            ));
+        }
+
+        self.indoor_position = Some(position);
+        self.entity.touch();
+        Ok(())
+    }
+
+    /// Whether both locations are on the same floor of the same building
+    ///
+    /// Locations without an indoor position are never on the same floor.
+    pub fn same_floor(&self, other: &Location) -> bool {
+        match (&self.indoor_position, &other.indoor_position) {
+            (Some(here), Some(there)) => here.same_floor(there),
+            _ => false,
+        }
+    }
+
     /// Set geographic coordinates
     pub fn set_coordinates(&mut self, coordinates: GeoCoordinates) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
         coordinates.validate()?;
 
         if self.location_type == LocationType::Virtual {
@@ -137,13 +449,53 @@ impl Location {
             ));
         }
 
+        if self.location_type == LocationType::Logical {
+            return Err(DomainError::ValidationError(
+                "Cannot set coordinates on logical location".to_string(),
+            ));
+        }
+
         self.coordinates = Some(coordinates);
         self.entity.touch();
         Ok(())
     }
 
+    /// Fill in the altitude of the current coordinates from an elevation service
+    ///
+    /// Any existing altitude is replaced. Fails if the location has no
+    /// coordinates or the lookup fails.
+    pub async fn enrich_altitude(
+        &mut self,
+        elevation: &dyn ElevationService,
+    ) -> DomainResult<CoordinatesUpdated> {
+        self.ensure_not_deleted()?;
+
+        let previous = self.coordinates.clone().ok_or_else(|| {
+            DomainError::ValidationError(
+                "Cannot enrich altitude of location without coordinates".to_string(),
+            )
+        })?;
+        let altitude = elevation
+            .elevation(&previous)
+            .await
+            .map_err(|e| DomainError::InternalError(e.to_string()))?;
+
+        let coordinates = previous.clone().with_altitude(altitude);
+        self.coordinates = Some(coordinates.clone());
+        self.entity.touch();
+
+        Ok(CoordinatesUpdated {
+            location_id: *self.entity.id.as_uuid(),
+            previous_coordinates: previous,
+            coordinates,
+            reason: "Altitude enriched from elevation service".to_string(),
+        })
+    }
+
     /// Set parent location for hierarchical structures
     pub fn set_parent(&mut self, parent_id: EntityId<LocationMarker>) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         // Prevent self-reference
         if parent_id == self.entity.id {
             return Err(DomainError::ValidationError(
@@ -157,9 +509,12 @@ impl Location {
     }
 
     /// Add metadata
-    pub fn add_metadata(&mut self, key: String, value: String) {
+    pub fn add_metadata(&mut self, key: String, value: String) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         self.metadata.insert(key, value);
         self.entity.touch();
+        Ok(())
     }
 
     /// Update location details
@@ -170,12 +525,22 @@ impl Location {
         coordinates: Option<GeoCoordinates>,
         virtual_location: Option<EnhancedVirtualLocation>,
     ) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         if self.archived {
             return Err(DomainError::ValidationError(
                 "Cannot update archived location".to_string(),
             ));
         }
 
+        if self.location_type == LocationType::Logical
+            && (address.is_some() || coordinates.is_some())
+        {
+            return Err(DomainError::ValidationError(
+                "Logical locations have no address or coordinates".to_string(),
+            ));
+        }
+
         // Validate new address if provided
         if let Some(ref addr) = address {
             addr.validate()?;
@@ -208,15 +573,39 @@ impl Location {
     }
 
     /// Add multiple metadata entries
-    pub fn add_metadata_bulk(&mut self, metadata: HashMap<String, String>) {
+    pub fn add_metadata_bulk(&mut self, metadata: HashMap<String, String>) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         for (key, value) in metadata {
             self.metadata.insert(key, value);
         }
         self.entity.touch();
+        Ok(())
+    }
+
+    /// Remove a metadata key
+    ///
+    /// Returns whether the key was present.
+    pub fn remove_metadata(&mut self, key: &str) -> DomainResult<bool> {
+        self.ensure_not_deleted()?;
+
+        if self.archived {
+            return Err(DomainError::ValidationError(
+                "Cannot modify archived location".to_string(),
+            ));
+        }
+
+        let removed = self.metadata.remove(key).is_some();
+        if removed {
+            self.entity.touch();
+        }
+        Ok(removed)
     }
 
     /// Remove parent (make top-level)
     pub fn remove_parent(&mut self) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         if self.archived {
             return Err(DomainError::ValidationError(
                 "Cannot modify archived location".to_string(),
@@ -230,6 +619,8 @@ impl Location {
 
     /// Archive this location (soft delete)
     pub fn archive(&mut self) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
         if self.archived {
             return Err(DomainError::ValidationError(
                 "Location is already archived".to_string(),
@@ -241,11 +632,69 @@ impl Location {
         Ok(())
     }
 
+    /// Restore an archived location to active use
+    pub fn restore(&mut self) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
+        if !self.archived {
+            return Err(DomainError::ValidationError(
+                "Location is not archived".to_string(),
+            ));
+        }
+
+        self.archived = false;
+        self.entity.touch();
+        Ok(())
+    }
+
     /// Check if location is archived
     pub fn is_archived(&self) -> bool {
         self.archived
     }
 
+    /// Delete this location (hard delete)
+    ///
+    /// Leaves a tombstone with all descriptive data erased. The aggregate does
+    /// not know its children, so the command handlers check them against the
+    /// read model with
+    /// [`LocationReadModel::deletion_set`](crate::projections::LocationReadModel::deletion_set)
+    /// before deleting.
+    pub fn delete(&mut self) -> DomainResult<()> {
+        self.ensure_not_deleted()?;
+
+        self.erase();
+        self.entity.touch();
+        Ok(())
+    }
+
+    /// Check if location is deleted
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    fn ensure_not_deleted(&self) -> DomainResult<()> {
+        if self.deleted {
+            return Err(DomainError::ValidationError(
+                "Location has been deleted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn erase(&mut self) {
+        self.name = String::new();
+        self.address = None;
+        self.addresses.clear();
+        self.coordinates = None;
+        self.indoor_position = None;
+        self.virtual_location = None;
+        self.parent_id = None;
+        self.metadata.clear();
+        self.position_history.clear();
+        self.checked_in.clear();
+        self.deleted = true;
+    }
+
     /// Get current metadata snapshot
     pub fn get_metadata(&self) -> &HashMap<String, String> {
         &self.metadata
@@ -277,11 +726,14 @@ impl Location {
                 new_aggregate.name = e.name.clone();
                 new_aggregate.location_type = e.location_type.clone();
                 new_aggregate.address = e.address.clone();
+                new_aggregate.addresses = e.addresses.clone();
+                new_aggregate.addresses.remove(&AddressRole::Physical);
                 new_aggregate.coordinates = e.coordinates.clone();
                 new_aggregate.virtual_location = e.virtual_location.clone();
                 new_aggregate.parent_id = e.parent_id.map(EntityId::from_uuid);
                 new_aggregate.metadata = HashMap::new();
                 new_aggregate.archived = false;
+                new_aggregate.deleted = false;
             }
             LocationDomainEvent::LocationUpdated(e) => {
                 // Apply changes from the update event
@@ -299,6 +751,19 @@ impl Location {
                 }
                 new_aggregate.entity.touch();
             }
+            LocationDomainEvent::LocationMoved(e) => {
+                new_aggregate.coordinates = Some(e.coordinates.clone());
+                if let (LocationType::Mobile, Some(recorded_at)) =
+                    (&new_aggregate.location_type, e.recorded_at)
+                {
+                    new_aggregate.position_history.push_back(PositionFix {
+                        coordinates: e.coordinates.clone(),
+                        recorded_at,
+                    });
+                    new_aggregate.trim_position_history();
+                }
+                new_aggregate.entity.touch();
+            }
             LocationDomainEvent::ParentLocationSet(e) => {
                 new_aggregate.parent_id = Some(EntityId::from_uuid(e.parent_id));
                 new_aggregate.entity.touch();
@@ -313,10 +778,72 @@ impl Location {
                 }
                 new_aggregate.entity.touch();
             }
+            LocationDomainEvent::LocationMetadataUpdated(e) => {
+                for (key, value) in &e.updated_metadata {
+                    new_aggregate.metadata.insert(key.clone(), value.clone());
+                }
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationMetadataRemoved(e) => {
+                new_aggregate.metadata.remove(&e.key);
+                new_aggregate.entity.touch();
+            }
             LocationDomainEvent::LocationArchived(_e) => {
                 new_aggregate.archived = true;
                 new_aggregate.entity.touch();
             }
+            LocationDomainEvent::LocationCheckedIn(e) => {
+                new_aggregate.checked_in.insert(e.user_id, e.checked_in_at);
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationCheckedOut(e) => {
+                new_aggregate.checked_in.remove(&e.user_id);
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationsMerged(_e) => {
+                // Children, metadata and archiving arrive as their own events
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationDeleted(_e) => {
+                new_aggregate.erase();
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::AddressGeocoded(e) => {
+                new_aggregate.coordinates = Some(e.coordinates.clone());
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::HierarchyReorganized(e) => {
+                let id = *new_aggregate.entity.id.as_uuid();
+                if let Some((_, _, new_parent)) = e.affected.iter().find(|(l, _, _)| *l == id) {
+                    new_aggregate.parent_id = new_parent.map(EntityId::from_uuid);
+                }
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::CoordinatesUpdated(e) => {
+                new_aggregate.coordinates = Some(e.coordinates.clone());
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationRestored(_e) => {
+                new_aggregate.archived = false;
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::LocationAddressSet(e) => {
+                match e.role {
+                    AddressRole::Physical => new_aggregate.address = Some(e.address.clone()),
+                    role => {
+                        new_aggregate.addresses.insert(role, e.address.clone());
+                    }
+                }
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::IndoorPositionSet(e) => {
+                new_aggregate.indoor_position = Some(e.position.clone());
+                new_aggregate.entity.touch();
+            }
+            LocationDomainEvent::CoordinatesValidated(_e) => {
+                // Validation reports on the coordinates without changing them
+                new_aggregate.entity.touch();
+            }
         }
 
         Ok(new_aggregate)
@@ -349,12 +876,73 @@ impl AggregateRoot for Location {
     }
 }
 
+impl Location {
+    /// Restore a location from a snapshot, including its id and version
+    pub fn from_snapshot(snapshot: LocationSnapshot) -> Self {
+        Self {
+            entity: Entity::with_id(EntityId::from_uuid(snapshot.location_id)),
+            version: snapshot.version,
+            name: snapshot.name,
+            location_type: snapshot.location_type,
+            address: snapshot.address,
+            addresses: snapshot.addresses.into_iter().collect(),
+            coordinates: snapshot.coordinates,
+            indoor_position: snapshot.indoor_position,
+            virtual_location: snapshot.virtual_location,
+            parent_id: snapshot.parent_id.map(EntityId::from_uuid),
+            metadata: snapshot.metadata.into_iter().collect(),
+            archived: snapshot.archived,
+            deleted: snapshot.deleted,
+            position_history: snapshot.position_history.into(),
+            position_history_limit: snapshot.position_history_limit,
+            checked_in: snapshot.checked_in.into_iter().collect(),
+        }
+    }
+}
+
+impl From<&Location> for LocationSnapshot {
+    fn from(location: &Location) -> Self {
+        Self {
+            location_id: *location.entity.id.as_uuid(),
+            version: location.version,
+            name: location.name.clone(),
+            location_type: location.location_type.clone(),
+            address: location.address.clone(),
+            addresses: location
+                .addresses
+                .iter()
+                .map(|(role, address)| (*role, address.clone()))
+                .collect(),
+            coordinates: location.coordinates.clone(),
+            indoor_position: location.indoor_position.clone(),
+            virtual_location: location.virtual_location.clone(),
+            parent_id: location.parent_id.map(|id| *id.as_uuid()),
+            metadata: location
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            archived: location.archived,
+            deleted: location.deleted,
+            position_history: location.position_history.iter().cloned().collect(),
+            position_history_limit: location.position_history_limit,
+            checked_in: location
+                .checked_in
+                .iter()
+                .map(|(user, at)| (*user, *at))
+                .collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::IndoorPositionSet;
     use crate::value_objects::{
         UrlType, VirtualLocation as EnhancedVirtualLocation, VirtualLocationType, VirtualUrl,
     };
+    use crate::LocationDomainEvent;
 
     /// Test address validation
     ///
@@ -547,6 +1135,47 @@ mod tests {
         assert_eq!(location.version, 0);
     }
 
+    /// Test physical location creation with address and coordinates
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Address + Coordinates] --> B{Validate Both}
+    ///     B -->|Valid| C[Physical Location]
+    ///     B -->|Invalid| D[Error]
+    /// ```
+    #[test]
+    fn test_physical_location_with_coordinates() {
+        let address = Address::new(
+            "1 Infinite Loop".to_string(),
+            "Cupertino".to_string(),
+            "CA".to_string(),
+            "USA".to_string(),
+            "95014".to_string(),
+        );
+        let coords = GeoCoordinates::new(37.3318, -122.0312);
+
+        let location = Location::new_physical_full(
+            EntityId::new(),
+            "Apple Park".to_string(),
+            address.clone(),
+            coords.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(location.location_type, LocationType::Physical);
+        assert_eq!(location.address, Some(address.clone()));
+        assert_eq!(location.coordinates, Some(coords));
+        assert_eq!(location.version, 0);
+
+        let result = Location::new_physical_full(
+            EntityId::new(),
+            "Apple Park".to_string(),
+            address,
+            GeoCoordinates::new(37.3318, -222.0),
+        );
+        assert!(result.is_err());
+    }
+
     /// Test virtual location creation
     ///
     /// ```mermaid
@@ -719,7 +1348,9 @@ mod tests {
         .unwrap();
 
         // Add single metadata
-        location.add_metadata("capacity".to_string(), "50".to_string());
+        location
+            .add_metadata("capacity".to_string(), "50".to_string())
+            .unwrap();
         assert_eq!(location.metadata.get("capacity"), Some(&"50".to_string()));
 
         // Add bulk metadata
@@ -729,7 +1360,7 @@ mod tests {
             ("accessibility".to_string(), "wheelchair".to_string()),
         ]);
 
-        location.add_metadata_bulk(bulk_metadata);
+        location.add_metadata_bulk(bulk_metadata).unwrap();
 
         assert_eq!(location.metadata.len(), 4);
         assert_eq!(
@@ -743,6 +1374,213 @@ mod tests {
         );
     }
 
+    /// Test metadata removal
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location with Metadata] --> B{Key Present?}
+    ///     B -->|Yes| C[Removed, true]
+    ///     B -->|No| D[Unchanged, false]
+    /// ```
+    #[test]
+    fn test_remove_metadata() {
+        let mut location = Location::new_from_coordinates(
+            EntityId::new(),
+            "Depot".to_string(),
+            GeoCoordinates::new(40.0, -74.0),
+        )
+        .unwrap();
+        location
+            .add_metadata("dock".to_string(), "3".to_string())
+            .unwrap();
+        location
+            .add_metadata("shift".to_string(), "night".to_string())
+            .unwrap();
+
+        assert!(location.remove_metadata("shift").unwrap());
+        assert_eq!(location.metadata.len(), 1);
+        assert!(!location.metadata.contains_key("shift"));
+
+        assert!(!location.remove_metadata("shift").unwrap());
+        assert!(!location.remove_metadata("missing").unwrap());
+        assert_eq!(location.metadata.len(), 1);
+
+        location.archive().unwrap();
+        assert!(location.remove_metadata("dock").is_err());
+        assert_eq!(location.metadata.len(), 1);
+
+        location.delete().unwrap();
+        assert!(location.remove_metadata("dock").is_err());
+    }
+
+    /// Test position tracking of mobile locations
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Mobile Location] --> B[Record Position]
+    ///     B --> C[Update Coordinates]
+    ///     B --> D[Append History]
+    ///     D --> E{Over Limit?}
+    ///     E -->|Yes| F[Drop Oldest]
+    /// ```
+    #[test]
+    fn test_mobile_location_records_positions() {
+        let start = GeoCoordinates::new(52.5200, 13.4050);
+        let mut truck =
+            Location::new_mobile(EntityId::new(), "Truck 7".to_string(), start.clone()).unwrap();
+        assert_eq!(truck.location_type, LocationType::Mobile);
+        assert_eq!(truck.position_history().count(), 0);
+
+        let at = chrono::Utc::now();
+        let next = GeoCoordinates::new(52.5300, 13.4150);
+        let moved = truck.record_position(next.clone(), at).unwrap();
+
+        assert_eq!(truck.coordinates, Some(next.clone()));
+        assert_eq!(moved.location_id, *truck.id().as_uuid());
+        assert_eq!(moved.previous_coordinates, start);
+        assert_eq!(moved.coordinates, next);
+        assert!(moved.distance_meters > 0.0);
+
+        let history: Vec<_> = truck.position_history().collect();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].recorded_at, at);
+        assert_eq!(moved.recorded_at, Some(at));
+
+        // Replaying the move rebuilds the history
+        let replayed = Location::new_mobile(truck.id(), "Truck 7".to_string(), start.clone())
+            .unwrap()
+            .apply_event_pure(&LocationDomainEvent::LocationMoved(moved))
+            .unwrap();
+        assert_eq!(replayed.coordinates, Some(next.clone()));
+        assert_eq!(
+            replayed.position_history().collect::<Vec<_>>(),
+            truck.position_history().collect::<Vec<_>>()
+        );
+
+        // Only mobile locations track positions
+        let mut depot =
+            Location::new_from_coordinates(EntityId::new(), "Depot".to_string(), start).unwrap();
+        assert!(depot.record_position(next, at).is_err());
+    }
+
+    /// Test the position history cap
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Limit 3] --> B[Record 5 Positions]
+    ///     B --> C[Keep Newest 3]
+    /// ```
+    #[test]
+    fn test_mobile_position_history_is_capped() {
+        let mut truck = Location::new_mobile(
+            EntityId::new(),
+            "Truck 7".to_string(),
+            GeoCoordinates::new(0.0, 0.0),
+        )
+        .unwrap();
+        truck.set_position_history_limit(3);
+
+        let start = chrono::Utc::now();
+        for i in 1..=5 {
+            truck
+                .record_position(
+                    GeoCoordinates::new(0.0, i as f64 * 0.01),
+                    start + chrono::Duration::minutes(i),
+                )
+                .unwrap();
+        }
+
+        let longitudes: Vec<_> = truck
+            .position_history()
+            .map(|fix| fix.coordinates.longitude)
+            .collect();
+        assert_eq!(longitudes, vec![0.03, 0.04, 0.05]);
+        assert_eq!(truck.coordinates.as_ref().unwrap().longitude, 0.05);
+
+        truck.set_position_history_limit(1);
+        assert_eq!(truck.position_history().count(), 1);
+        assert_eq!(
+            truck.position_history().next().unwrap().recorded_at,
+            start + chrono::Duration::minutes(5)
+        );
+    }
+
+    /// Test check-in and check-out tracking
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] --> B[Check In]
+    ///     B --> C{Already In?}
+    ///     C -->|Yes| D[Error]
+    ///     C -->|No| E[Occupancy + 1]
+    ///     E --> F[Check Out]
+    ///     F --> G[Occupancy - 1]
+    /// ```
+    #[test]
+    fn test_check_in_and_out_tracks_occupancy() {
+        let mut office = Location::new_from_coordinates(
+            EntityId::new(),
+            "Office".to_string(),
+            GeoCoordinates::new(40.0, -74.0),
+        )
+        .unwrap();
+        let (alice, bob, carol) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let morning = chrono::Utc::now();
+
+        office.check_in(alice, morning).unwrap();
+        office.check_in(bob, morning).unwrap();
+        let checked_in = office.check_in(carol, morning).unwrap();
+        assert_eq!(checked_in.occupancy, 3);
+        assert_eq!(office.current_occupancy(), 3);
+
+        let evening = morning + chrono::Duration::hours(8);
+        let checked_out = office.check_out(bob, evening).unwrap();
+        assert_eq!(checked_out.user_id, bob);
+        assert_eq!(checked_out.occupancy, 2);
+        assert_eq!(checked_out.visit_duration(), chrono::Duration::hours(8));
+        assert!(!office.is_checked_in(bob));
+
+        office.check_out(alice, evening).unwrap();
+        assert_eq!(office.current_occupancy(), 1);
+        assert!(office.is_checked_in(carol));
+
+        // Returning later starts a new visit
+        office.check_in(bob, evening).unwrap();
+        assert_eq!(office.current_occupancy(), 2);
+    }
+
+    /// Test check-in and check-out errors
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Check In Twice] --> B[Error]
+    ///     C[Check Out Unknown User] --> D[Error]
+    /// ```
+    #[test]
+    fn test_check_in_and_out_errors() {
+        let mut office = Location::new_from_coordinates(
+            EntityId::new(),
+            "Office".to_string(),
+            GeoCoordinates::new(40.0, -74.0),
+        )
+        .unwrap();
+        let user = Uuid::new_v4();
+        let now = chrono::Utc::now();
+
+        assert!(office.check_out(user, now).is_err());
+
+        office.check_in(user, now).unwrap();
+        assert!(office.check_in(user, now).is_err());
+        assert_eq!(office.current_occupancy(), 1);
+
+        assert!(office
+            .check_out(user, now - chrono::Duration::minutes(1))
+            .is_err());
+        office.check_out(user, now).unwrap();
+        assert!(office.check_out(user, now).is_err());
+        assert_eq!(office.current_occupancy(), 0);
+    }
+
     /// Test location archival
     ///
     /// ```mermaid
@@ -787,6 +1625,289 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Test restoring an archived location
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] --> B{Archived?}
+    ///     B -->|No| C[Restore Error]
+    ///     B -->|Yes| D[Restore]
+    ///     D --> E[Can Update Again]
+    /// ```
+    #[test]
+    fn test_location_restore() {
+        let mut location =
+            Location::new_logical(EntityId::<LocationMarker>::new(), "Annex".to_string()).unwrap();
+
+        assert!(location.restore().is_err());
+
+        location.archive().unwrap();
+        location.restore().unwrap();
+        assert!(!location.is_archived());
+        assert!(location
+            .update_details(Some("North Annex".to_string()), None, None, None)
+            .is_ok());
+        assert!(location.restore().is_err());
+    }
+
+    /// Test keeping separate addresses per role
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] -->|Physical| B[address]
+    ///     A -->|Billing / Mailing| C[addresses]
+    ///     C --> D[Validated]
+    /// ```
+    #[test]
+    fn test_addresses_by_role() {
+        let physical = Address::new(
+            "1 Warehouse Way".to_string(),
+            "Oakland".to_string(),
+            "CA".to_string(),
+            "USA".to_string(),
+            "94607".to_string(),
+        );
+        let billing = Address::new(
+            "500 Market Street".to_string(),
+            "San Francisco".to_string(),
+            "CA".to_string(),
+            "USA".to_string(),
+            "94105".to_string(),
+        );
+        let mut location = Location::new_physical(
+            EntityId::<LocationMarker>::new(),
+            "Depot".to_string(),
+            physical.clone(),
+        )
+        .unwrap();
+
+        location
+            .set_address_for(AddressRole::Billing, billing.clone())
+            .unwrap();
+        assert_eq!(location.address_for(AddressRole::Physical), Some(&physical));
+        assert_eq!(location.address_for(AddressRole::Billing), Some(&billing));
+        assert_eq!(location.address_for(AddressRole::Mailing), None);
+        assert_eq!(location.address, Some(physical));
+
+        // The physical role is the existing address
+        location
+            .set_address_for(AddressRole::Physical, billing.clone())
+            .unwrap();
+        assert_eq!(location.address, Some(billing.clone()));
+        assert!(!location.addresses.contains_key(&AddressRole::Physical));
+
+        let mut invalid = billing.clone();
+        invalid.street1 = String::new();
+        assert!(location
+            .set_address_for(AddressRole::Mailing, invalid)
+            .is_err());
+        assert_eq!(location.address_for(AddressRole::Mailing), None);
+
+        // A logical location can be billed but has no physical address
+        let mut service =
+            Location::new_logical(EntityId::<LocationMarker>::new(), "Sales".to_string()).unwrap();
+        assert!(service
+            .set_address_for(AddressRole::Physical, billing.clone())
+            .is_err());
+        service
+            .set_address_for(AddressRole::Billing, billing.clone())
+            .unwrap();
+        assert_eq!(service.address_for(AddressRole::Billing), Some(&billing));
+
+        let restored = Location::from_snapshot(LocationSnapshot::from(&service));
+        assert_eq!(restored.address_for(AddressRole::Billing), Some(&billing));
+    }
+
+    /// Test placing locations inside a building
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] -->|set_indoor_position| B[Building / Floor / Room]
+    ///     B --> C{same_floor?}
+    ///     D[Logical Room] -->|set_indoor_position| B
+    ///     E[Virtual Location] -->|set_indoor_position| F[Rejected]
+    /// ```
+    #[test]
+    fn test_indoor_positions_on_same_and_different_floors() {
+        let office = |name: &str, floor: i32, room: &str| {
+            let mut location = Location::new_from_coordinates(
+                EntityId::<LocationMarker>::new(),
+                name.to_string(),
+                GeoCoordinates::new(51.5074, -0.1278),
+            )
+            .unwrap();
+            location
+                .set_indoor_position(IndoorPosition::new("HQ", floor).with_room(room))
+                .unwrap();
+            location
+        };
+        let reception = office("Reception", 0, "001");
+        let lobby = office("Lobby", 0, "002");
+        let boardroom = office("Boardroom", 5, "501");
+
+        assert!(reception.same_floor(&lobby));
+        assert!(!reception.same_floor(&boardroom));
+
+        // A room without coordinates of its own is a logical location
+        let mut sales = Location::new_logical(
+            EntityId::<LocationMarker>::new(),
+            "Sales Office".to_string(),
+        )
+        .unwrap();
+        sales
+            .set_indoor_position(IndoorPosition::new("HQ", 5).with_room("502"))
+            .unwrap();
+        assert!(sales.same_floor(&boardroom));
+
+        let mut meeting = Location::new_virtual(
+            EntityId::<LocationMarker>::new(),
+            "Video Call".to_string(),
+            EnhancedVirtualLocation::website("https://example.com/meet", "Meet".to_string())
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(meeting
+            .set_indoor_position(IndoorPosition::new("HQ", 5))
+            .is_err());
+        assert!(!meeting.same_floor(&boardroom));
+
+        let restored = Location::from_snapshot(LocationSnapshot::from(&boardroom));
+        assert_eq!(restored.indoor_position, boardroom.indoor_position);
+
+        // Replaying the event places the location again
+        let replayed = Location::new_logical(sales.id(), "Sales Office".to_string())
+            .unwrap()
+            .apply_event_pure(&LocationDomainEvent::IndoorPositionSet(IndoorPositionSet {
+                location_id: *sales.id().as_uuid(),
+                position: IndoorPosition::new("HQ", 5).with_room("502"),
+                previous_position: None,
+                reason: "Placed".to_string(),
+            }))
+            .unwrap();
+        assert_eq!(replayed.indoor_position, sales.indoor_position);
+    }
+
+    /// Test location deletion
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Active Location] --> B[Delete]
+    ///     B --> C[Tombstone]
+    ///     C --> D{Delete Again?}
+    ///     D -->|Yes| E[Error]
+    ///     C --> F[Cannot Archive/Update]
+    /// ```
+    #[test]
+    fn test_location_deletion() {
+        let location_id = EntityId::<LocationMarker>::new();
+        let mut location = Location::new_from_coordinates(
+            location_id,
+            "Home".to_string(),
+            GeoCoordinates::new(51.5074, -0.1278),
+        )
+        .unwrap();
+        location
+            .add_metadata("owner".to_string(), "someone".to_string())
+            .unwrap();
+
+        assert!(!location.is_deleted());
+        location.delete().unwrap();
+        assert!(location.is_deleted());
+        assert!(!location.is_archived());
+
+        // Descriptive data is erased
+        assert!(location.name.is_empty());
+        assert!(location.coordinates.is_none());
+        assert!(location.metadata.is_empty());
+
+        // Deleting again fails
+        assert!(location.delete().is_err());
+
+        // A deleted location can be neither archived nor updated
+        assert!(location.archive().is_err());
+        let result = location.update_details(Some("Back".to_string()), None, None, None);
+        assert!(result.is_err());
+    }
+
+    fn deleted_location() -> Location {
+        let mut location = Location::new_from_coordinates(
+            EntityId::<LocationMarker>::new(),
+            "Home".to_string(),
+            GeoCoordinates::new(51.5074, -0.1278),
+        )
+        .unwrap();
+        location.delete().unwrap();
+        location
+    }
+
+    fn street_address() -> Address {
+        Address::new(
+            "10 Downing Street".to_string(),
+            "London".to_string(),
+            "Greater London".to_string(),
+            "UK".to_string(),
+            "SW1A 2AA".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_set_address() {
+        let mut location = deleted_location();
+        assert!(location.set_address(street_address()).is_err());
+        assert!(location.address.is_none());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_set_address_for() {
+        let mut location = deleted_location();
+        assert!(location
+            .set_address_for(AddressRole::Billing, street_address())
+            .is_err());
+        assert!(location.addresses.is_empty());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_set_indoor_position() {
+        let mut location = deleted_location();
+        assert!(location
+            .set_indoor_position(IndoorPosition::new("HQ", 1))
+            .is_err());
+        assert!(location.indoor_position.is_none());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_set_coordinates() {
+        let mut location = deleted_location();
+        assert!(location
+            .set_coordinates(GeoCoordinates::new(51.5, -0.12))
+            .is_err());
+        assert!(location.coordinates.is_none());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_set_parent() {
+        let mut location = deleted_location();
+        assert!(location.set_parent(EntityId::new()).is_err());
+        assert!(location.parent_id.is_none());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_add_metadata() {
+        let mut location = deleted_location();
+        assert!(location
+            .add_metadata("owner".to_string(), "someone".to_string())
+            .is_err());
+        assert!(location.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_deleted_location_rejects_add_metadata_bulk() {
+        let mut location = deleted_location();
+        let metadata = HashMap::from([("owner".to_string(), "someone".to_string())]);
+        assert!(location.add_metadata_bulk(metadata).is_err());
+        assert!(location.metadata.is_empty());
+    }
+
     /// Test distance calculation
     ///
     /// ```mermaid
@@ -858,6 +1979,60 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Test logical location constraints
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Logical Location] --> B{Set Address/Coords?}
+    ///     B --> C[Error]
+    ///     A --> D{Set Parent/Metadata?}
+    ///     D --> E[Success]
+    /// ```
+    #[test]
+    fn test_logical_location_constraints() {
+        let location_id = EntityId::<LocationMarker>::new();
+        let mut location =
+            Location::new_logical(location_id, "Engineering Department".to_string()).unwrap();
+
+        assert_eq!(location.location_type, LocationType::Logical);
+        assert!(location.address.is_none());
+        assert!(location.coordinates.is_none());
+
+        // Cannot set address or coordinates on logical location
+        let address = Address::new(
+            "123 Main St".to_string(),
+            "City".to_string(),
+            "State".to_string(),
+            "Country".to_string(),
+            "12345".to_string(),
+        );
+        assert!(location.set_address(address.clone()).is_err());
+        assert!(location
+            .set_coordinates(GeoCoordinates::new(0.0, 0.0))
+            .is_err());
+        assert!(location
+            .update_details(None, Some(address), None, None)
+            .is_err());
+
+        // Hierarchy and metadata still work
+        let parent_id = EntityId::<LocationMarker>::new();
+        location.set_parent(parent_id).unwrap();
+        assert_eq!(location.parent_id, Some(parent_id));
+
+        location
+            .add_metadata("cost_center".to_string(), "CC-42".to_string())
+            .unwrap();
+        assert_eq!(
+            location.get_metadata().get("cost_center"),
+            Some(&"CC-42".to_string())
+        );
+
+        location
+            .update_details(Some("Platform Engineering".to_string()), None, None, None)
+            .unwrap();
+        assert_eq!(location.name, "Platform Engineering");
+    }
+
     /// Test aggregate root implementation
     ///
     /// ```mermaid
@@ -897,4 +2072,92 @@ mod tests {
         location.increment_version();
         assert_eq!(location.version(), 2);
     }
+
+    /// Test distance between two location aggregates
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Two Locations] --> B{Both Have Coordinates?}
+    ///     B -->|Yes| C[Haversine Distance]
+    ///     B -->|No| D[None]
+    /// ```
+    #[test]
+    fn test_distance_between_locations() {
+        let san_francisco = Location::new_from_coordinates(
+            EntityId::new(),
+            "San Francisco".to_string(),
+            GeoCoordinates::new(37.7749, -122.4194),
+        )
+        .unwrap();
+        let oakland = Location::new_from_coordinates(
+            EntityId::new(),
+            "Oakland".to_string(),
+            GeoCoordinates::new(37.8044, -122.2712),
+        )
+        .unwrap();
+        let sales = Location::new_logical(EntityId::new(), "Sales".to_string()).unwrap();
+
+        let distance = san_francisco.distance_to(&oakland).unwrap();
+        assert!((distance - 13_400.0).abs() < 200.0);
+        assert!((oakland.distance_to(&san_francisco).unwrap() - distance).abs() < 1e-6);
+
+        assert_eq!(san_francisco.distance_to(&sales), None);
+        assert_eq!(sales.distance_to(&san_francisco), None);
+    }
+
+    /// Test radius containment of a location
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] --> B{Distance <= Radius?}
+    ///     B -->|Yes| C[Within]
+    ///     B -->|No| D[Outside]
+    /// ```
+    #[test]
+    fn test_location_is_within_radius() {
+        let center = GeoCoordinates::new(37.7749, -122.4194);
+        let oakland = Location::new_from_coordinates(
+            EntityId::new(),
+            "Oakland".to_string(),
+            GeoCoordinates::new(37.8044, -122.2712),
+        )
+        .unwrap();
+        let distance = center.distance_to(oakland.coordinates.as_ref().unwrap());
+
+        assert!(oakland.is_within(&center, distance));
+        assert!(oakland.is_within(&center, distance + 1.0));
+        assert!(!oakland.is_within(&center, distance - 1.0));
+
+        let sales = Location::new_logical(EntityId::new(), "Sales".to_string()).unwrap();
+        assert!(!sales.is_within(&center, f64::MAX));
+    }
+
+    /// Test altitude enrichment from an elevation service
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] --> B{Has Coordinates?}
+    ///     B -->|Yes| C[Look Up Elevation]
+    ///     C --> D[CoordinatesUpdated]
+    ///     B -->|No| E[Error]
+    /// ```
+    #[tokio::test]
+    async fn test_enrich_altitude() {
+        let elevation = crate::ports::MockElevationService::new(1_034.0);
+        let mut zermatt = Location::new_from_coordinates(
+            EntityId::new(),
+            "Zermatt".to_string(),
+            GeoCoordinates::new(46.0207, 7.7491),
+        )
+        .unwrap();
+
+        let event = zermatt.enrich_altitude(&elevation).await.unwrap();
+
+        assert_eq!(event.previous_coordinates.altitude, None);
+        assert_eq!(event.coordinates.altitude, Some(1_034.0));
+        assert_eq!(zermatt.coordinates, Some(event.coordinates));
+
+        let mut sales = Location::new_logical(EntityId::new(), "Sales".to_string()).unwrap();
+        assert!(sales.enrich_altitude(&elevation).await.is_err());
+    }
 }
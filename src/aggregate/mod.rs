@@ -1,5 +1,7 @@
 //! Location aggregate
 
 mod location;
+mod snapshot;
 
 pub use location::*;
+pub use snapshot::*;
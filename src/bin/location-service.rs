@@ -9,24 +9,50 @@
 //! - `STREAM_NAME` - JetStream stream name (default: LOCATION_EVENTS)
 //! - `LOG_LEVEL` - Logging level (default: info)
 //! - `SNAPSHOT_FREQUENCY` - Events between snapshots (default: 100)
+//! - `READ_MODEL_SHARDS` - Shards of the query read model; 0 keeps one lock (default: 0)
 //!
 //! ## NATS Subjects
 //!
 //! ### Commands (Request/Reply)
 //! - `location.commands.define` - Define a new location
+//! - `location.commands.batch_define` - Define many locations under one correlation chain
 //! - `location.commands.update` - Update location details
 //! - `location.commands.set_parent` - Set parent location
 //! - `location.commands.remove_parent` - Remove parent location
 //! - `location.commands.add_metadata` - Add metadata
 //! - `location.commands.archive` - Archive location
+//! - `location.commands.delete` - Delete location permanently
+//!
+//! Command payloads are JSON unless the request carries a
+//! `content-type: application/msgpack` header. Published events name their
+//! codec in the same header.
+//!
+//! Define, batch define and delete commands are deduplicated on their
+//! `Idempotency-Key` header, or their `message-id` header when there is none:
+//! a redelivery of an accepted command gets the original reply and changes
+//! nothing.
+//!
+//! ### Queries (Request/Reply)
+//! - `queries.location.location.get` - Get a location, optionally with children and ancestors
+//! - `queries.location.coordinates.find_nearby` - Find locations within a radius, nearest first
+//! - `queries.location.hierarchy.get_hierarchy` - Get the location tree below a root
+//!
+//! Queries are answered from a read model kept current from the event stream.
+//! With `READ_MODEL_SHARDS` set, location and nearby queries are answered from
+//! a sharded copy so they do not wait for every event to be applied; hierarchy
+//! queries and deletes always use the whole read model. Replies are JSON.
+//!
+//! ### Dead Letters (Publish)
+//! - `dlq.location.commands.{type}` - Commands whose payload failed to deserialize
 //!
 //! ### Events (Publish)
 //! - `events.location.{location_id}.defined` - Location defined
 //! - `events.location.{location_id}.updated` - Location updated
-//! - `events.location.{location_id}.parent.set` - Parent set
-//! - `events.location.{location_id}.parent.removed` - Parent removed
-//! - `events.location.{location_id}.metadata.added` - Metadata added
+//! - `events.location.{location_id}.parent_set` - Parent set
+//! - `events.location.{location_id}.parent_removed` - Parent removed
+//! - `events.location.{location_id}.metadata_added` - Metadata added
 //! - `events.location.{location_id}.archived` - Location archived
+//! - `events.location.{location_id}.deleted` - Location deleted
 //!
 //! ## Example Usage
 //!
@@ -42,17 +68,30 @@
 //! location-service
 //! ```
 
+use cim_domain::EntityId;
 use cim_domain_location::{
-    DefineLocation, UpdateLocation, SetParentLocation, RemoveParentLocation,
-    AddLocationMetadata, ArchiveLocation, LocationDomainEvent,
+    BatchCommand, BatchCommandResult, BatchItemResult, DefineLocation, UpdateLocation, SetParentLocation, RemoveParentLocation,
+    AddLocationMetadata, ArchiveLocation, DeleteLocation, LocationDomainEvent,
     NatsEventStore, LocationRepository, NatsEventPublisher,
+    CimDomainEvent, DomainEvent, LocationDefined, LocationDeleted, MessageIdentity,
+    Validate,
+    Codec, decode_message,
+    FindNearbyLocations, GetLocation, GetLocationHierarchy, LocationQuery,
+    LocationReadModel, ProjectionRunner, ProjectionTarget, ShardedReadModel,
 };
+use cim_domain_location::handlers::IdempotencyCache;
 use async_nats::jetstream;
+use async_trait::async_trait;
 use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 use tokio::signal;
+use tokio::sync::RwLock;
 use tracing::{info, error, warn, debug};
+use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -78,11 +117,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or_else(|_| "100".to_string())
         .parse()
         .unwrap_or(100);
+    let read_model_shards: usize = env::var("READ_MODEL_SHARDS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .unwrap_or(0);
 
     info!("Configuration:");
     info!("  NATS URL: {}", nats_url);
     info!("  Stream Name: {}", stream_name);
     info!("  Snapshot Frequency: {}", snapshot_frequency);
+    info!("  Read Model Shards: {}", read_model_shards);
 
     // Connect to NATS
     info!("Connecting to NATS at {}...", nats_url);
@@ -110,26 +154,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         NatsEventPublisher::new(jetstream.clone(), stream_name.clone())
     );
 
+    // Events of handled commands are appended to the stream through the
+    // repository; that append is their publication
+    let store = Arc::new(CommandStore {
+        repository: repository.clone(),
+    });
+
+    // Keep the query read models current from the event stream
+    let read_model = Arc::new(QueryReadModels::new(read_model_shards));
+    let projection_runner = ProjectionRunner::new(read_model.clone());
+    let projection_jetstream = jetstream.clone();
+    let projection_stream = stream_name.clone();
+    tokio::spawn(async move {
+        if let Err(e) = projection_runner
+            .run_jetstream(&projection_jetstream, &projection_stream)
+            .await
+        {
+            error!("Query read model stopped updating: {}", e);
+        }
+    });
+
     info!("Location service is ready");
     info!("Listening for commands on: location.commands.>");
+    info!("Listening for queries on: {}", QUERIES_SUBJECT);
 
     // Subscribe to command subjects
     let mut define_sub = client.subscribe("location.commands.define").await?;
+    let mut batch_define_sub = client.subscribe("location.commands.batch_define").await?;
     let mut update_sub = client.subscribe("location.commands.update").await?;
     let mut set_parent_sub = client.subscribe("location.commands.set_parent").await?;
     let mut remove_parent_sub = client.subscribe("location.commands.remove_parent").await?;
     let mut add_metadata_sub = client.subscribe("location.commands.add_metadata").await?;
     let mut archive_sub = client.subscribe("location.commands.archive").await?;
+    let mut delete_sub = client.subscribe("location.commands.delete").await?;
+    let mut query_sub = client.subscribe(QUERIES_SUBJECT).await?;
 
     // Clone Arc references for task handlers
-    let repo_define = repository.clone();
     let repo_update = repository.clone();
     let repo_set_parent = repository.clone();
     let repo_remove_parent = repository.clone();
     let repo_add_metadata = repository.clone();
     let repo_archive = repository.clone();
 
-    let pub_define = event_publisher.clone();
     let pub_update = event_publisher.clone();
     let pub_set_parent = event_publisher.clone();
     let pub_remove_parent = event_publisher.clone();
@@ -137,16 +203,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let pub_archive = event_publisher.clone();
 
     let client_define = client.clone();
+    let client_batch_define = client.clone();
     let client_update = client.clone();
     let client_set_parent = client.clone();
     let client_remove_parent = client.clone();
     let client_add_metadata = client.clone();
     let client_archive = client.clone();
+    let client_delete = client.clone();
+
+    let store_define = store.clone();
+    let store_batch_define = store.clone();
+    let store_delete = store.clone();
+    let read_model_delete = read_model.clone();
+
+    // Replies to accepted commands, replayed when NATS redelivers them
+    let processed = Arc::new(ProcessedCommands::default());
+    let processed_define = processed.clone();
+    let processed_batch_define = processed.clone();
+    let processed_delete = processed.clone();
 
     // Spawn command handlers
     tokio::spawn(async move {
         while let Some(msg) = define_sub.next().await {
-            handle_define_location(msg, repo_define.clone(), pub_define.clone(), client_define.clone()).await;
+            let sink = processed_define.sink_for(&msg, &client_define);
+            if !sink.replay().await {
+                handle_define_location(msg, &*store_define, &sink).await;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(msg) = batch_define_sub.next().await {
+            let sink = processed_batch_define.sink_for(&msg, &client_batch_define);
+            if !sink.replay().await {
+                handle_batch_define(msg, &*store_batch_define, &sink).await;
+            }
         }
     });
 
@@ -180,6 +271,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    tokio::spawn(async move {
+        while let Some(msg) = delete_sub.next().await {
+            let sink = processed_delete.sink_for(&msg, &client_delete);
+            if !sink.replay().await {
+                handle_delete_location(msg, &*store_delete, &read_model_delete.whole, &sink).await;
+            }
+        }
+    });
+
+    let client_query = client.clone();
+    tokio::spawn(async move {
+        while let Some(msg) = query_sub.next().await {
+            handle_query(&msg, &read_model, &client_query).await;
+        }
+    });
+
     // Wait for shutdown signal
     match signal::ctrl_c().await {
         Ok(()) => {
@@ -196,58 +303,396 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 // Command Handlers
 
+/// Sink for replies and dead letters, implemented by the NATS client
+#[async_trait]
+trait MessageSink: Send + Sync {
+    async fn send(&self, subject: String, payload: Vec<u8>) -> Result<(), String>;
+}
+
+#[async_trait]
+impl MessageSink for async_nats::Client {
+    async fn send(&self, subject: String, payload: Vec<u8>) -> Result<(), String> {
+        self.publish(subject, payload.into()).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Destination of the events produced by handled commands
+#[async_trait]
+trait EventLog: Send + Sync {
+    /// Whether any events are stored for the location
+    async fn contains(&self, location_id: Uuid) -> Result<bool, String>;
+
+    /// Persist events caused by a command, publishing them to subscribers of
+    /// the event stream
+    ///
+    /// Returns the envelopes that were stored, in the order of `events`.
+    async fn commit(
+        &self,
+        events: Vec<LocationDomainEvent>,
+        cause: &MessageIdentity,
+    ) -> Result<Vec<CimDomainEvent>, String>;
+}
+
+/// Event log backed by the event-sourced repository
+///
+/// The repository appends each event to `events.location.{location_id}.{type}`
+/// in the event stream. Publishing the events again through a
+/// [`NatsEventPublisher`] would put a second copy in the same stream, which the
+/// query read model would apply twice.
+struct CommandStore {
+    repository: Arc<LocationRepository>,
+}
+
+#[async_trait]
+impl EventLog for CommandStore {
+    async fn contains(&self, location_id: Uuid) -> Result<bool, String> {
+        self.repository
+            .load(EntityId::from_uuid(location_id))
+            .await
+            .map(|location| location.is_some())
+            .map_err(|e| format!("Failed to load location: {}", e))
+    }
+
+    async fn commit(
+        &self,
+        events: Vec<LocationDomainEvent>,
+        cause: &MessageIdentity,
+    ) -> Result<Vec<CimDomainEvent>, String> {
+        self.repository
+            .save_caused_by(events, Some(cause))
+            .await
+            .map_err(|e| format!("Failed to save events: {}", e))
+    }
+}
+
+/// A command payload that could not be deserialized
+#[derive(Debug, Serialize, Deserialize)]
+struct DeadLetter {
+    /// Subject the command arrived on
+    subject: String,
+    /// Deserialization error
+    error: String,
+    /// Raw payload, lossily decoded as UTF-8
+    payload: String,
+}
+
+/// Deserialize a command, dead-lettering malformed payloads
+///
+/// The payload is decoded with the codec named by its `content-type` header,
+/// JSON if there is none.
+///
+/// On failure the raw payload, error and original subject are published to
+/// `dlq.{subject}` (e.g. `dlq.location.commands.define`) and the requester gets
+/// an error reply.
+async fn deserialize_or_dlq<T: DeserializeOwned>(
+    msg: &async_nats::Message,
+    sink: &impl MessageSink,
+) -> Option<T> {
+    let error = match decode_message(msg) {
+        Ok(command) => return Some(command),
+        Err(e) => e,
+    };
+
+    error!("Failed to deserialize command on {}: {}", msg.subject, error);
+
+    let dead_letter = DeadLetter {
+        subject: msg.subject.to_string(),
+        error: error.to_string(),
+        payload: String::from_utf8_lossy(&msg.payload).into_owned(),
+    };
+    let dlq_subject = format!("dlq.{}", msg.subject);
+    if let Err(e) = sink.send(dlq_subject.clone(), serde_json::to_vec(&dead_letter).unwrap()).await {
+        error!("Failed to publish dead letter to {}: {}", dlq_subject, e);
+    }
+
+    if let Some(reply) = &msg.reply {
+        let _ = sink.send(reply.to_string(), format!("Error: {}", error).into_bytes()).await;
+    }
+    None
+}
+
+/// Reply with the command's field errors, returning whether it is valid
+async fn validate_command<C: Validate>(
+    command: &C,
+    reply: Option<&async_nats::Subject>,
+    sink: &impl MessageSink,
+) -> bool {
+    let errors = match command.validate() {
+        Ok(()) => return true,
+        Err(errors) => errors,
+    };
+
+    warn!("Rejected invalid command: {:?}", errors);
+    if let Some(reply) = reply {
+        let response = serde_json::json!({
+            "status": "rejected",
+            "errors": errors,
+        });
+        let _ = sink.send(reply.to_string(), serde_json::to_vec(&response).unwrap()).await;
+    }
+    false
+}
+
+/// Reply that a command was rejected, giving the reason
+async fn reply_rejected(reply: Option<&async_nats::Subject>, reason: &str, sink: &impl MessageSink) {
+    warn!("Rejected command: {}", reason);
+    if let Some(reply) = reply {
+        let response = serde_json::json!({
+            "status": "rejected",
+            "reason": reason,
+        });
+        let _ = sink.send(reply.to_string(), serde_json::to_vec(&response).unwrap()).await;
+    }
+}
+
+/// Header carrying a client-chosen key that identifies retries of one command
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Replies sent to accepted commands, by idempotency key
+///
+/// Rejected commands are not remembered, so a retry is handled again.
+#[derive(Default)]
+struct ProcessedCommands {
+    replies: std::sync::Mutex<IdempotencyCache<Vec<u8>>>,
+}
+
+impl ProcessedCommands {
+    /// Key identifying redeliveries of a command on its subject
+    fn key(msg: &async_nats::Message) -> Option<String> {
+        let headers = msg.headers.as_ref()?;
+        let key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+            Some(key) => key.as_str().to_string(),
+            None => MessageIdentity::from_headers(headers)?.message_id.to_string(),
+        };
+        Some(format!("{}:{}", msg.subject, key))
+    }
+
+    /// Sink that replies to the command and remembers the reply if it accepts it
+    fn sink_for<'a, S: MessageSink>(&'a self, msg: &async_nats::Message, inner: &'a S) -> RememberingSink<'a, S> {
+        RememberingSink {
+            inner,
+            processed: self,
+            key: Self::key(msg),
+            reply: msg.reply.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// Whether a reply reports the command as fully carried out
+fn is_accepted_reply(payload: &[u8]) -> bool {
+    let Ok(reply) = serde_json::from_slice::<serde_json::Value>(payload) else {
+        return false;
+    };
+    match reply["status"].as_str() {
+        Some("accepted") => true,
+        Some("processed") => reply["failed"].as_array().is_some_and(|failed| failed.is_empty()),
+        _ => false,
+    }
+}
+
+/// Message sink for one command that remembers its accepted reply
+struct RememberingSink<'a, S: MessageSink> {
+    inner: &'a S,
+    processed: &'a ProcessedCommands,
+    key: Option<String>,
+    reply: Option<String>,
+}
+
+impl<S: MessageSink> RememberingSink<'_, S> {
+    /// Answer a redelivered command with its original reply, returning whether it was one
+    async fn replay(&self) -> bool {
+        let Some(key) = &self.key else {
+            return false;
+        };
+        let cached = self.processed.replies.lock().unwrap().get(key);
+        let Some(payload) = cached else {
+            return false;
+        };
+
+        debug!("Replaying reply to redelivered command {}", key);
+        if let Some(reply) = &self.reply {
+            let _ = self.inner.send(reply.clone(), payload).await;
+        }
+        true
+    }
+}
+
+#[async_trait]
+impl<'a, S: MessageSink> MessageSink for RememberingSink<'a, S> {
+    async fn send(&self, subject: String, payload: Vec<u8>) -> Result<(), String> {
+        if let Some(key) = &self.key {
+            if self.reply.as_ref() == Some(&subject) && is_accepted_reply(&payload) {
+                self.processed.replies.lock().unwrap().insert(key.clone(), payload.clone());
+            }
+        }
+        self.inner.send(subject, payload).await
+    }
+}
+
+/// Define a location, acknowledging with the identity of its event once the
+/// event is persisted and published
+///
+/// The acknowledgment is built from the stored envelope, so its message ID,
+/// correlation ID and CID are the ones subscribers see.
 async fn handle_define_location(
     msg: async_nats::Message,
-    repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
-    client: async_nats::Client,
+    store: &impl EventLog,
+    sink: &impl MessageSink,
 ) {
     debug!("Received DefineLocation command");
 
     // Deserialize command
-    let command: DefineLocation = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
+    let command: DefineLocation = match deserialize_or_dlq(&msg, sink).await {
+        Some(command) => command,
+        None => return,
+    };
+
+    if !validate_command(&command, msg.reply.as_ref(), sink).await {
+        return;
+    }
+
+    let command_identity = command_identity(&msg);
+    let event = match define_location(&command, &command_identity, store).await {
+        Ok(event) => event,
         Err(e) => {
-            error!("Failed to deserialize DefineLocation: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
+            reply_rejected(msg.reply.as_ref(), &e, sink).await;
             return;
         }
     };
-
-    // TODO: Implement command handler logic
-    // For now, just acknowledge
     info!("DefineLocation: {} (id: {})", command.name, command.location_id);
+    debug!(
+        "LocationDefined {} (correlation: {})",
+        event.message_id(),
+        event.correlation_id()
+    );
+
+    if let Some(reply) = msg.reply {
+        let response = serde_json::to_value(event.acknowledgment()).unwrap();
+        let _ = sink.send(reply.to_string(), serde_json::to_vec(&response).unwrap()).await;
+    }
+}
+
+/// Identity of a command message, read from its headers
+///
+/// Commands sent without identity headers start a new correlation.
+fn command_identity(msg: &async_nats::Message) -> MessageIdentity {
+    msg.headers
+        .as_ref()
+        .and_then(MessageIdentity::from_headers)
+        .unwrap_or_else(MessageIdentity::new_root)
+}
+
+/// Record the `LocationDefined` event of a new location, caused by `cause`
+///
+/// Returns the stored envelope of the event. Fails if the command is invalid,
+/// the location already exists or the event cannot be committed.
+async fn define_location(
+    command: &DefineLocation,
+    cause: &MessageIdentity,
+    store: &impl EventLog,
+) -> Result<CimDomainEvent, String> {
+    command
+        .validate()
+        .map_err(|errors| errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))?;
+
+    if store.contains(command.location_id).await? {
+        return Err(format!("Location {} already exists", command.location_id));
+    }
+
+    let defined = LocationDefined {
+        location_id: command.location_id,
+        name: command.name.clone(),
+        location_type: command.location_type.clone(),
+        address: command.address.clone(),
+        addresses: command.addresses.clone(),
+        coordinates: command.coordinates.clone(),
+        virtual_location: command.virtual_location.clone(),
+        parent_id: command.parent_id,
+        actor: None,
+    };
+    store
+        .commit(vec![LocationDomainEvent::LocationDefined(defined)], cause)
+        .await?
+        .pop()
+        .ok_or_else(|| format!("No event stored for location {}", command.location_id))
+}
+
+/// Define every location of a batch, continuing past failed entries
+///
+/// Each entry is handled as a message caused by the batch and its event is
+/// committed on its own, so valid entries persist even when others fail.
+async fn handle_batch_define(
+    msg: async_nats::Message,
+    store: &impl EventLog,
+    sink: &impl MessageSink,
+) {
+    debug!("Received batch DefineLocation command");
+
+    let commands: Vec<DefineLocation> = match deserialize_or_dlq(&msg, sink).await {
+        Some(commands) => commands,
+        None => return,
+    };
+
+    let batch = BatchCommand::new(commands);
+
+    // Entries are committed one at a time, so they cannot go through the
+    // synchronous `BatchCommand::process`
+    let mut items = Vec::with_capacity(batch.commands.len());
+    for (index, command) in batch.commands.iter().enumerate() {
+        let identity = MessageIdentity::new_caused_by(&batch.identity);
+        let error = match define_location(command, &identity, store).await {
+            Ok(_) => {
+                info!(
+                    "DefineLocation: {} (id: {}, message: {})",
+                    command.name, command.location_id, identity.message_id
+                );
+                None
+            }
+            Err(e) => Some(e),
+        };
+        items.push(BatchItemResult { index, identity, error });
+    }
+    let result = BatchCommandResult {
+        correlation_id: batch.identity.correlation_id.clone(),
+        items,
+    };
+
+    info!(
+        "Batch {}: {} succeeded, {} failed",
+        result.correlation_id,
+        result.succeeded().len(),
+        result.failed().len()
+    );
 
     if let Some(reply) = msg.reply {
         let response = serde_json::json!({
-            "status": "accepted",
-            "location_id": command.location_id.to_string(),
+            "status": "processed",
+            "correlation_id": result.correlation_id.to_string(),
+            "succeeded": result.succeeded(),
+            "failed": result.failed(),
+            "items": result.items,
         });
-        let _ = client.publish(reply, serde_json::to_vec(&response).unwrap().into()).await;
+        let _ = sink.send(reply.to_string(), serde_json::to_vec(&response).unwrap()).await;
     }
 }
 
-async fn handle_update_location(
+async fn handle_update_location<C: Codec>(
     msg: async_nats::Message,
     repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
+    publisher: Arc<NatsEventPublisher<C>>,
     client: async_nats::Client,
 ) {
     debug!("Received UpdateLocation command");
 
-    let command: UpdateLocation = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
-        Err(e) => {
-            error!("Failed to deserialize UpdateLocation: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
-            return;
-        }
+    let command: UpdateLocation = match deserialize_or_dlq(&msg, &client).await {
+        Some(command) => command,
+        None => return,
     };
 
+    if !validate_command(&command, msg.reply.as_ref(), &client).await {
+        return;
+    }
+
     info!("UpdateLocation: {} - {}", command.location_id, command.reason);
 
     if let Some(reply) = msg.reply {
@@ -259,25 +704,23 @@ async fn handle_update_location(
     }
 }
 
-async fn handle_set_parent(
+async fn handle_set_parent<C: Codec>(
     msg: async_nats::Message,
     repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
+    publisher: Arc<NatsEventPublisher<C>>,
     client: async_nats::Client,
 ) {
     debug!("Received SetParentLocation command");
 
-    let command: SetParentLocation = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
-        Err(e) => {
-            error!("Failed to deserialize SetParentLocation: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
-            return;
-        }
+    let command: SetParentLocation = match deserialize_or_dlq(&msg, &client).await {
+        Some(command) => command,
+        None => return,
     };
 
+    if !validate_command(&command, msg.reply.as_ref(), &client).await {
+        return;
+    }
+
     info!("SetParentLocation: {} -> {} ({})", command.location_id, command.parent_id, command.reason);
 
     if let Some(reply) = msg.reply {
@@ -289,25 +732,23 @@ async fn handle_set_parent(
     }
 }
 
-async fn handle_remove_parent(
+async fn handle_remove_parent<C: Codec>(
     msg: async_nats::Message,
     repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
+    publisher: Arc<NatsEventPublisher<C>>,
     client: async_nats::Client,
 ) {
     debug!("Received RemoveParentLocation command");
 
-    let command: RemoveParentLocation = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
-        Err(e) => {
-            error!("Failed to deserialize RemoveParentLocation: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
-            return;
-        }
+    let command: RemoveParentLocation = match deserialize_or_dlq(&msg, &client).await {
+        Some(command) => command,
+        None => return,
     };
 
+    if !validate_command(&command, msg.reply.as_ref(), &client).await {
+        return;
+    }
+
     info!("RemoveParentLocation: {} ({})", command.location_id, command.reason);
 
     if let Some(reply) = msg.reply {
@@ -319,25 +760,23 @@ async fn handle_remove_parent(
     }
 }
 
-async fn handle_add_metadata(
+async fn handle_add_metadata<C: Codec>(
     msg: async_nats::Message,
     repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
+    publisher: Arc<NatsEventPublisher<C>>,
     client: async_nats::Client,
 ) {
     debug!("Received AddLocationMetadata command");
 
-    let command: AddLocationMetadata = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
-        Err(e) => {
-            error!("Failed to deserialize AddLocationMetadata: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
-            return;
-        }
+    let command: AddLocationMetadata = match deserialize_or_dlq(&msg, &client).await {
+        Some(command) => command,
+        None => return,
     };
 
+    if !validate_command(&command, msg.reply.as_ref(), &client).await {
+        return;
+    }
+
     info!("AddLocationMetadata: {} ({} entries) - {}",
         command.location_id, command.metadata.len(), command.reason);
 
@@ -350,25 +789,23 @@ async fn handle_add_metadata(
     }
 }
 
-async fn handle_archive_location(
+async fn handle_archive_location<C: Codec>(
     msg: async_nats::Message,
     repository: Arc<LocationRepository>,
-    publisher: Arc<NatsEventPublisher>,
+    publisher: Arc<NatsEventPublisher<C>>,
     client: async_nats::Client,
 ) {
     debug!("Received ArchiveLocation command");
 
-    let command: ArchiveLocation = match serde_json::from_slice(&msg.payload) {
-        Ok(cmd) => cmd,
-        Err(e) => {
-            error!("Failed to deserialize ArchiveLocation: {}", e);
-            if let Some(reply) = msg.reply {
-                let _ = client.publish(reply, format!("Error: {}", e).into()).await;
-            }
-            return;
-        }
+    let command: ArchiveLocation = match deserialize_or_dlq(&msg, &client).await {
+        Some(command) => command,
+        None => return,
     };
 
+    if !validate_command(&command, msg.reply.as_ref(), &client).await {
+        return;
+    }
+
     info!("ArchiveLocation: {} ({})", command.location_id, command.reason);
 
     if let Some(reply) = msg.reply {
@@ -379,3 +816,629 @@ async fn handle_archive_location(
         let _ = client.publish(reply, serde_json::to_vec(&response).unwrap().into()).await;
     }
 }
+
+/// Delete a location, checking its children against the read model
+///
+/// A location with children is rejected unless the command sets `cascade`.
+/// Each deleted location gets its own `LocationDeleted` event, descendants
+/// first, and only the requested location's event carries the cascade flag.
+async fn handle_delete_location(
+    msg: async_nats::Message,
+    store: &impl EventLog,
+    read_model: &RwLock<LocationReadModel>,
+    sink: &impl MessageSink,
+) {
+    debug!("Received DeleteLocation command");
+
+    let command: DeleteLocation = match deserialize_or_dlq(&msg, sink).await {
+        Some(command) => command,
+        None => return,
+    };
+
+    if !validate_command(&command, msg.reply.as_ref(), sink).await {
+        return;
+    }
+
+    info!(
+        "DeleteLocation: {} ({}, cascade: {})",
+        command.location_id, command.reason, command.cascade
+    );
+
+    let events = {
+        let read_model = read_model.read().await;
+        read_model
+            .deletion_set(command.location_id, command.cascade)
+            .map(|ids| {
+                ids.into_iter()
+                    .rev()
+                    .filter_map(|location_id| {
+                        let view = read_model.locations.get(&location_id)?;
+                        Some(LocationDomainEvent::LocationDeleted(LocationDeleted {
+                            location_id,
+                            location_type: view.location_type.clone(),
+                            reason: command.reason.clone(),
+                            cascade: location_id == command.location_id && command.cascade,
+                        }))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map_err(|e| e.to_string())
+    };
+
+    let deleted = match events {
+        Ok(events) => {
+            let deleted: Vec<String> = events
+                .iter()
+                .map(|event| event.aggregate_id().to_string())
+                .collect();
+            store
+                .commit(events, &command_identity(&msg))
+                .await
+                .map(|_| deleted)
+        }
+        Err(e) => Err(e),
+    };
+
+    match deleted {
+        Ok(deleted) => {
+            if let Some(reply) = msg.reply {
+                let response = serde_json::json!({
+                    "status": "accepted",
+                    "location_id": command.location_id.to_string(),
+                    "deleted": deleted,
+                });
+                let _ = sink.send(reply.to_string(), serde_json::to_vec(&response).unwrap()).await;
+            }
+        }
+        Err(reason) => reply_rejected(msg.reply.as_ref(), &reason, sink).await,
+    }
+}
+
+// Query Handlers
+
+/// Subject filter covering every location query
+const QUERIES_SUBJECT: &str = "queries.location.>";
+
+/// Read models the query subscriber answers from
+struct QueryReadModels {
+    /// The whole read model, needed for hierarchies and deletions
+    whole: RwLock<LocationReadModel>,
+    /// Sharded copy answering location and nearby queries, when enabled
+    sharded: Option<ShardedReadModel>,
+}
+
+impl QueryReadModels {
+    /// Keep only the whole read model when `shards` is 0
+    fn new(shards: usize) -> Self {
+        Self {
+            whole: RwLock::new(LocationReadModel::default()),
+            sharded: (shards > 0).then(|| ShardedReadModel::new(shards)),
+        }
+    }
+}
+
+#[async_trait]
+impl ProjectionTarget for QueryReadModels {
+    async fn apply_event(&self, event: &LocationDomainEvent) {
+        if let Some(sharded) = &self.sharded {
+            sharded.handle_event(event);
+        }
+        self.whole.apply_event(event).await;
+    }
+}
+
+/// Answer a query request from the read model
+///
+/// The query is picked by the request subject. Unknown subjects and payloads
+/// that fail to decode get an error reply; requests without a reply subject
+/// are dropped.
+async fn handle_query(
+    msg: &async_nats::Message,
+    read_models: &QueryReadModels,
+    sink: &impl MessageSink,
+) {
+    let Some(reply) = &msg.reply else {
+        warn!("Ignoring query on {} without a reply subject", msg.subject);
+        return;
+    };
+
+    let subject = msg.subject.to_string();
+    let whole = &read_models.whole;
+    let response = if subject == GetLocation::subject().to_subject() {
+        match &read_models.sharded {
+            Some(sharded) => answer_with(msg, |query: &GetLocation| sharded.get_location(query)),
+            None => answer_query::<GetLocation>(msg, whole).await,
+        }
+    } else if subject == FindNearbyLocations::subject().to_subject() {
+        match &read_models.sharded {
+            Some(sharded) => answer_with(msg, |query: &FindNearbyLocations| sharded.find_nearby(query)),
+            None => answer_query::<FindNearbyLocations>(msg, whole).await,
+        }
+    } else if subject == GetLocationHierarchy::subject().to_subject() {
+        answer_query::<GetLocationHierarchy>(msg, whole).await
+    } else {
+        Err(format!("Unknown query subject {}", subject))
+    };
+
+    let payload = response.unwrap_or_else(|error| {
+        warn!("Failed to answer query on {}: {}", subject, error);
+        let response = serde_json::json!({
+            "status": "error",
+            "error": error,
+        });
+        serde_json::to_vec(&response).unwrap()
+    });
+
+    if let Err(e) = sink.send(reply.to_string(), payload).await {
+        error!("Failed to reply to query on {}: {}", subject, e);
+    }
+}
+
+/// Decode a query, run it against the read model and encode the result as JSON
+async fn answer_query<Q>(
+    msg: &async_nats::Message,
+    read_model: &RwLock<LocationReadModel>,
+) -> Result<Vec<u8>, String>
+where
+    Q: LocationQuery + DeserializeOwned,
+    Q::Result: Serialize,
+{
+    let read_model = read_model.read().await;
+    answer_with(msg, |query: &Q| query.execute(&read_model))
+}
+
+/// Decode a query, answer it with `answer` and encode the result as JSON
+fn answer_with<Q, R>(msg: &async_nats::Message, answer: impl FnOnce(&Q) -> R) -> Result<Vec<u8>, String>
+where
+    Q: LocationQuery + DeserializeOwned,
+    R: Serialize,
+{
+    let query: Q = decode_message(msg).map_err(|e| e.to_string())?;
+    debug!("Received {} query", query.query_type());
+
+    serde_json::to_vec(&answer(&query)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cim_domain_location::chain_event;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        sent: Mutex<Vec<(String, Vec<u8>)>>,
+    }
+
+    #[async_trait]
+    impl MessageSink for RecordingSink {
+        async fn send(&self, subject: String, payload: Vec<u8>) -> Result<(), String> {
+            self.sent.lock().unwrap().push((subject, payload));
+            Ok(())
+        }
+    }
+
+    /// Event log that keeps committed events, and the envelopes it published
+    /// for them, in memory
+    #[derive(Default)]
+    struct RecordingLog {
+        events: Mutex<Vec<LocationDomainEvent>>,
+        published: Mutex<Vec<CimDomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventLog for RecordingLog {
+        async fn contains(&self, location_id: Uuid) -> Result<bool, String> {
+            Ok(self
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|event| event.aggregate_id() == location_id))
+        }
+
+        async fn commit(
+            &self,
+            events: Vec<LocationDomainEvent>,
+            cause: &MessageIdentity,
+        ) -> Result<Vec<CimDomainEvent>, String> {
+            let mut published = self.published.lock().unwrap();
+            let mut envelopes = Vec::with_capacity(events.len());
+            for event in &events {
+                let aggregate_id = event.aggregate_id().to_string();
+                let previous = published
+                    .iter()
+                    .chain(envelopes.iter())
+                    .rev()
+                    .find(|envelope: &&CimDomainEvent| envelope.aggregate_id == aggregate_id);
+                let envelope = chain_event(previous, event, Some(cause)).map_err(|e| e.to_string())?;
+                envelopes.push(envelope);
+            }
+            published.extend(envelopes.iter().cloned());
+            self.events.lock().unwrap().extend(events);
+            Ok(envelopes)
+        }
+    }
+
+    fn reply_json(sink: &RecordingSink) -> serde_json::Value {
+        let sent = sink.sent.lock().unwrap();
+        let (_, payload) = sent.last().expect("no reply sent");
+        serde_json::from_slice(payload).unwrap()
+    }
+
+    fn message(payload: &[u8]) -> async_nats::Message {
+        async_nats::Message {
+            subject: "location.commands.define".into(),
+            reply: Some("_INBOX.reply".into()),
+            payload: payload.to_vec().into(),
+            headers: None,
+            status: None,
+            description: None,
+            length: payload.len(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_command_is_dead_lettered_once() {
+        let sink = RecordingSink::default();
+
+        let command: Option<DefineLocation> = deserialize_or_dlq(&message(b"{not json"), &sink).await;
+        assert!(command.is_none());
+
+        let sent = sink.sent.lock().unwrap();
+        let dead_letters: Vec<_> = sent
+            .iter()
+            .filter(|(subject, _)| subject.starts_with("dlq."))
+            .collect();
+        assert_eq!(dead_letters.len(), 1);
+
+        let (subject, payload) = dead_letters[0];
+        assert_eq!(subject, "dlq.location.commands.define");
+        let dead_letter: DeadLetter = serde_json::from_slice(payload).unwrap();
+        assert_eq!(dead_letter.subject, "location.commands.define");
+        assert_eq!(dead_letter.payload, "{not json");
+        assert!(!dead_letter.error.is_empty());
+
+        // The requester still gets an error reply
+        assert!(sent.iter().any(|(subject, _)| subject == "_INBOX.reply"));
+    }
+
+    #[tokio::test]
+    async fn test_valid_command_is_not_dead_lettered() {
+        let sink = RecordingSink::default();
+        let payload = serde_json::json!({
+            "location_id": uuid::Uuid::new_v4(),
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        });
+
+        let command: Option<DefineLocation> =
+            deserialize_or_dlq(&message(&serde_json::to_vec(&payload).unwrap()), &sink).await;
+
+        assert_eq!(command.unwrap().name, "Warehouse");
+        assert!(sink.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_command_is_decoded_by_content_type() {
+        use cim_domain_location::{MsgpackCodec, CONTENT_TYPE_HEADER};
+
+        let sink = RecordingSink::default();
+        let payload = serde_json::json!({
+            "location_id": uuid::Uuid::new_v4(),
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        });
+        let mut msg = message(&MsgpackCodec.encode(&payload).unwrap());
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, MsgpackCodec::CONTENT_TYPE);
+        msg.headers = Some(headers);
+
+        let command: Option<DefineLocation> = deserialize_or_dlq(&msg, &sink).await;
+
+        assert_eq!(command.unwrap().name, "Warehouse");
+        assert!(sink.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_nearby_query_replies_with_matching_locations() {
+        use cim_domain_location::{Distance, GeoCoordinates, LocationType, NearbyLocation};
+
+        let define = |name: &str, latitude: f64, longitude: f64| {
+            LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id: uuid::Uuid::new_v4(),
+                name: name.to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(GeoCoordinates::new(latitude, longitude)),
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            })
+        };
+        let events = vec![
+            define("Ferry Building", 37.7955, -122.3937),
+            define("Coit Tower", 37.8024, -122.4058),
+            define("Golden Gate Park", 37.7694, -122.4862),
+        ];
+        let ferry_building = events[0].aggregate_id();
+        let coit_tower = events[1].aggregate_id();
+
+        let query = FindNearbyLocations {
+            center: GeoCoordinates::new(37.7946, -122.3950),
+            radius: Distance::from_kilometers(2.0),
+            location_types: None,
+            max_results: None,
+        };
+        let mut msg = message(&serde_json::to_vec(&query).unwrap());
+        msg.subject = FindNearbyLocations::subject().to_subject().into();
+
+        // The sharded read model answers exactly like the single one
+        for shards in [0, 4] {
+            let read_models = QueryReadModels::new(shards);
+            for event in &events {
+                read_models.apply_event(event).await;
+            }
+
+            let sink = RecordingSink::default();
+            handle_query(&msg, &read_models, &sink).await;
+
+            let sent = sink.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            let (subject, payload) = &sent[0];
+            assert_eq!(subject, "_INBOX.reply");
+
+            let nearby: Vec<NearbyLocation> = serde_json::from_slice(payload).unwrap();
+            let ids: Vec<_> = nearby.iter().map(|n| n.location.id).collect();
+            assert_eq!(ids, vec![ferry_building, coit_tower]);
+            assert!(nearby[0].distance_meters < nearby[1].distance_meters);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_define_location_commits_before_acknowledging() {
+        let location_id = Uuid::new_v4();
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "location_id": location_id,
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        }))
+        .unwrap();
+        let store = RecordingLog::default();
+        let sink = RecordingSink::default();
+
+        handle_define_location(message(&payload), &store, &sink).await;
+
+        let response = reply_json(&sink);
+        assert_eq!(response["status"], "accepted");
+        assert_eq!(response["location_id"], location_id.to_string());
+        assert!(store.contains(location_id).await.unwrap());
+
+        // Defining the same location again is rejected and commits nothing
+        handle_define_location(message(&payload), &store, &sink).await;
+
+        let response = reply_json(&sink);
+        assert_eq!(response["status"], "rejected");
+        assert_eq!(store.events.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_define_acknowledgment_matches_published_event() {
+        let location_id = Uuid::new_v4();
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "location_id": location_id,
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        }))
+        .unwrap();
+        let command = MessageIdentity::new_root();
+        let mut headers = async_nats::HeaderMap::new();
+        command.insert_headers(&mut headers);
+        let msg = async_nats::Message {
+            headers: Some(headers),
+            ..message(&payload)
+        };
+        let store = RecordingLog::default();
+        let sink = RecordingSink::default();
+
+        handle_define_location(msg, &store, &sink).await;
+
+        let response = reply_json(&sink);
+        let published = store.published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        let event = &published[0];
+        assert_eq!(response["status"], "accepted");
+        assert_eq!(response["message_id"], event.message_id().to_string());
+        assert_eq!(response["correlation_id"], event.correlation_id().to_string());
+        assert_eq!(response["causation_id"], event.causation_id().to_string());
+        assert_eq!(
+            response["event_cid"],
+            event.event_cid.as_ref().unwrap().to_string()
+        );
+
+        // The event is caused by the command, in the command's correlation
+        assert_eq!(event.correlation_id(), &command.correlation_id);
+        assert_eq!(event.causation_id().0, command.message_id.0);
+    }
+
+    #[tokio::test]
+    async fn test_redelivered_define_gets_original_reply() {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "location_id": Uuid::new_v4(),
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        }))
+        .unwrap();
+        let delivery = || {
+            let mut msg = message(&payload);
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert(IDEMPOTENCY_KEY_HEADER, "client-retry-1");
+            msg.headers = Some(headers);
+            msg
+        };
+        let processed = ProcessedCommands::default();
+        let store = RecordingLog::default();
+        let sink = RecordingSink::default();
+
+        for _ in 0..2 {
+            let msg = delivery();
+            let remembering = processed.sink_for(&msg, &sink);
+            if !remembering.replay().await {
+                handle_define_location(msg, &store, &remembering).await;
+            }
+        }
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0], sent[1]);
+        assert_eq!(store.events.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_reply_is_not_remembered() {
+        let processed = ProcessedCommands::default();
+        let sink = RecordingSink::default();
+        let mut msg = message(b"{}");
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "client-retry-2");
+        msg.headers = Some(headers);
+
+        let remembering = processed.sink_for(&msg, &sink);
+        reply_rejected(msg.reply.as_ref(), "Location not found", &remembering).await;
+
+        assert!(!processed.sink_for(&msg, &sink).replay().await);
+    }
+
+    #[tokio::test]
+    async fn test_batch_define_persists_valid_entries() {
+        let define = |name: &str| {
+            serde_json::json!({
+                "location_id": Uuid::new_v4(),
+                "name": name,
+                "location_type": "Logical",
+                "address": null,
+                "coordinates": null,
+                "virtual_location": null,
+                "parent_id": null
+            })
+        };
+        let batch = vec![define("Warehouse"), define(" "), define("Depot")];
+        let store = RecordingLog::default();
+        let sink = RecordingSink::default();
+
+        handle_batch_define(message(&serde_json::to_vec(&batch).unwrap()), &store, &sink).await;
+
+        let response = reply_json(&sink);
+        assert_eq!(response["succeeded"], serde_json::json!([0, 2]));
+        assert_eq!(response["failed"], serde_json::json!([1]));
+
+        let defined: Vec<String> = store
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.aggregate_id().to_string())
+            .collect();
+        let expected: Vec<&str> = [&batch[0], &batch[2]]
+            .iter()
+            .map(|command| command["location_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(defined, expected);
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_children_requires_cascade() {
+        use cim_domain_location::{LocationProjection, LocationType, ParentLocationSet};
+
+        let mut model = LocationReadModel::default();
+        let campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+        for (location_id, name) in [(campus, "Campus"), (building, "Building A")] {
+            model.handle_location_defined(&LocationDefined {
+                location_id,
+                name: name.to_string(),
+                location_type: LocationType::Logical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: None,
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            });
+        }
+        model.handle_parent_location_set(&ParentLocationSet {
+            location_id: building,
+            parent_id: campus,
+            previous_parent_id: None,
+            reason: "Site plan".to_string(),
+        });
+        let read_model = RwLock::new(model);
+        let store = RecordingLog::default();
+
+        let delete = |cascade: bool| {
+            let command = DeleteLocation {
+                location_id: campus,
+                reason: "Erasure request".to_string(),
+                cascade,
+            };
+            message(&serde_json::to_vec(&command).unwrap())
+        };
+
+        let sink = RecordingSink::default();
+        handle_delete_location(delete(false), &store, &read_model, &sink).await;
+        assert_eq!(reply_json(&sink)["status"], "rejected");
+        assert!(store.events.lock().unwrap().is_empty());
+
+        let sink = RecordingSink::default();
+        handle_delete_location(delete(true), &store, &read_model, &sink).await;
+        assert_eq!(reply_json(&sink)["status"], "accepted");
+
+        let deleted: Vec<_> = store
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| match event {
+                LocationDomainEvent::LocationDeleted(e) => (e.location_id, e.cascade),
+                other => panic!("Expected LocationDeleted, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(deleted, vec![(building, false), (campus, true)]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_query_subject_gets_error_reply() {
+        let read_models = QueryReadModels::new(0);
+        let mut msg = message(b"{}");
+        msg.subject = "queries.location.location.get_history".into();
+
+        let sink = RecordingSink::default();
+        handle_query(&msg, &read_models, &sink).await;
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let response: serde_json::Value = serde_json::from_slice(&sent[0].1).unwrap();
+        assert_eq!(response["status"], "error");
+    }
+}
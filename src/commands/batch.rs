@@ -0,0 +1,133 @@
+//! Batched location commands
+
+use crate::aggregate::LocationMarker;
+use crate::nats::{CorrelationId, MessageIdentity};
+use cim_domain::{Command, EntityId};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A batch of commands processed under one correlation chain
+///
+/// Every command in the batch is handled as a message caused by the batch
+/// itself, so all resulting work shares the batch's correlation ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCommand<C> {
+    /// Identity of the batch message
+    pub identity: MessageIdentity,
+    /// Commands in submission order
+    pub commands: Vec<C>,
+}
+
+/// Outcome of a single command within a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    /// Position of the command in the batch
+    pub index: usize,
+    /// Identity the command was processed under
+    pub identity: MessageIdentity,
+    /// Failure reason, `None` if the command succeeded
+    pub error: Option<String>,
+}
+
+/// Summary of a processed batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCommandResult {
+    /// Correlation ID shared by every command in the batch
+    pub correlation_id: CorrelationId,
+    /// Per-command outcomes in submission order
+    pub items: Vec<BatchItemResult>,
+}
+
+impl<C> BatchCommand<C> {
+    /// Create a batch as the root of a new correlation chain
+    pub fn new(commands: Vec<C>) -> Self {
+        Self {
+            identity: MessageIdentity::new_root(),
+            commands,
+        }
+    }
+
+    /// Process each command with `handler`, continuing past failures
+    pub fn process<E, F>(&self, mut handler: F) -> BatchCommandResult
+    where
+        E: Display,
+        F: FnMut(&C, &MessageIdentity) -> Result<(), E>,
+    {
+        let items = self
+            .commands
+            .iter()
+            .enumerate()
+            .map(|(index, command)| {
+                let identity = MessageIdentity::new_caused_by(&self.identity);
+                let error = handler(command, &identity).err().map(|e| e.to_string());
+                BatchItemResult {
+                    index,
+                    identity,
+                    error,
+                }
+            })
+            .collect();
+
+        BatchCommandResult {
+            correlation_id: self.identity.correlation_id.clone(),
+            items,
+        }
+    }
+}
+
+impl<C: Command<Aggregate = LocationMarker>> Command for BatchCommand<C> {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        // A batch spans many aggregates
+        None
+    }
+}
+
+impl BatchCommandResult {
+    /// Indices of commands that succeeded
+    pub fn succeeded(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .filter(|item| item.error.is_none())
+            .map(|item| item.index)
+            .collect()
+    }
+
+    /// Indices of commands that failed
+    pub fn failed(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .filter(|item| item.error.is_some())
+            .map(|item| item.index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_items_share_correlation() {
+        let batch = BatchCommand::new(vec![1, -2, 3]);
+
+        let result = batch.process(|n: &i32, _| {
+            if *n > 0 {
+                Ok(())
+            } else {
+                Err(format!("{n} is not positive"))
+            }
+        });
+
+        assert_eq!(result.succeeded(), vec![0, 2]);
+        assert_eq!(result.failed(), vec![1]);
+        assert_eq!(result.items[1].error.as_deref(), Some("-2 is not positive"));
+
+        for item in &result.items {
+            assert_eq!(item.identity.correlation_id, batch.identity.correlation_id);
+            assert_eq!(item.identity.causation_id.0, batch.identity.message_id.0);
+            assert!(!item.identity.is_root());
+        }
+        assert_eq!(result.correlation_id, batch.identity.correlation_id);
+    }
+}
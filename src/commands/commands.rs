@@ -1,7 +1,10 @@
 //! Location commands
 
 use crate::aggregate::LocationMarker;
-use crate::value_objects::{Address, GeoCoordinates, LocationType, VirtualLocation};
+use crate::services::SpatialRegion;
+use crate::value_objects::{
+    Address, AddressRole, GeoCoordinates, IndoorPosition, LocationType, VirtualLocation,
+};
 use cim_domain::{Command, EntityId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,6 +21,9 @@ pub struct DefineLocation {
     pub location_type: LocationType,
     /// Physical address (for physical locations)
     pub address: Option<Address>,
+    /// Mailing, billing and other non-physical addresses
+    #[serde(default)]
+    pub addresses: HashMap<AddressRole, Address>,
     /// Geographic coordinates (for physical locations)
     pub coordinates: Option<GeoCoordinates>,
     /// Virtual location details (for virtual locations)
@@ -26,6 +32,27 @@ pub struct DefineLocation {
     pub parent_id: Option<Uuid>,
 }
 
+impl DefineLocation {
+    /// Define a physical location that already has both an address and coordinates
+    pub fn physical(
+        location_id: Uuid,
+        name: String,
+        address: Address,
+        coordinates: GeoCoordinates,
+    ) -> Self {
+        Self {
+            location_id,
+            name,
+            location_type: LocationType::Physical,
+            address: Some(address),
+            addresses: HashMap::new(),
+            coordinates: Some(coordinates),
+            virtual_location: None,
+            parent_id: None,
+        }
+    }
+}
+
 /// Update an existing location's details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateLocation {
@@ -63,6 +90,35 @@ pub struct RemoveParentLocation {
     pub reason: String,
 }
 
+/// Set the mailing, billing or other non-physical address of a location
+///
+/// The physical address is changed with [`UpdateLocation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLocationAddress {
+    /// Location ID
+    pub location_id: Uuid,
+    /// Role of the address
+    pub role: AddressRole,
+    /// New address for the role
+    pub address: Address,
+    /// Reason for the change
+    pub reason: String,
+}
+
+/// Place a location inside a building
+///
+/// Rooms and floors are usually logical locations; virtual locations cannot
+/// be placed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetIndoorPosition {
+    /// Location ID
+    pub location_id: Uuid,
+    /// Building, floor and room
+    pub position: IndoorPosition,
+    /// Reason for the change
+    pub reason: String,
+}
+
 /// Add metadata to a location
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddLocationMetadata {
@@ -72,6 +128,12 @@ pub struct AddLocationMetadata {
     pub metadata: HashMap<String, String>,
     /// Reason for adding metadata
     pub reason: String,
+    /// Version of the location the metadata was based on
+    ///
+    /// When set, the command is rejected if the location has changed since,
+    /// so the client can merge with the latest metadata and retry.
+    #[serde(default)]
+    pub expected_version: Option<u64>,
 }
 
 /// Archive a location (soft delete)
@@ -83,6 +145,66 @@ pub struct ArchiveLocation {
     pub reason: String,
 }
 
+/// Delete a location permanently (hard delete, e.g. for erasure requests)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteLocation {
+    /// Location ID to delete
+    pub location_id: Uuid,
+    /// Reason for deletion
+    pub reason: String,
+    /// Also delete all descendant locations
+    pub cascade: bool,
+}
+
+/// Merge a duplicate location into another one
+///
+/// The source's children, as recorded in the location hierarchy, are
+/// re-parented to the target, its metadata is copied onto the target and the
+/// source is archived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeLocations {
+    /// Location that survives the merge
+    pub target_id: Uuid,
+    /// Duplicate location merged into the target and archived
+    pub source_id: Uuid,
+    /// Overwrite the target's value when both locations have the same metadata key
+    pub overwrite: bool,
+    /// Reason for merging
+    pub reason: String,
+}
+
+/// Tag every active location inside a spatial region
+///
+/// Each tag is stored as a `tag:{name}` metadata key, so tagging reuses the
+/// metadata events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagLocationsInRegion {
+    /// Region whose locations are tagged
+    pub region: SpatialRegion,
+    /// Tags to add to each location
+    pub tags: Vec<String>,
+}
+
+impl TagLocationsInRegion {
+    /// Metadata key prefix used for tags
+    pub const TAG_PREFIX: &'static str = "tag:";
+
+    /// Metadata key under which `tag` is stored
+    pub fn metadata_key(tag: &str) -> String {
+        format!("{}{}", Self::TAG_PREFIX, tag)
+    }
+}
+
+/// Check a location's stored coordinates against its address
+///
+/// The location needs both an address and coordinates, and the command handler
+/// needs a geocoder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateCoordinates {
+    /// Location ID to validate
+    pub location_id: Uuid,
+}
+
 /// Base trait for location commands
 pub trait LocationCommand {
     fn location_id(&self) -> Uuid;
@@ -112,6 +234,18 @@ impl LocationCommand for RemoveParentLocation {
     }
 }
 
+impl LocationCommand for SetLocationAddress {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl LocationCommand for SetIndoorPosition {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
 impl LocationCommand for AddLocationMetadata {
     fn location_id(&self) -> Uuid {
         self.location_id
@@ -124,6 +258,24 @@ impl LocationCommand for ArchiveLocation {
     }
 }
 
+impl LocationCommand for DeleteLocation {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl LocationCommand for MergeLocations {
+    fn location_id(&self) -> Uuid {
+        self.target_id
+    }
+}
+
+impl LocationCommand for ValidateCoordinates {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
 // Command implementations
 impl Command for DefineLocation {
     type Aggregate = LocationMarker;
@@ -153,6 +305,20 @@ impl Command for RemoveParentLocation {
     }
 }
 
+impl Command for SetLocationAddress {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(self.location_id))
+    }
+}
+
+impl Command for SetIndoorPosition {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(self.location_id))
+    }
+}
+
 impl Command for AddLocationMetadata {
     type Aggregate = LocationMarker;
     fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
@@ -166,3 +332,32 @@ impl Command for ArchiveLocation {
         Some(EntityId::from_uuid(self.location_id))
     }
 }
+
+impl Command for DeleteLocation {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(self.location_id))
+    }
+}
+
+impl Command for MergeLocations {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(self.target_id))
+    }
+}
+
+impl Command for ValidateCoordinates {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        Some(EntityId::from_uuid(self.location_id))
+    }
+}
+
+impl Command for TagLocationsInRegion {
+    type Aggregate = LocationMarker;
+    fn aggregate_id(&self) -> Option<EntityId<Self::Aggregate>> {
+        // Targets every location in the region rather than a single aggregate
+        None
+    }
+}
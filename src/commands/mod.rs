@@ -1,5 +1,9 @@
 //! Location commands
 
+mod batch;
 mod commands;
+mod validation;
 
+pub use batch::*;
 pub use commands::*;
+pub use validation::*;
@@ -0,0 +1,291 @@
+//! Structural validation of location commands
+//!
+//! Commands are checked field by field before they reach the aggregate, so a
+//! caller receives every problem with the command at once instead of the first
+//! error raised while building the location.
+
+use super::{
+    AddLocationMetadata, ArchiveLocation, DefineLocation, DeleteLocation, MergeLocations,
+    RemoveParentLocation, SetIndoorPosition, SetLocationAddress, SetParentLocation, UpdateLocation,
+};
+use crate::value_objects::{AddressRole, LocationType};
+use cim_domain::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A validation failure for one field of a command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Name of the offending field
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl FieldError {
+    /// Create a field error
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Commands that can be checked before they are handled
+pub trait Validate {
+    /// Check every field, returning all failures
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+/// Collects field errors while validating a command
+#[derive(Default)]
+struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, message));
+    }
+
+    /// Record a value object's validation failure against `field`
+    fn check(&mut self, field: &str, result: DomainResult<()>) {
+        if let Err(e) = result {
+            let message = match e {
+                DomainError::ValidationError(message) => message,
+                other => other.to_string(),
+            };
+            self.add(field, message);
+        }
+    }
+
+    fn require_text(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, format!("{field} cannot be empty"));
+        }
+    }
+
+    fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+impl Validate for DefineLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        errors.require_text("name", &self.name);
+        if let Some(address) = &self.address {
+            errors.check("address", address.validate());
+        }
+        for (role, address) in &self.addresses {
+            if *role == AddressRole::Physical {
+                errors.add("addresses", "The physical address is given as address");
+            } else {
+                errors.check("addresses", address.validate());
+            }
+        }
+        if let Some(coordinates) = &self.coordinates {
+            errors.check("coordinates", coordinates.validate());
+        }
+
+        match self.location_type {
+            LocationType::Physical if self.address.is_none() && self.coordinates.is_none() => {
+                errors.add(
+                    "location_type",
+                    "Physical location requires either address or coordinates",
+                );
+            }
+            LocationType::Virtual if self.virtual_location.is_none() => {
+                errors.add(
+                    "virtual_location",
+                    "Virtual location requires virtual location details",
+                );
+            }
+            LocationType::Mobile if self.coordinates.is_none() => {
+                errors.add(
+                    "coordinates",
+                    "Mobile location requires initial coordinates",
+                );
+            }
+            _ => {}
+        }
+
+        if self.parent_id == Some(self.location_id) {
+            errors.add("parent_id", "Location cannot be its own parent");
+        }
+
+        errors.finish()
+    }
+}
+
+impl Validate for UpdateLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        if let Some(name) = &self.name {
+            errors.require_text("name", name);
+        }
+        if let Some(address) = &self.address {
+            errors.check("address", address.validate());
+        }
+        if let Some(coordinates) = &self.coordinates {
+            errors.check("coordinates", coordinates.validate());
+        }
+
+        errors.finish()
+    }
+}
+
+impl Validate for SetParentLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        if self.parent_id == self.location_id {
+            errors.add("parent_id", "Location cannot be its own parent");
+        }
+
+        errors.finish()
+    }
+}
+
+impl Validate for RemoveParentLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Ok(())
+    }
+}
+
+impl Validate for AddLocationMetadata {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        if self.metadata.is_empty() {
+            errors.add("metadata", "metadata cannot be empty");
+        } else if self.metadata.keys().any(|key| key.trim().is_empty()) {
+            errors.add("metadata", "metadata keys cannot be empty");
+        }
+
+        errors.finish()
+    }
+}
+
+impl Validate for ArchiveLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Ok(())
+    }
+}
+
+impl Validate for DeleteLocation {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Ok(())
+    }
+}
+
+impl Validate for SetLocationAddress {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        if self.role == AddressRole::Physical {
+            errors.add("role", "Update the location to change its physical address");
+        }
+        errors.check("address", self.address.validate());
+        errors.require_text("reason", &self.reason);
+
+        errors.finish()
+    }
+}
+
+impl Validate for SetIndoorPosition {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        errors.check("position", self.position.validate());
+        errors.require_text("reason", &self.reason);
+
+        errors.finish()
+    }
+}
+
+impl Validate for MergeLocations {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+
+        if self.source_id == self.target_id {
+            errors.add("source_id", "Cannot merge a location into itself");
+        }
+
+        errors.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::GeoCoordinates;
+    use uuid::Uuid;
+
+    fn define(name: &str, coordinates: Option<GeoCoordinates>) -> DefineLocation {
+        DefineLocation {
+            location_id: Uuid::new_v4(),
+            name: name.to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: Default::default(),
+            coordinates,
+            virtual_location: None,
+            parent_id: None,
+        }
+    }
+
+    #[test]
+    fn test_define_location_reports_every_field_error() {
+        let command = define("  ", Some(GeoCoordinates::new(91.0, -122.4194)));
+
+        let errors = command.validate().unwrap_err();
+
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "coordinates"]);
+        assert!(errors[1].message.contains("Latitude"));
+    }
+
+    #[test]
+    fn test_define_location_accepts_valid_command() {
+        assert!(
+            define("Warehouse", Some(GeoCoordinates::new(37.7749, -122.4194)))
+                .validate()
+                .is_ok()
+        );
+
+        // A physical location still needs somewhere to be
+        let errors = define("Warehouse", None).validate().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![FieldError::new(
+                "location_type",
+                "Physical location requires either address or coordinates",
+            )]
+        );
+    }
+
+    #[test]
+    fn test_set_parent_rejects_self_reference() {
+        let location_id = Uuid::new_v4();
+        let command = SetParentLocation {
+            location_id,
+            parent_id: location_id,
+            reason: "Test".to_string(),
+        };
+
+        let errors = command.validate().unwrap_err();
+        assert_eq!(errors[0].field, "parent_id");
+    }
+}
@@ -1,9 +1,13 @@
 //! Domain events enum for location domain
 
 use crate::events::{
-    LocationArchived, LocationDefined, LocationMetadataAdded, LocationUpdated,
+    AddressGeocoded, CoordinatesUpdated, CoordinatesValidated, HierarchyReorganized,
+    IndoorPositionSet, LocationAddressSet, LocationArchived, LocationCheckedIn, LocationCheckedOut,
+    LocationDefined, LocationDeleted, LocationMetadataAdded, LocationMetadataRemoved,
+    LocationMetadataUpdated, LocationMoved, LocationRestored, LocationUpdated, LocationsMerged,
     ParentLocationRemoved, ParentLocationSet,
 };
+use crate::nats::ActorId;
 use cim_domain::DomainEvent;
 use serde::{Deserialize, Serialize};
 
@@ -14,14 +18,59 @@ pub enum LocationDomainEvent {
     LocationDefined(LocationDefined),
     /// A location was updated
     LocationUpdated(LocationUpdated),
+    /// A location moved to new coordinates
+    LocationMoved(LocationMoved),
     /// A parent location was set
     ParentLocationSet(ParentLocationSet),
     /// A parent location was removed
     ParentLocationRemoved(ParentLocationRemoved),
     /// Metadata was added to a location
     LocationMetadataAdded(LocationMetadataAdded),
+    /// Existing metadata values were overwritten
+    LocationMetadataUpdated(LocationMetadataUpdated),
+    /// A metadata key was removed from a location
+    LocationMetadataRemoved(LocationMetadataRemoved),
     /// A location was archived
     LocationArchived(LocationArchived),
+    /// A user checked in at a location
+    LocationCheckedIn(LocationCheckedIn),
+    /// A user checked out of a location
+    LocationCheckedOut(LocationCheckedOut),
+    /// A duplicate location was merged into another
+    LocationsMerged(LocationsMerged),
+    /// A location was deleted
+    LocationDeleted(LocationDeleted),
+    /// Coordinates were resolved from a location's address
+    AddressGeocoded(AddressGeocoded),
+    /// Several locations were re-parented by a hierarchy reorganization
+    HierarchyReorganized(HierarchyReorganized),
+    /// Coordinates were corrected or enriched without the location moving
+    CoordinatesUpdated(CoordinatesUpdated),
+    /// An archived location was restored
+    LocationRestored(LocationRestored),
+    /// Stored coordinates were checked against the location's address
+    CoordinatesValidated(CoordinatesValidated),
+    /// A mailing, billing or other non-physical address was set
+    LocationAddressSet(LocationAddressSet),
+    /// The building, floor and room of a location were set
+    IndoorPositionSet(IndoorPositionSet),
+}
+
+impl LocationDomainEvent {
+    /// Attribute the event to the actor that caused it
+    ///
+    /// Only events that record who made the change carry an actor; all others
+    /// are returned unchanged.
+    pub fn with_actor(mut self, actor: Option<ActorId>) -> Self {
+        match &mut self {
+            Self::LocationDefined(e) => e.actor = actor,
+            Self::LocationUpdated(e) => e.actor = actor,
+            Self::LocationArchived(e) => e.actor = actor,
+            Self::LocationRestored(e) => e.actor = actor,
+            _ => {}
+        }
+        self
+    }
 }
 
 impl DomainEvent for LocationDomainEvent {
@@ -29,10 +78,24 @@ impl DomainEvent for LocationDomainEvent {
         match self {
             Self::LocationDefined(e) => e.aggregate_id(),
             Self::LocationUpdated(e) => e.aggregate_id(),
+            Self::LocationMoved(e) => e.aggregate_id(),
             Self::ParentLocationSet(e) => e.aggregate_id(),
             Self::ParentLocationRemoved(e) => e.aggregate_id(),
             Self::LocationMetadataAdded(e) => e.aggregate_id(),
+            Self::LocationMetadataUpdated(e) => e.aggregate_id(),
+            Self::LocationMetadataRemoved(e) => e.aggregate_id(),
             Self::LocationArchived(e) => e.aggregate_id(),
+            Self::LocationCheckedIn(e) => e.aggregate_id(),
+            Self::LocationCheckedOut(e) => e.aggregate_id(),
+            Self::LocationsMerged(e) => e.aggregate_id(),
+            Self::LocationDeleted(e) => e.aggregate_id(),
+            Self::AddressGeocoded(e) => e.aggregate_id(),
+            Self::HierarchyReorganized(e) => e.aggregate_id(),
+            Self::CoordinatesUpdated(e) => e.aggregate_id(),
+            Self::LocationRestored(e) => e.aggregate_id(),
+            Self::CoordinatesValidated(e) => e.aggregate_id(),
+            Self::LocationAddressSet(e) => e.aggregate_id(),
+            Self::IndoorPositionSet(e) => e.aggregate_id(),
         }
     }
 
@@ -40,10 +103,24 @@ impl DomainEvent for LocationDomainEvent {
         match self {
             Self::LocationDefined(e) => e.event_type(),
             Self::LocationUpdated(e) => e.event_type(),
+            Self::LocationMoved(e) => e.event_type(),
             Self::ParentLocationSet(e) => e.event_type(),
             Self::ParentLocationRemoved(e) => e.event_type(),
             Self::LocationMetadataAdded(e) => e.event_type(),
+            Self::LocationMetadataUpdated(e) => e.event_type(),
+            Self::LocationMetadataRemoved(e) => e.event_type(),
             Self::LocationArchived(e) => e.event_type(),
+            Self::LocationCheckedIn(e) => e.event_type(),
+            Self::LocationCheckedOut(e) => e.event_type(),
+            Self::LocationsMerged(e) => e.event_type(),
+            Self::LocationDeleted(e) => e.event_type(),
+            Self::AddressGeocoded(e) => e.event_type(),
+            Self::HierarchyReorganized(e) => e.event_type(),
+            Self::CoordinatesUpdated(e) => e.event_type(),
+            Self::LocationRestored(e) => e.event_type(),
+            Self::CoordinatesValidated(e) => e.event_type(),
+            Self::LocationAddressSet(e) => e.event_type(),
+            Self::IndoorPositionSet(e) => e.event_type(),
         }
     }
 }
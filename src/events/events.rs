@@ -1,6 +1,10 @@
 //! Location domain events
 
-use crate::value_objects::{Address, GeoCoordinates, LocationType, VirtualLocation};
+use crate::nats::{ActorId, EventType, LocationAggregate, LocationSubject};
+use crate::value_objects::{
+    Address, AddressRole, GeoCoordinates, IndoorPosition, LocationType, VirtualLocation,
+};
+use chrono::{DateTime, Utc};
 use cim_domain::DomainEvent;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -17,12 +21,20 @@ pub struct LocationDefined {
     pub location_type: LocationType,
     /// The physical address (if applicable)
     pub address: Option<Address>,
+    /// Addresses for roles other than physical, e.g. mailing and billing
+    ///
+    /// The physical address is `address`; a `Physical` entry here is ignored.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub addresses: HashMap<AddressRole, Address>,
     /// The geographic coordinates (if applicable)
     pub coordinates: Option<GeoCoordinates>,
     /// Virtual location details (if applicable)
     pub virtual_location: Option<VirtualLocation>,
     /// The parent location ID (for hierarchical locations)
     pub parent_id: Option<Uuid>,
+    /// Who defined the location, if known
+    #[serde(default)]
+    pub actor: Option<ActorId>,
 }
 
 /// Location details updated
@@ -48,6 +60,25 @@ pub struct LocationUpdated {
     pub virtual_location: Option<VirtualLocation>,
     /// Reason for update
     pub reason: String,
+    /// Who updated the location, if known
+    #[serde(default)]
+    pub actor: Option<ActorId>,
+}
+
+/// Location moved to new coordinates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationMoved {
+    /// The unique identifier of the location
+    pub location_id: Uuid,
+    /// Coordinates before the move
+    pub previous_coordinates: GeoCoordinates,
+    /// Coordinates after the move
+    pub coordinates: GeoCoordinates,
+    /// Distance moved in meters
+    pub distance_meters: f64,
+    /// When the new position was observed, if it was recorded as a position fix
+    #[serde(default)]
+    pub recorded_at: Option<DateTime<Utc>>,
 }
 
 /// Parent location set for hierarchical structure
@@ -87,6 +118,36 @@ pub struct LocationMetadataAdded {
     pub reason: String,
 }
 
+/// Existing metadata values overwritten on a location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationMetadataUpdated {
+    /// Location ID
+    pub location_id: Uuid,
+    /// Values before the update, for the overwritten keys only
+    pub previous_metadata: HashMap<String, String>,
+    /// New values for the overwritten keys
+    pub updated_metadata: HashMap<String, String>,
+    /// All metadata after the update
+    pub current_metadata: HashMap<String, String>,
+    /// Reason for updating metadata
+    pub reason: String,
+}
+
+/// Metadata key removed from location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationMetadataRemoved {
+    /// Location ID
+    pub location_id: Uuid,
+    /// Key that was removed
+    pub key: String,
+    /// Value held by the key before removal
+    pub previous_value: String,
+    /// All metadata after removal
+    pub current_metadata: HashMap<String, String>,
+    /// Reason for removing metadata
+    pub reason: String,
+}
+
 /// Location archived (soft deleted)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationArchived {
@@ -98,6 +159,186 @@ pub struct LocationArchived {
     pub location_type: LocationType,
     /// Reason for archiving
     pub reason: String,
+    /// Who archived the location, if known
+    #[serde(default)]
+    pub actor: Option<ActorId>,
+}
+
+/// Archived location restored to active use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationRestored {
+    /// Location ID that was restored
+    pub location_id: Uuid,
+    /// Name of the restored location
+    pub name: String,
+    /// Type of the restored location
+    pub location_type: LocationType,
+    /// Reason for restoring
+    pub reason: String,
+    /// Who restored the location, if known
+    #[serde(default)]
+    pub actor: Option<ActorId>,
+}
+
+/// User checked in at a location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationCheckedIn {
+    /// Location ID
+    pub location_id: Uuid,
+    /// User who checked in
+    pub user_id: Uuid,
+    /// When the user checked in
+    pub checked_in_at: DateTime<Utc>,
+    /// Number of users checked in after this check-in
+    pub occupancy: usize,
+}
+
+/// User checked out of a location, closing their visit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationCheckedOut {
+    /// Location ID
+    pub location_id: Uuid,
+    /// User who checked out
+    pub user_id: Uuid,
+    /// When the visit started
+    pub checked_in_at: DateTime<Utc>,
+    /// When the user checked out
+    pub checked_out_at: DateTime<Utc>,
+    /// Number of users checked in after this check-out
+    pub occupancy: usize,
+}
+
+/// A duplicate location was merged into this one
+///
+/// Re-parenting, metadata and archiving are carried by their own events; this
+/// records which locations were involved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationsMerged {
+    /// Location that absorbed the duplicate
+    pub location_id: Uuid,
+    /// Duplicate location that was merged and archived
+    pub merged_location_id: Uuid,
+    /// Children moved from the duplicate to this location
+    pub reparented_children: Vec<Uuid>,
+    /// Metadata copied from the duplicate
+    pub merged_metadata: HashMap<String, String>,
+    /// Reason for merging
+    pub reason: String,
+}
+
+/// Location deleted (hard delete tombstone)
+///
+/// Unlike [`LocationArchived`], this carries no name so that erased details
+/// do not linger in the event stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationDeleted {
+    /// Location ID that was deleted
+    pub location_id: Uuid,
+    /// Type of the deleted location
+    pub location_type: LocationType,
+    /// Reason for deletion
+    pub reason: String,
+    /// Whether descendant locations were deleted along with this one
+    pub cascade: bool,
+}
+
+/// Coordinates were resolved from a location's address by a geocoder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressGeocoded {
+    /// Location whose address was geocoded
+    pub location_id: Uuid,
+    /// Address that was geocoded
+    pub address: Address,
+    /// Coordinates resolved for the address
+    pub coordinates: GeoCoordinates,
+    /// Geocoder confidence in the result (0.0 to 1.0)
+    pub confidence_score: f64,
+    /// Name of the geocoding provider
+    pub provider: String,
+}
+
+/// The mailing, billing or other non-physical address of a location was set
+///
+/// Physical address changes are carried by `LocationUpdated`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationAddressSet {
+    /// Location whose address was set
+    pub location_id: Uuid,
+    /// Role of the address
+    pub role: AddressRole,
+    /// New address for the role
+    pub address: Address,
+    /// Address previously held for the role, if any
+    pub previous_address: Option<Address>,
+    /// Reason for the change
+    pub reason: String,
+}
+
+/// The building, floor and room of a location were set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndoorPositionSet {
+    /// Location placed inside the building
+    pub location_id: Uuid,
+    /// New indoor position
+    pub position: IndoorPosition,
+    /// Indoor position held before, if any
+    pub previous_position: Option<IndoorPosition>,
+    /// Reason for the change
+    pub reason: String,
+}
+
+/// A hierarchy reorganization finished, re-parenting several locations at once
+///
+/// The parent changes form a single unit; projections apply all of them
+/// together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HierarchyReorganized {
+    /// Location the reorganization was carried out for
+    pub location_id: Uuid,
+    /// Re-parented locations as `(location_id, old_parent, new_parent)`
+    pub affected: Vec<(Uuid, Option<Uuid>, Option<Uuid>)>,
+    /// Reason for the reorganization
+    pub reason: String,
+}
+
+/// Coordinates of a location were corrected or enriched in place
+///
+/// Unlike `LocationMoved`, the location itself did not move; for example an
+/// altitude was filled in from an elevation service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoordinatesUpdated {
+    /// The unique identifier of the location
+    pub location_id: Uuid,
+    /// Coordinates before the update
+    pub previous_coordinates: GeoCoordinates,
+    /// Coordinates after the update
+    pub coordinates: GeoCoordinates,
+    /// Reason for the update
+    pub reason: String,
+}
+
+/// Stored coordinates of a location were checked against its address
+///
+/// The coordinates were reverse geocoded and the locality and region found
+/// there compared with the address. The location itself is unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoordinatesValidated {
+    /// The unique identifier of the location
+    pub location_id: Uuid,
+    /// Address the coordinates were checked against
+    pub address: Address,
+    /// Stored coordinates that were checked
+    pub coordinates: GeoCoordinates,
+    /// Address the geocoder found at the coordinates
+    pub reverse_geocoded_address: Address,
+    /// Coordinates the geocoder resolved the address to
+    pub geocoded_coordinates: GeoCoordinates,
+    /// Distance in meters between the stored and geocoded coordinates
+    pub distance_meters: f64,
+    /// Whether the locality or region at the coordinates differs from the address
+    pub mismatch: bool,
+    /// Name of the geocoding provider
+    pub provider: String,
 }
 
 /// Base trait for location events
@@ -148,6 +389,56 @@ impl LocationEvent for LocationUpdated {
     }
 }
 
+impl DomainEvent for LocationMoved {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationMoved"
+    }
+}
+
+impl LocationMoved {
+    /// Create a move event, computing the distance between both positions
+    pub fn new(
+        location_id: Uuid,
+        previous_coordinates: GeoCoordinates,
+        coordinates: GeoCoordinates,
+    ) -> Self {
+        let distance_meters = previous_coordinates.distance_to(&coordinates);
+        Self {
+            location_id,
+            previous_coordinates,
+            coordinates,
+            distance_meters,
+            recorded_at: None,
+        }
+    }
+
+    /// Mark the move as a position fix observed at `recorded_at`
+    pub fn with_recorded_at(mut self, recorded_at: DateTime<Utc>) -> Self {
+        self.recorded_at = Some(recorded_at);
+        self
+    }
+
+    /// Coordinate-scoped subject for the new position
+    pub fn subject(&self) -> String {
+        LocationSubject::coordinate_event(
+            self.coordinates.latitude,
+            self.coordinates.longitude,
+            EventType::LocationMoved,
+            Some(LocationAggregate::Coordinates),
+        )
+        .to_subject()
+    }
+}
+
+impl LocationEvent for LocationMoved {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
 impl DomainEvent for ParentLocationSet {
     fn aggregate_id(&self) -> Uuid {
         self.location_id
@@ -163,70 +454,356 @@ impl ParentLocationSet {
     }
 }
 
-impl LocationEvent for ParentLocationSet {
+impl LocationEvent for ParentLocationSet {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for ParentLocationRemoved {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "ParentLocationRemoved"
+    }
+}
+
+impl ParentLocationRemoved {
+    pub fn subject(&self) -> String {
+        format!("location.{}.parent.removed", self.location_id)
+    }
+}
+
+impl LocationEvent for ParentLocationRemoved {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationMetadataAdded {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationMetadataAdded"
+    }
+}
+
+impl LocationMetadataAdded {
+    pub fn subject(&self) -> String {
+        format!("location.{}.metadata.added", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationMetadataAdded {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationMetadataUpdated {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationMetadataUpdated"
+    }
+}
+
+impl LocationMetadataUpdated {
+    pub fn subject(&self) -> String {
+        format!("location.{}.metadata.updated", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationMetadataUpdated {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationMetadataRemoved {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationMetadataRemoved"
+    }
+}
+
+impl LocationMetadataRemoved {
+    pub fn subject(&self) -> String {
+        format!("location.{}.metadata.removed", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationMetadataRemoved {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationArchived {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationArchived"
+    }
+}
+
+impl LocationArchived {
+    pub fn subject(&self) -> String {
+        format!("location.{}.archived", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationArchived {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationRestored {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationRestored"
+    }
+}
+
+impl LocationRestored {
+    pub fn subject(&self) -> String {
+        format!("location.{}.restored", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationRestored {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationCheckedIn {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationCheckedIn"
+    }
+}
+
+impl LocationCheckedIn {
+    /// User + location scoped subject
+    pub fn subject(&self) -> String {
+        LocationSubject::user_location_event(&self.user_id, &self.location_id, EventType::CheckedIn)
+            .to_subject()
+    }
+}
+
+impl LocationEvent for LocationCheckedIn {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationCheckedOut {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationCheckedOut"
+    }
+}
+
+impl LocationCheckedOut {
+    /// User + location scoped subject
+    pub fn subject(&self) -> String {
+        LocationSubject::user_location_event(
+            &self.user_id,
+            &self.location_id,
+            EventType::CheckedOut,
+        )
+        .to_subject()
+    }
+
+    /// How long the visit lasted
+    pub fn visit_duration(&self) -> chrono::Duration {
+        self.checked_out_at - self.checked_in_at
+    }
+}
+
+impl LocationEvent for LocationCheckedOut {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationsMerged {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationsMerged"
+    }
+}
+
+impl LocationsMerged {
+    pub fn subject(&self) -> String {
+        format!("location.{}.merged", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationsMerged {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationDeleted {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationDeleted"
+    }
+}
+
+impl LocationDeleted {
+    pub fn subject(&self) -> String {
+        format!("location.{}.deleted", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationDeleted {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for AddressGeocoded {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "AddressGeocoded"
+    }
+}
+
+impl AddressGeocoded {
+    pub fn subject(&self) -> String {
+        format!("location.{}.address_geocoded", self.location_id)
+    }
+}
+
+impl LocationEvent for AddressGeocoded {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for LocationAddressSet {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "LocationAddressSet"
+    }
+}
+
+impl LocationAddressSet {
+    pub fn subject(&self) -> String {
+        format!("location.{}.address_set", self.location_id)
+    }
+}
+
+impl LocationEvent for LocationAddressSet {
+    fn location_id(&self) -> Uuid {
+        self.location_id
+    }
+}
+
+impl DomainEvent for IndoorPositionSet {
+    fn aggregate_id(&self) -> Uuid {
+        self.location_id
+    }
+    fn event_type(&self) -> &'static str {
+        "IndoorPositionSet"
+    }
+}
+
+impl IndoorPositionSet {
+    pub fn subject(&self) -> String {
+        format!("location.{}.indoor_position_set", self.location_id)
+    }
+}
+
+impl LocationEvent for IndoorPositionSet {
     fn location_id(&self) -> Uuid {
         self.location_id
     }
 }
 
-impl DomainEvent for ParentLocationRemoved {
+impl DomainEvent for HierarchyReorganized {
     fn aggregate_id(&self) -> Uuid {
         self.location_id
     }
     fn event_type(&self) -> &'static str {
-        "ParentLocationRemoved"
+        "HierarchyReorganized"
     }
 }
 
-impl ParentLocationRemoved {
+impl HierarchyReorganized {
     pub fn subject(&self) -> String {
-        format!("location.{}.parent.removed", self.location_id)
+        format!("location.{}.hierarchy_reorganized", self.location_id)
     }
 }
 
-impl LocationEvent for ParentLocationRemoved {
+impl LocationEvent for HierarchyReorganized {
     fn location_id(&self) -> Uuid {
         self.location_id
     }
 }
 
-impl DomainEvent for LocationMetadataAdded {
+impl DomainEvent for CoordinatesUpdated {
     fn aggregate_id(&self) -> Uuid {
         self.location_id
     }
     fn event_type(&self) -> &'static str {
-        "LocationMetadataAdded"
+        "CoordinatesUpdated"
     }
 }
 
-impl LocationMetadataAdded {
+impl CoordinatesUpdated {
     pub fn subject(&self) -> String {
-        format!("location.{}.metadata.added", self.location_id)
+        format!("location.{}.coordinates_updated", self.location_id)
     }
 }
 
-impl LocationEvent for LocationMetadataAdded {
+impl LocationEvent for CoordinatesUpdated {
     fn location_id(&self) -> Uuid {
         self.location_id
     }
 }
 
-impl DomainEvent for LocationArchived {
+impl DomainEvent for CoordinatesValidated {
     fn aggregate_id(&self) -> Uuid {
         self.location_id
     }
     fn event_type(&self) -> &'static str {
-        "LocationArchived"
+        "CoordinatesValidated"
     }
 }
 
-impl LocationArchived {
+impl CoordinatesValidated {
     pub fn subject(&self) -> String {
-        format!("location.{}.archived", self.location_id)
+        format!("location.{}.coordinates_validated", self.location_id)
     }
 }
 
-impl LocationEvent for LocationArchived {
+impl LocationEvent for CoordinatesValidated {
     fn location_id(&self) -> Uuid {
         self.location_id
     }
@@ -260,9 +837,11 @@ mod tests {
             name: "Test Location".to_string(),
             location_type: LocationType::Physical,
             address: Some(address.clone()),
+            addresses: HashMap::new(),
             coordinates: None,
             virtual_location: None,
             parent_id: None,
+            actor: None,
         };
 
         // Test LocationEvent trait
@@ -318,6 +897,7 @@ mod tests {
             previous_virtual_location: None,
             virtual_location: None,
             reason: "Office relocation".to_string(),
+            actor: None,
         };
 
         assert_eq!(event.location_id(), location_id);
@@ -327,6 +907,127 @@ mod tests {
         assert_eq!(event.reason, "Office relocation");
     }
 
+    /// Test events recorded before actors were tracked
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Legacy JSON] --> B[Deserialize]
+    ///     B --> C[No Actor]
+    /// ```
+    #[test]
+    fn test_legacy_events_without_actor() {
+        let location_id = Uuid::now_v7();
+
+        let defined: LocationDefined = serde_json::from_value(serde_json::json!({
+            "location_id": location_id,
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null,
+        }))
+        .unwrap();
+        assert_eq!(defined.location_id, location_id);
+        assert_eq!(defined.actor, None);
+
+        let archived: LocationArchived = serde_json::from_value(serde_json::json!({
+            "location_id": location_id,
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "reason": "Closed",
+        }))
+        .unwrap();
+        assert_eq!(archived.actor, None);
+
+        let updated = LocationUpdated {
+            location_id,
+            previous_name: None,
+            name: Some("Depot".to_string()),
+            previous_address: None,
+            address: None,
+            previous_coordinates: None,
+            coordinates: None,
+            previous_virtual_location: None,
+            virtual_location: None,
+            reason: "Renamed".to_string(),
+            actor: Some(ActorId::user(Uuid::now_v7())),
+        };
+        let mut json = serde_json::to_value(&updated).unwrap();
+        json.as_object_mut().unwrap().remove("actor");
+        let legacy: LocationUpdated = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.name.as_deref(), Some("Depot"));
+        assert_eq!(legacy.actor, None);
+    }
+
+    #[test]
+    fn test_location_defined_addresses_are_optional_in_json() {
+        let location_id = Uuid::now_v7();
+        let legacy = serde_json::json!({
+            "location_id": location_id,
+            "name": "Warehouse",
+            "location_type": "Logical",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null,
+        });
+
+        let defined: LocationDefined = serde_json::from_value(legacy).unwrap();
+        assert!(defined.addresses.is_empty());
+        assert!(serde_json::to_value(&defined)
+            .unwrap()
+            .get("addresses")
+            .is_none());
+
+        let billing = Address::new(
+            "500 Market Street".to_string(),
+            "San Francisco".to_string(),
+            "CA".to_string(),
+            "USA".to_string(),
+            "94105".to_string(),
+        );
+        let with_billing = LocationDefined {
+            addresses: HashMap::from([(AddressRole::Billing, billing.clone())]),
+            ..defined
+        };
+        let json = serde_json::to_value(&with_billing).unwrap();
+        assert_eq!(json["addresses"]["Billing"]["postal_code"], "94105");
+
+        let round_trip: LocationDefined = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            round_trip.addresses.get(&AddressRole::Billing),
+            Some(&billing)
+        );
+    }
+
+    /// Test LocationMoved event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Previous Coords] --> C[Create Event]
+    ///     B[New Coords] --> C
+    ///     C --> D[Verify Distance]
+    ///     D --> E[Verify Coordinate Subject]
+    /// ```
+    #[test]
+    fn test_location_moved_event() {
+        let location_id = Uuid::now_v7();
+        let previous = GeoCoordinates::new(40.7128, -74.0060);
+        let coordinates = GeoCoordinates::new(34.0522, -118.2437);
+
+        let event = LocationMoved::new(location_id, previous.clone(), coordinates.clone());
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.event_type(), "LocationMoved");
+        assert_eq!(event.distance_meters, previous.distance_to(&coordinates));
+        assert!((event.distance_meters - 3_935_746.0).abs() < 1.0);
+        assert_eq!(
+            event.subject(),
+            "events.location.coordinates.34.052200.-118.243700.coordinates.location_moved"
+        );
+    }
+
     /// Test ParentLocationSet event
     ///
     /// ```mermaid
@@ -427,6 +1128,51 @@ mod tests {
         assert_eq!(event.current_metadata.len(), 3);
     }
 
+    /// Test LocationMetadataUpdated and LocationMetadataRemoved events
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Location] --> B[Overwrite Key]
+    ///     A --> C[Remove Key]
+    ///     B --> D[Current State]
+    ///     C --> D
+    /// ```
+    #[test]
+    fn test_location_metadata_updated_and_removed_events() {
+        let location_id = Uuid::now_v7();
+
+        let updated = LocationMetadataUpdated {
+            location_id,
+            previous_metadata: HashMap::from([("capacity".to_string(), "100".to_string())]),
+            updated_metadata: HashMap::from([("capacity".to_string(), "120".to_string())]),
+            current_metadata: HashMap::from([("capacity".to_string(), "120".to_string())]),
+            reason: "Expanded".to_string(),
+        };
+
+        assert_eq!(updated.aggregate_id(), location_id);
+        assert_eq!(updated.event_type(), "LocationMetadataUpdated");
+        assert_eq!(
+            updated.subject(),
+            format!("location.{location_id}.metadata.updated")
+        );
+
+        let removed = LocationMetadataRemoved {
+            location_id,
+            key: "capacity".to_string(),
+            previous_value: "120".to_string(),
+            current_metadata: HashMap::new(),
+            reason: "No longer tracked".to_string(),
+        };
+
+        assert_eq!(removed.location_id(), location_id);
+        assert_eq!(removed.event_type(), "LocationMetadataRemoved");
+        assert_eq!(
+            removed.subject(),
+            format!("location.{location_id}.metadata.removed")
+        );
+        assert!(removed.current_metadata.is_empty());
+    }
+
     /// Test LocationArchived event
     ///
     /// ```mermaid
@@ -444,6 +1190,7 @@ mod tests {
             name: "Old Office".to_string(),
             location_type: LocationType::Physical,
             reason: "Office closed permanently".to_string(),
+            actor: None,
         };
 
         assert_eq!(event.location_id(), location_id);
@@ -454,6 +1201,325 @@ mod tests {
         assert_eq!(event.location_type, LocationType::Physical);
     }
 
+    /// Test LocationRestored event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Archived Location] --> B[Restore]
+    ///     B --> C[Restore Event]
+    ///     C --> D[Active Again]
+    /// ```
+    #[test]
+    fn test_location_restored_event() {
+        let location_id = Uuid::now_v7();
+
+        let event = LocationRestored {
+            location_id,
+            name: "Old Office".to_string(),
+            location_type: LocationType::Physical,
+            reason: "Lease renewed".to_string(),
+            actor: None,
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "LocationRestored");
+        assert_eq!(event.subject(), format!("location.{location_id}.restored"));
+        assert_eq!(event.reason, "Lease renewed");
+    }
+
+    /// Test LocationCheckedIn and LocationCheckedOut events
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Check In] --> B[User Location Subject]
+    ///     C[Check Out] --> B
+    ///     C --> D[Visit Duration]
+    /// ```
+    #[test]
+    fn test_location_check_in_out_events() {
+        let location_id = Uuid::now_v7();
+        let user_id = Uuid::now_v7();
+        let checked_in_at = Utc::now();
+
+        let checked_in = LocationCheckedIn {
+            location_id,
+            user_id,
+            checked_in_at,
+            occupancy: 1,
+        };
+        assert_eq!(checked_in.aggregate_id(), location_id);
+        assert_eq!(checked_in.event_type(), "LocationCheckedIn");
+        assert_eq!(
+            checked_in.subject(),
+            format!("events.location.user.{user_id}.location.{location_id}.checked_in")
+        );
+
+        let checked_out = LocationCheckedOut {
+            location_id,
+            user_id,
+            checked_in_at,
+            checked_out_at: checked_in_at + chrono::Duration::minutes(45),
+            occupancy: 0,
+        };
+        assert_eq!(checked_out.location_id(), location_id);
+        assert_eq!(checked_out.event_type(), "LocationCheckedOut");
+        assert!(checked_out.subject().ends_with(".checked_out"));
+        assert_eq!(checked_out.visit_duration(), chrono::Duration::minutes(45));
+    }
+
+    /// Test LocationDeleted event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_location_deleted_event() {
+        let location_id = Uuid::now_v7();
+
+        let event = LocationDeleted {
+            location_id,
+            location_type: LocationType::Physical,
+            reason: "Erasure request".to_string(),
+            cascade: true,
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "LocationDeleted");
+        assert_eq!(event.subject(), format!("location.{location_id}.deleted"));
+        assert!(event.cascade);
+    }
+
+    /// Test LocationsMerged event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_locations_merged_event() {
+        let location_id = Uuid::now_v7();
+        let merged_location_id = Uuid::now_v7();
+
+        let event = LocationsMerged {
+            location_id,
+            merged_location_id,
+            reparented_children: vec![Uuid::now_v7()],
+            merged_metadata: HashMap::from([("badge_zone".to_string(), "blue".to_string())]),
+            reason: "Duplicate from import".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "LocationsMerged");
+        assert_eq!(event.subject(), format!("location.{location_id}.merged"));
+        assert_ne!(event.merged_location_id, location_id);
+    }
+
+    /// Test AddressGeocoded event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_address_geocoded_event() {
+        let location_id = Uuid::now_v7();
+
+        let event = AddressGeocoded {
+            location_id,
+            address: Address::new(
+                "123 Main St".to_string(),
+                "Springfield".to_string(),
+                "IL".to_string(),
+                "USA".to_string(),
+                "62701".to_string(),
+            ),
+            coordinates: GeoCoordinates::new(39.7817, -89.6501),
+            confidence_score: 0.95,
+            provider: "mock".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "AddressGeocoded");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.address_geocoded")
+        );
+    }
+
+    /// Test LocationAddressSet event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_location_address_set_event() {
+        let location_id = Uuid::now_v7();
+
+        let event = LocationAddressSet {
+            location_id,
+            role: AddressRole::Billing,
+            address: Address::new(
+                "PO Box 12".to_string(),
+                "Springfield".to_string(),
+                "IL".to_string(),
+                "USA".to_string(),
+                "62701".to_string(),
+            ),
+            previous_address: None,
+            reason: "New accounts office".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "LocationAddressSet");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.address_set")
+        );
+    }
+
+    /// Test IndoorPositionSet event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_indoor_position_set_event() {
+        let location_id = Uuid::now_v7();
+
+        let event = IndoorPositionSet {
+            location_id,
+            position: IndoorPosition::new("HQ", 5).with_room("501"),
+            previous_position: Some(IndoorPosition::new("HQ", 4)),
+            reason: "Moved upstairs".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "IndoorPositionSet");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.indoor_position_set")
+        );
+    }
+
+    /// Test HierarchyReorganized event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_hierarchy_reorganized_event() {
+        let location_id = Uuid::now_v7();
+        let child = Uuid::now_v7();
+
+        let event = HierarchyReorganized {
+            location_id,
+            affected: vec![(child, None, Some(location_id))],
+            reason: "Regional restructure".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "HierarchyReorganized");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.hierarchy_reorganized")
+        );
+    }
+
+    /// Test CoordinatesUpdated event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    /// ```
+    #[test]
+    fn test_coordinates_updated_event() {
+        let location_id = Uuid::now_v7();
+        let previous = GeoCoordinates::new(46.5, 7.9);
+
+        let event = CoordinatesUpdated {
+            location_id,
+            previous_coordinates: previous.clone(),
+            coordinates: previous.with_altitude(1200.0),
+            reason: "Altitude enrichment".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "CoordinatesUpdated");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.coordinates_updated")
+        );
+    }
+
+    /// Test CoordinatesValidated event
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[Create Event] --> B[Verify Fields]
+    ///     B --> C[Test Subject]
+    ///     C --> D[Test Serialization]
+    /// ```
+    #[test]
+    fn test_coordinates_validated_event() {
+        let location_id = Uuid::now_v7();
+        let address = Address::new(
+            "1 Market St".to_string(),
+            "San Francisco".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            "94105".to_string(),
+        );
+
+        let event = CoordinatesValidated {
+            location_id,
+            address: address.clone(),
+            coordinates: GeoCoordinates::new(34.0522, -118.2437),
+            reverse_geocoded_address: Address::new(
+                String::new(),
+                "Los Angeles".to_string(),
+                "CA".to_string(),
+                "US".to_string(),
+                String::new(),
+            ),
+            geocoded_coordinates: GeoCoordinates::new(37.7946, -122.3950),
+            distance_meters: 559_000.0,
+            mismatch: true,
+            provider: "TestProvider".to_string(),
+        };
+
+        assert_eq!(event.location_id(), location_id);
+        assert_eq!(event.aggregate_id(), location_id);
+        assert_eq!(event.event_type(), "CoordinatesValidated");
+        assert_eq!(
+            event.subject(),
+            format!("location.{location_id}.coordinates_validated")
+        );
+
+        let json = serde_json::to_string(&event).unwrap();
+        let deserialized: CoordinatesValidated = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.address, address);
+        assert!(deserialized.mismatch);
+    }
+
     /// Test event serialization round-trip
     ///
     /// ```mermaid
@@ -473,9 +1539,11 @@ mod tests {
             name: "Test Location".to_string(),
             location_type: LocationType::Physical,
             address: None,
+            addresses: HashMap::new(),
             coordinates: Some(coords.clone()),
             virtual_location: None,
             parent_id: Some(Uuid::now_v7()),
+            actor: None,
         };
 
         // Serialize to JSON
@@ -514,9 +1582,11 @@ mod tests {
             name: "Community Voice Channel".to_string(),
             location_type: LocationType::Virtual,
             address: None,
+            addresses: HashMap::new(),
             coordinates: None,
             virtual_location: Some(virtual_loc.clone()),
             parent_id: None,
+            actor: None,
         };
 
         assert_eq!(event.location_type, LocationType::Virtual);
@@ -1,5 +1,7 @@
 //! Location events
 
 mod events;
+mod upcasting;
 
 pub use events::*;
+pub use upcasting::*;
@@ -0,0 +1,277 @@
+//! Schema-versioned event upcasting
+//!
+//! Stored events carry the schema version they were written with
+//! (`EventMetadata::schema_version`). When an event struct evolves, an upcaster
+//! registered for `(event_type, version)` rewrites the raw JSON payload into the
+//! next version's shape. Upcasters chain until no further migration is registered,
+//! after which the payload is deserialized into the current event struct.
+
+use crate::domain_events::LocationDomainEvent;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Migrates a raw event payload from one schema version to the next
+pub trait EventUpcaster: Send + Sync {
+    /// Schema version produced by this upcaster
+    fn target_version(&self) -> &str;
+
+    /// Rewrite the payload into the target version's shape
+    fn upcast(&self, payload: Value) -> Result<Value, UpcastError>;
+}
+
+/// Upcaster backed by a plain migration function
+struct FnUpcaster<F> {
+    target_version: String,
+    migrate: F,
+}
+
+impl<F> EventUpcaster for FnUpcaster<F>
+where
+    F: Fn(Value) -> Result<Value, UpcastError> + Send + Sync,
+{
+    fn target_version(&self) -> &str {
+        &self.target_version
+    }
+
+    fn upcast(&self, payload: Value) -> Result<Value, UpcastError> {
+        (self.migrate)(payload)
+    }
+}
+
+/// Registry of upcasters keyed by `(event_type, schema_version)`
+#[derive(Default)]
+pub struct UpcasterRegistry {
+    upcasters: HashMap<(String, String), Box<dyn EventUpcaster>>,
+}
+
+impl UpcasterRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an upcaster for payloads of `event_type` written at `from_version`
+    pub fn register(
+        &mut self,
+        event_type: impl Into<String>,
+        from_version: impl Into<String>,
+        upcaster: impl EventUpcaster + 'static,
+    ) -> &mut Self {
+        self.upcasters
+            .insert((event_type.into(), from_version.into()), Box::new(upcaster));
+        self
+    }
+
+    /// Register a migration function producing `to_version` from `from_version`
+    pub fn register_fn<F>(
+        &mut self,
+        event_type: impl Into<String>,
+        from_version: impl Into<String>,
+        to_version: impl Into<String>,
+        migrate: F,
+    ) -> &mut Self
+    where
+        F: Fn(Value) -> Result<Value, UpcastError> + Send + Sync + 'static,
+    {
+        self.register(
+            event_type,
+            from_version,
+            FnUpcaster {
+                target_version: to_version.into(),
+                migrate,
+            },
+        )
+    }
+
+    /// Migrate a payload to the latest registered version
+    ///
+    /// Returns the migrated payload together with the version it now conforms to.
+    /// Payloads with no registered upcaster are returned unchanged.
+    pub fn upcast(
+        &self,
+        event_type: &str,
+        schema_version: &str,
+        payload: Value,
+    ) -> Result<(String, Value), UpcastError> {
+        let mut version = schema_version.to_string();
+        let mut payload = payload;
+        let mut seen = HashSet::new();
+
+        while let Some(upcaster) = self
+            .upcasters
+            .get(&(event_type.to_string(), version.clone()))
+        {
+            if !seen.insert(version.clone()) {
+                return Err(UpcastError::VersionCycle {
+                    event_type: event_type.to_string(),
+                    version,
+                });
+            }
+
+            payload = upcaster.upcast(payload)?;
+            version = upcaster.target_version().to_string();
+        }
+
+        Ok((version, payload))
+    }
+
+    /// Migrate a stored payload and deserialize it into the current event shape
+    ///
+    /// `event_type` is the `DomainEvent::event_type` name, e.g. `"LocationDefined"`.
+    pub fn deserialize(
+        &self,
+        event_type: &str,
+        schema_version: &str,
+        payload: Value,
+    ) -> Result<LocationDomainEvent, UpcastError> {
+        let (_, payload) = self.upcast(event_type, schema_version, payload)?;
+
+        let mut tagged = Map::new();
+        tagged.insert(event_type.to_string(), payload);
+
+        serde_json::from_value(Value::Object(tagged))
+            .map_err(|e| UpcastError::Deserialization(e.to_string()))
+    }
+}
+
+/// Insert `field` with `default` when an older payload lacks it
+pub fn default_field(
+    mut payload: Value,
+    field: &str,
+    default: Value,
+) -> Result<Value, UpcastError> {
+    let object = payload
+        .as_object_mut()
+        .ok_or_else(|| UpcastError::InvalidPayload("expected a JSON object".to_string()))?;
+
+    object.entry(field).or_insert(default);
+    Ok(payload)
+}
+
+/// Errors raised while upcasting stored events
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum UpcastError {
+    #[error("Invalid event payload: {0}")]
+    InvalidPayload(String),
+
+    #[error("Upcasters for {event_type} loop back to version {version}")]
+    VersionCycle { event_type: String, version: String },
+
+    #[error("Failed to deserialize upcasted event: {0}")]
+    Deserialization(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::LocationType;
+    use cim_domain::DomainEvent;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    /// Simulates `LocationDefined` gaining a required `location_type` in 1.1
+    fn registry() -> UpcasterRegistry {
+        let mut registry = UpcasterRegistry::new();
+        registry.register_fn("LocationDefined", "1.0", "1.1", |payload| {
+            default_field(payload, "location_type", json!("Physical"))
+        });
+        registry
+    }
+
+    fn v1_location_defined(location_id: Uuid) -> Value {
+        json!({
+            "location_id": location_id,
+            "name": "Legacy HQ",
+            "address": null,
+            "coordinates": null,
+            "virtual_location": null,
+            "parent_id": null
+        })
+    }
+
+    /// Test upcasting a 1.0 LocationDefined payload
+    ///
+    /// ```mermaid
+    /// graph TD
+    ///     A[1.0 Payload] --> B[Registry Lookup]
+    ///     B --> C[Default Missing Field]
+    ///     C --> D[Deserialize Current Struct]
+    /// ```
+    #[test]
+    fn test_upcast_location_defined_defaults_new_field() {
+        let location_id = Uuid::new_v4();
+        let payload = v1_location_defined(location_id);
+
+        // Without upcasting the old payload no longer fits the struct
+        assert!(UpcasterRegistry::new()
+            .deserialize("LocationDefined", "1.0", payload.clone())
+            .is_err());
+
+        let event = registry()
+            .deserialize("LocationDefined", "1.0", payload)
+            .unwrap();
+
+        assert_eq!(event.event_type(), "LocationDefined");
+        match event {
+            LocationDomainEvent::LocationDefined(defined) => {
+                assert_eq!(defined.location_id, location_id);
+                assert_eq!(defined.name, "Legacy HQ");
+                assert_eq!(defined.location_type, LocationType::Physical);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    /// Test chaining upcasters across versions
+    ///
+    /// ```mermaid
+    /// graph LR
+    ///     A[1.0] --> B[1.1]
+    ///     B --> C[1.2]
+    /// ```
+    #[test]
+    fn test_upcast_chains_versions() {
+        let mut registry = registry();
+        registry.register_fn("LocationDefined", "1.1", "1.2", |payload| {
+            default_field(payload, "name", json!("Unnamed"))
+        });
+
+        let (version, payload) = registry
+            .upcast(
+                "LocationDefined",
+                "1.0",
+                json!({ "location_type": "Virtual" }),
+            )
+            .unwrap();
+
+        assert_eq!(version, "1.2");
+        assert_eq!(payload["location_type"], "Virtual");
+        assert_eq!(payload["name"], "Unnamed");
+
+        // Current payloads pass through untouched
+        let (version, _) = registry
+            .upcast("LocationDefined", "1.2", json!({}))
+            .unwrap();
+        assert_eq!(version, "1.2");
+    }
+
+    #[test]
+    fn test_upcast_rejects_version_cycle() {
+        let mut registry = UpcasterRegistry::new();
+        registry
+            .register_fn("LocationUpdated", "1.0", "1.1", Ok)
+            .register_fn("LocationUpdated", "1.1", "1.0", Ok);
+
+        let result = registry.upcast("LocationUpdated", "1.0", json!({}));
+
+        assert!(matches!(result, Err(UpcastError::VersionCycle { .. })));
+    }
+
+    #[test]
+    fn test_default_field_requires_object() {
+        assert!(matches!(
+            default_field(json!([]), "name", json!("x")),
+            Err(UpcastError::InvalidPayload(_))
+        ));
+    }
+}
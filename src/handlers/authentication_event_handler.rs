@@ -7,6 +7,7 @@ use crate::aggregate::{Location, LocationMarker};
 use crate::value_objects::{GeoCoordinates, VirtualLocation};
 use cim_domain::{AggregateRepository, DomainEvent, DomainResult, EntityId};
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use uuid::Uuid;
 
 /// Location validation requested event from Policy domain
@@ -107,6 +108,53 @@ pub struct NetworkRange {
     pub location_type: LocationType,
 }
 
+impl NetworkRange {
+    /// Check whether `ip` falls inside this range's CIDR block
+    ///
+    /// IPv4 and IPv6 blocks only match addresses of the same family. A
+    /// malformed CIDR matches nothing.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let Some((network, prefix_len)) = parse_cidr(&self.cidr) else {
+            return false;
+        };
+
+        match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parse `address/prefix` notation, rejecting prefixes too long for the family
+fn parse_cidr(cidr: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix_len) = cidr.trim().split_once('/')?;
+    let address: IpAddr = address.parse().ok()?;
+    let prefix_len: u8 = prefix_len.parse().ok()?;
+
+    let max_len = if address.is_ipv4() { 32 } else { 128 };
+    (prefix_len <= max_len).then_some((address, prefix_len))
+}
+
+/// Private address space not covered by a trusted network
+fn is_private_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_private(),
+        // Unique local addresses, fc00::/7
+        IpAddr::V6(ip) => ip.segments()[0] & 0xfe00 == 0xfc00,
+    }
+}
+
 /// Geographic restriction
 #[derive(Debug, Clone)]
 pub struct GeoRestriction {
@@ -211,18 +259,25 @@ impl<L: AggregateRepository<Location>> AuthenticationEventHandler<L> {
         ip: &str,
         risk_indicators: &mut Vec<RiskIndicator>,
     ) -> (bool, LocationType) {
-        // Simple implementation - in real system would use proper IP range checking
-        for network in &self.trusted_networks {
-            if network.name.contains("corporate") && ip.starts_with("10.") {
-                return (true, LocationType::Corporate);
-            }
-            if network.name.contains("vpn") && ip.starts_with("172.") {
-                return (true, LocationType::VPN);
-            }
+        let Ok(address) = ip.trim().parse::<IpAddr>() else {
+            risk_indicators.push(RiskIndicator {
+                indicator_type: "invalid_ip".to_string(),
+                risk_level: RiskLevel::Medium,
+                description: format!("Malformed IP address: {ip}"),
+            });
+            return (false, LocationType::Unknown);
+        };
+
+        if let Some(network) = self
+            .trusted_networks
+            .iter()
+            .find(|network| network.contains(&address))
+        {
+            return (true, network.location_type.clone());
         }
 
-        // Check for suspicious IPs
-        if ip.starts_with("192.168.") {
+        // Private addresses outside trusted ranges are most likely home networks
+        if is_private_address(&address) {
             return (false, LocationType::Home);
         }
 
@@ -332,15 +387,15 @@ impl<L: AggregateRepository<Location>> AuthenticationEventHandler<L> {
 
         // Add metadata based on context
         if let Some(ip) = &context.ip_address {
-            location.add_metadata("ip_address".to_string(), ip.clone());
+            location.add_metadata("ip_address".to_string(), ip.clone())?;
         }
 
         if let Some(network_type) = &context.network_type {
-            location.add_metadata("network_type".to_string(), network_type.clone());
+            location.add_metadata("network_type".to_string(), network_type.clone())?;
         }
 
         if let Some(device_id) = &context.device_id {
-            location.add_metadata("device_id".to_string(), device_id.clone());
+            location.add_metadata("device_id".to_string(), device_id.clone())?;
         }
 
         // Save the location
@@ -418,4 +473,107 @@ mod tests {
         // We can't downcast Box<dyn DomainEvent> directly, so we'll just verify the event type
         // In a real implementation, we'd use an enum or other pattern for event handling
     }
+
+    fn range(cidr: &str) -> NetworkRange {
+        NetworkRange {
+            name: cidr.to_string(),
+            cidr: cidr.to_string(),
+            location_type: LocationType::Corporate,
+        }
+    }
+
+    fn contains(cidr: &str, ip: &str) -> bool {
+        range(cidr).contains(&ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_cidr_ipv4_boundaries() {
+        assert!(contains("10.0.0.0/8", "10.0.0.0"));
+        assert!(contains("10.0.0.0/8", "10.5.3.2"));
+        assert!(contains("10.0.0.0/8", "10.255.255.255"));
+        assert!(!contains("10.0.0.0/8", "9.255.255.255"));
+        assert!(!contains("10.0.0.0/8", "11.0.0.1"));
+        assert!(!contains("10.0.0.0/8", "104.16.0.1"));
+
+        assert!(contains("172.16.0.0/12", "172.31.255.255"));
+        assert!(!contains("172.16.0.0/12", "172.32.0.0"));
+        assert!(!contains("172.16.0.0/12", "172.15.255.255"));
+
+        assert!(contains("192.0.2.128/25", "192.0.2.128"));
+        assert!(contains("192.0.2.128/25", "192.0.2.255"));
+        assert!(!contains("192.0.2.128/25", "192.0.2.127"));
+
+        assert!(contains("203.0.113.7/32", "203.0.113.7"));
+        assert!(!contains("203.0.113.7/32", "203.0.113.8"));
+        assert!(contains("0.0.0.0/0", "8.8.8.8"));
+    }
+
+    #[test]
+    fn test_cidr_ipv6_and_malformed_ranges() {
+        assert!(contains("2001:db8::/32", "2001:db8::1"));
+        assert!(contains(
+            "2001:db8::/32",
+            "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff"
+        ));
+        assert!(!contains("2001:db8::/32", "2001:db9::"));
+        assert!(contains("fd00::/8", "fd12:3456::1"));
+
+        // Address families never match each other
+        assert!(!contains("::/0", "10.0.0.1"));
+        assert!(!contains("0.0.0.0/0", "::1"));
+
+        assert!(!contains("10.0.0.0/33", "10.0.0.1"));
+        assert!(!contains("10.0.0.0", "10.0.0.1"));
+        assert!(!contains("not-a-network/8", "10.0.0.1"));
+    }
+
+    #[test]
+    fn test_validate_ip_address_against_trusted_networks() {
+        let handler = AuthenticationEventHandler::new(
+            InMemoryRepository::<Location>::new(),
+            vec![
+                NetworkRange {
+                    name: "corporate".to_string(),
+                    cidr: "10.0.0.0/8".to_string(),
+                    location_type: LocationType::Corporate,
+                },
+                NetworkRange {
+                    name: "remote access".to_string(),
+                    cidr: "2001:db8:100::/48".to_string(),
+                    location_type: LocationType::VPN,
+                },
+            ],
+            vec![],
+        );
+
+        let mut indicators = Vec::new();
+        assert_eq!(
+            handler.validate_ip_address("10.5.3.2", &mut indicators),
+            (true, LocationType::Corporate)
+        );
+        assert_eq!(
+            handler.validate_ip_address("2001:db8:100:7::1", &mut indicators),
+            (true, LocationType::VPN)
+        );
+        assert!(indicators.is_empty());
+
+        assert_eq!(
+            handler.validate_ip_address("192.168.1.20", &mut indicators),
+            (false, LocationType::Home)
+        );
+
+        // Outside every configured range
+        assert_eq!(
+            handler.validate_ip_address("104.16.0.1", &mut indicators),
+            (false, LocationType::Public)
+        );
+        assert_eq!(indicators.len(), 1);
+        assert_eq!(indicators[0].indicator_type, "untrusted_network");
+
+        assert_eq!(
+            handler.validate_ip_address("10.0.0.999", &mut indicators),
+            (false, LocationType::Unknown)
+        );
+        assert_eq!(indicators[1].indicator_type, "invalid_ip");
+    }
 }
@@ -0,0 +1,140 @@
+//! Deduplication of redelivered commands
+//!
+//! NATS delivers commands at least once, so a retried request can reach the
+//! handler twice. The handler remembers the acknowledgment of every command
+//! it processed for a while and answers repeats from memory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Default time a processed command is remembered
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(60);
+
+/// Default number of processed commands remembered
+pub const DEFAULT_IDEMPOTENCY_CAPACITY: usize = 10_000;
+
+struct Entry<V> {
+    value: V,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+/// Bounded least-recently-used map from idempotency keys to results
+///
+/// Entries expire `ttl` after they were stored. Once `capacity` is reached
+/// the least recently used entry is evicted.
+pub struct IdempotencyCache<V> {
+    entries: HashMap<String, Entry<V>>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl<V: Clone> IdempotencyCache<V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            ttl,
+            capacity,
+        }
+    }
+
+    /// Result stored for the key, if it has not expired
+    pub fn get(&mut self, key: &str) -> Option<V> {
+        let entry = self.entries.get_mut(key)?;
+        if entry.stored_at.elapsed() >= self.ttl {
+            self.remove(key);
+            return None;
+        }
+
+        self.clock += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.clock, key.to_string());
+        entry.last_used = self.clock;
+        Some(entry.value.clone())
+    }
+
+    /// Remember the result for the key, evicting the least recently used entry if full
+    pub fn insert(&mut self, key: impl Into<String>, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let key = key.into();
+        self.remove(&key);
+        while self.entries.len() >= self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                stored_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+impl<V: Clone> Default for IdempotencyCache<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL, DEFAULT_IDEMPOTENCY_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = IdempotencyCache::new(DEFAULT_IDEMPOTENCY_TTL, 2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+
+        // Touching "a" makes "b" the eviction candidate
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c", 3);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let mut cache = IdempotencyCache::new(Duration::from_millis(20), 10);
+        cache.insert("a", 1);
+        assert_eq!(cache.get("a"), Some(1));
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.is_empty());
+    }
+}
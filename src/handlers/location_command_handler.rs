@@ -1,14 +1,29 @@
 //! Location command handler
 
+use super::idempotency::IdempotencyCache;
 use crate::aggregate::Location;
-use crate::value_objects::{GeoCoordinates, LocationType};
+use crate::nats::ActorId;
+use crate::projections::LocationReadModel;
+use crate::services::{GeocodeResult, GeocodingError, GeocodingService};
+use crate::value_objects::{Address, AddressRole, GeoCoordinates, LocationType};
 use crate::LocationDomainEvent;
-use crate::{DefineLocation, LocationDefined};
+use crate::{
+    AddLocationMetadata, AddressGeocoded, BatchCommand, BatchCommandResult, CoordinatesValidated,
+    DefineLocation, DeleteLocation, IndoorPositionSet, LocationAddressSet, LocationArchived,
+    LocationDefined, LocationDeleted, LocationMetadataAdded, LocationMetadataUpdated,
+    LocationMoved, LocationUpdated, LocationsMerged, MergeLocations, ParentLocationSet,
+    SetIndoorPosition, SetLocationAddress, TagLocationsInRegion, UpdateLocation,
+    ValidateCoordinates,
+};
 use cim_domain::{
-    AggregateRepository, CommandAcknowledgment, CommandEnvelope, CommandHandler, CommandStatus,
-    CorrelationId, EntityId,
+    AggregateRepository, AggregateRoot, Command, CommandAcknowledgment, CommandEnvelope,
+    CommandHandler, CommandStatus, CorrelationId, DomainError, DomainResult, EntityId, IdType,
 };
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
 
 /// Event publisher trait for location domain
 pub trait EventPublisher: Send + Sync {
@@ -20,10 +35,16 @@ pub trait EventPublisher: Send + Sync {
     ) -> Result<(), String>;
 }
 
+/// Default minimum confidence for a geocode result to be attached to a location
+pub const DEFAULT_MIN_GEOCODE_CONFIDENCE: f64 = 0.8;
+
 /// Handles location-related commands
 pub struct LocationCommandHandler<R: AggregateRepository<Location>> {
     repository: Arc<R>,
     event_publisher: Arc<dyn EventPublisher>,
+    geocoder: Option<Arc<dyn GeocodingService>>,
+    min_geocode_confidence: f64,
+    processed: IdempotencyCache<CommandAcknowledgment>,
 }
 
 impl<R: AggregateRepository<Location>> LocationCommandHandler<R> {
@@ -32,177 +53,2122 @@ impl<R: AggregateRepository<Location>> LocationCommandHandler<R> {
         Self {
             repository,
             event_publisher,
+            geocoder: None,
+            min_geocode_confidence: DEFAULT_MIN_GEOCODE_CONFIDENCE,
+            processed: IdempotencyCache::default(),
         }
     }
-}
 
-impl<R: AggregateRepository<Location>> CommandHandler<DefineLocation>
-    for LocationCommandHandler<R>
-{
-    fn handle(&mut self, envelope: CommandEnvelope<DefineLocation>) -> CommandAcknowledgment {
-        let cmd = &envelope.command;
+    /// Remember up to `capacity` processed commands for `ttl`
+    ///
+    /// A command redelivered within that window gets its original
+    /// acknowledgment back without being handled again. Only accepted commands
+    /// are remembered, so a rejected command can be retried once the cause of
+    /// the rejection is fixed.
+    pub fn with_idempotency(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.processed = IdempotencyCache::new(ttl, capacity);
+        self
+    }
+
+    /// Handle a command under a client-supplied idempotency key
+    ///
+    /// Repeats of an accepted key within the idempotency window get the
+    /// original acknowledgment, even if they arrive in a new envelope.
+    pub fn handle_idempotent<C: Command>(
+        &mut self,
+        envelope: CommandEnvelope<C>,
+        idempotency_key: &str,
+    ) -> CommandAcknowledgment
+    where
+        Self: CommandHandler<C>,
+    {
+        let key = format!("key:{idempotency_key}");
+        if let Some(ack) = self.processed.get(&key) {
+            return ack;
+        }
+
+        let ack = <Self as CommandHandler<C>>::handle(self, envelope);
+        self.remember(key, &ack);
+        ack
+    }
+
+    /// Geocode physical locations that are defined with an address only
+    ///
+    /// Results with a confidence score below `min_confidence` are discarded and
+    /// the location is defined without coordinates.
+    pub fn with_geocoder(
+        mut self,
+        geocoder: Arc<dyn GeocodingService>,
+        min_confidence: f64,
+    ) -> Self {
+        self.geocoder = Some(geocoder);
+        self.min_geocode_confidence = min_confidence;
+        self
+    }
+
+    /// Create and persist a new location, returning the resulting events
+    ///
+    /// An `AddressGeocoded` event follows the `LocationDefined` event when a
+    /// physical location without coordinates had its address geocoded.
+    fn define_location(&self, cmd: &DefineLocation) -> DomainResult<Vec<LocationDomainEvent>> {
         let location_id = EntityId::from_uuid(cmd.location_id);
 
         // Check if location already exists
-        match self.repository.load(location_id) {
-            Ok(Some(_)) => CommandAcknowledgment {
-                command_id: envelope.id,
-                correlation_id: envelope.identity.correlation_id.clone(),
-                status: CommandStatus::Rejected,
-                reason: Some("Location already exists".to_string()),
+        let existing = self
+            .repository
+            .load(location_id)
+            .map_err(|e| DomainError::InternalError(format!("Repository error: {e}")))?;
+        if existing.is_some() {
+            return Err(DomainError::ValidationError(format!(
+                "Location {} already exists",
+                cmd.location_id
+            )));
+        }
+
+        // Resolve coordinates for physical locations defined by address alone
+        let geocoded = match (&cmd.location_type, &cmd.address, &cmd.coordinates) {
+            (LocationType::Physical, Some(address), None) => self.geocode(address),
+            _ => None,
+        };
+
+        // Create new location based on type
+        let mut location = match &cmd.location_type {
+            LocationType::Physical => match (&cmd.address, &cmd.coordinates) {
+                (Some(address), Some(coords)) => Location::new_physical_full(
+                    location_id,
+                    cmd.name.clone(),
+                    address.clone(),
+                    coords.clone(),
+                )?,
+                (Some(address), None) => match &geocoded {
+                    Some(result) => Location::new_physical_full(
+                        location_id,
+                        cmd.name.clone(),
+                        address.clone(),
+                        result.coordinates.clone(),
+                    )?,
+                    None => Location::new_physical(location_id, cmd.name.clone(), address.clone())?,
+                },
+                (None, Some(coords)) => {
+                    Location::new_from_coordinates(location_id, cmd.name.clone(), coords.clone())?
+                }
+                (None, None) => {
+                    return Err(DomainError::ValidationError(
+                        "Physical location requires either address or coordinates".to_string(),
+                    ));
+                }
             },
-            Ok(None) => {
-                // Create new location based on type
-                let location = match &cmd.location_type {
-                    LocationType::Physical => {
-                        if let Some(address) = &cmd.address {
-                            match Location::new_physical(
-                                location_id,
-                                cmd.name.clone(),
-                                address.clone(),
-                            ) {
-                                Ok(mut loc) => {
-                                    // Add coordinates if provided
-                                    if let Some(coords) = &cmd.coordinates {
-                                        if let Err(e) = loc.set_coordinates(coords.clone()) {
-                                            return CommandAcknowledgment {
-                                                command_id: envelope.id,
-                                                correlation_id: envelope
-                                                    .identity
-                                                    .correlation_id
-                                                    .clone(),
-                                                status: CommandStatus::Rejected,
-                                                reason: Some(format!("Invalid coordinates: {e}")),
-                                            };
-                                        }
-                                    }
-                                    loc
-                                }
-                                Err(e) => {
-                                    return CommandAcknowledgment {
-                                        command_id: envelope.id,
-                                        correlation_id: envelope.identity.correlation_id.clone(),
-                                        status: CommandStatus::Rejected,
-                                        reason: Some(format!("Failed to create location: {e}")),
-                                    };
-                                }
-                            }
-                        } else if let Some(coords) = &cmd.coordinates {
-                            match Location::new_from_coordinates(
-                                location_id,
-                                cmd.name.clone(),
-                                coords.clone(),
-                            ) {
-                                Ok(loc) => loc,
-                                Err(e) => {
-                                    return CommandAcknowledgment {
-                                        command_id: envelope.id,
-                                        correlation_id: envelope.identity.correlation_id.clone(),
-                                        status: CommandStatus::Rejected,
-                                        reason: Some(format!("Failed to create location: {e}")),
-                                    };
-                                }
-                            }
-                        } else {
-                            return CommandAcknowledgment {
-                                command_id: envelope.id,
-                                correlation_id: envelope.identity.correlation_id.clone(),
-                                status: CommandStatus::Rejected,
-                                reason: Some(
-                                    "Physical location requires either address or coordinates"
-                                        .to_string(),
-                                ),
-                            };
-                        }
-                    }
-                    LocationType::Virtual => {
-                        if let Some(virtual_loc) = &cmd.virtual_location {
-                            match Location::new_virtual(
-                                location_id,
-                                cmd.name.clone(),
-                                virtual_loc.clone(),
-                            ) {
-                                Ok(loc) => loc,
-                                Err(e) => {
-                                    return CommandAcknowledgment {
-                                        command_id: envelope.id,
-                                        correlation_id: envelope.identity.correlation_id.clone(),
-                                        status: CommandStatus::Rejected,
-                                        reason: Some(format!(
-                                            "Failed to create virtual location: {e}"
-                                        )),
-                                    };
-                                }
-                            }
-                        } else {
-                            return CommandAcknowledgment {
-                                command_id: envelope.id,
-                                correlation_id: envelope.identity.correlation_id.clone(),
-                                status: CommandStatus::Rejected,
-                                reason: Some(
-                                    "Virtual location requires virtual location details"
-                                        .to_string(),
-                                ),
-                            };
-                        }
-                    }
-                    _ => {
-                        // For Logical and Hybrid types, create a basic location
-                        let mut loc = Location::new_from_coordinates(
-                            location_id,
-                            cmd.name.clone(),
-                            GeoCoordinates::new(0.0, 0.0), // Default coordinates
-                        )
-                        .unwrap();
-                        loc.location_type = cmd.location_type.clone();
-                        loc
-                    }
-                };
+            LocationType::Virtual => {
+                let virtual_loc = cmd.virtual_location.as_ref().ok_or_else(|| {
+                    DomainError::ValidationError(
+                        "Virtual location requires virtual location details".to_string(),
+                    )
+                })?;
+                Location::new_virtual(location_id, cmd.name.clone(), virtual_loc.clone())?
+            }
+            LocationType::Logical => Location::new_logical(location_id, cmd.name.clone())?,
+            LocationType::Mobile => {
+                let coords = cmd.coordinates.as_ref().ok_or_else(|| {
+                    DomainError::ValidationError(
+                        "Mobile location requires initial coordinates".to_string(),
+                    )
+                })?;
+                Location::new_mobile(location_id, cmd.name.clone(), coords.clone())?
+            }
+            _ => {
+                // For Hybrid types, create a basic location
+                let mut loc = Location::new_from_coordinates(
+                    location_id,
+                    cmd.name.clone(),
+                    GeoCoordinates::new(0.0, 0.0), // Default coordinates
+                )?;
+                loc.location_type = cmd.location_type.clone();
+                loc
+            }
+        };
+
+        if let Some(parent_id) = cmd.parent_id {
+            location.set_parent(EntityId::from_uuid(parent_id))?;
+        }
+
+        for (role, address) in &cmd.addresses {
+            if *role == AddressRole::Physical {
+                return Err(DomainError::ValidationError(
+                    "The physical address is given as address".to_string(),
+                ));
+            }
+            location.set_address_for(*role, address.clone())?;
+        }
+
+        // Save location
+        self.repository
+            .save(&location)
+            .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+
+        let mut events = vec![LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id: cmd.location_id,
+            name: cmd.name.clone(),
+            location_type: cmd.location_type.clone(),
+            address: cmd.address.clone(),
+            addresses: cmd.addresses.clone(),
+            coordinates: cmd.coordinates.clone(),
+            virtual_location: cmd.virtual_location.clone(),
+            parent_id: cmd.parent_id,
+            actor: None,
+        })];
+
+        if let Some(result) = geocoded {
+            events.push(LocationDomainEvent::AddressGeocoded(AddressGeocoded {
+                location_id: cmd.location_id,
+                address: result.input_address,
+                coordinates: result.coordinates,
+                confidence_score: result.confidence_score,
+                provider: result.additional_info.provider,
+            }));
+        }
+
+        Ok(events)
+    }
+
+    /// Geocode an address with the configured geocoder
+    ///
+    /// Returns `None` when no geocoder is configured, the lookup fails or the
+    /// result is not confident enough. Failures are logged rather than
+    /// rejecting the command.
+    fn geocode(&self, address: &Address) -> Option<GeocodeResult> {
+        let geocoder = self.geocoder.as_ref()?;
+
+        match geocode_blocking(geocoder.geocode(address)) {
+            Ok(result) if result.confidence_score >= self.min_geocode_confidence => Some(result),
+            Ok(result) => {
+                tracing::warn!(
+                    "Discarding geocode for '{}': confidence {} is below {}",
+                    address.format_single_line(),
+                    result.confidence_score,
+                    self.min_geocode_confidence
+                );
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Failed to geocode '{}': {e}", address.format_single_line());
+                None
+            }
+        }
+    }
+
+    /// Update an existing location, returning the resulting events
+    ///
+    /// A `LocationMoved` event follows the `LocationUpdated` event whenever the
+    /// coordinates actually change.
+    fn update_location(&self, cmd: &UpdateLocation) -> DomainResult<Vec<LocationDomainEvent>> {
+        let location_id = EntityId::from_uuid(cmd.location_id);
+
+        let mut location = self
+            .repository
+            .load(location_id)
+            .map_err(|e| DomainError::InternalError(format!("Repository error: {e}")))?
+            .ok_or_else(|| {
+                DomainError::ValidationError(format!("Location {} not found", cmd.location_id))
+            })?;
+
+        let previous = location.clone();
+        location.update_details(
+            cmd.name.clone(),
+            cmd.address.clone(),
+            cmd.coordinates.clone(),
+            cmd.virtual_location.clone(),
+        )?;
+
+        self.repository
+            .save(&location)
+            .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+
+        let mut events = vec![LocationDomainEvent::LocationUpdated(LocationUpdated {
+            location_id: cmd.location_id,
+            previous_name: cmd.name.as_ref().map(|_| previous.name.clone()),
+            name: cmd.name.clone(),
+            previous_address: cmd.address.as_ref().and(previous.address.clone()),
+            address: cmd.address.clone(),
+            previous_coordinates: cmd.coordinates.as_ref().and(previous.coordinates.clone()),
+            coordinates: cmd.coordinates.clone(),
+            previous_virtual_location: cmd
+                .virtual_location
+                .as_ref()
+                .and(previous.virtual_location.clone()),
+            virtual_location: cmd.virtual_location.clone(),
+            reason: cmd.reason.clone(),
+            actor: None,
+        })];
+
+        if let (Some(from), Some(to)) = (&previous.coordinates, &cmd.coordinates) {
+            if from != to {
+                events.push(LocationDomainEvent::LocationMoved(LocationMoved::new(
+                    cmd.location_id,
+                    from.clone(),
+                    to.clone(),
+                )));
+            }
+        }
+
+        Ok(events)
+    }
 
-                // Save location
-                if let Err(e) = self.repository.save(&location) {
-                    return CommandAcknowledgment {
-                        command_id: envelope.id,
-                        correlation_id: envelope.identity.correlation_id.clone(),
-                        status: CommandStatus::Rejected,
-                        reason: Some(format!("Failed to save location: {e}")),
-                    };
+    /// Set a mailing, billing or other non-physical address, returning the resulting events
+    ///
+    /// The physical address is part of the location's details and changes
+    /// through [`UpdateLocation`] instead.
+    fn set_location_address(
+        &self,
+        cmd: &SetLocationAddress,
+    ) -> DomainResult<Vec<LocationDomainEvent>> {
+        if cmd.role == AddressRole::Physical {
+            return Err(DomainError::ValidationError(
+                "Update the location to change its physical address".to_string(),
+            ));
+        }
+
+        let mut location = self.load_location(cmd.location_id)?;
+        let previous_address = location.address_for(cmd.role).cloned();
+        location.set_address_for(cmd.role, cmd.address.clone())?;
+
+        self.repository
+            .save(&location)
+            .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+
+        Ok(vec![LocationDomainEvent::LocationAddressSet(
+            LocationAddressSet {
+                location_id: cmd.location_id,
+                role: cmd.role,
+                address: cmd.address.clone(),
+                previous_address,
+                reason: cmd.reason.clone(),
+            },
+        )])
+    }
+
+    /// Place a location inside a building, returning the resulting events
+    fn set_indoor_position(
+        &self,
+        cmd: &SetIndoorPosition,
+    ) -> DomainResult<Vec<LocationDomainEvent>> {
+        let mut location = self.load_location(cmd.location_id)?;
+        let previous_position = location.indoor_position.clone();
+        location.set_indoor_position(cmd.position.clone())?;
+
+        self.repository
+            .save(&location)
+            .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+
+        Ok(vec![LocationDomainEvent::IndoorPositionSet(
+            IndoorPositionSet {
+                location_id: cmd.location_id,
+                position: cmd.position.clone(),
+                previous_position,
+                reason: cmd.reason.clone(),
+            },
+        )])
+    }
+
+    /// Add metadata to a location, returning the resulting events
+    ///
+    /// New keys produce a `LocationMetadataAdded` event and changed values a
+    /// `LocationMetadataUpdated` event. A command carrying an `expected_version`
+    /// is rejected with a version conflict once the location has moved past it.
+    fn add_metadata(&self, cmd: &AddLocationMetadata) -> DomainResult<Vec<LocationDomainEvent>> {
+        let mut location = self.load_location(cmd.location_id)?;
+
+        if let Some(expected) = cmd.expected_version {
+            if location.version() != expected {
+                return Err(DomainError::VersionConflict(format!(
+                    "Location {} is at version {}, expected {expected}",
+                    cmd.location_id,
+                    location.version()
+                )));
+            }
+        }
+
+        let mut added = HashMap::new();
+        let mut previous = HashMap::new();
+        let mut updated = HashMap::new();
+        for (key, value) in &cmd.metadata {
+            match location.metadata.get(key) {
+                None => {
+                    added.insert(key.clone(), value.clone());
+                }
+                Some(existing) if existing != value => {
+                    previous.insert(key.clone(), existing.clone());
+                    updated.insert(key.clone(), value.clone());
                 }
+                Some(_) => {}
+            }
+        }
 
-                // Emit event
-                let event = LocationDomainEvent::LocationDefined(LocationDefined {
+        let mut events = Vec::new();
+        if !added.is_empty() {
+            location.add_metadata_bulk(added.clone())?;
+            events.push(LocationDomainEvent::LocationMetadataAdded(
+                LocationMetadataAdded {
+                    location_id: cmd.location_id,
+                    added_metadata: added,
+                    current_metadata: location.metadata.clone(),
+                    reason: cmd.reason.clone(),
+                },
+            ));
+        }
+        if !updated.is_empty() {
+            location.add_metadata_bulk(updated.clone())?;
+            events.push(LocationDomainEvent::LocationMetadataUpdated(
+                LocationMetadataUpdated {
                     location_id: cmd.location_id,
-                    name: cmd.name.clone(),
-                    location_type: cmd.location_type.clone(),
-                    address: cmd.address.clone(),
-                    coordinates: cmd.coordinates.clone(),
-                    virtual_location: cmd.virtual_location.clone(),
-                    parent_id: cmd.parent_id,
-                });
-
-                // Publish the event
-                if let Err(e) = self
-                    .event_publisher
-                    .publish_events(vec![event], envelope.identity.correlation_id.clone())
-                {
-                    // Log the error but don't fail the command
-                    // Events can be retried or handled separately
-                    eprintln!("Failed to publish LocationDefined event: {e}");
+                    previous_metadata: previous,
+                    updated_metadata: updated,
+                    current_metadata: location.metadata.clone(),
+                    reason: cmd.reason.clone(),
+                },
+            ));
+        }
+
+        // The repository's own version check catches a writer that got in
+        // between the load above and this save
+        if !events.is_empty() {
+            self.repository
+                .save(&location)
+                .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+        }
+
+        Ok(events)
+    }
+
+    /// Merge a duplicate location into the target, returning the resulting events
+    ///
+    /// The source's children are taken from the read model's hierarchy and
+    /// checked against the repository. Every location is loaded and checked
+    /// before anything is saved, so a rejected merge leaves all of them
+    /// untouched. The repository has no transactions; a failing save stops the
+    /// merge before any event is published.
+    fn merge_locations(
+        &self,
+        cmd: &MergeLocations,
+        read_model: &LocationReadModel,
+    ) -> DomainResult<Vec<LocationDomainEvent>> {
+        if cmd.target_id == cmd.source_id {
+            return Err(DomainError::ValidationError(
+                "Cannot merge a location into itself".to_string(),
+            ));
+        }
+
+        let mut target = self.load_location(cmd.target_id)?;
+        let mut source = self.load_location(cmd.source_id)?;
+        if target.is_archived() || source.is_archived() {
+            return Err(DomainError::ValidationError(
+                "Cannot merge archived locations".to_string(),
+            ));
+        }
+
+        let child_ids = read_model
+            .hierarchy
+            .parent_child_map
+            .get(&cmd.source_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let source_ref = EntityId::from_uuid(cmd.source_id);
+        let mut children = Vec::with_capacity(child_ids.len());
+        for child_id in &child_ids {
+            if *child_id == cmd.target_id {
+                return Err(DomainError::ValidationError(format!(
+                    "Cannot merge {} into its own child {}",
+                    cmd.source_id, cmd.target_id
+                )));
+            }
+            let mut child = self.load_location(*child_id)?;
+            if child.parent_id != Some(source_ref) {
+                return Err(DomainError::ValidationError(format!(
+                    "Location {child_id} is not a child of {}",
+                    cmd.source_id
+                )));
+            }
+            child.set_parent(EntityId::from_uuid(cmd.target_id))?;
+            children.push(child);
+        }
+
+        // Keys missing on the target are copied; conflicting keys keep the
+        // target's value unless the command asks to overwrite
+        let mut added = HashMap::new();
+        let mut previous = HashMap::new();
+        let mut updated = HashMap::new();
+        for (key, value) in source.get_metadata() {
+            match target.metadata.get(key) {
+                None => {
+                    added.insert(key.clone(), value.clone());
                 }
+                Some(existing) if cmd.overwrite && existing != value => {
+                    previous.insert(key.clone(), existing.clone());
+                    updated.insert(key.clone(), value.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut events: Vec<LocationDomainEvent> = child_ids
+            .iter()
+            .map(|child_id| {
+                LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                    location_id: *child_id,
+                    parent_id: cmd.target_id,
+                    previous_parent_id: Some(cmd.source_id),
+                    reason: cmd.reason.clone(),
+                })
+            })
+            .collect();
+
+        if !added.is_empty() {
+            target.add_metadata_bulk(added.clone())?;
+            events.push(LocationDomainEvent::LocationMetadataAdded(
+                LocationMetadataAdded {
+                    location_id: cmd.target_id,
+                    added_metadata: added.clone(),
+                    current_metadata: target.metadata.clone(),
+                    reason: cmd.reason.clone(),
+                },
+            ));
+        }
+        if !updated.is_empty() {
+            target.add_metadata_bulk(updated.clone())?;
+            events.push(LocationDomainEvent::LocationMetadataUpdated(
+                LocationMetadataUpdated {
+                    location_id: cmd.target_id,
+                    previous_metadata: previous,
+                    updated_metadata: updated.clone(),
+                    current_metadata: target.metadata.clone(),
+                    reason: cmd.reason.clone(),
+                },
+            ));
+        }
+
+        source.archive()?;
+        events.push(LocationDomainEvent::LocationArchived(LocationArchived {
+            location_id: cmd.source_id,
+            name: source.name.clone(),
+            location_type: source.location_type.clone(),
+            reason: cmd.reason.clone(),
+            actor: None,
+        }));
+
+        for location in children.iter().chain([&target, &source]) {
+            self.repository
+                .save(location)
+                .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+        }
+
+        added.extend(updated);
+        events.push(LocationDomainEvent::LocationsMerged(LocationsMerged {
+            location_id: cmd.target_id,
+            merged_location_id: cmd.source_id,
+            reparented_children: child_ids,
+            merged_metadata: added,
+            reason: cmd.reason.clone(),
+        }));
+
+        Ok(events)
+    }
 
-                CommandAcknowledgment {
+    /// Check a location's stored coordinates against its address
+    ///
+    /// The coordinates are reverse geocoded and the locality and region found
+    /// there compared with the address; the address is geocoded forward to
+    /// report how far the stored coordinates are from it. The location is not
+    /// changed and nothing is saved.
+    fn validate_coordinates(
+        &self,
+        cmd: &ValidateCoordinates,
+    ) -> DomainResult<Vec<LocationDomainEvent>> {
+        let geocoder = self.geocoder.as_ref().ok_or_else(|| {
+            DomainError::ValidationError("Validating coordinates requires a geocoder".to_string())
+        })?;
+
+        let location = self.load_location(cmd.location_id)?;
+        let (Some(address), Some(coordinates)) = (&location.address, &location.coordinates) else {
+            return Err(DomainError::ValidationError(format!(
+                "Location {} needs both an address and coordinates to validate",
+                cmd.location_id
+            )));
+        };
+
+        let geocoding_failed =
+            |e: GeocodingError| DomainError::InternalError(format!("Geocoding failed: {e}"));
+        let reverse =
+            geocode_blocking(geocoder.reverse_geocode(coordinates)).map_err(geocoding_failed)?;
+        let forward = geocode_blocking(geocoder.geocode(address)).map_err(geocoding_failed)?;
+
+        let expected = address.normalized();
+        let found = reverse.best_candidate().normalized();
+        let mismatch = !expected.locality.eq_ignore_ascii_case(&found.locality)
+            || !expected.region.eq_ignore_ascii_case(&found.region);
+
+        Ok(vec![LocationDomainEvent::CoordinatesValidated(
+            CoordinatesValidated {
+                location_id: cmd.location_id,
+                address: address.clone(),
+                coordinates: coordinates.clone(),
+                reverse_geocoded_address: reverse.best_candidate().clone(),
+                distance_meters: forward.coordinates.distance_to(coordinates),
+                geocoded_coordinates: forward.coordinates,
+                mismatch,
+                provider: reverse.additional_info.provider,
+            },
+        )])
+    }
+
+    /// Delete a location, and with `cascade` its descendants, returning the resulting events
+    ///
+    /// The descendants are taken from the read model, and a location that has
+    /// children is only deleted with `cascade`. Every location is loaded and
+    /// deleted before any is saved, so a rejected delete leaves all of them
+    /// untouched. Each deleted location gets its own `LocationDeleted` event,
+    /// deepest first, so every aggregate replays its own deletion.
+    fn delete_location(
+        &self,
+        cmd: &DeleteLocation,
+        read_model: &LocationReadModel,
+    ) -> DomainResult<Vec<LocationDomainEvent>> {
+        let ids = read_model.deletion_set(cmd.location_id, cmd.cascade)?;
+
+        let mut locations = Vec::with_capacity(ids.len());
+        for id in ids.into_iter().rev() {
+            let mut location = self.load_location(id)?;
+            location.delete()?;
+            locations.push((id, location));
+        }
+
+        for (_, location) in &locations {
+            self.repository
+                .save(location)
+                .map_err(|e| DomainError::InternalError(format!("Failed to save location: {e}")))?;
+        }
+
+        Ok(locations
+            .into_iter()
+            .map(|(location_id, location)| {
+                LocationDomainEvent::LocationDeleted(LocationDeleted {
+                    location_id,
+                    location_type: location.location_type,
+                    reason: cmd.reason.clone(),
+                    cascade: location_id == cmd.location_id && cmd.cascade,
+                })
+            })
+            .collect())
+    }
+
+    /// Load an existing location or fail validation
+    fn load_location(&self, id: Uuid) -> DomainResult<Location> {
+        self.repository
+            .load(EntityId::from_uuid(id))
+            .map_err(|e| DomainError::InternalError(format!("Repository error: {e}")))?
+            .ok_or_else(|| DomainError::ValidationError(format!("Location {id} not found")))
+    }
+
+    /// Define every location in a batch, continuing past invalid entries
+    ///
+    /// Events for the locations that were created are published together under
+    /// the batch's correlation ID, the one returned in the result.
+    pub fn handle_batch_define(
+        &mut self,
+        envelope: CommandEnvelope<BatchCommand<DefineLocation>>,
+    ) -> BatchCommandResult {
+        let actor = issuing_actor(&envelope);
+        let mut events = Vec::new();
+        let result = envelope.command.process(|command, _identity| {
+            events.extend(
+                self.define_location(command)?
+                    .into_iter()
+                    .map(|event| event.with_actor(actor.clone())),
+            );
+            Ok::<_, DomainError>(())
+        });
+
+        if !events.is_empty() {
+            if let Err(e) = self
+                .event_publisher
+                .publish_events(events, CorrelationId(IdType::Uuid(result.correlation_id.0)))
+            {
+                eprintln!("Failed to publish location events: {e}");
+            }
+        }
+
+        result
+    }
+
+    /// Tag every active location of the read model that lies inside the region
+    ///
+    /// Locations already carrying all of the tags are left alone. Every
+    /// candidate is loaded before anything is saved. The metadata events are
+    /// published together under the envelope's correlation ID, and the number of
+    /// locations that received new tags is returned. If a save fails, the events
+    /// of the locations already saved are still published.
+    pub fn handle_tag_in_region(
+        &mut self,
+        envelope: CommandEnvelope<TagLocationsInRegion>,
+        read_model: &LocationReadModel,
+    ) -> DomainResult<usize> {
+        let cmd = &envelope.command;
+        if cmd.tags.is_empty() || cmd.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(DomainError::ValidationError(
+                "Tags must be non-empty".to_string(),
+            ));
+        }
+
+        // Archived locations are dropped from the spatial index, so only active
+        // locations are candidates
+        let mut location_ids: Vec<Uuid> = read_model
+            .locations
+            .keys()
+            .copied()
+            .filter(|id| {
+                read_model
+                    .spatial_index
+                    .get(*id)
+                    .is_some_and(|coords| cmd.region.contains(coords))
+            })
+            .collect();
+        location_ids.sort();
+
+        let mut tagged_locations = Vec::new();
+        let mut events = Vec::new();
+        for location_id in location_ids {
+            let mut location = self.load_location(location_id)?;
+            // The read model may not have caught up with a delete yet
+            if location.is_deleted() {
+                continue;
+            }
+            let added: HashMap<String, String> = cmd
+                .tags
+                .iter()
+                .map(|tag| TagLocationsInRegion::metadata_key(tag.trim()))
+                .filter(|key| !location.metadata.contains_key(key))
+                .map(|key| (key, "true".to_string()))
+                .collect();
+            if added.is_empty() {
+                continue;
+            }
+
+            location.add_metadata_bulk(added.clone())?;
+            events.push(LocationDomainEvent::LocationMetadataAdded(
+                LocationMetadataAdded {
+                    location_id,
+                    added_metadata: added,
+                    current_metadata: location.metadata.clone(),
+                    reason: "Tagged by spatial region".to_string(),
+                },
+            ));
+            tagged_locations.push(location);
+        }
+
+        let mut saved = 0;
+        let mut failure = None;
+        for location in &tagged_locations {
+            if let Err(e) = self.repository.save(location) {
+                failure = Some(DomainError::InternalError(format!(
+                    "Failed to save location: {e}"
+                )));
+                break;
+            }
+            saved += 1;
+        }
+
+        // Saved locations have changed, so their events go out even if a later
+        // save failed
+        events.truncate(saved);
+        if !events.is_empty() {
+            if let Err(e) = self
+                .event_publisher
+                .publish_events(events, envelope.identity.correlation_id.clone())
+            {
+                eprintln!("Failed to publish location events: {e}");
+            }
+        }
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(saved),
+        }
+    }
+
+    /// Delete a location, checking its children against the read model
+    ///
+    /// A location with children is rejected unless the command sets `cascade`,
+    /// in which case all of its descendants are deleted with it.
+    pub fn handle_delete(
+        &mut self,
+        envelope: CommandEnvelope<DeleteLocation>,
+        read_model: &LocationReadModel,
+    ) -> CommandAcknowledgment {
+        self.handle_once(envelope, |handler, cmd| {
+            handler.delete_location(cmd, read_model)
+        })
+    }
+
+    /// Merge a duplicate location into another, re-parenting the children the
+    /// read model records for it
+    pub fn handle_merge(
+        &mut self,
+        envelope: CommandEnvelope<MergeLocations>,
+        read_model: &LocationReadModel,
+    ) -> CommandAcknowledgment {
+        self.handle_once(envelope, |handler, cmd| {
+            handler.merge_locations(cmd, read_model)
+        })
+    }
+
+    /// Handle a command once per command ID within the idempotency window
+    ///
+    /// Rejected commands are not remembered and are handled again when
+    /// redelivered.
+    fn handle_once<C: Command>(
+        &mut self,
+        envelope: CommandEnvelope<C>,
+        handle: impl FnOnce(&Self, &C) -> DomainResult<Vec<LocationDomainEvent>>,
+    ) -> CommandAcknowledgment {
+        let key = format!("command:{:?}", envelope.id);
+        if let Some(ack) = self.processed.get(&key) {
+            return ack;
+        }
+
+        let actor = issuing_actor(&envelope);
+        let events = handle(self, &envelope.command).map(|events| {
+            events
+                .into_iter()
+                .map(|event| event.with_actor(actor.clone()))
+                .collect()
+        });
+        let ack = self.acknowledge(envelope, events);
+        self.remember(key, &ack);
+        ack
+    }
+
+    /// Remember an accepted command's acknowledgment for its redeliveries
+    fn remember(&mut self, key: String, ack: &CommandAcknowledgment) {
+        if matches!(ack.status, CommandStatus::Accepted) {
+            self.processed.insert(key, ack.clone());
+        }
+    }
+
+    /// Publish the events of a handled command and acknowledge it
+    fn acknowledge<C: Command>(
+        &self,
+        envelope: CommandEnvelope<C>,
+        events: DomainResult<Vec<LocationDomainEvent>>,
+    ) -> CommandAcknowledgment {
+        let correlation_id = envelope.identity.correlation_id.clone();
+
+        let events = match events {
+            Ok(events) => events,
+            Err(e) => {
+                return CommandAcknowledgment {
                     command_id: envelope.id,
-                    correlation_id: envelope.identity.correlation_id.clone(),
-                    status: CommandStatus::Accepted,
-                    reason: None,
-                }
+                    correlation_id,
+                    status: CommandStatus::Rejected,
+                    reason: Some(e.to_string()),
+                };
+            }
+        };
+
+        // Publish the events
+        if let Err(e) = self
+            .event_publisher
+            .publish_events(events, correlation_id.clone())
+        {
+            // Log the error but don't fail the command
+            // Events can be retried or handled separately
+            eprintln!("Failed to publish location events: {e}");
+        }
+
+        CommandAcknowledgment {
+            command_id: envelope.id,
+            correlation_id,
+            status: CommandStatus::Accepted,
+            reason: None,
+        }
+    }
+}
+
+/// The actor that issued a command, if the issuer names one
+///
+/// Authenticated users issue commands under their user ID; issuers that are
+/// not a recognisable actor leave the resulting events unattributed.
+fn issuing_actor<C>(envelope: &CommandEnvelope<C>) -> Option<ActorId> {
+    envelope.issued_by.parse().ok()
+}
+
+/// Run a geocoding request to completion from the synchronous command path
+///
+/// Inside a multi-threaded Tokio runtime the current worker is handed over with
+/// `block_in_place`; otherwise the request runs on a scoped thread with its own
+/// runtime, since a runtime cannot be started from within another.
+fn geocode_blocking<T: Send>(
+    request: impl Future<Output = Result<T, GeocodingError>> + Send,
+) -> Result<T, GeocodingError> {
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread {
+            return tokio::task::block_in_place(|| handle.block_on(request));
+        }
+    }
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|e| GeocodingError::ServiceUnavailable(e.to_string()))?
+                    .block_on(request)
+            })
+            .join()
+            .unwrap_or_else(|_| {
+                Err(GeocodingError::ProviderError(
+                    "Geocoding thread panicked".to_string(),
+                ))
+            })
+    })
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<DefineLocation>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<DefineLocation>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::define_location)
+    }
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<UpdateLocation>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<UpdateLocation>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::update_location)
+    }
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<SetLocationAddress>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<SetLocationAddress>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::set_location_address)
+    }
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<SetIndoorPosition>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<SetIndoorPosition>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::set_indoor_position)
+    }
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<AddLocationMetadata>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<AddLocationMetadata>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::add_metadata)
+    }
+}
+
+impl<R: AggregateRepository<Location>> CommandHandler<ValidateCoordinates>
+    for LocationCommandHandler<R>
+{
+    fn handle(&mut self, envelope: CommandEnvelope<ValidateCoordinates>) -> CommandAcknowledgment {
+        self.handle_once(envelope, Self::validate_coordinates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::InMemoryLocationRepository;
+    use crate::services::{
+        AddressValidationResult, GeocodeInfo, GeocodingMethod, MockGeocodingService,
+        PrecisionLevel, ReverseGeocodeResult,
+    };
+    use crate::value_objects::IndoorPosition;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Publisher that records every event it is given
+    #[derive(Default)]
+    struct CapturingPublisher {
+        events: Mutex<Vec<LocationDomainEvent>>,
+        correlation_ids: Mutex<Vec<CorrelationId>>,
+    }
+
+    impl EventPublisher for CapturingPublisher {
+        fn publish_events(
+            &self,
+            events: Vec<LocationDomainEvent>,
+            correlation_id: CorrelationId,
+        ) -> Result<(), String> {
+            self.events.lock().unwrap().extend(events);
+            self.correlation_ids.lock().unwrap().push(correlation_id);
+            Ok(())
+        }
+    }
+
+    fn define_command(location_id: Uuid) -> DefineLocation {
+        DefineLocation::physical(
+            location_id,
+            "Headquarters".to_string(),
+            Address::new(
+                "1 Market St".to_string(),
+                "San Francisco".to_string(),
+                "CA".to_string(),
+                "US".to_string(),
+                "94105".to_string(),
+            ),
+            GeoCoordinates::new(37.7946, -122.3950),
+        )
+    }
+
+    #[test]
+    fn test_define_location_emits_location_defined() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        let command = define_command(location_id);
+        let ack = handler.handle(CommandEnvelope::new(command.clone(), "test".to_string()));
+
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert!(repository
+            .load(EntityId::from_uuid(location_id))
+            .unwrap()
+            .is_some());
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            LocationDomainEvent::LocationDefined(e) => {
+                assert_eq!(e.location_id, location_id);
+                assert_eq!(e.name, "Headquarters");
+                assert_eq!(e.location_type, LocationType::Physical);
+                assert_eq!(e.address, command.address);
+                assert_eq!(e.coordinates, command.coordinates);
+                assert_eq!(e.parent_id, None);
+                assert_eq!(e.actor, None);
             }
-            Err(e) => CommandAcknowledgment {
-                command_id: envelope.id,
-                correlation_id: envelope.identity.correlation_id.clone(),
-                status: CommandStatus::Rejected,
-                reason: Some(format!("Repository error: {e}")),
+            other => panic!("Expected LocationDefined, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_events_are_attributed_to_issuing_user() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let user_id = Uuid::new_v4();
+        let location_id = Uuid::new_v4();
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            user_id.to_string(),
+        ));
+        handler.handle(CommandEnvelope::new(
+            UpdateLocation {
+                location_id,
+                name: Some("Head Office".to_string()),
+                address: None,
+                coordinates: None,
+                virtual_location: None,
+                reason: "Renamed".to_string(),
             },
+            ActorId::user(user_id).to_string(),
+        ));
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        match (&events[0], &events[1]) {
+            (
+                LocationDomainEvent::LocationDefined(defined),
+                LocationDomainEvent::LocationUpdated(updated),
+            ) => {
+                assert_eq!(defined.actor, Some(ActorId::user(user_id)));
+                assert_eq!(updated.actor, Some(ActorId::user(user_id)));
+            }
+            other => panic!("Expected LocationDefined then LocationUpdated, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_redelivered_command_is_handled_once() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let envelope = CommandEnvelope::new(define_command(Uuid::new_v4()), "test".to_string());
+        let first = handler.handle(envelope.clone());
+        let second = handler.handle(envelope);
+
+        assert!(matches!(first.status, CommandStatus::Accepted));
+        assert!(matches!(second.status, CommandStatus::Accepted));
+        assert_eq!(second.command_id, first.command_id);
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rejected_command_is_handled_again_when_redelivered() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+        let location_id = Uuid::new_v4();
+
+        // Metadata for a location that does not exist yet is rejected
+        let envelope = CommandEnvelope::new(
+            metadata_command(location_id, &[("dock", "4")], None),
+            "test".to_string(),
+        );
+        let first = handler.handle(envelope.clone());
+        assert!(matches!(first.status, CommandStatus::Rejected));
+
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+        let second = handler.handle(envelope);
+
+        assert!(matches!(second.status, CommandStatus::Accepted));
+        assert!(matches!(
+            publisher.events.lock().unwrap().last(),
+            Some(LocationDomainEvent::LocationMetadataAdded(_))
+        ));
+    }
+
+    #[test]
+    fn test_idempotency_key_deduplicates_across_envelopes() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+        let command = define_command(Uuid::new_v4());
+
+        let first = handler.handle_idempotent(
+            CommandEnvelope::new(command.clone(), "test".to_string()),
+            "client-retry-1",
+        );
+        let second = handler.handle_idempotent(
+            CommandEnvelope::new(command, "test".to_string()),
+            "client-retry-1",
+        );
+
+        assert!(matches!(first.status, CommandStatus::Accepted));
+        assert!(matches!(second.status, CommandStatus::Accepted));
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], LocationDomainEvent::LocationDefined(_)));
+    }
+
+    #[test]
+    fn test_define_location_stores_address_and_coordinates() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        let command = define_command(location_id);
+        handler.handle(CommandEnvelope::new(command.clone(), "test".to_string()));
+
+        let location = repository
+            .load(EntityId::from_uuid(location_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(location.address, command.address);
+        assert_eq!(location.coordinates, command.coordinates);
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+
+        // Invalid coordinates reject the whole definition
+        let mut invalid = define_command(Uuid::new_v4());
+        invalid.coordinates = Some(GeoCoordinates::new(95.0, -122.3950));
+        let ack = handler.handle(CommandEnvelope::new(invalid.clone(), "test".to_string()));
+
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert!(repository
+            .load(EntityId::from_uuid(invalid.location_id))
+            .unwrap()
+            .is_none());
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_define_location_rejects_existing_id() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+        let ack = handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert!(ack.reason.unwrap().contains("already exists"));
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+    }
+
+    fn billing_address() -> Address {
+        Address::new(
+            "PO Box 12".to_string(),
+            "San Francisco".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            "94105".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_define_location_stores_role_addresses() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        let mut command = define_command(location_id);
+        command
+            .addresses
+            .insert(AddressRole::Billing, billing_address());
+        handler.handle(CommandEnvelope::new(command, "test".to_string()));
+
+        let location = load(&repository, location_id);
+        assert_eq!(
+            location.address_for(AddressRole::Billing),
+            Some(&billing_address())
+        );
+        match &publisher.events.lock().unwrap()[0] {
+            LocationDomainEvent::LocationDefined(e) => {
+                assert_eq!(
+                    e.addresses.get(&AddressRole::Billing),
+                    Some(&billing_address())
+                );
+            }
+            other => panic!("Expected LocationDefined, got {other:?}"),
+        }
+
+        // The physical address is only ever given as `address`
+        let mut physical = define_command(Uuid::new_v4());
+        physical
+            .addresses
+            .insert(AddressRole::Physical, billing_address());
+        let ack = handler.handle(CommandEnvelope::new(physical, "test".to_string()));
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert_eq!(publisher.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_set_location_address_emits_address_set() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+        let ack = handler.handle(CommandEnvelope::new(
+            SetLocationAddress {
+                location_id,
+                role: AddressRole::Mailing,
+                address: billing_address(),
+                reason: "Post goes to the PO box".to_string(),
+            },
+            "test".to_string(),
+        ));
+
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert_eq!(
+            load(&repository, location_id).address_for(AddressRole::Mailing),
+            Some(&billing_address())
+        );
+        match publisher.events.lock().unwrap().last() {
+            Some(LocationDomainEvent::LocationAddressSet(e)) => {
+                assert_eq!(e.role, AddressRole::Mailing);
+                assert_eq!(e.address, billing_address());
+                assert_eq!(e.previous_address, None);
+            }
+            other => panic!("Expected LocationAddressSet, got {other:?}"),
+        }
+
+        let ack = handler.handle(CommandEnvelope::new(
+            SetLocationAddress {
+                location_id,
+                role: AddressRole::Physical,
+                address: billing_address(),
+                reason: "Moved".to_string(),
+            },
+            "test".to_string(),
+        ));
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert_eq!(publisher.events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_set_indoor_position_places_logical_room() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let room_id = Uuid::new_v4();
+        let mut room = define_command(room_id);
+        room.location_type = LocationType::Logical;
+        room.address = None;
+        room.coordinates = None;
+        handler.handle(CommandEnvelope::new(room, "test".to_string()));
+
+        let position = IndoorPosition::new("HQ", 5).with_room("501");
+        let ack = handler.handle(CommandEnvelope::new(
+            SetIndoorPosition {
+                location_id: room_id,
+                position: position.clone(),
+                reason: "Fitted out".to_string(),
+            },
+            "test".to_string(),
+        ));
+
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert_eq!(
+            load(&repository, room_id).indoor_position,
+            Some(position.clone())
+        );
+        match publisher.events.lock().unwrap().last() {
+            Some(LocationDomainEvent::IndoorPositionSet(e)) => {
+                assert_eq!(e.position, position);
+                assert_eq!(e.previous_position, None);
+            }
+            other => panic!("Expected IndoorPositionSet, got {other:?}"),
+        }
+    }
+
+    fn address_only_command(location_id: Uuid) -> DefineLocation {
+        let mut command = define_command(location_id);
+        command.coordinates = None;
+        command
+    }
+
+    #[test]
+    fn test_define_location_geocodes_address_without_coordinates() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let geocoder = Arc::new(MockGeocodingService::new().with_delay(0));
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone())
+            .with_geocoder(geocoder, DEFAULT_MIN_GEOCODE_CONFIDENCE);
+
+        let location_id = Uuid::new_v4();
+        let command = address_only_command(location_id);
+        let ack = handler.handle(CommandEnvelope::new(command.clone(), "test".to_string()));
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        let location = repository
+            .load(EntityId::from_uuid(location_id))
+            .unwrap()
+            .unwrap();
+        let geocoded = GeoCoordinates::new(37.7749, -122.4194);
+        assert_eq!(location.coordinates, Some(geocoded.clone()));
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            LocationDomainEvent::LocationDefined(e) => assert_eq!(e.coordinates, None),
+            other => panic!("Expected LocationDefined, got {other:?}"),
+        }
+        match &events[1] {
+            LocationDomainEvent::AddressGeocoded(e) => {
+                assert_eq!(e.location_id, location_id);
+                assert_eq!(Some(&e.address), command.address.as_ref());
+                assert_eq!(e.coordinates, geocoded);
+                assert_eq!(e.confidence_score, 0.95);
+                assert_eq!(e.provider, "MockProvider");
+            }
+            other => panic!("Expected AddressGeocoded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_define_location_skips_low_confidence_geocode() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let geocoder = Arc::new(
+            MockGeocodingService::new()
+                .with_delay(0)
+                .with_confidence(0.4),
+        );
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone())
+            .with_geocoder(geocoder, DEFAULT_MIN_GEOCODE_CONFIDENCE);
+
+        let location_id = Uuid::new_v4();
+        let ack = handler.handle(CommandEnvelope::new(
+            address_only_command(location_id),
+            "test".to_string(),
+        ));
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        let location = repository
+            .load(EntityId::from_uuid(location_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(location.coordinates, None);
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], LocationDomainEvent::LocationDefined(_)));
+    }
+
+    #[test]
+    fn test_update_location_emits_location_moved() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        let define = define_command(location_id);
+        let previous = define.coordinates.clone().unwrap();
+        handler.handle(CommandEnvelope::new(define, "test".to_string()));
+
+        let moved_to = GeoCoordinates::new(37.8044, -122.2712);
+        let ack = handler.handle(CommandEnvelope::new(
+            UpdateLocation {
+                location_id,
+                name: None,
+                address: None,
+                coordinates: Some(moved_to.clone()),
+                virtual_location: None,
+                reason: "Relocated to Oakland".to_string(),
+            },
+            "test".to_string(),
+        ));
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[1], LocationDomainEvent::LocationUpdated(_)));
+        match &events[2] {
+            LocationDomainEvent::LocationMoved(e) => {
+                assert_eq!(e.location_id, location_id);
+                assert_eq!(e.previous_coordinates, previous);
+                assert_eq!(e.coordinates, moved_to);
+                assert_eq!(e.distance_meters, previous.distance_to(&moved_to));
+            }
+            other => panic!("Expected LocationMoved, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_update_without_move_emits_only_location_updated() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        let define = define_command(location_id);
+        let same_coordinates = define.coordinates.clone();
+        handler.handle(CommandEnvelope::new(define, "test".to_string()));
+
+        handler.handle(CommandEnvelope::new(
+            UpdateLocation {
+                location_id,
+                name: Some("HQ".to_string()),
+                address: None,
+                coordinates: same_coordinates,
+                virtual_location: None,
+                reason: "Renamed".to_string(),
+            },
+            "test".to_string(),
+        ));
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], LocationDomainEvent::LocationUpdated(_)));
+    }
+
+    #[test]
+    fn test_batch_define_reports_failing_index() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let mut invalid = define_command(ids[1]);
+        invalid.address = None;
+        invalid.coordinates = None;
+        let batch = BatchCommand::new(vec![
+            define_command(ids[0]),
+            invalid,
+            define_command(ids[2]),
+        ]);
+
+        let result = handler.handle_batch_define(CommandEnvelope::new(batch, "test".to_string()));
+
+        assert_eq!(result.succeeded(), vec![0, 2]);
+        assert_eq!(result.failed(), vec![1]);
+        assert!(result.items[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("address or coordinates"));
+
+        for (index, id) in ids.iter().enumerate() {
+            let stored = repository.load(EntityId::from_uuid(*id)).unwrap();
+            assert_eq!(stored.is_some(), index != 1);
+        }
+        assert_eq!(publisher.events.lock().unwrap().len(), 2);
+        assert_eq!(
+            *publisher.correlation_ids.lock().unwrap(),
+            vec![CorrelationId(IdType::Uuid(result.correlation_id.0))]
+        );
+    }
+
+    fn metadata_command(
+        location_id: Uuid,
+        entries: &[(&str, &str)],
+        expected_version: Option<u64>,
+    ) -> AddLocationMetadata {
+        AddLocationMetadata {
+            location_id,
+            metadata: entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            reason: "Inventory".to_string(),
+            expected_version,
         }
     }
+
+    #[test]
+    fn test_stale_metadata_command_is_rejected() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+        let current = |id: Uuid| repository.load(EntityId::from_uuid(id)).unwrap().unwrap();
+
+        let location_id = Uuid::new_v4();
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+        let version = current(location_id).version();
+
+        // Two clients read the same version and both try to write
+        let first = handler.handle(CommandEnvelope::new(
+            metadata_command(location_id, &[("dock", "7")], Some(version)),
+            "test".to_string(),
+        ));
+        assert!(matches!(first.status, CommandStatus::Accepted));
+
+        let second = handler.handle(CommandEnvelope::new(
+            metadata_command(location_id, &[("dock", "9")], Some(version)),
+            "test".to_string(),
+        ));
+        assert!(matches!(second.status, CommandStatus::Rejected));
+        assert!(second
+            .reason
+            .unwrap()
+            .contains(&format!("expected {version}")));
+        assert_eq!(current(location_id).metadata["dock"], "7");
+
+        // Retrying against the current version succeeds
+        let retry = handler.handle(CommandEnvelope::new(
+            metadata_command(
+                location_id,
+                &[("dock", "9")],
+                Some(current(location_id).version()),
+            ),
+            "test".to_string(),
+        ));
+        assert!(matches!(retry.status, CommandStatus::Accepted));
+        assert_eq!(current(location_id).metadata["dock"], "9");
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[1],
+            LocationDomainEvent::LocationMetadataAdded(_)
+        ));
+        match &events[2] {
+            LocationDomainEvent::LocationMetadataUpdated(e) => {
+                assert_eq!(e.previous_metadata["dock"], "7");
+                assert_eq!(e.updated_metadata["dock"], "9");
+            }
+            other => panic!("Expected LocationMetadataUpdated, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_metadata_command_without_expected_version_skips_check() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher);
+
+        let location_id = stored_location(&repository, "Depot", &[("dock", "7")], None);
+        let ack = handler.handle(CommandEnvelope::new(
+            metadata_command(location_id, &[("gate", "B")], None),
+            "test".to_string(),
+        ));
+
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert_eq!(load(&repository, location_id).metadata["gate"], "B");
+    }
+
+    /// Save a location with the given metadata and parent straight into the repository
+    fn stored_location(
+        repository: &InMemoryLocationRepository,
+        name: &str,
+        metadata: &[(&str, &str)],
+        parent_id: Option<Uuid>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        let mut location = Location::new_from_coordinates(
+            EntityId::from_uuid(id),
+            name.to_string(),
+            GeoCoordinates::new(37.7946, -122.3950),
+        )
+        .unwrap();
+        for (key, value) in metadata {
+            location
+                .add_metadata(key.to_string(), value.to_string())
+                .unwrap();
+        }
+        if let Some(parent_id) = parent_id {
+            location.set_parent(EntityId::from_uuid(parent_id)).unwrap();
+        }
+        repository.save(&location).unwrap();
+        id
+    }
+
+    fn load(repository: &InMemoryLocationRepository, id: Uuid) -> Location {
+        repository.load(EntityId::from_uuid(id)).unwrap().unwrap()
+    }
+
+    /// Read model knowing only the given child and parent links
+    fn hierarchy(links: &[(Uuid, Uuid)]) -> LocationReadModel {
+        LocationReadModel::replay(links.iter().map(|&(location_id, parent_id)| {
+            LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                location_id,
+                parent_id,
+                previous_parent_id: None,
+                reason: "test".to_string(),
+            })
+        }))
+    }
+
+    #[test]
+    fn test_merge_locations_reparents_children_and_copies_metadata() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let target = stored_location(
+            &repository,
+            "HQ",
+            &[("floor_count", "12"), ("owner", "facilities")],
+            None,
+        );
+        let source = stored_location(
+            &repository,
+            "Headquarters (import)",
+            &[("floor_count", "10"), ("badge_zone", "blue")],
+            None,
+        );
+        let target_child = stored_location(&repository, "Lobby", &[], Some(target));
+        let source_children = [
+            stored_location(&repository, "Cafeteria", &[], Some(source)),
+            stored_location(&repository, "Loading Dock", &[], Some(source)),
+        ];
+
+        let read_model = hierarchy(&[
+            (target_child, target),
+            (source_children[0], source),
+            (source_children[1], source),
+        ]);
+
+        let ack = handler.handle_merge(
+            CommandEnvelope::new(
+                MergeLocations {
+                    target_id: target,
+                    source_id: source,
+                    overwrite: false,
+                    reason: "Duplicate from import".to_string(),
+                },
+                "test".to_string(),
+            ),
+            &read_model,
+        );
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        for child in source_children {
+            assert_eq!(
+                load(&repository, child).parent_id,
+                Some(EntityId::from_uuid(target))
+            );
+        }
+        assert_eq!(
+            load(&repository, target_child).parent_id,
+            Some(EntityId::from_uuid(target))
+        );
+
+        let merged = load(&repository, target);
+        assert_eq!(merged.metadata.get("floor_count").unwrap(), "12");
+        assert_eq!(merged.metadata.get("owner").unwrap(), "facilities");
+        assert_eq!(merged.metadata.get("badge_zone").unwrap(), "blue");
+        assert!(load(&repository, source).is_archived());
+
+        let events = publisher.events.lock().unwrap();
+        assert_eq!(events.len(), 5);
+        assert!(
+            matches!(&events[0], LocationDomainEvent::ParentLocationSet(e)
+            if e.location_id == source_children[0] && e.previous_parent_id == Some(source))
+        );
+        assert!(
+            matches!(&events[1], LocationDomainEvent::ParentLocationSet(e)
+            if e.location_id == source_children[1] && e.parent_id == target)
+        );
+        match &events[2] {
+            LocationDomainEvent::LocationMetadataAdded(e) => {
+                assert_eq!(e.location_id, target);
+                assert_eq!(e.added_metadata.len(), 1);
+                assert_eq!(e.added_metadata.get("badge_zone").unwrap(), "blue");
+            }
+            other => panic!("Expected LocationMetadataAdded, got {other:?}"),
+        }
+        assert!(
+            matches!(&events[3], LocationDomainEvent::LocationArchived(e)
+            if e.location_id == source)
+        );
+        match &events[4] {
+            LocationDomainEvent::LocationsMerged(e) => {
+                assert_eq!(e.location_id, target);
+                assert_eq!(e.merged_location_id, source);
+                assert_eq!(e.reparented_children, source_children.to_vec());
+            }
+            other => panic!("Expected LocationsMerged, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_merge_locations_reparents_children_defined_under_source() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let target = Uuid::new_v4();
+        let source = Uuid::new_v4();
+        let child = Uuid::new_v4();
+        for (id, parent_id) in [(target, None), (source, None), (child, Some(source))] {
+            let mut command = define_command(id);
+            command.parent_id = parent_id;
+            handler.handle(CommandEnvelope::new(command, "test".to_string()));
+        }
+
+        // The child is linked only by its LocationDefined event
+        let read_model = LocationReadModel::replay(publisher.events.lock().unwrap().drain(..));
+
+        let ack = handler.handle_merge(
+            CommandEnvelope::new(
+                MergeLocations {
+                    target_id: target,
+                    source_id: source,
+                    overwrite: false,
+                    reason: "Duplicate from import".to_string(),
+                },
+                "test".to_string(),
+            ),
+            &read_model,
+        );
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert_eq!(
+            load(&repository, child).parent_id,
+            Some(EntityId::from_uuid(target))
+        );
+
+        let events = publisher.events.lock().unwrap();
+        assert!(
+            matches!(&events[0], LocationDomainEvent::ParentLocationSet(e)
+            if e.location_id == child && e.parent_id == target && e.previous_parent_id == Some(source))
+        );
+        assert!(events.iter().any(|event| matches!(event,
+            LocationDomainEvent::LocationsMerged(e) if e.reparented_children == vec![child])));
+    }
+
+    #[test]
+    fn test_tag_in_region_only_tags_contained_locations() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let mut define_at = |coordinates: GeoCoordinates| {
+            let location_id = Uuid::new_v4();
+            let mut command = define_command(location_id);
+            command.coordinates = Some(coordinates);
+            handler.handle(CommandEnvelope::new(command, "test".to_string()));
+            location_id
+        };
+        let inside = define_at(GeoCoordinates::new(37.7946, -122.3950));
+        let nearby = define_at(GeoCoordinates::new(37.7950, -122.3960));
+        let outside = define_at(GeoCoordinates::new(34.0522, -118.2437));
+
+        let read_model = LocationReadModel::replay(publisher.events.lock().unwrap().drain(..));
+        let command = TagLocationsInRegion {
+            region: crate::services::SpatialRegion::Circle {
+                center: GeoCoordinates::new(37.7946, -122.3950),
+                radius_meters: 1_000.0,
+            },
+            tags: vec!["promo".to_string(), "west".to_string()],
+        };
+
+        let tagged = handler
+            .handle_tag_in_region(
+                CommandEnvelope::new(command.clone(), "test".to_string()),
+                &read_model,
+            )
+            .unwrap();
+        assert_eq!(tagged, 2);
+
+        for id in [inside, nearby] {
+            let metadata = load(&repository, id).metadata;
+            assert_eq!(metadata.get("tag:promo").map(String::as_str), Some("true"));
+            assert_eq!(metadata.get("tag:west").map(String::as_str), Some("true"));
+        }
+        assert!(load(&repository, outside).metadata.is_empty());
+
+        let events = publisher
+            .events
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| matches!(
+            event,
+            LocationDomainEvent::LocationMetadataAdded(e) if e.location_id != outside
+        )));
+
+        // Locations that already carry every tag are not tagged again
+        let tagged = handler
+            .handle_tag_in_region(
+                CommandEnvelope::new(command, "test".to_string()),
+                &read_model,
+            )
+            .unwrap();
+        assert_eq!(tagged, 0);
+        assert!(publisher.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_location_with_children_requires_cascade() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+        let room = Uuid::new_v4();
+        for (id, parent_id) in [
+            (campus, None),
+            (building, Some(campus)),
+            (room, Some(building)),
+        ] {
+            let mut command = define_command(id);
+            command.parent_id = parent_id;
+            handler.handle(CommandEnvelope::new(command, "test".to_string()));
+        }
+        let hierarchy = [(building, campus), (room, building)].map(|(location_id, parent_id)| {
+            LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                location_id,
+                parent_id,
+                previous_parent_id: None,
+                reason: "test".to_string(),
+            })
+        });
+        let read_model =
+            LocationReadModel::replay(publisher.events.lock().unwrap().drain(..).chain(hierarchy));
+
+        let delete = |cascade| DeleteLocation {
+            location_id: campus,
+            reason: "Erasure request".to_string(),
+            cascade,
+        };
+
+        let ack = handler.handle_delete(
+            CommandEnvelope::new(delete(false), "test".to_string()),
+            &read_model,
+        );
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert!(publisher.events.lock().unwrap().is_empty());
+        assert!([campus, building, room]
+            .iter()
+            .all(|id| !load(&repository, *id).is_deleted()));
+
+        let ack = handler.handle_delete(
+            CommandEnvelope::new(delete(true), "test".to_string()),
+            &read_model,
+        );
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        assert!([campus, building, room]
+            .iter()
+            .all(|id| load(&repository, *id).is_deleted()));
+
+        // Descendants are deleted first, and only the requested location cascades
+        let deleted: Vec<(Uuid, bool)> = publisher
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| match event {
+                LocationDomainEvent::LocationDeleted(e) => (e.location_id, e.cascade),
+                other => panic!("Expected LocationDeleted, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            deleted,
+            vec![(room, false), (building, false), (campus, true)]
+        );
+    }
+
+    #[test]
+    fn test_merge_locations_overwrite_prefers_source_metadata() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let target = stored_location(&repository, "HQ", &[("floor_count", "12")], None);
+        let source = stored_location(&repository, "HQ copy", &[("floor_count", "10")], None);
+
+        let ack = handler.handle_merge(
+            CommandEnvelope::new(
+                MergeLocations {
+                    target_id: target,
+                    source_id: source,
+                    overwrite: true,
+                    reason: "Duplicate".to_string(),
+                },
+                "test".to_string(),
+            ),
+            &LocationReadModel::default(),
+        );
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        assert_eq!(
+            load(&repository, target)
+                .metadata
+                .get("floor_count")
+                .unwrap(),
+            "10"
+        );
+        let events = publisher.events.lock().unwrap();
+        assert!(events.iter().any(|event| matches!(event,
+            LocationDomainEvent::LocationMetadataUpdated(e)
+                if e.previous_metadata.get("floor_count").map(String::as_str) == Some("12"))));
+    }
+
+    /// A stale read model naming a child the repository does not confirm
+    #[test]
+    fn test_merge_locations_rejects_foreign_child() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone());
+
+        let target = stored_location(&repository, "HQ", &[], None);
+        let source = stored_location(&repository, "HQ copy", &[], None);
+        let stranger = stored_location(&repository, "Elsewhere", &[], None);
+
+        let ack = handler.handle_merge(
+            CommandEnvelope::new(
+                MergeLocations {
+                    target_id: target,
+                    source_id: source,
+                    overwrite: false,
+                    reason: "Duplicate".to_string(),
+                },
+                "test".to_string(),
+            ),
+            &hierarchy(&[(stranger, source)]),
+        );
+
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert!(ack.reason.unwrap().contains("is not a child of"));
+        assert!(!load(&repository, source).is_archived());
+        assert!(publisher.events.lock().unwrap().is_empty());
+    }
+
+    /// Geocoder that knows a few city centers
+    ///
+    /// Addresses geocode to the center of their locality and coordinates
+    /// reverse geocode to the nearest center.
+    struct CityGeocoder {
+        cities: Vec<(&'static str, &'static str, GeoCoordinates)>,
+    }
+
+    impl CityGeocoder {
+        fn new() -> Self {
+            Self {
+                cities: vec![
+                    (
+                        "San Francisco",
+                        "CA",
+                        GeoCoordinates::new(37.7749, -122.4194),
+                    ),
+                    ("Los Angeles", "CA", GeoCoordinates::new(34.0522, -118.2437)),
+                ],
+            }
+        }
+
+        fn info() -> GeocodeInfo {
+            GeocodeInfo {
+                provider: "CityGeocoder".to_string(),
+                response_time_ms: 0,
+                rate_limit_remaining: None,
+                geocoding_method: GeocodingMethod::Offline,
+                data_sources: vec![],
+            }
+        }
+    }
+
+    #[async_trait]
+    impl GeocodingService for CityGeocoder {
+        async fn geocode(&self, address: &Address) -> Result<GeocodeResult, GeocodingError> {
+            let (_, _, center) = self
+                .cities
+                .iter()
+                .find(|(city, _, _)| *city == address.locality)
+                .ok_or(GeocodingError::NoResults)?;
+            Ok(GeocodeResult {
+                request_id: Uuid::new_v4(),
+                input_address: address.clone(),
+                coordinates: center.clone(),
+                confidence_score: 0.9,
+                precision_level: PrecisionLevel::City,
+                formatted_address: address.clone(),
+                additional_info: Self::info(),
+            })
+        }
+
+        async fn reverse_geocode(
+            &self,
+            coordinates: &GeoCoordinates,
+        ) -> Result<ReverseGeocodeResult, GeocodingError> {
+            let (city, region, _) = self
+                .cities
+                .iter()
+                .min_by(|a, b| {
+                    a.2.distance_to(coordinates)
+                        .total_cmp(&b.2.distance_to(coordinates))
+                })
+                .unwrap();
+            Ok(ReverseGeocodeResult {
+                request_id: Uuid::new_v4(),
+                input_coordinates: coordinates.clone(),
+                address: Address::new(
+                    String::new(),
+                    city.to_string(),
+                    region.to_string(),
+                    "US".to_string(),
+                    String::new(),
+                ),
+                confidence_score: 0.9,
+                precision_level: PrecisionLevel::City,
+                additional_info: Self::info(),
+                candidates: vec![],
+            })
+        }
+
+        async fn batch_geocode(
+            &self,
+            addresses: &[Address],
+        ) -> Vec<Result<GeocodeResult, GeocodingError>> {
+            let mut results = Vec::new();
+            for address in addresses {
+                results.push(self.geocode(address).await);
+            }
+            results
+        }
+
+        async fn validate_address(
+            &self,
+            address: &Address,
+        ) -> Result<AddressValidationResult, GeocodingError> {
+            MockGeocodingService::new()
+                .with_delay(0)
+                .validate_address(address)
+                .await
+        }
+    }
+
+    fn validate_stored(coordinates: GeoCoordinates) -> CoordinatesValidated {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository.clone(), publisher.clone())
+            .with_geocoder(
+                Arc::new(CityGeocoder::new()),
+                DEFAULT_MIN_GEOCODE_CONFIDENCE,
+            );
+
+        let location_id = Uuid::new_v4();
+        let mut command = define_command(location_id);
+        command.coordinates = Some(coordinates);
+        let ack = handler.handle(CommandEnvelope::new(command, "test".to_string()));
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+        publisher.events.lock().unwrap().clear();
+
+        let ack = handler.handle(CommandEnvelope::new(
+            ValidateCoordinates { location_id },
+            "test".to_string(),
+        ));
+        assert!(matches!(ack.status, CommandStatus::Accepted));
+
+        let events = publisher.events.lock().unwrap();
+        match events.as_slice() {
+            [LocationDomainEvent::CoordinatesValidated(e)] => e.clone(),
+            other => panic!("Expected CoordinatesValidated, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_coordinates_matching_address_passes() {
+        // 1 Market St, a few kilometers from the city center
+        let event = validate_stored(GeoCoordinates::new(37.7946, -122.3950));
+
+        assert!(!event.mismatch);
+        assert_eq!(event.reverse_geocoded_address.locality, "San Francisco");
+        assert_eq!(event.provider, "CityGeocoder");
+        assert!(event.distance_meters < 5_000.0);
+    }
+
+    #[test]
+    fn test_validate_coordinates_in_another_city_is_flagged() {
+        // The address is in San Francisco but the coordinates are in Los Angeles
+        let event = validate_stored(GeoCoordinates::new(34.0407, -118.2468));
+
+        assert!(event.mismatch);
+        assert_eq!(event.reverse_geocoded_address.locality, "Los Angeles");
+        assert!(event.distance_meters > 500_000.0);
+    }
+
+    #[test]
+    fn test_validate_coordinates_requires_geocoder() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let publisher = Arc::new(CapturingPublisher::default());
+        let mut handler = LocationCommandHandler::new(repository, publisher.clone());
+
+        let location_id = Uuid::new_v4();
+        handler.handle(CommandEnvelope::new(
+            define_command(location_id),
+            "test".to_string(),
+        ));
+        let ack = handler.handle(CommandEnvelope::new(
+            ValidateCoordinates { location_id },
+            "test".to_string(),
+        ));
+
+        assert!(matches!(ack.status, CommandStatus::Rejected));
+        assert!(ack.reason.unwrap().contains("requires a geocoder"));
+    }
 }
@@ -1,12 +1,49 @@
 //! Location query handlers and projections for CQRS read side
 
 use crate::aggregate::Location;
-use crate::value_objects::{Address, GeoCoordinates, LocationType, VirtualLocation};
-use cim_domain::{AggregateRoot, DomainError, DomainResult};
+use crate::nats::{ActorId, CimDomainEvent};
+use crate::value_objects::{
+    Address, Distance, GeoBounds, GeoCoordinates, LocationType, VirtualLocation,
+};
+use crate::LocationDomainEvent;
+use cim_domain::{AggregateRoot, DomainError};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::SystemTime;
 use uuid::Uuid;
 
+/// Errors returned by location queries
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LocationQueryError {
+    #[error("Location {0} not found")]
+    LocationNotFound(Uuid),
+
+    #[error("Invalid bounds: {0}")]
+    InvalidBounds(String),
+
+    #[error("Invalid radius: {0}")]
+    InvalidRadius(f64),
+
+    #[error("Hierarchy depth {depth} exceeds maximum of {max_depth}")]
+    MaxDepthExceeded { depth: u32, max_depth: u32 },
+}
+
+impl From<LocationQueryError> for DomainError {
+    fn from(error: LocationQueryError) -> Self {
+        match error {
+            LocationQueryError::LocationNotFound(_) => DomainError::generic(error.to_string()),
+            LocationQueryError::InvalidBounds(_)
+            | LocationQueryError::InvalidRadius(_)
+            | LocationQueryError::MaxDepthExceeded { .. } => {
+                DomainError::ValidationError(error.to_string())
+            }
+        }
+    }
+}
+
+/// Result type for location queries
+pub type LocationQueryResult<T> = Result<T, LocationQueryError>;
+
 /// Location read model for queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationReadModel {
@@ -53,14 +90,49 @@ pub struct LocationWithDistance {
 pub struct FindLocationsQuery {
     pub name_pattern: Option<String>,
     pub location_type: Option<LocationType>,
-    pub within_distance_of: Option<(GeoCoordinates, f64)>, // coordinates and radius in meters
+    pub within_distance_of: Option<(GeoCoordinates, Distance)>, // coordinates and radius
     pub parent_id: Option<Uuid>,
-    pub metadata_filters: HashMap<String, String>,
+    pub metadata_filters: Vec<(String, MetadataFilter)>,
     pub include_archived: bool,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
 }
 
+/// Condition on the value stored under a metadata key
+///
+/// Numeric comparisons parse the stored string as a number; locations whose
+/// value is missing or not numeric never match them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MetadataFilter {
+    /// Value is exactly this string
+    Equals(String),
+    /// Value is missing or differs from this string
+    NotEquals(String),
+    /// Value contains this substring
+    Contains(String),
+    /// Value is a number strictly greater than this one
+    NumericGreaterThan(f64),
+    /// Value is a number strictly less than this one
+    NumericLessThan(f64),
+    /// Key is present, whatever its value
+    Exists,
+}
+
+impl MetadataFilter {
+    /// Check the filter against the value stored under its key, if any
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        let number = || value.and_then(|v| v.trim().parse::<f64>().ok());
+        match self {
+            MetadataFilter::Equals(expected) => value == Some(expected.as_str()),
+            MetadataFilter::NotEquals(expected) => value != Some(expected.as_str()),
+            MetadataFilter::Contains(needle) => value.is_some_and(|v| v.contains(needle.as_str())),
+            MetadataFilter::NumericGreaterThan(min) => number().is_some_and(|n| n > *min),
+            MetadataFilter::NumericLessThan(max) => number().is_some_and(|n| n < *max),
+            MetadataFilter::Exists => value.is_some(),
+        }
+    }
+}
+
 /// Query for location hierarchy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetLocationHierarchyQuery {
@@ -78,6 +150,37 @@ pub struct FindLocationsInBoundsQuery {
     pub include_archived: bool,
 }
 
+impl FindLocationsInBoundsQuery {
+    /// Validate the corners of the box
+    ///
+    /// Both corners must be valid coordinates and the southwest corner must lie
+    /// south of the northeast one. A southwest longitude east of the northeast
+    /// longitude describes a box crossing the antimeridian.
+    pub fn validate(&self) -> LocationQueryResult<()> {
+        for (corner, coords) in [
+            ("southwest", &self.southwest),
+            ("northeast", &self.northeast),
+        ] {
+            coords
+                .validate()
+                .map_err(|e| LocationQueryError::InvalidBounds(format!("{corner} corner: {e}")))?;
+        }
+
+        if self.southwest.latitude >= self.northeast.latitude {
+            return Err(LocationQueryError::InvalidBounds(format!(
+                "southwest latitude {} is not south of northeast latitude {}",
+                self.southwest.latitude, self.northeast.latitude
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self) -> GeoBounds {
+        GeoBounds::from_corners(&self.southwest, &self.northeast)
+    }
+}
+
 /// Location query handler
 pub struct LocationQueryHandler {
     /// In production, this would be a read-optimized store
@@ -119,7 +222,7 @@ impl LocationQueryHandler {
     pub fn find_locations(
         &self,
         query: FindLocationsQuery,
-    ) -> DomainResult<Vec<LocationReadModel>> {
+    ) -> LocationQueryResult<Vec<LocationReadModel>> {
         let mut results: Vec<_> = self
             .locations
             .values()
@@ -155,8 +258,8 @@ impl LocationQueryHandler {
                 }
 
                 // Filter by metadata
-                for (key, value) in &query.metadata_filters {
-                    if location.metadata.get(key) != Some(value) {
+                for (key, filter) in &query.metadata_filters {
+                    if !filter.matches(location.metadata.get(key).map(String::as_str)) {
                         return false;
                     }
                 }
@@ -168,9 +271,10 @@ impl LocationQueryHandler {
 
         // Filter by geographic distance
         if let Some((center_coords, radius)) = query.within_distance_of {
+            validate_radius(radius)?;
             results.retain(|location| {
                 if let Some(ref coords) = location.coordinates {
-                    coords.distance_to(&center_coords) <= radius
+                    coords.distance_to(&center_coords) <= radius.as_meters()
                 } else {
                     false
                 }
@@ -193,16 +297,48 @@ impl LocationQueryHandler {
         Ok(results)
     }
 
+    /// Fuzzy-search active locations by name, best matches first
+    ///
+    /// Unlike the exact `name_pattern` filter of [`Self::find_locations`], this tolerates
+    /// typos: each name is scored with Jaro-Winkler similarity against the query (and
+    /// against every run of words in the name as long as the query), and only scores of
+    /// at least [`NAME_MATCH_THRESHOLD`] are returned.
+    pub fn search_by_name(&self, query: &str, limit: usize) -> Vec<(LocationReadModel, f64)> {
+        let query = normalize_name(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<_> = self
+            .locations
+            .values()
+            .filter(|location| !location.archived)
+            .filter_map(|location| {
+                let score = name_similarity(&query, &normalize_name(&location.name));
+                (score >= NAME_MATCH_THRESHOLD).then(|| (location.clone(), score))
+            })
+            .collect();
+
+        // Highest score first; ties fall back to name order for stable results
+        matches.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.name.cmp(&b.0.name))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
     /// Get location hierarchy
     pub fn get_hierarchy(
         &self,
         query: GetLocationHierarchyQuery,
-    ) -> DomainResult<Vec<LocationHierarchy>> {
+    ) -> LocationQueryResult<Vec<LocationHierarchy>> {
         let root_locations = if let Some(root_id) = query.root_location_id {
             vec![self
                 .locations
                 .get(&root_id)
-                .ok_or_else(|| DomainError::generic(format!("Location {root_id} not found")))?
+                .ok_or(LocationQueryError::LocationNotFound(root_id))?
                 .clone()]
         } else {
             // Find all top-level locations (no parent)
@@ -228,11 +364,138 @@ impl LocationQueryHandler {
         Ok(hierarchies)
     }
 
+    /// Get the ancestors of a location, nearest parent first
+    ///
+    /// Walks `parent_id` links up to the root. A parent that is not in the read
+    /// model ends the walk; archived ancestors are skipped.
+    pub fn get_ancestors(&self, id: Uuid) -> LocationQueryResult<Vec<LocationSummary>> {
+        let location = self
+            .locations
+            .get(&id)
+            .ok_or(LocationQueryError::LocationNotFound(id))?;
+
+        let mut ancestors = Vec::new();
+        let mut visited = HashSet::from([id]);
+        let mut next_parent = location.parent_id;
+
+        while let Some(parent_id) = next_parent {
+            // Guard against cycles in inconsistent data
+            if !visited.insert(parent_id) {
+                break;
+            }
+            let Some(parent) = self.locations.get(&parent_id) else {
+                break;
+            };
+            if !parent.archived {
+                ancestors.push(self.summarize(parent));
+            }
+            next_parent = parent.parent_id;
+        }
+
+        Ok(ancestors)
+    }
+
+    /// Find the lowest common ancestor of two locations
+    ///
+    /// A location counts as its own ancestor, so for a location and one of its
+    /// descendants the location itself is returned. Archived ancestors are
+    /// included. Returns `None` if either location is unknown or they belong to
+    /// different trees.
+    pub fn common_ancestor(&self, a: Uuid, b: Uuid) -> Option<Uuid> {
+        let ancestors_of_a: HashSet<Uuid> = self.ancestor_chain(a).into_iter().collect();
+        self.ancestor_chain(b)
+            .into_iter()
+            .find(|id| ancestors_of_a.contains(id))
+    }
+
+    /// Get the descendants of a location in breadth-first order
+    ///
+    /// Archived locations and everything below them are left out. With
+    /// `max_depth` set, only descendants up to that many levels down are returned.
+    pub fn get_descendants(
+        &self,
+        id: Uuid,
+        max_depth: Option<u32>,
+    ) -> LocationQueryResult<Vec<LocationSummary>> {
+        if !self.locations.contains_key(&id) {
+            return Err(LocationQueryError::LocationNotFound(id));
+        }
+
+        let mut descendants = Vec::new();
+        let mut visited = HashSet::from([id]);
+        let mut queue = VecDeque::from([(id, 0u32)]);
+
+        while let Some((current_id, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+
+            let mut children: Vec<_> = self
+                .locations
+                .values()
+                .filter(|child| child.parent_id == Some(current_id))
+                .filter(|child| !child.archived)
+                .collect();
+            children.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for child in children {
+                if visited.insert(child.id) {
+                    descendants.push(self.summarize(child));
+                    queue.push_back((child.id, depth + 1));
+                }
+            }
+        }
+
+        Ok(descendants)
+    }
+
+    /// Check that placing `child` under `proposed_parent` keeps the hierarchy within `max_depth` levels
+    ///
+    /// A root location is at depth 1. The aggregate cannot see its ancestors, so
+    /// the depth is computed from the read model: the proposed parent's depth plus
+    /// the height of the subtree already below `child`, if it is known.
+    pub fn validate_depth(
+        &self,
+        child: Uuid,
+        proposed_parent: Uuid,
+        max_depth: u32,
+    ) -> LocationQueryResult<()> {
+        let parent = self
+            .locations
+            .get(&proposed_parent)
+            .ok_or(LocationQueryError::LocationNotFound(proposed_parent))?;
+
+        let mut parent_depth = 1;
+        let mut visited = HashSet::from([proposed_parent]);
+        let mut next_parent = parent.parent_id;
+        while let Some(parent_id) = next_parent {
+            // Guard against cycles in inconsistent data
+            if !visited.insert(parent_id) {
+                break;
+            }
+            let Some(ancestor) = self.locations.get(&parent_id) else {
+                break;
+            };
+            parent_depth += 1;
+            next_parent = ancestor.parent_id;
+        }
+
+        let depth = parent_depth + self.subtree_height(child);
+        if depth > max_depth {
+            return Err(LocationQueryError::MaxDepthExceeded { depth, max_depth });
+        }
+
+        Ok(())
+    }
+
     /// Find locations within geographic bounds
     pub fn find_in_bounds(
         &self,
         query: FindLocationsInBoundsQuery,
-    ) -> DomainResult<Vec<LocationReadModel>> {
+    ) -> LocationQueryResult<Vec<LocationReadModel>> {
+        query.validate()?;
+        let bounds = query.bounds();
+
         let results: Vec<_> = self
             .locations
             .values()
@@ -250,14 +513,10 @@ impl LocationQueryHandler {
                 }
 
                 // Filter by geographic bounds
-                if let Some(ref coords) = location.coordinates {
-                    coords.latitude >= query.southwest.latitude
-                        && coords.latitude <= query.northeast.latitude
-                        && coords.longitude >= query.southwest.longitude
-                        && coords.longitude <= query.northeast.longitude
-                } else {
-                    false
-                }
+                location
+                    .coordinates
+                    .as_ref()
+                    .is_some_and(|coords| bounds.contains(coords))
             })
             .cloned()
             .collect();
@@ -269,8 +528,10 @@ impl LocationQueryHandler {
     pub fn find_nearby(
         &self,
         center: GeoCoordinates,
-        radius_meters: f64,
-    ) -> DomainResult<Vec<LocationWithDistance>> {
+        radius: Distance,
+    ) -> LocationQueryResult<Vec<LocationWithDistance>> {
+        validate_radius(radius)?;
+
         let mut results: Vec<_> = self
             .locations
             .values()
@@ -278,7 +539,7 @@ impl LocationQueryHandler {
             .filter_map(|location| {
                 if let Some(ref coords) = location.coordinates {
                     let distance = coords.distance_to(&center);
-                    if distance <= radius_meters {
+                    if distance <= radius.as_meters() {
                         Some(LocationWithDistance {
                             location: location.clone(),
                             distance_meters: Some(distance),
@@ -302,6 +563,59 @@ impl LocationQueryHandler {
         Ok(results)
     }
 
+    /// Group located, non-archived locations into geohash cells for map clustering
+    ///
+    /// `precision` is the geohash length; lower values give larger cells suited to
+    /// zoomed-out views. Locations without coordinates are left out.
+    pub fn cluster_for_zoom(&self, precision: usize) -> HashMap<String, Vec<Uuid>> {
+        self.locations
+            .values()
+            .filter(|location| !location.archived)
+            .filter_map(|location| {
+                let coords = location.coordinates.as_ref()?;
+                Some((coords.geohash(precision), location.id))
+            })
+            .fold(HashMap::new(), |mut clusters, (cell, id)| {
+                clusters.entry(cell).or_insert_with(Vec::new).push(id);
+                clusters
+            })
+    }
+
+    /// Count located, non-archived locations per cell of a `rows` x `cols` grid
+    ///
+    /// The grid spans `bounds` evenly in latitude and longitude. Row 0 is the
+    /// northern edge and column 0 the western edge; points on the far edges are
+    /// counted in the last row or column rather than falling off the grid.
+    /// Locations outside the bounds are not counted.
+    pub fn density_grid(&self, bounds: GeoBounds, rows: usize, cols: usize) -> Vec<Vec<u32>> {
+        let mut grid = vec![vec![0; cols]; rows];
+        if rows == 0 || cols == 0 {
+            return grid;
+        }
+
+        let lat_span = bounds.north - bounds.south;
+        let lon_span = if bounds.crosses_antimeridian {
+            bounds.east - bounds.west + 360.0
+        } else {
+            bounds.east - bounds.west
+        };
+
+        let located = self
+            .locations
+            .values()
+            .filter(|location| !location.archived)
+            .filter_map(|location| location.coordinates.as_ref())
+            .filter(|coords| bounds.contains(coords));
+        for coords in located {
+            let lon_offset = (coords.longitude - bounds.west).rem_euclid(360.0);
+            let row = grid_index(bounds.north - coords.latitude, lat_span, rows);
+            let col = grid_index(lon_offset, lon_span, cols);
+            grid[row][col] += 1;
+        }
+
+        grid
+    }
+
     /// Get location statistics
     pub fn get_statistics(&self) -> LocationStatistics {
         let total = self.locations.len();
@@ -331,6 +645,32 @@ impl LocationQueryHandler {
         }
     }
 
+    /// Render a location's event history as a human-readable change log
+    ///
+    /// Entries follow the order of `events`. The actor recorded on the event
+    /// itself takes precedence over the actor of its envelope.
+    pub fn change_log(&self, events: &[CimDomainEvent]) -> Vec<ChangeLogEntry> {
+        events
+            .iter()
+            .map(|envelope| {
+                let event = serde_json::from_value::<LocationDomainEvent>(envelope.payload.clone());
+                let (actor, description) = match event {
+                    Ok(event) => (event_actor(&event), describe_event(&event)),
+                    Err(_) => (
+                        None,
+                        format!("Unrecognized event '{}'", envelope.event_type),
+                    ),
+                };
+
+                ChangeLogEntry {
+                    timestamp: envelope.metadata.timestamp,
+                    actor: actor.or_else(|| envelope.metadata.actor.clone()),
+                    description,
+                }
+            })
+            .collect()
+    }
+
     // Helper method to build hierarchy recursively
     fn build_hierarchy_recursive(
         &self,
@@ -339,14 +679,7 @@ impl LocationQueryHandler {
         max_depth: u32,
         include_archived: bool,
     ) -> LocationHierarchy {
-        let summary = LocationSummary {
-            id: location.id,
-            name: location.name.clone(),
-            location_type: location.location_type.clone(),
-            formatted_address: location.address.as_ref().map(|a| a.format_single_line()),
-            parent_name: None, // Could be populated if needed
-            archived: location.archived,
-        };
+        let summary = self.summarize(location);
 
         let children = if depth < max_depth {
             self.locations
@@ -367,6 +700,62 @@ impl LocationQueryHandler {
             depth,
         }
     }
+
+    /// The location followed by its ancestors up to the root
+    ///
+    /// Only locations present in the read model are included.
+    fn ancestor_chain(&self, id: Uuid) -> Vec<Uuid> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = Some(id);
+
+        while let Some(current_id) = next {
+            // Guard against cycles in inconsistent data
+            if !visited.insert(current_id) {
+                break;
+            }
+            let Some(location) = self.locations.get(&current_id) else {
+                break;
+            };
+            chain.push(current_id);
+            next = location.parent_id;
+        }
+
+        chain
+    }
+
+    // Helper method to count the levels of a subtree, including its root
+    fn subtree_height(&self, root: Uuid) -> u32 {
+        let mut height = 0;
+        let mut visited = HashSet::from([root]);
+        let mut queue = VecDeque::from([(root, 1u32)]);
+
+        while let Some((current_id, depth)) = queue.pop_front() {
+            height = height.max(depth);
+            for child in self.locations.values() {
+                if child.parent_id == Some(current_id) && visited.insert(child.id) {
+                    queue.push_back((child.id, depth + 1));
+                }
+            }
+        }
+
+        height
+    }
+
+    // Helper method to build a summary view of a location
+    fn summarize(&self, location: &LocationReadModel) -> LocationSummary {
+        LocationSummary {
+            id: location.id,
+            name: location.name.clone(),
+            location_type: location.location_type.clone(),
+            formatted_address: location.address.as_ref().map(|a| a.format_single_line()),
+            parent_name: location
+                .parent_id
+                .and_then(|parent_id| self.locations.get(&parent_id))
+                .map(|parent| parent.name.clone()),
+            archived: location.archived,
+        }
+    }
 }
 
 /// Location statistics
@@ -379,8 +768,964 @@ pub struct LocationStatistics {
     pub with_coordinates: usize,
 }
 
+/// One entry of a location's change log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeLogEntry {
+    /// When the change was recorded
+    pub timestamp: SystemTime,
+    /// Who made the change, if known
+    pub actor: Option<ActorId>,
+    /// What changed, e.g. "Renamed 'Depot' to 'Warehouse' (Rebranding)"
+    pub description: String,
+}
+
+/// Actor recorded on the event itself, for events that carry one
+fn event_actor(event: &LocationDomainEvent) -> Option<ActorId> {
+    match event {
+        LocationDomainEvent::LocationDefined(e) => e.actor.clone(),
+        LocationDomainEvent::LocationUpdated(e) => e.actor.clone(),
+        LocationDomainEvent::LocationArchived(e) => e.actor.clone(),
+        LocationDomainEvent::LocationRestored(e) => e.actor.clone(),
+        _ => None,
+    }
+}
+
+fn describe_event(event: &LocationDomainEvent) -> String {
+    match event {
+        LocationDomainEvent::LocationDefined(e) => {
+            format!("Defined {} location '{}'", e.location_type, e.name)
+        }
+        LocationDomainEvent::LocationUpdated(e) => {
+            let mut changes = Vec::new();
+            if let Some(name) = &e.name {
+                match &e.previous_name {
+                    Some(previous) => changes.push(format!("renamed '{previous}' to '{name}'")),
+                    None => changes.push(format!("named '{name}'")),
+                }
+            }
+            if e.address.is_some() {
+                changes.push("changed address".to_string());
+            }
+            if e.coordinates.is_some() {
+                changes.push("changed coordinates".to_string());
+            }
+            if e.virtual_location.is_some() {
+                changes.push("changed virtual location".to_string());
+            }
+
+            let summary = if changes.is_empty() {
+                "Updated location".to_string()
+            } else {
+                let mut summary = changes.join(", ");
+                summary[..1].make_ascii_uppercase();
+                summary
+            };
+            format!("{summary} ({})", e.reason)
+        }
+        LocationDomainEvent::LocationMoved(e) => format!(
+            "Moved {} to ({:.6}, {:.6})",
+            Distance::from_meters(e.distance_meters),
+            e.coordinates.latitude,
+            e.coordinates.longitude
+        ),
+        LocationDomainEvent::ParentLocationSet(e) => {
+            format!("Set parent to {} ({})", e.parent_id, e.reason)
+        }
+        LocationDomainEvent::ParentLocationRemoved(e) => {
+            format!("Removed parent {} ({})", e.previous_parent_id, e.reason)
+        }
+        LocationDomainEvent::LocationMetadataAdded(e) => {
+            format!(
+                "Added metadata {} ({})",
+                sorted_keys(&e.added_metadata),
+                e.reason
+            )
+        }
+        LocationDomainEvent::LocationMetadataUpdated(e) => format!(
+            "Updated metadata {} ({})",
+            sorted_keys(&e.updated_metadata),
+            e.reason
+        ),
+        LocationDomainEvent::LocationMetadataRemoved(e) => {
+            format!("Removed metadata '{}' ({})", e.key, e.reason)
+        }
+        LocationDomainEvent::LocationArchived(e) => {
+            format!("Archived location '{}' ({})", e.name, e.reason)
+        }
+        LocationDomainEvent::LocationRestored(e) => {
+            format!("Restored location '{}' ({})", e.name, e.reason)
+        }
+        LocationDomainEvent::LocationCheckedIn(e) => {
+            format!("User {} checked in (occupancy {})", e.user_id, e.occupancy)
+        }
+        LocationDomainEvent::LocationCheckedOut(e) => {
+            format!("User {} checked out (occupancy {})", e.user_id, e.occupancy)
+        }
+        LocationDomainEvent::LocationsMerged(e) => {
+            format!(
+                "Merged location {} into this one ({})",
+                e.merged_location_id, e.reason
+            )
+        }
+        LocationDomainEvent::LocationDeleted(e) => {
+            format!("Deleted {} location ({})", e.location_type, e.reason)
+        }
+        LocationDomainEvent::AddressGeocoded(e) => format!(
+            "Geocoded address via {} to ({:.6}, {:.6})",
+            e.provider, e.coordinates.latitude, e.coordinates.longitude
+        ),
+        LocationDomainEvent::HierarchyReorganized(e) => format!(
+            "Reorganized hierarchy of {} locations ({})",
+            e.affected.len(),
+            e.reason
+        ),
+        LocationDomainEvent::CoordinatesUpdated(e) => format!(
+            "Corrected coordinates to ({:.6}, {:.6}) ({})",
+            e.coordinates.latitude, e.coordinates.longitude, e.reason
+        ),
+        LocationDomainEvent::CoordinatesValidated(e) => format!(
+            "Validated coordinates via {}: {} the address, {:.0} m from its geocoded point",
+            e.provider,
+            if e.mismatch { "do not match" } else { "match" },
+            e.distance_meters
+        ),
+        LocationDomainEvent::LocationAddressSet(e) => {
+            format!("Set {:?} address ({})", e.role, e.reason)
+        }
+        LocationDomainEvent::IndoorPositionSet(e) => format!(
+            "Placed in {} on floor {} ({})",
+            e.position.building, e.position.floor, e.reason
+        ),
+    }
+}
+
+/// Metadata keys in a stable order, e.g. `'dock', 'gate'`
+fn sorted_keys(metadata: &HashMap<String, String>) -> String {
+    let mut keys: Vec<&String> = metadata.keys().collect();
+    keys.sort();
+    keys.iter()
+        .map(|key| format!("'{key}'"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Minimum Jaro-Winkler similarity for [`LocationQueryHandler::search_by_name`]
+pub const NAME_MATCH_THRESHOLD: f64 = 0.85;
+
+/// Lowercase and collapse whitespace so spacing and case never affect scores
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Best similarity between the query and the whole name or any same-length run of its words
+fn name_similarity(query: &str, name: &str) -> f64 {
+    let query_words = query.split(' ').count();
+    let name_words: Vec<&str> = name.split(' ').collect();
+
+    name_words
+        .windows(query_words.min(name_words.len()))
+        .map(|window| jaro_winkler(query, &window.join(" ")))
+        .fold(jaro_winkler(query, name), f64::max)
+}
+
+/// Jaro-Winkler similarity in `[0, 1]`, boosting strings that share a prefix
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if let Some(j) = (start..end).find(|&j| !b_matched[j] && b[j] == *ca) {
+            a_matched[i] = true;
+            b_matched[j] = true;
+            matches += 1;
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_sequence = a
+        .iter()
+        .zip(&a_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_sequence = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_sequence.zip(b_sequence).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// Reject negative or non-finite search radii
+fn validate_radius(radius: Distance) -> LocationQueryResult<()> {
+    let meters = radius.as_meters();
+    if meters.is_finite() && meters >= 0.0 {
+        Ok(())
+    } else {
+        Err(LocationQueryError::InvalidRadius(meters))
+    }
+}
+
+/// Cell along one grid axis for a point `offset` into a `span` split into `cells`
+///
+/// Points on the far edge are clamped into the last cell.
+fn grid_index(offset: f64, span: f64, cells: usize) -> usize {
+    if span <= 0.0 {
+        return 0;
+    }
+    ((offset / span * cells as f64).floor() as usize).min(cells - 1)
+}
+
 impl Default for LocationQueryHandler {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cim_domain::EntityId;
+
+    /// Campus -> Building A -> Floor 3, plus an archived Building B
+    fn campus_hierarchy() -> (LocationQueryHandler, Uuid, Uuid, Uuid, Uuid) {
+        let mut handler = LocationQueryHandler::new();
+
+        let campus_id = Uuid::now_v7();
+        let campus = Location::new_from_coordinates(
+            EntityId::from_uuid(campus_id),
+            "Tech Campus".to_string(),
+            GeoCoordinates::new(37.7749, -122.4194),
+        )
+        .unwrap();
+
+        let building_id = Uuid::now_v7();
+        let mut building = Location::new_from_coordinates(
+            EntityId::from_uuid(building_id),
+            "Building A".to_string(),
+            GeoCoordinates::new(37.7750, -122.4195),
+        )
+        .unwrap();
+        building.set_parent(EntityId::from_uuid(campus_id)).unwrap();
+
+        let floor_id = Uuid::now_v7();
+        let mut floor = Location::new_from_coordinates(
+            EntityId::from_uuid(floor_id),
+            "Floor 3".to_string(),
+            GeoCoordinates::new(37.7750, -122.4195),
+        )
+        .unwrap();
+        floor.set_parent(EntityId::from_uuid(building_id)).unwrap();
+
+        let archived_id = Uuid::now_v7();
+        let mut archived = Location::new_from_coordinates(
+            EntityId::from_uuid(archived_id),
+            "Building B".to_string(),
+            GeoCoordinates::new(37.7751, -122.4196),
+        )
+        .unwrap();
+        archived.set_parent(EntityId::from_uuid(campus_id)).unwrap();
+        archived.archive().unwrap();
+
+        handler.upsert_location(&campus);
+        handler.upsert_location(&building);
+        handler.upsert_location(&floor);
+        handler.upsert_location(&archived);
+
+        (handler, campus_id, building_id, floor_id, archived_id)
+    }
+
+    #[test]
+    fn test_get_ancestors() {
+        let (handler, campus_id, building_id, floor_id, _) = campus_hierarchy();
+
+        let ancestors = handler.get_ancestors(floor_id).unwrap();
+        let ids: Vec<_> = ancestors.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![building_id, campus_id]);
+        assert_eq!(ancestors[0].parent_name.as_deref(), Some("Tech Campus"));
+
+        assert!(handler.get_ancestors(campus_id).unwrap().is_empty());
+        assert!(handler.get_ancestors(Uuid::now_v7()).is_err());
+    }
+
+    #[test]
+    fn test_get_ancestors_stops_at_missing_parent() {
+        let mut handler = LocationQueryHandler::new();
+        let orphan_id = Uuid::now_v7();
+        let mut orphan = Location::new_from_coordinates(
+            EntityId::from_uuid(orphan_id),
+            "Orphan".to_string(),
+            GeoCoordinates::new(0.0, 0.0),
+        )
+        .unwrap();
+        orphan.set_parent(EntityId::new()).unwrap();
+        handler.upsert_location(&orphan);
+
+        assert!(handler.get_ancestors(orphan_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_descendants() {
+        let (handler, campus_id, building_id, floor_id, archived_id) = campus_hierarchy();
+
+        let descendants = handler.get_descendants(campus_id, None).unwrap();
+        let ids: Vec<_> = descendants.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![building_id, floor_id]);
+        assert!(!ids.contains(&archived_id));
+
+        let direct = handler.get_descendants(campus_id, Some(1)).unwrap();
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct[0].name, "Building A");
+
+        assert!(handler.get_descendants(floor_id, None).unwrap().is_empty());
+        assert!(handler.get_descendants(Uuid::now_v7(), None).is_err());
+    }
+
+    /// Add a location under `parent` and return its ID
+    fn add_child(handler: &mut LocationQueryHandler, name: &str, parent: Uuid) -> Uuid {
+        let id = Uuid::now_v7();
+        let mut location = Location::new_from_coordinates(
+            EntityId::from_uuid(id),
+            name.to_string(),
+            GeoCoordinates::new(37.7750, -122.4195),
+        )
+        .unwrap();
+        location.set_parent(EntityId::from_uuid(parent)).unwrap();
+        handler.upsert_location(&location);
+        id
+    }
+
+    #[test]
+    fn test_common_ancestor() {
+        let (mut handler, campus_id, building_id, floor_id, _) = campus_hierarchy();
+        let floor_4 = add_child(&mut handler, "Floor 4", building_id);
+        let building_c = add_child(&mut handler, "Building C", campus_id);
+        let floor_1 = add_child(&mut handler, "Floor 1", building_c);
+        let other_root = location_chain(&mut handler, 2);
+
+        // Floors of the same building share the building
+        assert_eq!(
+            handler.common_ancestor(floor_id, floor_4),
+            Some(building_id)
+        );
+        // Floors of different buildings only share the campus
+        assert_eq!(handler.common_ancestor(floor_id, floor_1), Some(campus_id));
+        assert_eq!(handler.common_ancestor(floor_1, floor_id), Some(campus_id));
+        // A location is its own ancestor
+        assert_eq!(
+            handler.common_ancestor(building_id, floor_4),
+            Some(building_id)
+        );
+        assert_eq!(handler.common_ancestor(floor_id, floor_id), Some(floor_id));
+
+        // Separate trees and unknown locations have no common ancestor
+        assert_eq!(handler.common_ancestor(floor_id, other_root[1]), None);
+        assert_eq!(handler.common_ancestor(campus_id, other_root[0]), None);
+        assert_eq!(handler.common_ancestor(floor_id, Uuid::now_v7()), None);
+    }
+
+    /// A chain of `levels` nested locations, root first
+    fn location_chain(handler: &mut LocationQueryHandler, levels: usize) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = Vec::new();
+        for level in 0..levels {
+            let id = Uuid::now_v7();
+            let mut location = Location::new_from_coordinates(
+                EntityId::from_uuid(id),
+                format!("Level {}", level + 1),
+                GeoCoordinates::new(37.7749, -122.4194),
+            )
+            .unwrap();
+            if let Some(parent_id) = ids.last() {
+                location
+                    .set_parent(EntityId::from_uuid(*parent_id))
+                    .unwrap();
+            }
+            handler.upsert_location(&location);
+            ids.push(id);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_validate_depth_below_and_at_limit() {
+        let mut handler = LocationQueryHandler::new();
+        let chain = location_chain(&mut handler, 5);
+
+        // New leaf under level 3 lands at depth 4
+        assert!(handler.validate_depth(Uuid::now_v7(), chain[2], 6).is_ok());
+        // New leaf under level 5 lands exactly at the limit
+        assert!(handler.validate_depth(Uuid::now_v7(), chain[4], 6).is_ok());
+    }
+
+    #[test]
+    fn test_validate_depth_above_limit() {
+        let mut handler = LocationQueryHandler::new();
+        let chain = location_chain(&mut handler, 6);
+
+        let error = handler
+            .validate_depth(Uuid::now_v7(), chain[5], 6)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            LocationQueryError::MaxDepthExceeded {
+                depth: 7,
+                max_depth: 6
+            }
+        );
+        assert!(matches!(
+            DomainError::from(error),
+            DomainError::ValidationError(_)
+        ));
+
+        // Moving an existing two-level subtree under level 5 also overflows
+        let subtree = location_chain(&mut handler, 2);
+        assert!(handler.validate_depth(subtree[0], chain[3], 6).is_ok());
+        assert!(handler.validate_depth(subtree[0], chain[4], 6).is_err());
+
+        let missing = Uuid::now_v7();
+        assert_eq!(
+            handler.validate_depth(subtree[0], missing, 6),
+            Err(LocationQueryError::LocationNotFound(missing))
+        );
+    }
+
+    #[test]
+    fn test_get_hierarchy_not_found_is_typed() {
+        let (handler, ..) = campus_hierarchy();
+        let missing = Uuid::now_v7();
+
+        let error = handler
+            .get_hierarchy(GetLocationHierarchyQuery {
+                root_location_id: Some(missing),
+                max_depth: None,
+                include_archived: false,
+            })
+            .unwrap_err();
+
+        assert_eq!(error, LocationQueryError::LocationNotFound(missing));
+        assert!(DomainError::from(error)
+            .to_string()
+            .contains(&missing.to_string()));
+    }
+
+    #[test]
+    fn test_restored_location_reappears_in_find_nearby() {
+        let mut handler = LocationQueryHandler::new();
+        let center = GeoCoordinates::new(37.7749, -122.4194);
+        let mut location =
+            Location::new_from_coordinates(EntityId::new(), "Depot".to_string(), center.clone())
+                .unwrap();
+        let nearby = |handler: &LocationQueryHandler| {
+            handler
+                .find_nearby(center.clone(), Distance::from_meters(100.0))
+                .unwrap()
+                .len()
+        };
+
+        location.archive().unwrap();
+        handler.upsert_location(&location);
+        assert_eq!(nearby(&handler), 0);
+
+        location.restore().unwrap();
+        handler.upsert_location(&location);
+        assert_eq!(nearby(&handler), 1);
+    }
+
+    #[test]
+    fn test_invalid_radius_and_bounds_are_rejected() {
+        let (handler, ..) = campus_hierarchy();
+
+        let error = handler
+            .find_nearby(
+                GeoCoordinates::new(37.7749, -122.4194),
+                Distance::from_meters(-5.0),
+            )
+            .unwrap_err();
+        assert_eq!(error, LocationQueryError::InvalidRadius(-5.0));
+
+        let error = handler
+            .find_in_bounds(FindLocationsInBoundsQuery {
+                southwest: GeoCoordinates::new(38.0, -123.0),
+                northeast: GeoCoordinates::new(37.0, -122.0),
+                location_types: None,
+                include_archived: false,
+            })
+            .unwrap_err();
+        assert!(matches!(error, LocationQueryError::InvalidBounds(_)));
+        assert!(matches!(
+            DomainError::from(error),
+            DomainError::ValidationError(_)
+        ));
+    }
+
+    fn bounds_query(
+        southwest: GeoCoordinates,
+        northeast: GeoCoordinates,
+    ) -> FindLocationsInBoundsQuery {
+        FindLocationsInBoundsQuery {
+            southwest,
+            northeast,
+            location_types: None,
+            include_archived: false,
+        }
+    }
+
+    #[test]
+    fn test_find_in_bounds_valid_box() {
+        let (handler, ..) = campus_hierarchy();
+        let query = bounds_query(
+            GeoCoordinates::new(37.7, -122.5),
+            GeoCoordinates::new(37.8, -122.4),
+        );
+
+        assert!(query.validate().is_ok());
+        // Archived Building B is excluded
+        assert_eq!(handler.find_in_bounds(query).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_find_in_bounds_rejects_swapped_and_out_of_range_boxes() {
+        let (handler, ..) = campus_hierarchy();
+
+        let swapped = bounds_query(
+            GeoCoordinates::new(37.8, -122.4),
+            GeoCoordinates::new(37.7, -122.5),
+        );
+        assert!(matches!(
+            handler.find_in_bounds(swapped),
+            Err(LocationQueryError::InvalidBounds(_))
+        ));
+
+        let out_of_range = bounds_query(
+            GeoCoordinates::new(-95.0, 0.0),
+            GeoCoordinates::new(10.0, 10.0),
+        );
+        assert!(matches!(
+            out_of_range.validate(),
+            Err(LocationQueryError::InvalidBounds(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_in_bounds_across_antimeridian() {
+        let mut handler = LocationQueryHandler::new();
+        for (name, lat, lon) in [
+            ("Fiji", -17.7, 178.0),
+            ("Samoa", -13.8, -172.1),
+            ("Sydney", -33.9, 151.2),
+        ] {
+            let location = Location::new_from_coordinates(
+                EntityId::new(),
+                name.to_string(),
+                GeoCoordinates::new(lat, lon),
+            )
+            .unwrap();
+            handler.upsert_location(&location);
+        }
+
+        let query = bounds_query(
+            GeoCoordinates::new(-25.0, 170.0),
+            GeoCoordinates::new(-10.0, -165.0),
+        );
+        assert!(query.validate().is_ok());
+
+        let mut names: Vec<_> = handler
+            .find_in_bounds(query)
+            .unwrap()
+            .into_iter()
+            .map(|location| location.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Fiji", "Samoa"]);
+    }
+
+    #[test]
+    fn test_find_in_bounds_excludes_prime_meridian_when_crossing() {
+        let mut handler = LocationQueryHandler::new();
+        for (name, lon) in [("Dateline", 179.0), ("Greenwich", 0.0)] {
+            let location = Location::new_from_coordinates(
+                EntityId::new(),
+                name.to_string(),
+                GeoCoordinates::new(0.0, lon),
+            )
+            .unwrap();
+            handler.upsert_location(&location);
+        }
+
+        let query = bounds_query(
+            GeoCoordinates::new(-10.0, 170.0),
+            GeoCoordinates::new(10.0, -170.0),
+        );
+        let names: Vec<_> = handler
+            .find_in_bounds(query)
+            .unwrap()
+            .into_iter()
+            .map(|location| location.name)
+            .collect();
+        assert_eq!(names, vec!["Dateline"]);
+    }
+
+    #[test]
+    fn test_cluster_for_zoom_buckets_by_geohash() {
+        let mut handler = LocationQueryHandler::new();
+        let mut add = |lat: f64, lon: f64| {
+            let location = Location::new_from_coordinates(
+                EntityId::new(),
+                "Marker".to_string(),
+                GeoCoordinates::new(lat, lon),
+            )
+            .unwrap();
+            handler.upsert_location(&location);
+            *location.id().as_uuid()
+        };
+        let sf = add(37.7749, -122.4194);
+        let sf_north = add(37.7793, -122.4193);
+        let tokyo = add(35.6762, 139.6503);
+
+        let clusters = handler.cluster_for_zoom(5);
+        assert_eq!(clusters.len(), 2);
+        let mut bay_area = clusters["9q8yy"].clone();
+        bay_area.sort();
+        let mut expected = vec![sf, sf_north];
+        expected.sort();
+        assert_eq!(bay_area, expected);
+        assert_eq!(clusters["xn76c"], vec![tokyo]);
+
+        // Zooming in splits the San Francisco cluster
+        assert_eq!(handler.cluster_for_zoom(6).len(), 3);
+    }
+
+    #[test]
+    fn test_density_grid_buckets_by_cell() {
+        let mut handler = LocationQueryHandler::new();
+        let mut add = |lat: f64, lon: f64| {
+            let location = Location::new_from_coordinates(
+                EntityId::new(),
+                "Marker".to_string(),
+                GeoCoordinates::new(lat, lon),
+            )
+            .unwrap();
+            handler.upsert_location(&location);
+            *location.id().as_uuid()
+        };
+
+        // 2 rows of 5 degrees and 4 columns of 10 degrees over 0..10N, 0..40E
+        add(7.5, 5.0); // row 0, col 0
+        add(7.5, 6.0); // row 0, col 0
+        add(2.5, 25.0); // row 1, col 2
+        add(10.0, 0.0); // north-west corner: row 0, col 0
+        add(0.0, 40.0); // south-east corner: row 1, col 3
+        add(5.0, 10.0); // on the inner lines: row 1, col 1
+        add(10.0, 40.0); // north-east corner: row 0, col 3
+        add(20.0, 20.0); // outside the bounds
+        let archived = add(2.5, 35.0);
+        handler.locations.get_mut(&archived).unwrap().archived = true;
+
+        let bounds = GeoBounds::from_corners(
+            &GeoCoordinates::new(0.0, 0.0),
+            &GeoCoordinates::new(10.0, 40.0),
+        );
+        assert_eq!(
+            handler.density_grid(bounds.clone(), 2, 4),
+            vec![vec![3, 0, 0, 1], vec![0, 1, 1, 1]]
+        );
+        assert_eq!(handler.density_grid(bounds.clone(), 1, 1), vec![vec![7]]);
+        assert!(handler.density_grid(bounds, 0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_density_grid_across_antimeridian() {
+        let mut handler = LocationQueryHandler::new();
+        for lon in [171.0, 179.9, -179.9, -171.0, 0.0] {
+            let location = Location::new_from_coordinates(
+                EntityId::new(),
+                "Marker".to_string(),
+                GeoCoordinates::new(0.0, lon),
+            )
+            .unwrap();
+            handler.upsert_location(&location);
+        }
+
+        // Columns of 5 degrees: 170..175E, 175..180, 180..175W, 175W..170W
+        let bounds = GeoBounds::from_corners(
+            &GeoCoordinates::new(-10.0, 170.0),
+            &GeoCoordinates::new(10.0, -170.0),
+        );
+        assert_eq!(handler.density_grid(bounds, 1, 4), vec![vec![1, 1, 1, 1]]);
+    }
+
+    fn location_with_metadata(
+        handler: &mut LocationQueryHandler,
+        name: &str,
+        metadata: &[(&str, &str)],
+    ) -> Uuid {
+        let id = Uuid::now_v7();
+        let mut location = Location::new_from_coordinates(
+            EntityId::from_uuid(id),
+            name.to_string(),
+            GeoCoordinates::new(37.7749, -122.4194),
+        )
+        .unwrap();
+        for (key, value) in metadata {
+            location
+                .add_metadata(key.to_string(), value.to_string())
+                .unwrap();
+        }
+        handler.upsert_location(&location);
+        id
+    }
+
+    fn metadata_query(filters: Vec<(&str, MetadataFilter)>) -> FindLocationsQuery {
+        FindLocationsQuery {
+            name_pattern: None,
+            location_type: None,
+            within_distance_of: None,
+            parent_id: None,
+            metadata_filters: filters
+                .into_iter()
+                .map(|(key, filter)| (key.to_string(), filter))
+                .collect(),
+            include_archived: false,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    fn sorted_ids(results: Vec<LocationReadModel>) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = results.into_iter().map(|location| location.id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_find_locations_by_numeric_metadata() {
+        let mut handler = LocationQueryHandler::new();
+        let hall = location_with_metadata(&mut handler, "Hall", &[("capacity", "120")]);
+        let room = location_with_metadata(&mut handler, "Room", &[("capacity", "50")]);
+        let closet = location_with_metadata(&mut handler, "Closet", &[("capacity", "4")]);
+        location_with_metadata(&mut handler, "Annex", &[("capacity", "unknown")]);
+        location_with_metadata(&mut handler, "Lobby", &[]);
+
+        let large = handler
+            .find_locations(metadata_query(vec![(
+                "capacity",
+                MetadataFilter::NumericGreaterThan(49.0),
+            )]))
+            .unwrap();
+        let mut expected = vec![hall, room];
+        expected.sort();
+        assert_eq!(sorted_ids(large), expected);
+
+        // Comparisons are strict, so a value equal to the bound is excluded
+        let above_room = handler
+            .find_locations(metadata_query(vec![(
+                "capacity",
+                MetadataFilter::NumericGreaterThan(50.0),
+            )]))
+            .unwrap();
+        assert_eq!(sorted_ids(above_room), vec![hall]);
+
+        let small = handler
+            .find_locations(metadata_query(vec![(
+                "capacity",
+                MetadataFilter::NumericLessThan(10.0),
+            )]))
+            .unwrap();
+        assert_eq!(sorted_ids(small), vec![closet]);
+
+        let below_closet = handler
+            .find_locations(metadata_query(vec![(
+                "capacity",
+                MetadataFilter::NumericLessThan(4.0),
+            )]))
+            .unwrap();
+        assert!(below_closet.is_empty());
+    }
+
+    #[test]
+    fn test_find_locations_by_metadata_existence() {
+        let mut handler = LocationQueryHandler::new();
+        let cafe = location_with_metadata(
+            &mut handler,
+            "Cafe",
+            &[("wifi", "guest-network"), ("capacity", "30")],
+        );
+        let office = location_with_metadata(&mut handler, "Office", &[("wifi", "corp")]);
+        let garage = location_with_metadata(&mut handler, "Garage", &[("capacity", "2")]);
+
+        let with_wifi = handler
+            .find_locations(metadata_query(vec![("wifi", MetadataFilter::Exists)]))
+            .unwrap();
+        let mut expected = vec![cafe, office];
+        expected.sort();
+        assert_eq!(sorted_ids(with_wifi), expected);
+
+        // Filters combine, and NotEquals passes locations without the key
+        let combined = handler
+            .find_locations(metadata_query(vec![
+                ("wifi", MetadataFilter::NotEquals("corp".to_string())),
+                ("capacity", MetadataFilter::Exists),
+            ]))
+            .unwrap();
+        let mut expected = vec![cafe, garage];
+        expected.sort();
+        assert_eq!(sorted_ids(combined), expected);
+
+        let guest = handler
+            .find_locations(metadata_query(vec![(
+                "wifi",
+                MetadataFilter::Contains("guest".to_string()),
+            )]))
+            .unwrap();
+        assert_eq!(sorted_ids(guest), vec![cafe]);
+    }
+
+    fn named_location(handler: &mut LocationQueryHandler, name: &str) -> Uuid {
+        let id = Uuid::now_v7();
+        let location = Location::new_from_coordinates(
+            EntityId::from_uuid(id),
+            name.to_string(),
+            GeoCoordinates::new(37.7749, -122.4194),
+        )
+        .unwrap();
+        handler.upsert_location(&location);
+        id
+    }
+
+    #[test]
+    fn test_search_by_name_tolerates_typos() {
+        let mut handler = LocationQueryHandler::new();
+        let sf_office = named_location(&mut handler, "SF Office");
+        named_location(&mut handler, "Tokyo Warehouse");
+        named_location(&mut handler, "Berlin Data Center");
+
+        let results = handler.search_by_name("SF Offce", 10);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.id, sf_office);
+        assert!(results[0].1 >= NAME_MATCH_THRESHOLD);
+
+        // The exact filter still requires a literal substring
+        let query = FindLocationsQuery {
+            name_pattern: Some("SF Offce".to_string()),
+            location_type: None,
+            within_distance_of: None,
+            parent_id: None,
+            metadata_filters: Vec::new(),
+            include_archived: false,
+            limit: None,
+            offset: None,
+        };
+        assert!(handler.find_locations(query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_by_name_ranks_and_limits() {
+        let mut handler = LocationQueryHandler::new();
+        let warehouse = named_location(&mut handler, "Tokyo Warehouse");
+        let wharf = named_location(&mut handler, "Tokyo Wharf");
+        named_location(&mut handler, "Osaka Warehouse");
+
+        let results = handler.search_by_name("Tokyo Warehose", 10);
+        let ids: Vec<Uuid> = results.iter().map(|(location, _)| location.id).collect();
+
+        assert_eq!(ids, vec![warehouse, wharf]);
+        assert!(results[0].1 > results[1].1);
+        assert_eq!(handler.search_by_name("Tokyo Warehose", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_search_by_name_unrelated_query_returns_nothing() {
+        let mut handler = LocationQueryHandler::new();
+        named_location(&mut handler, "SF Office");
+        named_location(&mut handler, "Tokyo Warehouse");
+
+        assert!(handler.search_by_name("Quantum Bakery", 10).is_empty());
+        assert!(handler.search_by_name("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_change_log_describes_history_in_order() {
+        use crate::events::{LocationArchived, LocationDefined, LocationUpdated};
+        use crate::infrastructure::chain_events;
+
+        let location_id = Uuid::now_v7();
+        let user = ActorId::user(Uuid::now_v7());
+        let events = vec![
+            LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id,
+                name: "Depot".to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(GeoCoordinates::new(37.7749, -122.4194)),
+                virtual_location: None,
+                parent_id: None,
+                actor: Some(user.clone()),
+            }),
+            LocationDomainEvent::LocationUpdated(LocationUpdated {
+                location_id,
+                previous_name: Some("Depot".to_string()),
+                name: Some("Warehouse".to_string()),
+                previous_address: None,
+                address: None,
+                previous_coordinates: None,
+                coordinates: None,
+                previous_virtual_location: None,
+                virtual_location: None,
+                reason: "Rebranding".to_string(),
+                actor: Some(user.clone()),
+            }),
+            LocationDomainEvent::LocationArchived(LocationArchived {
+                location_id,
+                name: "Warehouse".to_string(),
+                location_type: LocationType::Physical,
+                reason: "Closed".to_string(),
+                actor: None,
+            }),
+        ];
+        let history = chain_events(None, &events).unwrap();
+
+        let log = LocationQueryHandler::new().change_log(&history);
+        let descriptions: Vec<&str> = log.iter().map(|e| e.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Defined Physical location 'Depot'",
+                "Renamed 'Depot' to 'Warehouse' (Rebranding)",
+                "Archived location 'Warehouse' (Closed)",
+            ]
+        );
+
+        assert_eq!(log[0].actor, Some(user.clone()));
+        assert_eq!(log[1].actor, Some(user));
+        // Without an actor on the event, the envelope's actor is reported
+        assert_eq!(log[2].actor, Some(ActorId::system("location-repository")));
+        assert!(log[0].timestamp <= log[2].timestamp);
+    }
+}
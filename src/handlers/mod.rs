@@ -1,10 +1,12 @@
 //! Location domain handlers
 
 pub mod authentication_event_handler;
+pub mod idempotency;
 pub mod location_command_handler;
 pub mod location_query_handler;
 
 pub use authentication_event_handler::*;
+pub use idempotency::*;
 pub use location_command_handler::*;
 pub use location_query_handler::*;
 
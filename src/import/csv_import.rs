@@ -0,0 +1,111 @@
+//! CSV location import
+
+use super::{ImportError, ImportRow};
+use crate::commands::DefineLocation;
+use std::io::Read;
+
+/// Stream `DefineLocation` commands from CSV with a header row
+///
+/// Columns are matched by header name, ignoring unknown columns and
+/// surrounding whitespace. Empty cells count as missing.
+pub fn import_csv<R: Read>(reader: R) -> impl Iterator<Item = Result<DefineLocation, ImportError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let (headers, header_error) = match reader.headers() {
+        Ok(headers) => (Some(headers.clone()), None),
+        Err(e) => (None, Some(ImportError::new(1, e.to_string()))),
+    };
+
+    CsvImport {
+        records: reader.into_records(),
+        headers,
+        header_error,
+    }
+}
+
+struct CsvImport<R> {
+    records: csv::StringRecordsIntoIter<R>,
+    headers: Option<csv::StringRecord>,
+    header_error: Option<ImportError>,
+}
+
+impl<R: Read> Iterator for CsvImport<R> {
+    type Item = Result<DefineLocation, ImportError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.header_error.take() {
+            return Some(Err(error));
+        }
+        // Without a header row no record can be mapped
+        let headers = self.headers.as_ref()?;
+
+        let result = match self.records.next()? {
+            Ok(record) => {
+                let line = record.position().map_or(0, |position| position.line());
+                record
+                    .deserialize::<ImportRow>(Some(headers))
+                    .map_err(|e| e.to_string())
+                    .and_then(ImportRow::into_command)
+                    .map_err(|message| ImportError::new(line, message))
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |position| position.line());
+                Err(ImportError::new(line, e.to_string()))
+            }
+        };
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::LocationType;
+
+    const PLACES: &str = "\
+name,lat,lng,type,street,city,region,country,postal_code
+Ferry Building,37.7955,-122.3937,physical,1 Ferry Building,San Francisco,CA,USA,94111
+Coit Tower,not-a-number,-122.4058,,,,,,
+Sales,,,logical,,,,,
+Alcatraz,37.8267,-122.4230,,,,,,
+";
+
+    #[test]
+    fn test_malformed_row_reports_line_and_import_continues() {
+        let results: Vec<_> = import_csv(PLACES.as_bytes()).collect();
+        assert_eq!(results.len(), 4);
+
+        let ferry = results[0].as_ref().unwrap();
+        assert_eq!(ferry.name, "Ferry Building");
+        assert_eq!(ferry.location_type, LocationType::Physical);
+        assert_eq!(ferry.address.as_ref().unwrap().locality, "San Francisco");
+        assert_eq!(ferry.coordinates.as_ref().unwrap().latitude, 37.7955);
+
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let sales = results[2].as_ref().unwrap();
+        assert_eq!(sales.location_type, LocationType::Logical);
+        assert!(sales.address.is_none() && sales.coordinates.is_none());
+
+        let alcatraz = results[3].as_ref().unwrap();
+        assert_eq!(alcatraz.location_type, LocationType::Physical);
+        assert!(alcatraz.address.is_none());
+    }
+
+    #[test]
+    fn test_invalid_commands_are_row_errors() {
+        let csv = "\
+name,latitude,longitude
+,10.0,20.0
+Half,10.0,
+Pole,95.0,0.0
+";
+        let lines: Vec<_> = import_csv(csv.as_bytes())
+            .map(|result| result.unwrap_err().line)
+            .collect();
+
+        assert_eq!(lines, vec![2, 3, 4]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Bulk import of location definitions
+//!
+//! Rows are read lazily from CSV or newline-delimited JSON and turned into
+//! `DefineLocation` commands one at a time, so arbitrarily large files can be
+//! streamed. A bad row yields an [`ImportError`] carrying its line number and
+//! the import carries on with the next row.
+//!
+//! Both formats share the same fields:
+//!
+//! | Field | Aliases | Notes |
+//! |-------|---------|-------|
+//! | `id` | | Location ID; generated when absent |
+//! | `name` | | Required |
+//! | `type` | | `physical` (default), `logical`, `mobile`, ... |
+//! | `lat`, `lng` | `latitude`, `longitude`, `lon` | Given together or not at all |
+//! | `street`, `city`, `region`, `country`, `postal_code` | `state`, `postcode`, `zip` | Any of them makes an address |
+//! | `parent_id` | | Parent location ID |
+
+mod csv_import;
+mod ndjson_import;
+
+pub use csv_import::*;
+pub use ndjson_import::*;
+
+use crate::commands::{DefineLocation, Validate};
+use crate::value_objects::{Address, GeoCoordinates, LocationType};
+use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A row that could not be turned into a command
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct ImportError {
+    /// 1-based line of the row in the input
+    pub line: u64,
+    pub message: String,
+}
+
+impl ImportError {
+    pub fn new(line: u64, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// One imported location, as read from either format
+#[derive(Debug, Deserialize)]
+struct ImportRow {
+    id: Option<Uuid>,
+    name: String,
+    #[serde(rename = "type")]
+    location_type: Option<String>,
+    #[serde(alias = "latitude")]
+    lat: Option<f64>,
+    #[serde(alias = "longitude", alias = "lon")]
+    lng: Option<f64>,
+    street: Option<String>,
+    city: Option<String>,
+    #[serde(alias = "state")]
+    region: Option<String>,
+    country: Option<String>,
+    #[serde(alias = "postcode", alias = "zip")]
+    postal_code: Option<String>,
+    parent_id: Option<Uuid>,
+}
+
+impl ImportRow {
+    /// Build and validate the command for this row
+    fn into_command(self) -> Result<DefineLocation, String> {
+        let location_type = match self.location_type.as_deref() {
+            Some(name) if !name.trim().is_empty() => {
+                name.parse::<LocationType>().map_err(|e| e.to_string())?
+            }
+            _ => LocationType::Physical,
+        };
+
+        let coordinates = match (self.lat, self.lng) {
+            (Some(lat), Some(lng)) => Some(GeoCoordinates::new(lat, lng)),
+            (None, None) => None,
+            _ => return Err("lat and lng must be given together".to_string()),
+        };
+
+        let address_fields = [
+            &self.street,
+            &self.city,
+            &self.region,
+            &self.country,
+            &self.postal_code,
+        ];
+        let address = if address_fields.iter().any(|field| field.is_some()) {
+            Some(Address::new(
+                self.street.unwrap_or_default(),
+                self.city.unwrap_or_default(),
+                self.region.unwrap_or_default(),
+                self.country.unwrap_or_default(),
+                self.postal_code.unwrap_or_default(),
+            ))
+        } else {
+            None
+        };
+
+        let command = DefineLocation {
+            location_id: self.id.unwrap_or_else(Uuid::new_v4),
+            name: self.name,
+            location_type,
+            address,
+            addresses: HashMap::new(),
+            coordinates,
+            virtual_location: None,
+            parent_id: self.parent_id,
+        };
+
+        command.validate().map_err(|errors| {
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        })?;
+        Ok(command)
+    }
+}
@@ -0,0 +1,74 @@
+//! Newline-delimited JSON location import
+
+use super::{ImportError, ImportRow};
+use crate::commands::DefineLocation;
+use std::io::{BufRead, BufReader, Read};
+
+/// Stream `DefineLocation` commands from newline-delimited JSON
+///
+/// Each non-blank line holds one JSON object with the import fields. Reading
+/// stops at the first I/O error, which is reported against the line it
+/// occurred on.
+pub fn import_ndjson<R: Read>(
+    reader: R,
+) -> impl Iterator<Item = Result<DefineLocation, ImportError>> {
+    let mut failed = false;
+
+    BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .map_while(move |(index, line)| {
+            if failed {
+                return None;
+            }
+            let line_number = index as u64 + 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    failed = true;
+                    return Some(Some(Err(ImportError::new(line_number, e.to_string()))));
+                }
+            };
+            if line.trim().is_empty() {
+                return Some(None);
+            }
+
+            let result = serde_json::from_str::<ImportRow>(&line)
+                .map_err(|e| e.to_string())
+                .and_then(ImportRow::into_command)
+                .map_err(|message| ImportError::new(line_number, message));
+            Some(Some(result))
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_ndjson_rows_with_malformed_line() {
+        let parent = Uuid::new_v4();
+        let ndjson = format!(
+            r#"{{"name": "Pier 39", "lat": 37.8087, "lng": -122.4098, "parent_id": "{parent}"}}
+
+{{"name": "Broken", "lat": 37.8
+{{"name": "Depot", "type": "mobile", "latitude": 37.75, "longitude": -122.39}}
+"#
+        );
+
+        let results: Vec<_> = import_ndjson(ndjson.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+
+        let pier = results[0].as_ref().unwrap();
+        assert_eq!(pier.name, "Pier 39");
+        assert_eq!(pier.parent_id, Some(parent));
+
+        // Blank lines are skipped but still counted
+        assert_eq!(results[1].as_ref().unwrap_err().line, 3);
+
+        let depot = results[2].as_ref().unwrap();
+        assert_eq!(depot.coordinates.as_ref().unwrap().longitude, -122.39);
+    }
+}
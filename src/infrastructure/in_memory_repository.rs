@@ -0,0 +1,162 @@
+//! In-memory location repository
+//!
+//! A lightweight `AggregateRepository<Location>` for tests and single-process
+//! deployments. Saves are checked against the stored version so concurrent
+//! writers cannot silently overwrite each other.
+
+use crate::aggregate::{Location, LocationMarker};
+use cim_domain::{AggregateRepository, AggregateRoot, EntityId};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// In-memory repository for Location aggregates with optimistic concurrency
+///
+/// A save succeeds when the aggregate's `version()` matches the stored version,
+/// i.e. it was loaded from the latest state. The stored copy then moves on to
+/// the next version, so a writer holding an older copy gets a conflict.
+#[derive(Debug, Default)]
+pub struct InMemoryLocationRepository {
+    locations: RwLock<HashMap<EntityId<LocationMarker>, Location>>,
+}
+
+impl InMemoryLocationRepository {
+    /// Create an empty repository
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored locations
+    pub fn len(&self) -> usize {
+        self.locations.read().map(|l| l.len()).unwrap_or(0)
+    }
+
+    /// Check if the repository is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AggregateRepository<Location> for InMemoryLocationRepository {
+    fn load(&self, id: EntityId<LocationMarker>) -> Result<Option<Location>, String> {
+        let locations = self
+            .locations
+            .read()
+            .map_err(|e| format!("Repository lock poisoned: {e}"))?;
+        Ok(locations.get(&id).cloned())
+    }
+
+    fn save(&self, aggregate: &Location) -> Result<(), String> {
+        let mut locations = self
+            .locations
+            .write()
+            .map_err(|e| format!("Repository lock poisoned: {e}"))?;
+
+        let id = aggregate.id();
+        if let Some(stored) = locations.get(&id) {
+            if stored.version() != aggregate.version() {
+                return Err(format!(
+                    "Version conflict for location {}: expected version {}, got {}",
+                    id.as_uuid(),
+                    stored.version(),
+                    aggregate.version()
+                ));
+            }
+        }
+
+        let mut stored = aggregate.clone();
+        stored.increment_version();
+        locations.insert(id, stored);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::GeoCoordinates;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    fn location(name: &str) -> Location {
+        Location::new_from_coordinates(
+            EntityId::new(),
+            name.to_string(),
+            GeoCoordinates::new(52.52, 13.405),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let repository = InMemoryLocationRepository::new();
+        let location = location("Berlin Office");
+        let id = location.id();
+
+        assert!(repository.load(id).unwrap().is_none());
+        repository.save(&location).unwrap();
+        assert_eq!(repository.len(), 1);
+
+        let mut loaded = repository.load(id).unwrap().unwrap();
+        assert_eq!(loaded.name, "Berlin Office");
+        assert_eq!(loaded.version(), 1);
+
+        loaded.name = "Berlin HQ".to_string();
+        repository.save(&loaded).unwrap();
+
+        let reloaded = repository.load(id).unwrap().unwrap();
+        assert_eq!(reloaded.name, "Berlin HQ");
+        assert_eq!(reloaded.version(), 2);
+    }
+
+    #[test]
+    fn test_stale_save_is_rejected() {
+        let repository = InMemoryLocationRepository::new();
+        let location = location("Depot");
+        let id = location.id();
+        repository.save(&location).unwrap();
+
+        let mut first = repository.load(id).unwrap().unwrap();
+        let mut second = repository.load(id).unwrap().unwrap();
+
+        first.name = "Depot North".to_string();
+        repository.save(&first).unwrap();
+
+        second.name = "Depot South".to_string();
+        let result = repository.save(&second);
+        assert!(result.unwrap_err().contains("Version conflict"));
+        assert_eq!(repository.load(id).unwrap().unwrap().name, "Depot North");
+    }
+
+    #[test]
+    fn test_concurrent_writers_conflict() {
+        let repository = Arc::new(InMemoryLocationRepository::new());
+        let location = location("Warehouse");
+        let id = location.id();
+        repository.save(&location).unwrap();
+
+        let writers = 8;
+        let barrier = Arc::new(Barrier::new(writers));
+        let handles: Vec<_> = (0..writers)
+            .map(|i| {
+                let repository = Arc::clone(&repository);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    let mut copy = repository.load(id).unwrap().unwrap();
+                    barrier.wait();
+                    copy.name = format!("Warehouse {i}");
+                    repository.save(&copy).is_ok()
+                })
+            })
+            .collect();
+
+        let successes = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|saved| *saved)
+            .count();
+
+        // Every writer loaded version 1, so only one of them can win
+        assert_eq!(successes, 1);
+        assert_eq!(repository.load(id).unwrap().unwrap().version(), 2);
+    }
+}
@@ -5,15 +5,23 @@
 
 use crate::aggregate::{Location, LocationMarker};
 use crate::infrastructure::{NatsError, NatsEventStore};
+use crate::nats::{ActorId, CimDomainEvent, MessageIdentity};
 use crate::LocationDomainEvent;
-use cim_domain::{DomainResult, EntityId};
+use cim_domain::{DomainEvent, DomainResult, EntityId};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 /// Repository for Location aggregates using event sourcing
 pub struct LocationRepository {
     event_store: Arc<NatsEventStore>,
     snapshot_frequency: u64,
+    /// Last chained envelope per aggregate, seeded from the store on first save
+    ///
+    /// Held for the whole of a save, so concurrent saves cannot chain two
+    /// events to the same head.
+    chain_heads: Mutex<HashMap<Uuid, CimDomainEvent>>,
 }
 
 impl LocationRepository {
@@ -26,6 +34,7 @@ impl LocationRepository {
         Self {
             event_store,
             snapshot_frequency: 100, // Default: snapshot every 100 events
+            chain_heads: Mutex::new(HashMap::new()),
         }
     }
 
@@ -61,7 +70,7 @@ impl LocationRepository {
                     // First event must be LocationDefined
                     if let LocationDomainEvent::LocationDefined(e) = &event {
                         // Create initial aggregate from LocationDefined event
-                        location = Some(self.create_from_defined_event(e)?);
+                        location = Some(Self::create_from_defined_event(e)?);
                     } else {
                         return Err(RepositoryError::InvalidEventSequence(
                             "First event must be LocationDefined".to_string(),
@@ -81,29 +90,89 @@ impl LocationRepository {
         Ok(location)
     }
 
-    /// Save events for a location aggregate
+    /// Load the full change history of a location, oldest first
     ///
-    /// This appends new events to the event store.
-    pub async fn save(&self, events: Vec<LocationDomainEvent>) -> Result<(), RepositoryError> {
+    /// Events are returned in the envelopes they were stored with, so each
+    /// carries its stored sequence, CID chain and identity. Verifying the
+    /// history compares those stored CIDs with ones recomputed from the events.
+    pub async fn history(&self, location_id: EntityId<LocationMarker>) -> Result<Vec<CimDomainEvent>, RepositoryError> {
+        self.stored_envelopes(location_id.into()).await
+    }
+
+    async fn stored_envelopes(&self, aggregate_id: Uuid) -> Result<Vec<CimDomainEvent>, RepositoryError> {
         self.event_store
-            .append_events(events)
+            .load_stored_events(aggregate_id)
             .await
-            .map_err(|e| RepositoryError::EventStoreFailed(e.to_string()))?;
+            .map_err(|e| RepositoryError::EventStoreFailed(e.to_string()))?
+            .iter()
+            .map(|(event, headers)| stored_envelope(event, headers))
+            .collect()
+    }
+
+    /// Save events for a location aggregate
+    ///
+    /// Each event is wrapped in an envelope whose CID links to the previous event
+    /// of the same aggregate, and appended to the event store with that chain.
+    /// Saves are serialized, so each event links to the one appended before it.
+    pub async fn save(&self, events: Vec<LocationDomainEvent>) -> Result<(), RepositoryError> {
+        self.save_caused_by(events, None).await.map(|_| ())
+    }
+
+    /// Save events caused by a command, returning the envelopes that were stored
+    ///
+    /// Works like [`save`](Self::save), but each envelope's identity is caused
+    /// by `cause` (a new root when `None`). The identity is stored with the
+    /// event, so the envelopes can acknowledge the command with the message ID,
+    /// correlation ID and CID subscribers see.
+    pub async fn save_caused_by(
+        &self,
+        events: Vec<LocationDomainEvent>,
+        cause: Option<&MessageIdentity>,
+    ) -> Result<Vec<CimDomainEvent>, RepositoryError> {
+        let mut chain_heads = self.chain_heads.lock().await;
+        let mut envelopes = Vec::with_capacity(events.len());
+        for event in events {
+            let aggregate_id = event.aggregate_id();
+            let head = self.chain_head(&chain_heads, aggregate_id).await?;
+            let envelope = chain_event(head.as_ref(), &event, cause)?;
+
+            self.event_store
+                .append_chained_event(event, &envelope)
+                .await
+                .map_err(|e| RepositoryError::EventStoreFailed(e.to_string()))?;
+
+            chain_heads.insert(aggregate_id, envelope.clone());
+            envelopes.push(envelope);
+        }
+
+        Ok(envelopes)
+    }
+
+    /// Last chained envelope of an aggregate
+    ///
+    /// After a restart the head is the last envelope stored for the aggregate.
+    async fn chain_head(
+        &self,
+        chain_heads: &HashMap<Uuid, CimDomainEvent>,
+        aggregate_id: Uuid,
+    ) -> Result<Option<CimDomainEvent>, RepositoryError> {
+        if let Some(head) = chain_heads.get(&aggregate_id) {
+            return Ok(Some(head.clone()));
+        }
 
-        Ok(())
+        Ok(self.stored_envelopes(aggregate_id).await?.pop())
     }
 
     /// Helper to create initial aggregate from LocationDefined event
     fn create_from_defined_event(
-        &self,
         event: &crate::events::LocationDefined,
     ) -> Result<Location, RepositoryError> {
-        use crate::value_objects::LocationType;
+        use crate::value_objects::{AddressRole, LocationType};
 
         let location_id = EntityId::from_uuid(event.location_id);
 
         // Create location based on type
-        let location = match &event.location_type {
+        let mut location = match &event.location_type {
             LocationType::Physical => {
                 if let Some(address) = &event.address {
                     Location::new_physical(location_id, event.name.clone(), address.clone())
@@ -128,7 +197,17 @@ impl LocationRepository {
                     ));
                 }
             }
-            // For Logical and Hybrid, we'll use coordinates if available, otherwise address
+            LocationType::Logical => Location::new_logical(location_id, event.name.clone()),
+            LocationType::Mobile => {
+                if let Some(coords) = &event.coordinates {
+                    Location::new_mobile(location_id, event.name.clone(), coords.clone())
+                } else {
+                    return Err(RepositoryError::InvalidEvent(
+                        "Mobile location requires coordinates".to_string(),
+                    ));
+                }
+            }
+            // For Hybrid, we'll use coordinates if available, otherwise address
             _ => {
                 if let Some(coords) = &event.coordinates {
                     Location::new_from_coordinates(
@@ -147,10 +226,113 @@ impl LocationRepository {
         }
         .map_err(|e| RepositoryError::AggregateCreationFailed(e.to_string()))?;
 
+        // The physical address is carried by `address` alone
+        for (role, address) in event
+            .addresses
+            .iter()
+            .filter(|(role, _)| **role != AddressRole::Physical)
+        {
+            location
+                .set_address_for(*role, address.clone())
+                .map_err(|e| RepositoryError::AggregateCreationFailed(e.to_string()))?;
+        }
+
         Ok(location)
     }
 }
 
+/// Rebuild the envelope an event was stored with from its headers
+///
+/// The sequence, CIDs and identity come from the headers written by
+/// [`NatsEventStore::chained_event_headers`], not from the event, so a stored
+/// event whose content was altered no longer matches its CID. Fails for events
+/// stored without a chain; events stored without identity headers get a new
+/// root identity.
+pub fn stored_envelope(
+    event: &LocationDomainEvent,
+    headers: &async_nats::HeaderMap,
+) -> Result<CimDomainEvent, RepositoryError> {
+    let header = |name: &str| headers.get(name).map(|value| value.as_str());
+    let cid = |name: &str| {
+        header(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| RepositoryError::InvalidEvent(format!("Invalid {} header: {}", name, e)))
+            })
+            .transpose()
+    };
+
+    let sequence = header("event-sequence")
+        .ok_or_else(|| RepositoryError::InvalidEvent("Missing event-sequence header".to_string()))?
+        .parse()
+        .map_err(|e| RepositoryError::InvalidEvent(format!("Invalid event-sequence header: {}", e)))?;
+    let payload = serde_json::to_value(event)
+        .map_err(|e| RepositoryError::InvalidEvent(e.to_string()))?;
+
+    let mut envelope = CimDomainEvent::new(
+        event.aggregate_id().to_string(),
+        sequence,
+        event.event_type().to_string(),
+        payload,
+        None,
+        Some(ActorId::system("location-repository")),
+    );
+    if let Some(identity) = MessageIdentity::from_headers(headers) {
+        envelope.metadata.identity = identity;
+    }
+    envelope.event_cid = cid("event-cid")?;
+    envelope.previous_cid = cid("previous-cid")?;
+
+    Ok(envelope)
+}
+
+/// Wrap an event in an envelope chained to `previous`
+///
+/// The envelope's sequence follows the previous envelope's, its `previous_cid`
+/// is the previous envelope's CID, and its own CID covers both. Its identity is
+/// caused by `cause`, or a new root without one; the CID does not cover it.
+pub fn chain_event(
+    previous: Option<&CimDomainEvent>,
+    event: &LocationDomainEvent,
+    cause: Option<&MessageIdentity>,
+) -> Result<CimDomainEvent, RepositoryError> {
+    let payload = serde_json::to_value(event)
+        .map_err(|e| RepositoryError::InvalidEvent(e.to_string()))?;
+
+    let mut envelope = CimDomainEvent::new(
+        event.aggregate_id().to_string(),
+        previous.map_or(1, |previous| previous.sequence + 1),
+        event.event_type().to_string(),
+        payload,
+        cause,
+        Some(ActorId::system("location-repository")),
+    );
+    envelope.previous_cid = previous.and_then(|previous| previous.event_cid);
+
+    let event_cid = envelope
+        .compute_cid()
+        .map_err(|e| RepositoryError::InvalidEvent(e.to_string()))?;
+    let previous_cid = envelope.previous_cid;
+
+    Ok(envelope.with_cid(event_cid, previous_cid))
+}
+
+/// Wrap consecutive events of one aggregate in a CID chain starting after `previous`
+pub fn chain_events(
+    previous: Option<&CimDomainEvent>,
+    events: &[LocationDomainEvent],
+) -> Result<Vec<CimDomainEvent>, RepositoryError> {
+    let mut chained: Vec<CimDomainEvent> = Vec::with_capacity(events.len());
+
+    for event in events {
+        let envelope = chain_event(chained.last().or(previous), event, None)?;
+        chained.push(envelope);
+    }
+
+    Ok(chained)
+}
+
 /// Errors that can occur during repository operations
 #[derive(Debug, thiserror::Error)]
 pub enum RepositoryError {
@@ -172,3 +354,185 @@ pub enum RepositoryError {
     #[error("Aggregate not found")]
     AggregateNotFound,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LocationArchived, LocationDefined, LocationMetadataAdded};
+    use crate::nats::{verify_chain, IdentityError};
+    use crate::value_objects::{GeoCoordinates, LocationType};
+
+    fn history(location_id: Uuid) -> Vec<LocationDomainEvent> {
+        vec![
+            LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id,
+                name: "Warehouse".to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(GeoCoordinates::new(37.7749, -122.4194)),
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            }),
+            LocationDomainEvent::LocationMetadataAdded(LocationMetadataAdded {
+                location_id,
+                added_metadata: HashMap::from([("dock".to_string(), "7".to_string())]),
+                current_metadata: HashMap::from([("dock".to_string(), "7".to_string())]),
+                reason: "Import".to_string(),
+            }),
+            LocationDomainEvent::LocationArchived(LocationArchived {
+                location_id,
+                name: "Warehouse".to_string(),
+                location_type: LocationType::Physical,
+                reason: "Closed".to_string(),
+                actor: None,
+            }),
+        ]
+    }
+
+    /// Test chaining three events of one aggregate
+    ///
+    /// ```mermaid
+    /// graph LR
+    ///     A[Defined] -->|previous_cid| B[MetadataAdded]
+    ///     B -->|previous_cid| C[Archived]
+    /// ```
+    #[test]
+    fn test_chain_events_links_cids() {
+        let chain = chain_events(None, &history(Uuid::new_v4())).unwrap();
+
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[0].previous_cid, None);
+        assert_eq!(chain[1].previous_cid, chain[0].event_cid);
+        assert_eq!(chain[2].previous_cid, chain[1].event_cid);
+        assert_eq!(
+            chain.iter().map(|event| event.sequence).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(verify_chain(&chain).is_ok());
+
+        // Replaying the same history reproduces the same chain
+        let location_id = chain[0].aggregate_id.parse().unwrap();
+        let replayed = chain_events(None, &history(location_id)).unwrap();
+        assert_eq!(replayed[2].event_cid, chain[2].event_cid);
+
+        // Continuing from a head extends the chain
+        let head = chain_event(None, &history(location_id)[0], None).unwrap();
+        let rest = chain_events(Some(&head), &history(location_id)[1..]).unwrap();
+        assert_eq!(rest[1].event_cid, chain[2].event_cid);
+    }
+
+    #[test]
+    fn test_chain_event_is_caused_by_command() {
+        let command = MessageIdentity::new_root();
+        let event = &history(Uuid::new_v4())[0];
+
+        let envelope = chain_event(None, event, Some(&command)).unwrap();
+        assert_eq!(envelope.correlation_id(), &command.correlation_id);
+        assert_eq!(envelope.causation_id().0, command.message_id.0);
+        assert_ne!(envelope.message_id(), &command.message_id);
+
+        // The identity is not part of the content, so the CID is unchanged
+        let root = chain_event(None, event, None).unwrap();
+        assert_eq!(envelope.event_cid, root.event_cid);
+    }
+
+    #[test]
+    fn test_logical_location_round_trips_through_its_events() {
+        let location_id = Uuid::new_v4();
+        let defined = LocationDefined {
+            location_id,
+            name: "Sales".to_string(),
+            location_type: LocationType::Logical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        };
+        let metadata_added = LocationDomainEvent::LocationMetadataAdded(LocationMetadataAdded {
+            location_id,
+            added_metadata: HashMap::from([("region".to_string(), "west".to_string())]),
+            current_metadata: HashMap::from([("region".to_string(), "west".to_string())]),
+            reason: "Import".to_string(),
+        });
+
+        let location = LocationRepository::create_from_defined_event(&defined)
+            .unwrap()
+            .apply_event_pure(&metadata_added)
+            .unwrap();
+
+        assert_eq!(location.name, "Sales");
+        assert_eq!(location.location_type, LocationType::Logical);
+        assert!(location.address.is_none());
+        assert!(location.coordinates.is_none());
+        assert_eq!(location.metadata.get("region"), Some(&"west".to_string()));
+    }
+
+    #[test]
+    fn test_stored_envelopes_keep_their_stored_cids_and_identity() {
+        let events = history(Uuid::new_v4());
+        let command = MessageIdentity::new_root();
+        let mut chain: Vec<CimDomainEvent> = Vec::new();
+        for event in &events {
+            chain.push(chain_event(chain.last(), event, Some(&command)).unwrap());
+        }
+        let stored: Vec<_> = events
+            .iter()
+            .zip(&chain)
+            .map(|(event, envelope)| (event.clone(), NatsEventStore::chained_event_headers(event, envelope)))
+            .collect();
+
+        let loaded: Vec<_> = stored
+            .iter()
+            .map(|(event, headers)| stored_envelope(event, headers).unwrap())
+            .collect();
+        for (loaded, chained) in loaded.iter().zip(&chain) {
+            assert_eq!(loaded.sequence, chained.sequence);
+            assert_eq!(loaded.event_cid, chained.event_cid);
+            assert_eq!(loaded.previous_cid, chained.previous_cid);
+            assert_eq!(loaded.metadata.identity, chained.metadata.identity);
+        }
+        assert!(verify_chain(&loaded).is_ok());
+
+        // An event altered in the store no longer matches its stored CID
+        let LocationDomainEvent::LocationMetadataAdded(mut forged) = events[1].clone() else {
+            panic!("expected metadata event");
+        };
+        forged.reason = "Forged".to_string();
+        let mut tampered = loaded.clone();
+        tampered[1] =
+            stored_envelope(&LocationDomainEvent::LocationMetadataAdded(forged), &stored[1].1).unwrap();
+        assert!(matches!(
+            verify_chain(&tampered),
+            Err(IdentityError::CidMismatch { sequence: 2 })
+        ));
+
+        // Events stored without a chain cannot be verified
+        let unchained = stored_envelope(&events[0], &async_nats::HeaderMap::new());
+        assert!(matches!(unchained, Err(RepositoryError::InvalidEvent(_))));
+    }
+
+    #[test]
+    fn test_verify_chain_detects_tampered_middle_event() {
+        let chain = chain_events(None, &history(Uuid::new_v4())).unwrap();
+
+        // Altered content no longer matches its CID
+        let mut tampered = chain.clone();
+        tampered[1].payload["LocationMetadataAdded"]["reason"] = "Forged".into();
+        assert!(matches!(
+            verify_chain(&tampered),
+            Err(IdentityError::CidMismatch { sequence: 2 })
+        ));
+
+        // Re-sealing the altered event breaks the next link instead
+        let resealed_cid = tampered[1].compute_cid().unwrap();
+        tampered[1].event_cid = Some(resealed_cid);
+        assert!(matches!(
+            verify_chain(&tampered),
+            Err(IdentityError::BrokenCidLink { sequence: 3 })
+        ));
+    }
+}
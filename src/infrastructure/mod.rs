@@ -5,6 +5,12 @@
 
 pub mod nats_integration;
 pub mod location_repository;
+pub mod in_memory_repository;
+pub mod projection_runner;
+pub mod sharded_read_model;
 
 pub use nats_integration::*;
 pub use location_repository::*;
+pub use in_memory_repository::*;
+pub use projection_runner::*;
+pub use sharded_read_model::*;
@@ -3,11 +3,11 @@
 //! This module provides event store implementation using NATS JetStream
 //! for durable, distributed event storage and replay.
 
+use crate::nats::{decode_message, CimDomainEvent, Codec, JsonCodec, CONTENT_TYPE_HEADER};
 use crate::LocationDomainEvent;
 use async_nats::jetstream::{self, stream::Stream};
 use cim_domain::DomainEvent;
 use futures::StreamExt;
-use serde_json;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -60,14 +60,62 @@ impl NatsEventStore {
 
     /// Append a single event to the event store
     pub async fn append_event(&self, event: LocationDomainEvent) -> Result<(), NatsError> {
-        let subject = self.event_subject(&event);
-        let payload =
-            serde_json::to_vec(&event).map_err(|e| NatsError::SerializationError(e.to_string()))?;
+        let headers = Self::event_headers(&event);
+        self.publish_event(event, headers).await
+    }
+
+    /// Append an event together with the CID chain and identity of its envelope
+    ///
+    /// See [`chained_event_headers`](Self::chained_event_headers) for the
+    /// headers stored with the event.
+    pub async fn append_chained_event(
+        &self,
+        event: LocationDomainEvent,
+        envelope: &CimDomainEvent,
+    ) -> Result<(), NatsError> {
+        let headers = Self::chained_event_headers(&event, envelope);
+        self.publish_event(event, headers).await
+    }
 
-        // Add event metadata as headers
+    /// Headers of an event appended with its envelope
+    ///
+    /// The envelope's sequence, `event_cid` and `previous_cid` are stored as the
+    /// `event-sequence`, `event-cid` and `previous-cid` headers, and its identity
+    /// as the `message-id`, `correlation-id` and `causation-id` headers.
+    pub fn chained_event_headers(
+        event: &LocationDomainEvent,
+        envelope: &CimDomainEvent,
+    ) -> async_nats::HeaderMap {
+        let mut headers = Self::event_headers(event);
+        headers.insert("event-sequence", envelope.sequence.to_string().as_str());
+        if let Some(event_cid) = &envelope.event_cid {
+            headers.insert("event-cid", event_cid.to_string().as_str());
+        }
+        if let Some(previous_cid) = &envelope.previous_cid {
+            headers.insert("previous-cid", previous_cid.to_string().as_str());
+        }
+        envelope.metadata.identity.insert_headers(&mut headers);
+        headers
+    }
+
+    /// Event metadata headers shared by every appended event
+    fn event_headers(event: &LocationDomainEvent) -> async_nats::HeaderMap {
         let mut headers = async_nats::HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, JsonCodec::CONTENT_TYPE);
         headers.insert("event-type", event.event_type());
         headers.insert("aggregate-id", event.aggregate_id().to_string().as_str());
+        headers
+    }
+
+    async fn publish_event(
+        &self,
+        event: LocationDomainEvent,
+        headers: async_nats::HeaderMap,
+    ) -> Result<(), NatsError> {
+        let subject = self.event_subject(&event);
+        let payload = JsonCodec
+            .encode(&event)
+            .map_err(|e| NatsError::SerializationError(e.to_string()))?;
 
         self.jetstream
             .publish_with_headers(subject, headers, payload.into())
@@ -84,6 +132,22 @@ impl NatsEventStore {
         &self,
         aggregate_id: Uuid,
     ) -> Result<Vec<LocationDomainEvent>, NatsError> {
+        Ok(self
+            .load_stored_events(aggregate_id)
+            .await?
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect())
+    }
+
+    /// Load all events for a given aggregate ID with the headers stored with them
+    ///
+    /// Events appended with [`append_chained_event`](Self::append_chained_event)
+    /// carry their envelope's CID chain and identity in these headers.
+    pub async fn load_stored_events(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<Vec<(LocationDomainEvent, async_nats::HeaderMap)>, NatsError> {
         let subject = format!("events.location.{}.>", aggregate_id);
 
         // Create a durable consumer for this aggregate
@@ -110,10 +174,10 @@ impl NatsEventStore {
 
         // Fetch all available messages
         while let Some(Ok(msg)) = messages.next().await {
-            let event: LocationDomainEvent = serde_json::from_slice(&msg.payload)
-                .map_err(|e| NatsError::DeserializationError(e.to_string()))?;
+            let event: LocationDomainEvent =
+                decode_message(&msg).map_err(|e| NatsError::DeserializationError(e.to_string()))?;
 
-            events.push(event);
+            events.push((event, msg.headers.clone().unwrap_or_default()));
 
             msg.ack()
                 .await
@@ -130,10 +194,24 @@ impl NatsEventStore {
         let event_type = match event {
             LocationDomainEvent::LocationDefined(_) => "defined",
             LocationDomainEvent::LocationUpdated(_) => "updated",
+            LocationDomainEvent::LocationMoved(_) => "moved",
             LocationDomainEvent::ParentLocationSet(_) => "parent_set",
             LocationDomainEvent::ParentLocationRemoved(_) => "parent_removed",
             LocationDomainEvent::LocationMetadataAdded(_) => "metadata_added",
+            LocationDomainEvent::LocationMetadataUpdated(_) => "metadata_updated",
+            LocationDomainEvent::LocationMetadataRemoved(_) => "metadata_removed",
             LocationDomainEvent::LocationArchived(_) => "archived",
+            LocationDomainEvent::LocationCheckedIn(_) => "checked_in",
+            LocationDomainEvent::LocationCheckedOut(_) => "checked_out",
+            LocationDomainEvent::LocationsMerged(_) => "merged",
+            LocationDomainEvent::LocationDeleted(_) => "deleted",
+            LocationDomainEvent::AddressGeocoded(_) => "address_geocoded",
+            LocationDomainEvent::HierarchyReorganized(_) => "hierarchy_reorganized",
+            LocationDomainEvent::CoordinatesUpdated(_) => "coordinates_updated",
+            LocationDomainEvent::LocationRestored(_) => "restored",
+            LocationDomainEvent::CoordinatesValidated(_) => "coordinates_validated",
+            LocationDomainEvent::LocationAddressSet(_) => "address_set",
+            LocationDomainEvent::IndoorPositionSet(_) => "indoor_position_set",
         };
 
         format!("events.location.{}.{}", location_id, event_type)
@@ -167,3 +245,51 @@ pub enum NatsError {
     #[error("Connection error: {0}")]
     ConnectionError(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::LocationDefined;
+    use crate::infrastructure::chain_event;
+    use crate::nats::MessageIdentity;
+    use crate::value_objects::LocationType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_chained_event_headers_carry_envelope_identity_and_cid() {
+        let location_id = Uuid::new_v4();
+        let event = LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id,
+            name: "Sales".to_string(),
+            location_type: LocationType::Logical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+        let command = MessageIdentity::new_root();
+        let envelope = chain_event(None, &event, Some(&command)).unwrap();
+
+        let headers = NatsEventStore::chained_event_headers(&event, &envelope);
+
+        assert_eq!(
+            MessageIdentity::from_headers(&headers).as_ref(),
+            Some(&envelope.metadata.identity)
+        );
+        assert_eq!(
+            headers.get("correlation-id").map(|value| value.as_str()),
+            Some(command.correlation_id.to_string().as_str())
+        );
+        assert_eq!(
+            headers.get("event-cid").map(|value| value.to_string()),
+            envelope.event_cid.as_ref().map(|cid| cid.to_string())
+        );
+        assert_eq!(
+            headers.get("aggregate-id").map(|value| value.to_string()),
+            Some(location_id.to_string())
+        );
+        assert!(headers.get("previous-cid").is_none());
+    }
+}
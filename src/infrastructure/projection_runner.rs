@@ -0,0 +1,331 @@
+//! Streaming projection updates from NATS JetStream
+//!
+//! The runner consumes every location event from the event stream and feeds it
+//! to a shared read model, either a [`LocationReadModel`] behind one lock or a
+//! [`ShardedReadModel`], remembering the last JetStream sequence it processed
+//! so that a restarted runner resumes where it stopped.
+
+use super::{NatsError, ShardedReadModel};
+use crate::nats::{decode_with_content_type, CONTENT_TYPE_HEADER};
+use crate::projections::{LocationProjection, LocationReadModel};
+use crate::LocationDomainEvent;
+use async_nats::jetstream::{self, consumer::DeliverPolicy};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Subject filter covering every location event
+pub const LOCATION_EVENTS_SUBJECT: &str = "events.location.>";
+
+/// A raw event message together with its JetStream stream sequence
+#[derive(Debug, Clone)]
+pub struct ProjectionMessage {
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+    /// Codec named by the message's `content-type` header; `None` means JSON
+    pub content_type: Option<String>,
+}
+
+/// A shared read model the runner can apply events to
+#[async_trait]
+pub trait ProjectionTarget: Send + Sync {
+    /// Apply one event
+    async fn apply_event(&self, event: &LocationDomainEvent);
+}
+
+#[async_trait]
+impl ProjectionTarget for RwLock<LocationReadModel> {
+    async fn apply_event(&self, event: &LocationDomainEvent) {
+        self.write().await.handle_event(event);
+    }
+}
+
+#[async_trait]
+impl ProjectionTarget for ShardedReadModel {
+    async fn apply_event(&self, event: &LocationDomainEvent) {
+        self.handle_event(event);
+    }
+}
+
+/// Keeps a shared read model current from the location event stream
+pub struct ProjectionRunner<T: ProjectionTarget = RwLock<LocationReadModel>> {
+    read_model: Arc<T>,
+    last_sequence: AtomicU64,
+}
+
+impl<T: ProjectionTarget> ProjectionRunner<T> {
+    /// Create a runner that starts from the beginning of the stream
+    pub fn new(read_model: Arc<T>) -> Self {
+        Self {
+            read_model,
+            last_sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// Resume after `sequence`, e.g. one persisted by a previous run
+    pub fn with_start_sequence(self, sequence: u64) -> Self {
+        self.last_sequence.store(sequence, Ordering::SeqCst);
+        self
+    }
+
+    /// The read model kept up to date by this runner
+    pub fn read_model(&self) -> Arc<T> {
+        self.read_model.clone()
+    }
+
+    /// Sequence of the last message processed (0 before any message)
+    pub fn last_sequence(&self) -> u64 {
+        self.last_sequence.load(Ordering::SeqCst)
+    }
+
+    /// Apply one message to the read model
+    ///
+    /// Messages at or below the last processed sequence are redeliveries and are
+    /// ignored. Malformed payloads are logged and skipped, but still advance the
+    /// sequence so they are not retried forever. Returns whether an event was applied.
+    pub async fn apply(&self, message: &ProjectionMessage) -> bool {
+        if message.sequence <= self.last_sequence() {
+            return false;
+        }
+
+        let decoded = decode_with_content_type::<LocationDomainEvent>(
+            message.content_type.as_deref(),
+            &message.payload,
+        );
+        let applied = match decoded {
+            Ok(event) => {
+                self.read_model.apply_event(&event).await;
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    sequence = message.sequence,
+                    "Skipping malformed location event: {e}"
+                );
+                false
+            }
+        };
+
+        self.last_sequence.store(message.sequence, Ordering::SeqCst);
+        applied
+    }
+
+    /// Apply every message of a stream until it ends
+    pub async fn run<S>(&self, messages: S)
+    where
+        S: Stream<Item = ProjectionMessage>,
+    {
+        futures::pin_mut!(messages);
+        while let Some(message) = messages.next().await {
+            self.apply(&message).await;
+        }
+    }
+
+    /// Consume `events.location.>` from a JetStream stream, resuming after the last sequence
+    ///
+    /// Runs until the consumer's message stream ends.
+    pub async fn run_jetstream(
+        &self,
+        jetstream: &jetstream::Context,
+        stream_name: &str,
+    ) -> Result<(), NatsError> {
+        let deliver_policy = match self.last_sequence() {
+            0 => DeliverPolicy::All,
+            last => DeliverPolicy::ByStartSequence {
+                start_sequence: last + 1,
+            },
+        };
+
+        let consumer = jetstream
+            .get_stream(stream_name)
+            .await
+            .map_err(|e| NatsError::ConsumerCreationFailed(e.to_string()))?
+            .create_consumer(jetstream::consumer::pull::Config {
+                filter_subject: LOCATION_EVENTS_SUBJECT.to_string(),
+                deliver_policy,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| NatsError::ConsumerCreationFailed(e.to_string()))?;
+
+        let mut messages = consumer
+            .messages()
+            .await
+            .map_err(|e| NatsError::FetchFailed(e.to_string()))?;
+
+        while let Some(message) = messages.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!("Failed to receive location event: {e}");
+                    continue;
+                }
+            };
+
+            match message.info() {
+                Ok(info) => {
+                    let sequence = info.stream_sequence;
+                    let content_type = message
+                        .headers
+                        .as_ref()
+                        .and_then(|headers| headers.get(CONTENT_TYPE_HEADER))
+                        .map(|value| value.to_string());
+                    self.apply(&ProjectionMessage {
+                        sequence,
+                        payload: message.payload.to_vec(),
+                        content_type,
+                    })
+                    .await;
+                }
+                Err(e) => tracing::warn!("Skipping location event without stream info: {e}"),
+            }
+
+            message
+                .ack()
+                .await
+                .map_err(|e| NatsError::AckFailed(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LocationDefined, LocationMetadataAdded, LocationMoved};
+    use crate::value_objects::{GeoCoordinates, LocationType};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn message(sequence: u64, event: &LocationDomainEvent) -> ProjectionMessage {
+        ProjectionMessage {
+            sequence,
+            payload: serde_json::to_vec(event).unwrap(),
+            content_type: None,
+        }
+    }
+
+    fn defined(location_id: Uuid) -> LocationDomainEvent {
+        LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id,
+            name: "Warehouse".to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: Some(GeoCoordinates::new(37.7749, -122.4194)),
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_projection_converges_and_tracks_sequence() {
+        let runner = ProjectionRunner::new(Arc::new(RwLock::new(LocationReadModel::default())));
+        let location_id = Uuid::new_v4();
+        let moved_to = GeoCoordinates::new(37.8044, -122.2712);
+
+        let messages = vec![
+            message(1, &defined(location_id)),
+            ProjectionMessage {
+                sequence: 2,
+                payload: b"{not json".to_vec(),
+                content_type: None,
+            },
+            message(
+                3,
+                &LocationDomainEvent::LocationMetadataAdded(LocationMetadataAdded {
+                    location_id,
+                    added_metadata: HashMap::from([("dock".to_string(), "7".to_string())]),
+                    current_metadata: HashMap::from([("dock".to_string(), "7".to_string())]),
+                    reason: "Import".to_string(),
+                }),
+            ),
+            message(
+                4,
+                &LocationDomainEvent::LocationMoved(LocationMoved::new(
+                    location_id,
+                    GeoCoordinates::new(37.7749, -122.4194),
+                    moved_to.clone(),
+                )),
+            ),
+        ];
+
+        runner.run(futures::stream::iter(messages)).await;
+
+        assert_eq!(runner.last_sequence(), 4);
+        let read_model = runner.read_model();
+        let read_model = read_model.read().await;
+        let view = read_model.locations.get(&location_id).unwrap();
+        assert_eq!(view.name, "Warehouse");
+        assert_eq!(view.attributes.get("dock").unwrap(), "7");
+        assert_eq!(view.coordinates, Some(moved_to));
+    }
+
+    #[tokio::test]
+    async fn test_projection_ignores_redelivered_sequences() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let runner = ProjectionRunner::new(Arc::new(RwLock::new(LocationReadModel::default())))
+            .with_start_sequence(10);
+
+        assert!(!runner.apply(&message(10, &defined(first))).await);
+        assert!(runner.apply(&message(11, &defined(second))).await);
+        assert!(!runner.apply(&message(11, &defined(second))).await);
+
+        assert_eq!(runner.last_sequence(), 11);
+        let read_model = runner.read_model();
+        let read_model = read_model.read().await;
+        assert!(!read_model.locations.contains_key(&first));
+        assert!(read_model.locations.contains_key(&second));
+    }
+
+    #[tokio::test]
+    async fn test_projection_feeds_sharded_read_model() {
+        use crate::queries::GetLocation;
+
+        let runner = ProjectionRunner::new(Arc::new(ShardedReadModel::new(4)));
+        let locations: Vec<Uuid> = (0..8).map(|_| Uuid::new_v4()).collect();
+
+        let messages = locations
+            .iter()
+            .enumerate()
+            .map(|(index, location_id)| message(index as u64 + 1, &defined(*location_id)));
+        runner.run(futures::stream::iter(messages)).await;
+
+        assert_eq!(runner.last_sequence(), 8);
+        let read_model = runner.read_model();
+        assert_eq!(read_model.len(), 8);
+        for location_id in locations {
+            let details = read_model.get_location(&GetLocation {
+                location_id,
+                include_children: false,
+                include_ancestors: false,
+            });
+            assert_eq!(details.unwrap().location.name, "Warehouse");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_projection_decodes_msgpack_payloads() {
+        use crate::nats::{Codec, MsgpackCodec};
+
+        let location_id = Uuid::new_v4();
+        let runner = ProjectionRunner::new(Arc::new(RwLock::new(LocationReadModel::default())));
+
+        let applied = runner
+            .apply(&ProjectionMessage {
+                sequence: 1,
+                payload: MsgpackCodec.encode(&defined(location_id)).unwrap(),
+                content_type: Some(MsgpackCodec::CONTENT_TYPE.to_string()),
+            })
+            .await;
+
+        assert!(applied);
+        let read_model = runner.read_model();
+        assert!(read_model.read().await.locations.contains_key(&location_id));
+    }
+}
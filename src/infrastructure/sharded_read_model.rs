@@ -0,0 +1,429 @@
+//! Read model partitioned into independently locked shards
+//!
+//! A [`LocationReadModel`] behind a single lock makes every query wait for
+//! every write. [`ShardedReadModel`] spreads locations over several inner read
+//! models by a hash of their ID, each behind its own lock, so writes to one
+//! shard do not hold up queries answered by another.
+
+use crate::projections::{LocationProjection, LocationReadModel, LocationView};
+use crate::queries::{
+    FindNearbyLocations, GetLocation, LocationDetails, LocationQuery, NearbyLocation,
+};
+use crate::{HierarchyReorganized, LocationDeleted, LocationDomainEvent};
+use cim_domain::DomainEvent;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
+
+/// Location read model split into shards by location ID
+///
+/// Events are applied to the shard owning their location; a reorganization is
+/// split up by shard and a delete is applied to every shard, so children held
+/// elsewhere are detached as in an unsharded model. Queries that span shards
+/// take one shard lock at a time and may observe such an event half-applied.
+///
+/// Only the location and proximity queries are answered across shards; the
+/// hierarchy maps of each shard cover only that shard's locations.
+#[derive(Debug)]
+pub struct ShardedReadModel {
+    shards: Vec<RwLock<LocationReadModel>>,
+}
+
+impl ShardedReadModel {
+    /// Number of shards used by [`Default`]
+    pub const DEFAULT_SHARDS: usize = 16;
+
+    /// Create an empty model with `shard_count` shards (at least one)
+    pub fn new(shard_count: usize) -> Self {
+        Self {
+            shards: (0..shard_count.max(1))
+                .map(|_| RwLock::new(LocationReadModel::default()))
+                .collect(),
+        }
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Number of locations across all shards
+    pub fn len(&self) -> usize {
+        (0..self.shards.len())
+            .map(|index| self.read(index).locations.len())
+            .sum()
+    }
+
+    /// Check if no shard holds a location
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Apply an event to the shards it affects
+    pub fn handle_event(&self, event: &LocationDomainEvent) {
+        match event {
+            LocationDomainEvent::HierarchyReorganized(e) => self.handle_reorganized(e),
+            LocationDomainEvent::LocationDeleted(e) => self.handle_deleted(e),
+            _ => self
+                .write(self.shard_of(event.aggregate_id()))
+                .handle_event(event),
+        }
+    }
+
+    /// Answer a [`GetLocation`] query, gathering children and ancestors from every shard
+    pub fn get_location(&self, query: &GetLocation) -> Option<LocationDetails> {
+        let location = self.view(query.location_id)?;
+
+        let children = if query.include_children {
+            let mut children: Vec<LocationView> = (0..self.shards.len())
+                .flat_map(|index| {
+                    self.read(index)
+                        .locations
+                        .values()
+                        .filter(|child| child.parent_id == Some(query.location_id))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            children.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+            children
+        } else {
+            Vec::new()
+        };
+
+        // The visited set guards against a corrupt hierarchy containing a cycle
+        let mut ancestors = Vec::new();
+        if query.include_ancestors {
+            let mut visited = HashSet::from([query.location_id]);
+            let mut parent_id = location.parent_id;
+            while let Some(parent) = parent_id.and_then(|id| self.view(id)) {
+                if !visited.insert(parent.id) {
+                    break;
+                }
+                parent_id = parent.parent_id;
+                ancestors.push(parent);
+            }
+        }
+
+        Some(LocationDetails {
+            location,
+            children,
+            ancestors,
+        })
+    }
+
+    /// Answer a [`FindNearbyLocations`] query on every shard and merge the results
+    ///
+    /// Results are ordered as for a single read model: nearest first, ties
+    /// broken by ID. Each shard returns up to `max_results`, and the merged
+    /// list is cut to that many.
+    pub fn find_nearby(&self, query: &FindNearbyLocations) -> Vec<NearbyLocation> {
+        let mut nearby: Vec<NearbyLocation> = (0..self.shards.len())
+            .flat_map(|index| query.execute(&self.read(index)))
+            .collect();
+        nearby.sort_by(|a, b| {
+            a.distance_meters
+                .total_cmp(&b.distance_meters)
+                .then_with(|| a.location.id.cmp(&b.location.id))
+        });
+        if let Some(max_results) = query.max_results {
+            nearby.truncate(max_results);
+        }
+        nearby
+    }
+
+    /// Give each shard the part of a reorganization concerning its locations
+    fn handle_reorganized(&self, event: &HierarchyReorganized) {
+        for index in 0..self.shards.len() {
+            let affected: Vec<_> = event
+                .affected
+                .iter()
+                .filter(|(location_id, _, _)| self.shard_of(*location_id) == index)
+                .cloned()
+                .collect();
+            if affected.is_empty() {
+                continue;
+            }
+
+            self.write(index)
+                .handle_event(&LocationDomainEvent::HierarchyReorganized(
+                    HierarchyReorganized {
+                        affected,
+                        ..event.clone()
+                    },
+                ));
+        }
+    }
+
+    /// Delete a location, and with `cascade` its descendants, from every shard
+    ///
+    /// Children are recorded in the shard of the child, so the descendants are
+    /// collected across shards first and then deleted one at a time, deepest
+    /// first.
+    fn handle_deleted(&self, event: &LocationDeleted) {
+        let mut ids = vec![event.location_id];
+        if event.cascade {
+            let mut next = 0;
+            while next < ids.len() {
+                for child_id in self.children_of(ids[next]) {
+                    if !ids.contains(&child_id) {
+                        ids.push(child_id);
+                    }
+                }
+                next += 1;
+            }
+        }
+
+        for location_id in ids.into_iter().rev() {
+            let delete = LocationDomainEvent::LocationDeleted(LocationDeleted {
+                location_id,
+                cascade: false,
+                ..event.clone()
+            });
+            for index in 0..self.shards.len() {
+                self.write(index).handle_event(&delete);
+            }
+        }
+    }
+
+    /// IDs of the direct children of a location, from every shard
+    fn children_of(&self, parent_id: Uuid) -> Vec<Uuid> {
+        (0..self.shards.len())
+            .flat_map(|index| {
+                self.read(index)
+                    .hierarchy
+                    .parent_child_map
+                    .get(&parent_id)
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn view(&self, location_id: Uuid) -> Option<LocationView> {
+        self.read(self.shard_of(location_id))
+            .locations
+            .get(&location_id)
+            .cloned()
+    }
+
+    fn shard_of(&self, location_id: Uuid) -> usize {
+        let mut hasher = DefaultHasher::new();
+        location_id.hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+
+    /// A panicking writer poisons only its shard; the shard keeps being served
+    fn read(&self, index: usize) -> RwLockReadGuard<'_, LocationReadModel> {
+        self.shards[index]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, index: usize) -> RwLockWriteGuard<'_, LocationReadModel> {
+        self.shards[index]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ShardedReadModel {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SHARDS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LocationArchived, LocationDefined, LocationMoved, ParentLocationSet};
+    use crate::value_objects::{Distance, GeoCoordinates, LocationType};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn defined(location_id: Uuid, name: &str, coordinates: GeoCoordinates) -> LocationDomainEvent {
+        LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id,
+            name: name.to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: Some(coordinates),
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        })
+    }
+
+    fn parent_set(location_id: Uuid, parent_id: Uuid) -> LocationDomainEvent {
+        LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+            location_id,
+            parent_id,
+            previous_parent_id: None,
+            reason: "Test".to_string(),
+        })
+    }
+
+    fn nearby(center: GeoCoordinates, meters: f64) -> FindNearbyLocations {
+        FindNearbyLocations {
+            center,
+            radius: Distance::from_meters(meters),
+            location_types: None,
+            max_results: None,
+        }
+    }
+
+    fn get(location_id: Uuid) -> GetLocation {
+        GetLocation {
+            location_id,
+            include_children: true,
+            include_ancestors: true,
+        }
+    }
+
+    #[test]
+    fn test_sharded_queries_match_single_read_model() {
+        let sharded = ShardedReadModel::new(4);
+        let mut single = LocationReadModel::default();
+
+        let campus = Uuid::new_v4();
+        let buildings: Vec<Uuid> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let room = Uuid::new_v4();
+        let mut events = vec![defined(campus, "Campus", GeoCoordinates::new(40.0, -74.0))];
+        for (i, building) in buildings.iter().enumerate() {
+            events.push(defined(
+                *building,
+                &format!("Building {i}"),
+                GeoCoordinates::new(40.0 + i as f64 * 0.001, -74.0),
+            ));
+            events.push(parent_set(*building, campus));
+        }
+        events.push(defined(room, "Room", GeoCoordinates::new(40.0, -74.001)));
+        events.push(parent_set(room, buildings[3]));
+
+        for event in &events {
+            sharded.handle_event(event);
+            single.handle_event(event);
+        }
+
+        let center = GeoCoordinates::new(40.0, -74.0);
+        let ids = |found: Vec<NearbyLocation>| -> Vec<Uuid> {
+            found.into_iter().map(|n| n.location.id).collect()
+        };
+        assert_eq!(
+            ids(sharded.find_nearby(&nearby(center.clone(), 500.0))),
+            ids(nearby(center.clone(), 500.0).execute(&single))
+        );
+
+        let details = sharded.get_location(&get(campus)).unwrap();
+        let expected = get(campus).execute(&single).unwrap();
+        let names = |views: &[LocationView]| -> Vec<String> {
+            views.iter().map(|view| view.name.clone()).collect()
+        };
+        assert_eq!(names(&details.children), names(&expected.children));
+        assert_eq!(details.children.len(), 8);
+
+        let ancestors = sharded.get_location(&get(room)).unwrap().ancestors;
+        assert_eq!(names(&ancestors), vec!["Building 3", "Campus"]);
+
+        // A cascading delete reaches descendants held by other shards
+        let delete = LocationDomainEvent::LocationDeleted(LocationDeleted {
+            location_id: campus,
+            location_type: LocationType::Physical,
+            reason: "Sold".to_string(),
+            cascade: true,
+        });
+        sharded.handle_event(&delete);
+        single.handle_event(&delete);
+        assert!(sharded.is_empty());
+        assert!(single.locations.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_readers_and_writer() {
+        const LOCATIONS: usize = 200;
+
+        let model = Arc::new(ShardedReadModel::default());
+        let ids: Arc<Vec<Uuid>> = Arc::new((0..LOCATIONS).map(|_| Uuid::new_v4()).collect());
+        let origin = GeoCoordinates::new(51.5, -0.12);
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let (model, ids, done, origin) =
+                    (model.clone(), ids.clone(), done.clone(), origin.clone());
+                tokio::spawn(async move {
+                    let mut queries = 0;
+                    while !done.load(Ordering::SeqCst) {
+                        let id = ids[queries % ids.len()];
+                        if let Some(details) = model.get_location(&get(id)) {
+                            assert_eq!(details.location.id, id);
+                        }
+                        let found = model.find_nearby(&nearby(origin.clone(), 50_000.0));
+                        assert!(found.len() <= ids.len());
+                        queries += 1;
+                        tokio::task::yield_now().await;
+                    }
+                    queries
+                })
+            })
+            .collect();
+
+        let writer = {
+            let (model, ids, done, origin) =
+                (model.clone(), ids.clone(), done.clone(), origin.clone());
+            tokio::spawn(async move {
+                for (i, id) in ids.iter().enumerate() {
+                    model.handle_event(&defined(*id, &format!("Site {i}"), origin.clone()));
+                    tokio::task::yield_now().await;
+                }
+                for (i, id) in ids.iter().enumerate() {
+                    if i % 2 == 0 {
+                        model.handle_event(&LocationDomainEvent::LocationMoved(
+                            LocationMoved::new(
+                                *id,
+                                origin.clone(),
+                                GeoCoordinates::new(48.85, 2.35),
+                            ),
+                        ));
+                    } else if i % 5 == 0 {
+                        model.handle_event(&LocationDomainEvent::LocationArchived(
+                            LocationArchived {
+                                location_id: *id,
+                                name: format!("Site {i}"),
+                                location_type: LocationType::Physical,
+                                reason: "Closed".to_string(),
+                                actor: None,
+                            },
+                        ));
+                    }
+                    tokio::task::yield_now().await;
+                }
+                done.store(true, Ordering::SeqCst);
+            })
+        };
+
+        tokio::time::timeout(Duration::from_secs(30), async {
+            writer.await.unwrap();
+            for reader in readers {
+                assert!(reader.await.unwrap() > 0);
+            }
+        })
+        .await
+        .expect("readers and writer deadlocked");
+
+        assert_eq!(model.len(), LOCATIONS);
+        let archived = (0..LOCATIONS).filter(|i| i % 2 == 1 && i % 5 == 0).count();
+        let in_paris = model.find_nearby(&nearby(GeoCoordinates::new(48.85, 2.35), 1_000.0));
+        let in_london = model.find_nearby(&nearby(origin, 1_000.0));
+        assert_eq!(in_paris.len(), LOCATIONS / 2);
+        assert_eq!(in_london.len(), LOCATIONS / 2 - archived);
+        assert!(in_london
+            .iter()
+            .all(|n| ids.iter().position(|id| *id == n.location.id).unwrap() % 2 == 1));
+    }
+}
@@ -14,6 +14,7 @@ pub mod commands;
 pub mod domain_events;
 pub mod events;
 pub mod handlers;
+pub mod import;
 pub mod infrastructure;
 pub mod nats;
 pub mod ports;
@@ -33,6 +34,8 @@ pub use domain_events::LocationDomainEvent;
 pub use events::*;
 // Export command handler from handlers
 pub use handlers::LocationCommandHandler;
+// Export bulk importers
+pub use import::*;
 // Export infrastructure
 pub use infrastructure::*;
 // Export NATS communication types
@@ -42,7 +45,10 @@ pub use ports::*;
 // Export projections
 pub use projections::*;
 // Export queries
-pub use queries::{FindNearbyLocations, GetLocation, GetLocationHierarchy};
+pub use queries::{
+    FindNearbyLocations, GetLocation, GetLocationHierarchy, LocationDetails, LocationQuery,
+    LocationTree, NearbyLocation,
+};
 // Export query handler separately to avoid conflicts
 pub use queries::LocationQueryHandler as QueryHandler;
 // Export services
@@ -0,0 +1,188 @@
+//! Wire formats for NATS payloads
+//!
+//! Events and commands are JSON by default. High-frequency publishers such as
+//! position tracking can switch to MessagePack, which is considerably smaller.
+//! The codec in use is announced in the `content-type` header so consumers can
+//! pick the matching decoder; messages without the header are JSON.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Header naming the payload codec
+pub const CONTENT_TYPE_HEADER: &str = "content-type";
+
+/// Encodes and decodes NATS message payloads
+pub trait Codec: Send + Sync {
+    /// MIME type announced in the `content-type` header
+    fn content_type(&self) -> &'static str;
+
+    /// Serialize a value into a payload
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, CodecError>;
+
+    /// Deserialize a payload
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError>;
+}
+
+/// JSON payloads, the default wire format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl JsonCodec {
+    pub const CONTENT_TYPE: &'static str = "application/json";
+}
+
+impl Codec for JsonCodec {
+    fn content_type(&self) -> &'static str {
+        Self::CONTENT_TYPE
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        serde_json::to_vec(value).map_err(|e| CodecError::Encode(e.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        serde_json::from_slice(bytes).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+/// MessagePack payloads
+///
+/// Structs are encoded as maps keyed by field name, so optional fields can be
+/// added without breaking older consumers, just as with JSON.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgpackCodec;
+
+impl MsgpackCodec {
+    pub const CONTENT_TYPE: &'static str = "application/msgpack";
+}
+
+impl Codec for MsgpackCodec {
+    fn content_type(&self) -> &'static str {
+        Self::CONTENT_TYPE
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        rmp_serde::to_vec_named(value).map_err(|e| CodecError::Encode(e.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        rmp_serde::from_slice(bytes).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+/// Decode a payload with the codec named by its `content-type` header
+///
+/// A missing header means JSON, which is what every publisher sent before
+/// codecs were configurable.
+pub fn decode_with_content_type<T: DeserializeOwned>(
+    content_type: Option<&str>,
+    bytes: &[u8],
+) -> Result<T, CodecError> {
+    match content_type {
+        None | Some(JsonCodec::CONTENT_TYPE) => JsonCodec.decode(bytes),
+        Some(MsgpackCodec::CONTENT_TYPE) => MsgpackCodec.decode(bytes),
+        Some(other) => Err(CodecError::UnsupportedContentType(other.to_string())),
+    }
+}
+
+/// Decode a NATS message with the codec named in its headers
+pub fn decode_message<T: DeserializeOwned>(message: &async_nats::Message) -> Result<T, CodecError> {
+    let content_type = message
+        .headers
+        .as_ref()
+        .and_then(|headers| headers.get(CONTENT_TYPE_HEADER))
+        .map(|value| value.as_str());
+    decode_with_content_type(content_type, &message.payload)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    #[error("Failed to encode payload: {0}")]
+    Encode(String),
+
+    #[error("Failed to decode payload: {0}")]
+    Decode(String),
+
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::LocationDefined;
+    use crate::value_objects::{Address, GeoCoordinates, LocationType};
+    use crate::LocationDomainEvent;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn defined() -> LocationDomainEvent {
+        LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id: Uuid::new_v4(),
+            name: "Ferry Building".to_string(),
+            location_type: LocationType::Physical,
+            address: Some(Address::new(
+                "1 Ferry Building".to_string(),
+                "San Francisco".to_string(),
+                "CA".to_string(),
+                "USA".to_string(),
+                "94111".to_string(),
+            )),
+            addresses: HashMap::new(),
+            coordinates: Some(GeoCoordinates::new(37.7955, -122.3937).with_altitude(4.0)),
+            virtual_location: None,
+            parent_id: Some(Uuid::new_v4()),
+            actor: None,
+        })
+    }
+
+    fn assert_round_trip<C: Codec>(codec: C) -> Vec<u8> {
+        let event = defined();
+
+        let bytes = codec.encode(&event).unwrap();
+        let decoded: LocationDomainEvent = codec.decode(&bytes).unwrap();
+        let via_header: LocationDomainEvent =
+            decode_with_content_type(Some(codec.content_type()), &bytes).unwrap();
+
+        match (&event, &decoded, &via_header) {
+            (
+                LocationDomainEvent::LocationDefined(original),
+                LocationDomainEvent::LocationDefined(decoded),
+                LocationDomainEvent::LocationDefined(via_header),
+            ) => {
+                for restored in [decoded, via_header] {
+                    assert_eq!(restored.location_id, original.location_id);
+                    assert_eq!(restored.name, original.name);
+                    assert_eq!(restored.address, original.address);
+                    assert_eq!(restored.coordinates, original.coordinates);
+                    assert_eq!(restored.parent_id, original.parent_id);
+                }
+            }
+            other => panic!("Expected LocationDefined, got {other:?}"),
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let bytes = assert_round_trip(JsonCodec);
+
+        // Messages without a content-type header are JSON
+        assert!(decode_with_content_type::<LocationDomainEvent>(None, &bytes).is_ok());
+    }
+
+    #[test]
+    fn test_msgpack_round_trip_is_smaller_than_json() {
+        let bytes = assert_round_trip(MsgpackCodec);
+
+        assert!(bytes.len() < JsonCodec.encode(&defined()).unwrap().len());
+        assert!(decode_with_content_type::<LocationDomainEvent>(None, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_unknown_content_type_is_rejected() {
+        let result = decode_with_content_type::<LocationDomainEvent>(Some("text/csv"), b"");
+
+        assert!(matches!(result, Err(CodecError::UnsupportedContentType(t)) if t == "text/csv"));
+    }
+}
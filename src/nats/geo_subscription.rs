@@ -0,0 +1,150 @@
+//! Client-side geographic filtering for coordinate event subscriptions
+//!
+//! NATS wildcards match whole subject tokens, so a subject pattern cannot express
+//! a numeric range. [`GeoSubscriptionFilter`] subscribes to every coordinate event
+//! and keeps only those whose subject coordinates fall inside a bounding box.
+
+use super::SubjectPatterns;
+use crate::value_objects::{BoundingBox, GeoCoordinates};
+use futures::{future, Stream, StreamExt};
+
+/// Filters coordinate event subjects by a geographic bounding box
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoSubscriptionFilter {
+    bounds: BoundingBox,
+}
+
+impl GeoSubscriptionFilter {
+    /// Create a filter for the given bounding box
+    pub fn new(bounds: BoundingBox) -> Self {
+        Self { bounds }
+    }
+
+    /// The bounding box events must fall inside
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    /// Broad subject to subscribe to before filtering
+    pub fn subscription_subject(&self) -> String {
+        SubjectPatterns::all_coordinate_activity()
+    }
+
+    /// Check whether a coordinate event subject lies inside the bounding box
+    ///
+    /// Subjects that are not coordinate-scoped, or whose coordinates cannot be
+    /// parsed, never match.
+    pub fn matches(&self, subject: &str) -> bool {
+        parse_subject_coordinates(subject).is_some_and(|coords| self.bounds.contains(&coords))
+    }
+
+    /// Subscribe to coordinate events, yielding only messages inside the bounding box
+    pub async fn subscribe(
+        &self,
+        client: &async_nats::Client,
+    ) -> Result<impl Stream<Item = async_nats::Message>, async_nats::SubscribeError> {
+        let filter = self.clone();
+        let subscriber = client.subscribe(self.subscription_subject()).await?;
+
+        Ok(subscriber.filter(move |message| future::ready(filter.matches(&message.subject))))
+    }
+}
+
+/// Extract the coordinates from a `{namespace}.location.coordinates.{lat}.{lng}...` subject
+///
+/// Coordinates are written with a fixed number of decimals (see
+/// [`SubjectPatterns::coordinate_events`]), so each one spans two subject tokens.
+pub fn parse_subject_coordinates(subject: &str) -> Option<GeoCoordinates> {
+    let tokens: Vec<&str> = subject.split('.').collect();
+    let start = tokens
+        .windows(2)
+        .position(|pair| pair == ["location", "coordinates"])?
+        + 2;
+
+    let number = |whole: &str, fraction: &str| -> Option<f64> {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        format!("{whole}.{fraction}").parse().ok()
+    };
+
+    let latitude = number(tokens.get(start)?, tokens.get(start + 1)?)?;
+    let longitude = number(tokens.get(start + 2)?, tokens.get(start + 3)?)?;
+
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+
+    Some(GeoCoordinates::new(latitude, longitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(lat: f64, lng: f64) -> String {
+        format!(
+            "events.location.coordinates.{:.6}.{:.6}.coordinates.location_moved",
+            lat, lng
+        )
+    }
+
+    #[test]
+    fn test_parse_subject_coordinates() {
+        let coords = parse_subject_coordinates(&subject(37.7749, -122.4194)).unwrap();
+        assert!((coords.latitude - 37.7749).abs() < 1e-9);
+        assert!((coords.longitude + 122.4194).abs() < 1e-9);
+
+        assert!(parse_subject_coordinates("events.location.location.defined.abc").is_none());
+        assert!(parse_subject_coordinates("events.location.coordinates.37.774900").is_none());
+        assert!(
+            parse_subject_coordinates("events.location.coordinates.95.000000.0.000000.x").is_none()
+        );
+        assert!(
+            parse_subject_coordinates("events.location.coordinates.37.-122.419400.x").is_none()
+        );
+    }
+
+    #[test]
+    fn test_filter_passes_only_subjects_inside_box() {
+        // Roughly the San Francisco Bay Area
+        let filter = GeoSubscriptionFilter::new(BoundingBox {
+            min_lat: 37.0,
+            max_lat: 38.5,
+            min_lon: -123.0,
+            max_lon: -121.5,
+        });
+
+        let subjects = [
+            (subject(37.7749, -122.4194), true),  // San Francisco
+            (subject(37.8044, -122.2712), true),  // Oakland
+            (subject(37.3382, -121.8863), true),  // San Jose
+            (subject(34.0522, -118.2437), false), // Los Angeles
+            (subject(37.7749, -120.0000), false), // East of the box
+            (subject(40.7128, -74.0060), false),  // New York
+            ("events.location.location.defined.abc".to_string(), false),
+        ];
+
+        for (subject, inside) in subjects {
+            assert_eq!(filter.matches(&subject), inside, "{subject}");
+        }
+        assert_eq!(
+            filter.subscription_subject(),
+            "events.location.coordinates.*.*.>"
+        );
+    }
+
+    #[test]
+    fn test_filter_handles_antimeridian_box() {
+        let filter = GeoSubscriptionFilter::new(BoundingBox {
+            min_lat: -20.0,
+            max_lat: -10.0,
+            min_lon: 170.0,
+            max_lon: -170.0,
+        });
+
+        assert!(filter.matches(&subject(-17.7134, 178.0650))); // Fiji
+        assert!(filter.matches(&subject(-14.2710, -170.1322))); // American Samoa
+        assert!(!filter.matches(&subject(-17.7134, 150.0)));
+    }
+}
@@ -5,9 +5,18 @@
 //! and system coherence.
 
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
 use uuid::Uuid;
+use cid::multihash::Multihash;
 use cid::Cid;
+use sha2::{Digest, Sha256};
+
+/// Multicodec code for SHA2-256 multihashes
+const SHA2_256_CODE: u64 = 0x12;
+
+/// Multicodec code for DAG-JSON content
+const DAG_JSON_CODEC: u64 = 0x0129;
 
 /// Message identifiers required by CIM principles
 /// Every message in the system MUST have correlation and causation IDs
@@ -67,18 +76,108 @@ impl MessageIdentity {
         self.message_id.0 == self.correlation_id.0 && self.correlation_id.0 == self.causation_id.0
     }
 
+    /// Read an identity from `message-id`/`correlation-id`/`causation-id` headers
+    ///
+    /// A message carrying only a `message-id` is treated as a root message.
+    pub fn from_headers(headers: &async_nats::HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| Uuid::parse_str(value.as_str()).ok())
+        };
+
+        let message_id = header("message-id")?;
+        Some(Self {
+            message_id: MessageId(message_id),
+            correlation_id: CorrelationId(header("correlation-id").unwrap_or(message_id)),
+            causation_id: CausationId(header("causation-id").unwrap_or(message_id)),
+        })
+    }
+
+    /// Write this identity as `message-id`/`correlation-id`/`causation-id` headers
+    pub fn insert_headers(&self, headers: &mut async_nats::HeaderMap) {
+        headers.insert("message-id", self.message_id.to_string().as_str());
+        headers.insert("correlation-id", self.correlation_id.to_string().as_str());
+        headers.insert("causation-id", self.causation_id.to_string().as_str());
+    }
+
     /// Get the correlation chain depth (0 for root, 1+ for caused messages)
+    ///
+    /// A single identity only knows whether it is a root, so every caused message
+    /// reports 1. Use [`CausationChain::depth`] for the exact depth.
     pub fn chain_depth(&self) -> u32 {
         if self.is_root() {
             0
         } else {
-            // In a real system, this would traverse the causation chain
-            // For now, we indicate it's at least 1 level deep
             1
         }
     }
 }
 
+/// A set of related messages indexed for causation traversal
+#[derive(Debug, Clone, Default)]
+pub struct CausationChain {
+    messages: HashMap<Uuid, MessageIdentity>,
+}
+
+impl CausationChain {
+    /// Index a collection of message identities
+    pub fn new(identities: impl IntoIterator<Item = MessageIdentity>) -> Result<Self, IdentityError> {
+        let mut messages = HashMap::new();
+        for identity in identities {
+            let message_id = identity.message_id.0;
+            if messages.insert(message_id, identity).is_some() {
+                return Err(IdentityError::DuplicateMessage(message_id));
+            }
+        }
+        Ok(Self { messages })
+    }
+
+    /// Index the identities carried by a collection of event metadata
+    pub fn from_metadata<'a>(
+        metadata: impl IntoIterator<Item = &'a EventMetadata>,
+    ) -> Result<Self, IdentityError> {
+        Self::new(metadata.into_iter().map(|m| m.identity.clone()))
+    }
+
+    /// Number of messages in the chain
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Check if the chain is empty
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Depth of a message: 0 for the root, 1 for messages it caused, and so on
+    ///
+    /// Walks `causation_id` links back to the root. Fails if the message or one
+    /// of its ancestors is missing, or if the links loop back on themselves.
+    pub fn depth(&self, message_id: &MessageId) -> Result<u32, IdentityError> {
+        let mut current = self
+            .messages
+            .get(&message_id.0)
+            .ok_or(IdentityError::UnknownMessage(message_id.0))?;
+        let mut visited = HashSet::from([message_id.0]);
+        let mut depth = 0;
+
+        while !current.is_root() {
+            let parent_id = current.causation_id.0;
+            if !visited.insert(parent_id) {
+                return Err(IdentityError::CausationCycle);
+            }
+            current = self
+                .messages
+                .get(&parent_id)
+                .ok_or(IdentityError::UnknownMessage(parent_id))?;
+            depth += 1;
+        }
+
+        Ok(depth)
+    }
+}
+
 /// Unique identifier for each message
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MessageId(pub Uuid);
@@ -280,6 +379,35 @@ impl std::fmt::Display for ActorId {
     }
 }
 
+impl std::str::FromStr for ActorId {
+    type Err = String;
+
+    /// Parse the `kind:name` form produced by `Display`
+    ///
+    /// A bare UUID is taken to be a user, as authenticated users are issued
+    /// by their ID.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(user_id) = Uuid::parse_str(s) {
+            return Ok(Self::User(user_id));
+        }
+
+        let (kind, name) = s
+            .split_once(':')
+            .filter(|(_, name)| !name.is_empty())
+            .ok_or_else(|| format!("Invalid actor '{}'", s))?;
+        match kind {
+            "user" => Uuid::parse_str(name)
+                .map(Self::User)
+                .map_err(|e| format!("Invalid user actor '{}': {}", s, e)),
+            "system" => Ok(Self::System(name.to_string())),
+            "external" => Ok(Self::External(name.to_string())),
+            "location-tracker" => Ok(Self::LocationTracker(name.to_string())),
+            "geocoder" => Ok(Self::Geocoder(name.to_string())),
+            _ => Err(format!("Unknown actor kind '{}'", kind)),
+        }
+    }
+}
+
 /// CIM-compliant domain event with mandatory correlation/causation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CimDomainEvent {
@@ -336,6 +464,38 @@ impl CimDomainEvent {
         self
     }
 
+    /// Compute the content identifier of this event
+    ///
+    /// The CID is a CIDv1 (DAG-JSON codec) over the SHA-256 of the aggregate ID,
+    /// sequence, event type, payload and previous CID, so it changes whenever the
+    /// event content or its position in the chain changes.
+    pub fn compute_cid(&self) -> Result<Cid, serde_json::Error> {
+        let content = serde_json::to_vec(&serde_json::json!({
+            "aggregate_id": self.aggregate_id,
+            "sequence": self.sequence,
+            "event_type": self.event_type,
+            "payload": self.payload,
+            "previous_cid": self.previous_cid.map(|cid| cid.to_string()),
+        }))?;
+
+        let digest = Sha256::digest(&content);
+        let hash = Multihash::wrap(SHA2_256_CODE, &digest)
+            .expect("SHA-256 digest fits in a multihash");
+        Ok(Cid::new_v1(DAG_JSON_CODEC, hash))
+    }
+
+    /// Acknowledgment to send back to the command that produced this event
+    pub fn acknowledgment(&self) -> EventAcknowledgment {
+        EventAcknowledgment {
+            status: "accepted".to_string(),
+            location_id: self.aggregate_id.clone(),
+            message_id: self.message_id().clone(),
+            correlation_id: self.correlation_id().clone(),
+            causation_id: self.causation_id().clone(),
+            event_cid: self.event_cid.map(|cid| cid.to_string()),
+        }
+    }
+
     /// Validate the event structure
     pub fn validate(&self) -> Result<(), IdentityError> {
         self.metadata.validate()?;
@@ -372,6 +532,50 @@ impl CimDomainEvent {
     }
 }
 
+/// Verify the CID chain of consecutive events of one aggregate
+///
+/// Every event must carry a CID matching its content, and each event after the
+/// first must link to its predecessor's CID through `previous_cid`.
+pub fn verify_chain(events: &[CimDomainEvent]) -> Result<(), IdentityError> {
+    let mut previous: Option<&CimDomainEvent> = None;
+
+    for event in events {
+        let event_cid = event
+            .event_cid
+            .ok_or(IdentityError::MissingCid { sequence: event.sequence })?;
+
+        match event.compute_cid() {
+            Ok(computed) if computed == event_cid => {}
+            _ => return Err(IdentityError::CidMismatch { sequence: event.sequence }),
+        }
+
+        if let Some(previous) = previous {
+            if event.previous_cid != previous.event_cid {
+                return Err(IdentityError::BrokenCidLink { sequence: event.sequence });
+            }
+        }
+
+        previous = Some(event);
+    }
+
+    Ok(())
+}
+
+/// Reply to a command describing the event it produced
+///
+/// Clients use the message and correlation IDs to follow the event through the
+/// stream, and the CID (when computed) to verify its content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventAcknowledgment {
+    pub status: String,
+    pub location_id: String,
+    pub message_id: MessageId,
+    pub correlation_id: CorrelationId,
+    pub causation_id: CausationId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_cid: Option<String>,
+}
+
 /// Message factory for creating properly correlated messages
 /// ALWAYS use this factory - NEVER create messages directly
 pub struct MessageFactory;
@@ -478,6 +682,18 @@ pub enum IdentityError {
 
     #[error("Duplicate message ID in correlation chain: {0}")]
     DuplicateMessage(Uuid),
+
+    #[error("Message not found in correlation chain: {0}")]
+    UnknownMessage(Uuid),
+
+    #[error("Event {sequence} has no CID")]
+    MissingCid { sequence: u64 },
+
+    #[error("Event {sequence} content does not match its CID")]
+    CidMismatch { sequence: u64 },
+
+    #[error("Event {sequence} does not link to the previous event's CID")]
+    BrokenCidLink { sequence: u64 },
 }
 
 #[cfg(test)]
@@ -569,6 +785,25 @@ mod tests {
         assert_eq!(geocoder_actor.to_string(), "geocoder:google-maps");
     }
 
+    #[test]
+    fn test_actor_id_round_trips_through_display() {
+        let user_id = Uuid::new_v4();
+        for actor in [
+            ActorId::user(user_id),
+            ActorId::system("location-service"),
+            ActorId::External("partner-api".to_string()),
+            ActorId::location_tracker("gps-tracker"),
+            ActorId::geocoder("google-maps"),
+        ] {
+            assert_eq!(actor.to_string().parse::<ActorId>(), Ok(actor));
+        }
+
+        assert_eq!(user_id.to_string().parse::<ActorId>(), Ok(ActorId::user(user_id)));
+        assert!("test".parse::<ActorId>().is_err());
+        assert!("user:not-a-uuid".parse::<ActorId>().is_err());
+        assert!("system:".parse::<ActorId>().is_err());
+    }
+
     #[test]
     fn test_correlation_chain() {
         // Create a chain of 3 messages
@@ -590,6 +825,68 @@ mod tests {
         assert!(!child2.is_root());
     }
 
+    #[test]
+    fn test_causation_chain_depth() {
+        let root = MessageIdentity::new_root();
+        let child = MessageIdentity::new_caused_by(&root);
+        let grandchild = MessageIdentity::new_caused_by(&child);
+        let great_grandchild = MessageIdentity::new_caused_by(&grandchild);
+        let sibling = MessageIdentity::new_caused_by(&root);
+
+        let chain = CausationChain::new(vec![
+            great_grandchild.clone(),
+            root.clone(),
+            sibling.clone(),
+            grandchild.clone(),
+            child.clone(),
+        ])
+        .unwrap();
+
+        assert_eq!(chain.len(), 5);
+        assert_eq!(chain.depth(&root.message_id).unwrap(), 0);
+        assert_eq!(chain.depth(&child.message_id).unwrap(), 1);
+        assert_eq!(chain.depth(&sibling.message_id).unwrap(), 1);
+        assert_eq!(chain.depth(&grandchild.message_id).unwrap(), 2);
+        assert_eq!(chain.depth(&great_grandchild.message_id).unwrap(), 3);
+
+        assert!(matches!(
+            chain.depth(&MessageId::new()),
+            Err(IdentityError::UnknownMessage(_))
+        ));
+    }
+
+    #[test]
+    fn test_causation_chain_detects_cycles_and_gaps() {
+        let correlation = Uuid::new_v4();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let caused = |message: Uuid, cause: Uuid| MessageIdentity {
+            message_id: MessageId::from_uuid(message),
+            correlation_id: CorrelationId(correlation),
+            causation_id: CausationId(cause),
+        };
+
+        let cyclic = CausationChain::new(vec![caused(a, b), caused(b, a)]).unwrap();
+        assert!(matches!(
+            cyclic.depth(&MessageId::from_uuid(a)),
+            Err(IdentityError::CausationCycle)
+        ));
+
+        // Parent never recorded
+        let root = MessageIdentity::new_root();
+        let orphan = MessageIdentity::new_caused_by(&MessageIdentity::new_caused_by(&root));
+        let gapped = CausationChain::new(vec![root, orphan.clone()]).unwrap();
+        assert!(matches!(
+            gapped.depth(&orphan.message_id),
+            Err(IdentityError::UnknownMessage(_))
+        ));
+
+        let duplicate = MessageIdentity::new_root();
+        assert!(matches!(
+            CausationChain::new(vec![duplicate.clone(), duplicate]),
+            Err(IdentityError::DuplicateMessage(_))
+        ));
+    }
+
     #[test]
     fn test_cim_message_to_domain_event() {
         #[derive(Serialize)]
@@ -624,4 +921,69 @@ mod tests {
         assert_eq!(domain_event.payload["longitude"], -122.4194);
         assert!(domain_event.validate().is_ok());
     }
+
+    #[test]
+    fn test_identity_from_headers() {
+        let command = MessageIdentity::new_caused_by(&MessageIdentity::new_root());
+        let mut headers = async_nats::HeaderMap::new();
+        command.insert_headers(&mut headers);
+
+        assert_eq!(MessageIdentity::from_headers(&headers), Some(command.clone()));
+
+        let mut root_headers = async_nats::HeaderMap::new();
+        root_headers.insert("message-id", command.message_id.to_string().as_str());
+        assert!(MessageIdentity::from_headers(&root_headers).unwrap().is_root());
+
+        assert_eq!(MessageIdentity::from_headers(&async_nats::HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_acknowledgment_carries_event_identity() {
+        let command = MessageIdentity::new_root();
+        let location_id = Uuid::new_v4();
+        let event = CimDomainEvent::new(
+            location_id.to_string(),
+            1,
+            "LocationDefined".to_string(),
+            serde_json::json!({"name": "Test Location"}),
+            Some(&command),
+            Some(ActorId::system("location-service")),
+        );
+        let cid = event.compute_cid().unwrap();
+        let event = event.with_cid(cid, None);
+
+        let ack = serde_json::to_value(event.acknowledgment()).unwrap();
+
+        assert_eq!(ack["status"], "accepted");
+        assert_eq!(ack["location_id"], location_id.to_string());
+
+        let correlation_id = Uuid::parse_str(ack["correlation_id"].as_str().unwrap()).unwrap();
+        assert_eq!(correlation_id, event.correlation_id().0);
+        assert_eq!(correlation_id, command.correlation_id.0);
+
+        let message_id = Uuid::parse_str(ack["message_id"].as_str().unwrap()).unwrap();
+        assert_eq!(message_id, event.message_id().0);
+        assert_eq!(ack["causation_id"], command.message_id.to_string());
+
+        let event_cid: Cid = ack["event_cid"].as_str().unwrap().parse().unwrap();
+        assert_eq!(event_cid, cid);
+    }
+
+    #[test]
+    fn test_compute_cid_tracks_content() {
+        let event = CimDomainEvent::new(
+            "location-123".to_string(),
+            1,
+            "LocationDefined".to_string(),
+            serde_json::json!({"name": "Test Location"}),
+            None,
+            None,
+        );
+        let mut renamed = event.clone();
+        renamed.payload = serde_json::json!({"name": "Other Location"});
+
+        assert_eq!(event.compute_cid().unwrap(), event.compute_cid().unwrap());
+        assert_ne!(event.compute_cid().unwrap(), renamed.compute_cid().unwrap());
+        assert!(event.acknowledgment().event_cid.is_none());
+    }
 }
\ No newline at end of file
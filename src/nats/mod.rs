@@ -5,6 +5,10 @@
 
 pub mod subjects;
 pub mod message_identity;
+pub mod geo_subscription;
+pub mod codec;
 
 pub use subjects::*;
-pub use message_identity::*;
\ No newline at end of file
+pub use message_identity::*;
+pub use geo_subscription::*;
+pub use codec::*;
\ No newline at end of file
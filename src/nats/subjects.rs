@@ -25,6 +25,8 @@
 //! - Semantic clarity for AI-driven understanding
 //! - NATS wildcard support for subscription patterns
 
+use crate::domain_events::LocationDomainEvent;
+use cim_domain::DomainEvent;
 use serde::{Serialize, Deserialize};
 use std::fmt;
 use uuid::Uuid;
@@ -237,6 +239,23 @@ impl LocationSubject {
         }
     }
 
+    /// Coarse subscription pattern for coordinate events by whole-degree prefix
+    ///
+    /// Coordinate subjects carry each value as two tokens, e.g. `37.774900` is
+    /// `37` then `774900`, and NATS wildcards only match whole tokens. The
+    /// prefixes are therefore the whole-degree tokens (`"37"`, `"-122"`), giving
+    /// `events.location.coordinates.37.*.-122.*.>`.
+    ///
+    /// Each pattern covers a one-degree cell: roughly 111 km of latitude, and of
+    /// longitude shrinking with the cosine of latitude. Finer cells cannot be
+    /// expressed as subjects; subscribe to the cell and filter with
+    /// [`GeoSubscriptionFilter`](super::GeoSubscriptionFilter). Values between
+    /// -1 and 0 are written as `-0`, so the cells either side of the equator or
+    /// prime meridian need both `"0"` and `"-0"`.
+    pub fn coordinate_prefix_pattern(lat_prefix: &str, lng_prefix: &str) -> String {
+        format!("events.location.coordinates.{}.*.{}.*.>", lat_prefix, lng_prefix)
+    }
+
     /// Convert to NATS subject string
     pub fn to_subject(&self) -> String {
         let base_subject = self.build_base_subject();
@@ -293,6 +312,38 @@ impl fmt::Display for LocationSubject {
     }
 }
 
+/// Derive the canonical event subject for a domain event
+///
+/// Every event is published under the standard aggregate scope, i.e.
+/// `events.location.{aggregate}.{event_type}.{location_id}`, so consumers can
+/// filter on a single aggregate with `events.location.*.*.{location_id}`.
+pub fn subject_for_event(event: &LocationDomainEvent) -> LocationSubject {
+    let (aggregate, event_type) = match event {
+        LocationDomainEvent::LocationDefined(_) => (LocationAggregate::Location, EventType::Defined),
+        LocationDomainEvent::LocationUpdated(_) => (LocationAggregate::Location, EventType::Updated),
+        LocationDomainEvent::LocationMoved(_) => (LocationAggregate::Coordinates, EventType::LocationMoved),
+        LocationDomainEvent::ParentLocationSet(_) => (LocationAggregate::Hierarchy, EventType::ParentSet),
+        LocationDomainEvent::ParentLocationRemoved(_) => (LocationAggregate::Hierarchy, EventType::ParentRemoved),
+        LocationDomainEvent::LocationMetadataAdded(_) => (LocationAggregate::Metadata, EventType::MetadataAdded),
+        LocationDomainEvent::LocationMetadataUpdated(_) => (LocationAggregate::Metadata, EventType::MetadataUpdated),
+        LocationDomainEvent::LocationMetadataRemoved(_) => (LocationAggregate::Metadata, EventType::MetadataRemoved),
+        LocationDomainEvent::LocationArchived(_) => (LocationAggregate::Location, EventType::Archived),
+        LocationDomainEvent::LocationCheckedIn(_) => (LocationAggregate::History, EventType::CheckedIn),
+        LocationDomainEvent::LocationCheckedOut(_) => (LocationAggregate::History, EventType::CheckedOut),
+        LocationDomainEvent::LocationsMerged(_) => (LocationAggregate::Location, EventType::Merged),
+        LocationDomainEvent::LocationDeleted(_) => (LocationAggregate::Location, EventType::Deleted),
+        LocationDomainEvent::AddressGeocoded(_) => (LocationAggregate::Address, EventType::AddressGeocoded),
+        LocationDomainEvent::HierarchyReorganized(_) => (LocationAggregate::Hierarchy, EventType::HierarchyReorganized),
+        LocationDomainEvent::CoordinatesUpdated(_) => (LocationAggregate::Coordinates, EventType::CoordinatesUpdated),
+        LocationDomainEvent::LocationRestored(_) => (LocationAggregate::Location, EventType::Restored),
+        LocationDomainEvent::CoordinatesValidated(_) => (LocationAggregate::Coordinates, EventType::CoordinatesValidated),
+        LocationDomainEvent::LocationAddressSet(_) => (LocationAggregate::Address, EventType::AddressUpdated),
+        LocationDomainEvent::IndoorPositionSet(_) => (LocationAggregate::Location, EventType::Updated),
+    };
+
+    LocationSubject::event(aggregate, event_type, event.aggregate_id().to_string())
+}
+
 /// Subject namespaces for different message types
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SubjectNamespace {
@@ -405,6 +456,7 @@ pub enum EventType {
     Updated,
     Archived,
     Restored,
+    Merged,
     Deleted,
     
     // Address events
@@ -479,6 +531,7 @@ impl EventType {
             Self::Updated => "updated",
             Self::Archived => "archived",
             Self::Restored => "restored",
+            Self::Merged => "merged",
             Self::Deleted => "deleted",
             Self::AddressUpdated => "address_updated",
             Self::AddressValidated => "address_validated",
@@ -848,8 +901,11 @@ impl SubjectPatterns {
     // ===== GEOGRAPHIC PATTERNS =====
     
     /// Events within a geographic bounding box (simplified)
+    ///
+    /// Subjects cannot express numeric ranges, so this is the broad coordinate
+    /// pattern; use [`GeoSubscriptionFilter`](super::GeoSubscriptionFilter) to keep
+    /// only events inside the box.
     pub fn geographic_area_events(min_lat: f64, max_lat: f64, min_lng: f64, max_lng: f64) -> String {
-        // This would need more sophisticated wildcard matching in practice
         format!("events.location.coordinates.*.*.>")
     }
     
@@ -1013,6 +1069,7 @@ pub enum SubjectError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_location_subject_creation() {
@@ -1135,6 +1192,53 @@ mod tests {
         );
     }
     
+    /// Match a subject against a pattern using NATS `*` and `>` semantics
+    fn nats_matches(pattern: &str, subject: &str) -> bool {
+        let mut subject_tokens = subject.split('.');
+        for token in pattern.split('.') {
+            match (token, subject_tokens.next()) {
+                (">", Some(_)) => return true,
+                (_, None) => return false,
+                ("*", Some(_)) => {}
+                (expected, Some(actual)) if expected == actual => {}
+                _ => return false,
+            }
+        }
+        subject_tokens.next().is_none()
+    }
+
+    #[test]
+    fn test_coordinate_prefix_pattern() {
+        assert_eq!(
+            LocationSubject::coordinate_prefix_pattern("37", "-122"),
+            "events.location.coordinates.37.*.-122.*.>"
+        );
+        assert_eq!(
+            LocationSubject::coordinate_prefix_pattern("-33", "151"),
+            "events.location.coordinates.-33.*.151.*.>"
+        );
+
+        let pattern = LocationSubject::coordinate_prefix_pattern("37", "-122");
+        let inside = LocationSubject::coordinate_event(
+            37.7749,
+            -122.4194,
+            EventType::LocationMoved,
+            Some(LocationAggregate::Coordinates),
+        );
+        let other_cell = LocationSubject::coordinate_event(
+            38.5816,
+            -122.4194,
+            EventType::LocationMoved,
+            Some(LocationAggregate::Coordinates),
+        );
+        let unscoped = LocationSubject::coordinate_event(37.0, -122.0, EventType::Defined, None);
+
+        assert!(nats_matches(&pattern, &inside.to_subject()));
+        assert!(nats_matches(&pattern, &unscoped.to_subject()));
+        assert!(!nats_matches(&pattern, &other_cell.to_subject()));
+        assert!(!nats_matches(&pattern, "events.location.coordinates.37.774900.-122.419400"));
+    }
+    
     #[test]
     fn test_subject_builder_validation() {
         let result = SubjectBuilder::new()
@@ -1205,4 +1309,40 @@ mod tests {
         
         assert_ne!(subject_1.to_subject(), subject_2.to_subject());
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_subject_for_defined_event() {
+        let location_id = Uuid::new_v4();
+        let event = LocationDomainEvent::LocationDefined(crate::events::LocationDefined {
+            location_id,
+            name: "HQ".to_string(),
+            location_type: crate::value_objects::LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+
+        let subject = subject_for_event(&event);
+
+        assert_eq!(subject.to_subject(), format!("events.location.location.defined.{}", location_id));
+    }
+
+    #[test]
+    fn test_subject_for_event_uses_event_aggregate() {
+        let location_id = Uuid::new_v4();
+        let parent_id = Uuid::new_v4();
+        let event = LocationDomainEvent::ParentLocationSet(crate::events::ParentLocationSet {
+            location_id,
+            parent_id,
+            previous_parent_id: None,
+            reason: "Reorganized".to_string(),
+        });
+
+        let subject = subject_for_event(&event);
+
+        assert!(matches!(subject.scope, SubjectScope::Aggregate(LocationAggregate::Hierarchy)));
+        assert_eq!(subject.to_subject(), format!("events.location.hierarchy.parent_set.{}", location_id));
+    }
+}
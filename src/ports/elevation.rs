@@ -0,0 +1,95 @@
+//! Elevation port for altitude enrichment
+//!
+//! Looks up the terrain elevation of a point, typically from a digital
+//! elevation model (DEM), so coordinates captured without altitude can be
+//! completed.
+
+use crate::value_objects::GeoCoordinates;
+use async_trait::async_trait;
+
+/// Looks up terrain elevation in meters above sea level
+#[async_trait]
+pub trait ElevationService: Send + Sync {
+    /// Elevation of a single point
+    async fn elevation(&self, coords: &GeoCoordinates) -> Result<f64, ElevationError>;
+
+    /// Elevations of several points, in the same order as the input
+    ///
+    /// The default looks each point up in turn; services with a batch API
+    /// should override it.
+    async fn elevations(&self, coords: &[GeoCoordinates]) -> Result<Vec<f64>, ElevationError> {
+        let mut elevations = Vec::with_capacity(coords.len());
+        for point in coords {
+            elevations.push(self.elevation(point).await?);
+        }
+        Ok(elevations)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ElevationError {
+    #[error("No elevation data for ({latitude}, {longitude})")]
+    NoData { latitude: f64, longitude: f64 },
+
+    #[error("Elevation service unavailable: {0}")]
+    ServiceUnavailable(String),
+}
+
+/// Mock elevation service returning the same elevation for every point
+pub struct MockElevationService {
+    pub elevation_meters: f64,
+}
+
+impl MockElevationService {
+    pub fn new(elevation_meters: f64) -> Self {
+        Self { elevation_meters }
+    }
+}
+
+impl Default for MockElevationService {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+#[async_trait]
+impl ElevationService for MockElevationService {
+    async fn elevation(&self, coords: &GeoCoordinates) -> Result<f64, ElevationError> {
+        coords.validate().map_err(|_| ElevationError::NoData {
+            latitude: coords.latitude,
+            longitude: coords.longitude,
+        })?;
+        Ok(self.elevation_meters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_batch_elevations_preserve_order() {
+        let service = MockElevationService::new(250.0);
+        let points = vec![
+            GeoCoordinates::new(46.5, 7.9),
+            GeoCoordinates::new(27.98, 86.92),
+        ];
+
+        let elevations = service.elevations(&points).await.unwrap();
+
+        assert_eq!(elevations, vec![250.0, 250.0]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_fails_on_invalid_point() {
+        let service = MockElevationService::default();
+        let points = vec![
+            GeoCoordinates::new(46.5, 7.9),
+            GeoCoordinates::new(95.0, 0.0),
+        ];
+
+        let result = service.elevations(&points).await;
+
+        assert!(matches!(result, Err(ElevationError::NoData { .. })));
+    }
+}
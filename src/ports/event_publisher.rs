@@ -4,8 +4,8 @@
 //! The actual implementation (adapter) would be injected at runtime.
 
 use async_trait::async_trait;
+use crate::nats::subject_for_event;
 use crate::LocationDomainEvent;
-use cim_domain::DomainEvent;
 use uuid::Uuid;
 
 #[async_trait]
@@ -58,27 +58,8 @@ pub enum QueryError {
 }
 
 /// Helper to determine the NATS subject for an event
+///
+/// Delegates to [`subject_for_event`] so every publisher shares one subject mapping.
 pub fn event_to_subject(event: &LocationDomainEvent) -> String {
-    let location_id = event.aggregate_id();
-
-    match event {
-        LocationDomainEvent::LocationDefined(_) => {
-            format!("events.location.{}.defined", location_id)
-        }
-        LocationDomainEvent::LocationUpdated(_) => {
-            format!("events.location.{}.updated", location_id)
-        }
-        LocationDomainEvent::ParentLocationSet(_) => {
-            format!("events.location.{}.parent.set", location_id)
-        }
-        LocationDomainEvent::ParentLocationRemoved(_) => {
-            format!("events.location.{}.parent.removed", location_id)
-        }
-        LocationDomainEvent::LocationMetadataAdded(_) => {
-            format!("events.location.{}.metadata.added", location_id)
-        }
-        LocationDomainEvent::LocationArchived(_) => {
-            format!("events.location.{}.archived", location_id)
-        }
-    }
+    subject_for_event(event).to_subject()
 }
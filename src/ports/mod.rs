@@ -3,6 +3,10 @@
 //! Ports define interfaces that infrastructure adapters implement,
 //! following the Hexagonal Architecture pattern.
 
+pub mod elevation;
 pub mod event_publisher;
+pub mod reachability;
 
+pub use elevation::*;
 pub use event_publisher::*;
+pub use reachability::*;
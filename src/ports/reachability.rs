@@ -0,0 +1,234 @@
+//! Reachability port for virtual locations
+//!
+//! Checks whether the URLs and IP addresses of a virtual location are live and
+//! rolls the individual results up into an overall health.
+
+use crate::value_objects::{IpAddress, VirtualLocation, VirtualUrl};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Outcome of checking a single URL or IP address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReachabilityResult {
+    /// The URL or IP address that was checked
+    pub target: String,
+    pub reachable: bool,
+    /// Round-trip time of a successful check
+    pub latency_ms: Option<u64>,
+    /// Why the target could not be reached
+    pub error: Option<String>,
+}
+
+impl ReachabilityResult {
+    /// A target that answered
+    pub fn reachable(target: impl Into<String>, latency_ms: u64) -> Self {
+        Self {
+            target: target.into(),
+            reachable: true,
+            latency_ms: Some(latency_ms),
+            error: None,
+        }
+    }
+
+    /// A target that could not be reached
+    pub fn unreachable(target: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            reachable: false,
+            latency_ms: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Overall health of a virtual location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthStatus {
+    /// Every checked target is reachable
+    Healthy,
+    /// Some targets are reachable, some are not
+    Degraded,
+    /// No checked target is reachable
+    Unreachable,
+    /// The location has no active URLs or IP addresses to check
+    Unknown,
+}
+
+/// Reachability of every active URL and IP address of a virtual location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualLocationHealth {
+    pub status: HealthStatus,
+    pub urls: Vec<ReachabilityResult>,
+    pub ip_addresses: Vec<ReachabilityResult>,
+}
+
+impl VirtualLocationHealth {
+    /// Roll individual results up into an overall status
+    pub fn from_results(
+        urls: Vec<ReachabilityResult>,
+        ip_addresses: Vec<ReachabilityResult>,
+    ) -> Self {
+        let total = urls.len() + ip_addresses.len();
+        let reachable = urls
+            .iter()
+            .chain(&ip_addresses)
+            .filter(|result| result.reachable)
+            .count();
+
+        let status = match reachable {
+            _ if total == 0 => HealthStatus::Unknown,
+            0 => HealthStatus::Unreachable,
+            n if n == total => HealthStatus::Healthy,
+            _ => HealthStatus::Degraded,
+        };
+
+        Self {
+            status,
+            urls,
+            ip_addresses,
+        }
+    }
+
+    /// Deactivate the location's URLs and IP addresses that could not be reached
+    ///
+    /// Reachable targets are left as they are.
+    pub fn apply_to(&self, location: &mut VirtualLocation) {
+        for result in self.urls.iter().filter(|result| !result.reachable) {
+            location.set_url_active(&result.target, false);
+        }
+        for result in self.ip_addresses.iter().filter(|result| !result.reachable) {
+            if let Ok(address) = result.target.parse() {
+                location.set_ip_active(&address, false);
+            }
+        }
+    }
+}
+
+/// Checks whether virtual location endpoints are live
+#[async_trait]
+pub trait ReachabilityChecker: Send + Sync {
+    /// Check a single URL
+    async fn check_url(&self, url: &VirtualUrl) -> ReachabilityResult;
+
+    /// Check a single IP address
+    async fn check_ip(&self, ip: &IpAddress) -> ReachabilityResult;
+
+    /// Check every active URL and IP address of a location
+    async fn check_virtual_location(&self, location: &VirtualLocation) -> VirtualLocationHealth {
+        let mut urls = Vec::new();
+        for url in location.active_urls() {
+            urls.push(self.check_url(url).await);
+        }
+
+        let mut ip_addresses = Vec::new();
+        for ip in location.active_ips() {
+            ip_addresses.push(self.check_ip(ip).await);
+        }
+
+        VirtualLocationHealth::from_results(urls, ip_addresses)
+    }
+}
+
+/// Mock reachability checker for testing
+///
+/// Every target is reachable unless it was registered with
+/// [`MockReachabilityChecker::with_unreachable`].
+#[derive(Debug, Default)]
+pub struct MockReachabilityChecker {
+    unreachable: HashSet<String>,
+}
+
+impl MockReachabilityChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the given URL or IP address as down
+    pub fn with_unreachable(mut self, target: impl Into<String>) -> Self {
+        self.unreachable.insert(target.into());
+        self
+    }
+
+    fn check(&self, target: String) -> ReachabilityResult {
+        if self.unreachable.contains(&target) {
+            ReachabilityResult::unreachable(target, "Mock failure")
+        } else {
+            ReachabilityResult::reachable(target, 1)
+        }
+    }
+}
+
+#[async_trait]
+impl ReachabilityChecker for MockReachabilityChecker {
+    async fn check_url(&self, url: &VirtualUrl) -> ReachabilityResult {
+        self.check(url.url.clone())
+    }
+
+    async fn check_ip(&self, ip: &IpAddress) -> ReachabilityResult {
+        self.check(ip.address.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{IpAddressType, UrlType};
+
+    fn website_with_mirror() -> VirtualLocation {
+        let mut location =
+            VirtualLocation::website("https://example.com", "Example".to_string()).unwrap();
+        let mut mirror =
+            VirtualUrl::new("https://mirror.example.com".to_string(), UrlType::Primary).unwrap();
+        mirror.priority = 1;
+        location.add_url(mirror).unwrap();
+        location
+            .add_ip_address(IpAddress::new("203.0.113.10", IpAddressType::Primary).unwrap())
+            .unwrap();
+        location
+    }
+
+    #[tokio::test]
+    async fn test_down_url_falls_back_to_next_priority() {
+        let mut location = website_with_mirror();
+        assert_eq!(location.primary_url(), Some("https://example.com"));
+
+        let checker = MockReachabilityChecker::new().with_unreachable("https://example.com");
+        let health = checker.check_virtual_location(&location).await;
+
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.urls.len(), 2);
+        assert_eq!(health.ip_addresses.len(), 1);
+
+        health.apply_to(&mut location);
+
+        assert_eq!(location.primary_url(), Some("https://mirror.example.com"));
+        assert_eq!(location.active_urls().len(), 1);
+        assert_eq!(location.active_ips().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_health_rolls_up_results() {
+        let mut location = website_with_mirror();
+
+        let healthy = MockReachabilityChecker::new()
+            .check_virtual_location(&location)
+            .await;
+        assert_eq!(healthy.status, HealthStatus::Healthy);
+
+        let checker = MockReachabilityChecker::new()
+            .with_unreachable("https://example.com")
+            .with_unreachable("https://mirror.example.com")
+            .with_unreachable("203.0.113.10");
+        let down = checker.check_virtual_location(&location).await;
+        assert_eq!(down.status, HealthStatus::Unreachable);
+
+        down.apply_to(&mut location);
+        assert_eq!(location.primary_url(), None);
+        assert_eq!(location.primary_ip(), None);
+
+        // Nothing active is left to check
+        let unknown = checker.check_virtual_location(&location).await;
+        assert_eq!(unknown.status, HealthStatus::Unknown);
+    }
+}
@@ -0,0 +1,341 @@
+//! K-d tree for nearest-neighbour queries over located points
+//!
+//! Points are stored as unit vectors on the sphere rather than as
+//! `[longitude, latitude]`. In degrees a step in longitude shrinks with
+//! `cos(latitude)`, so a planar tree over raw coordinates would rank
+//! neighbours wrongly near the poles and split pairs that straddle the
+//! antimeridian. The squared chordal distance between unit vectors grows
+//! monotonically with the great-circle distance, so ranking by it gives the
+//! same neighbours as haversine, with no trigonometry in the inner loop.
+
+use crate::value_objects::GeoCoordinates;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use uuid::Uuid;
+
+/// Mean Earth radius used to turn chord lengths back into meters
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Node count below which the tree is not worth rebalancing
+const MIN_REBUILD_SIZE: usize = 32;
+
+/// 3-dimensional k-d tree keyed by location ID
+///
+/// Inserts descend the tree without rebalancing and removals only mark their
+/// node as deleted. Once the tree has grown to twice its size at the last
+/// rebuild, or half its nodes are deleted, it is rebuilt balanced from the
+/// live points, keeping updates amortised O(log n).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KdTree {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    /// Node holding the live point of each location
+    live: HashMap<Uuid, usize>,
+    /// Node count right after the last rebuild
+    balanced_size: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    point: [f64; 3],
+    location_id: Uuid,
+    left: Option<usize>,
+    right: Option<usize>,
+    removed: bool,
+}
+
+impl KdTree {
+    /// Insert or move a location
+    pub(crate) fn insert(&mut self, location_id: Uuid, coordinates: &GeoCoordinates) {
+        self.remove(location_id);
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            point: unit_vector(coordinates),
+            location_id,
+            left: None,
+            right: None,
+            removed: false,
+        });
+        self.live.insert(location_id, index);
+
+        match self.root {
+            None => self.root = Some(index),
+            Some(root) => self.attach(root, index),
+        }
+
+        if self.nodes.len() >= (2 * self.balanced_size).max(MIN_REBUILD_SIZE) {
+            self.rebuild();
+        }
+    }
+
+    /// Remove a location, returning whether it was present
+    pub(crate) fn remove(&mut self, location_id: Uuid) -> bool {
+        match self.live.remove(&location_id) {
+            Some(index) => {
+                self.nodes[index].removed = true;
+                if self.nodes.len() >= (2 * self.live.len()).max(MIN_REBUILD_SIZE) {
+                    self.rebuild();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Find the `k` locations closest to `center`, nearest first
+    ///
+    /// Each location comes with its great-circle distance in meters.
+    pub(crate) fn nearest_k(&self, center: &GeoCoordinates, k: usize) -> Vec<(Uuid, f64)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let target = unit_vector(center);
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if let Some(root) = self.root {
+            self.search(root, 0, &target, k, &mut best);
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| (candidate.location_id, chord_to_meters(candidate.chord2)))
+            .collect()
+    }
+
+    /// Descend from `node` to the empty slot for `index` and link it there
+    fn attach(&mut self, mut node: usize, index: usize) {
+        let point = self.nodes[index].point;
+        let mut axis = 0;
+        loop {
+            let go_left = point[axis] < self.nodes[node].point[axis];
+            let child = if go_left {
+                &mut self.nodes[node].left
+            } else {
+                &mut self.nodes[node].right
+            };
+            match *child {
+                Some(next) => node = next,
+                None => {
+                    *child = Some(index);
+                    return;
+                }
+            }
+            axis = (axis + 1) % 3;
+        }
+    }
+
+    /// Rebuild a balanced tree from the live points, dropping removed nodes
+    fn rebuild(&mut self) {
+        let mut points: Vec<([f64; 3], Uuid)> = self
+            .nodes
+            .iter()
+            .filter(|node| !node.removed)
+            .map(|node| (node.point, node.location_id))
+            .collect();
+
+        self.nodes.clear();
+        self.live.clear();
+        self.root = self.build(&mut points, 0);
+        self.balanced_size = self.nodes.len();
+    }
+
+    fn build(&mut self, points: &mut [([f64; 3], Uuid)], axis: usize) -> Option<usize> {
+        if points.is_empty() {
+            return None;
+        }
+
+        // Points equal to the median on this axis must end up on its right,
+        // matching the descent rule used by `attach`
+        points.sort_by(|a, b| a.0[axis].total_cmp(&b.0[axis]));
+        let mut median = points.len() / 2;
+        while median > 0 && points[median - 1].0[axis] == points[median].0[axis] {
+            median -= 1;
+        }
+
+        let (point, location_id) = points[median];
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            point,
+            location_id,
+            left: None,
+            right: None,
+            removed: false,
+        });
+        self.live.insert(location_id, index);
+
+        let (left, rest) = points.split_at_mut(median);
+        let next_axis = (axis + 1) % 3;
+        self.nodes[index].left = self.build(left, next_axis);
+        self.nodes[index].right = self.build(&mut rest[1..], next_axis);
+        Some(index)
+    }
+
+    fn search(
+        &self,
+        index: usize,
+        axis: usize,
+        target: &[f64; 3],
+        k: usize,
+        best: &mut BinaryHeap<Candidate>,
+    ) {
+        let node = &self.nodes[index];
+        if !node.removed {
+            best.push(Candidate {
+                chord2: squared_distance(&node.point, target),
+                location_id: node.location_id,
+            });
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let offset = target[axis] - node.point[axis];
+        let (near, far) = if offset < 0.0 {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+        let next_axis = (axis + 1) % 3;
+
+        if let Some(near) = near {
+            self.search(near, next_axis, target, k, best);
+        }
+        if let Some(far) = far {
+            let worst = best.peek().map_or(f64::INFINITY, |c| c.chord2);
+            if best.len() < k || offset * offset < worst {
+                self.search(far, next_axis, target, k, best);
+            }
+        }
+    }
+}
+
+/// Search candidate ordered by squared chord length, farthest on top of the heap
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    chord2: f64,
+    location_id: Uuid,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.chord2
+            .total_cmp(&other.chord2)
+            .then_with(|| self.location_id.cmp(&other.location_id))
+    }
+}
+
+fn unit_vector(coordinates: &GeoCoordinates) -> [f64; 3] {
+    let lat = coordinates.latitude.to_radians();
+    let lon = coordinates.longitude.to_radians();
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn squared_distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Great-circle distance in meters for a squared chord on the unit sphere
+fn chord_to_meters(chord2: f64) -> f64 {
+    2.0 * EARTH_RADIUS_M * (chord2.sqrt() / 2.0).min(1.0).asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_point(rng: &mut StdRng) -> GeoCoordinates {
+        GeoCoordinates::new(rng.gen_range(-90.0..=90.0), rng.gen_range(-180.0..180.0))
+    }
+
+    fn brute_force(
+        points: &HashMap<Uuid, GeoCoordinates>,
+        center: &GeoCoordinates,
+        k: usize,
+    ) -> Vec<Uuid> {
+        let mut by_distance: Vec<_> = points
+            .iter()
+            .map(|(id, coords)| (center.distance_to(coords), *id))
+            .collect();
+        by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+        by_distance.into_iter().take(k).map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn test_nearest_k_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tree = KdTree::default();
+        let mut points = HashMap::new();
+        for _ in 0..500 {
+            let id = Uuid::new_v4();
+            let coords = random_point(&mut rng);
+            tree.insert(id, &coords);
+            points.insert(id, coords);
+        }
+
+        // Move and remove some points so tombstones and rebuilds are exercised
+        let ids: Vec<Uuid> = points.keys().copied().collect();
+        for id in &ids[..100] {
+            let coords = random_point(&mut rng);
+            tree.insert(*id, &coords);
+            points.insert(*id, coords);
+        }
+        for id in &ids[100..200] {
+            assert!(tree.remove(*id));
+            points.remove(id);
+        }
+        assert!(!tree.remove(ids[100]));
+
+        for _ in 0..50 {
+            let center = random_point(&mut rng);
+            for k in [1, 5, 20] {
+                let nearest = tree.nearest_k(&center, k);
+                let ids: Vec<Uuid> = nearest.iter().map(|(id, _)| *id).collect();
+                assert_eq!(ids, brute_force(&points, &center, k));
+
+                for (id, distance) in nearest {
+                    assert!((distance - center.distance_to(&points[&id])).abs() < 1.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nearest_k_near_pole_and_antimeridian() {
+        let mut tree = KdTree::default();
+        let across = Uuid::new_v4();
+        let same_side = Uuid::new_v4();
+        let polar = Uuid::new_v4();
+        tree.insert(across, &GeoCoordinates::new(0.0, -179.9));
+        tree.insert(same_side, &GeoCoordinates::new(0.0, 179.0));
+        tree.insert(polar, &GeoCoordinates::new(89.99, -90.0));
+
+        let nearest = tree.nearest_k(&GeoCoordinates::new(0.0, 179.95), 2);
+        assert_eq!(nearest[0].0, across);
+        assert_eq!(nearest[1].0, same_side);
+
+        // Half a world apart in longitude, but only a few kilometres apart
+        let nearest = tree.nearest_k(&GeoCoordinates::new(89.99, 90.0), 1);
+        assert_eq!(nearest[0].0, polar);
+        assert!(nearest[0].1 < 5_000.0);
+
+        assert!(tree.nearest_k(&GeoCoordinates::new(0.0, 0.0), 0).is_empty());
+        assert_eq!(tree.nearest_k(&GeoCoordinates::new(0.0, 0.0), 10).len(), 3);
+    }
+}
@@ -1,28 +1,104 @@
 //! Location Domain Projections
 
+mod kd_tree;
+mod tag_index;
+
+use crate::domain_events::LocationDomainEvent;
 use crate::events::*;
-use crate::value_objects::{GeoCoordinates, LocationType};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::nats::CimMessage;
+use crate::value_objects::{
+    Address, AddressRole, BoundingBox, GeoCoordinates, IndoorPosition, LocationType,
+};
+use chrono::{DateTime, Utc};
+use cim_domain::{DomainError, DomainEvent, DomainResult};
+use kd_tree::KdTree;
+use rstar::primitives::GeomWithData;
+use rstar::{RTree, AABB};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
 use uuid::Uuid;
 
+pub use tag_index::TagIndex;
+
 /// Base trait for location projections
 pub trait LocationProjection: Send + Sync {
     fn handle_location_defined(&mut self, event: &LocationDefined);
     fn handle_location_updated(&mut self, event: &LocationUpdated);
+    fn handle_location_moved(&mut self, event: &LocationMoved);
     fn handle_parent_location_set(&mut self, event: &ParentLocationSet);
     fn handle_parent_location_removed(&mut self, event: &ParentLocationRemoved);
     fn handle_location_metadata_added(&mut self, event: &LocationMetadataAdded);
+    fn handle_location_metadata_updated(&mut self, event: &LocationMetadataUpdated);
+    fn handle_location_metadata_removed(&mut self, event: &LocationMetadataRemoved);
     fn handle_location_archived(&mut self, event: &LocationArchived);
+    fn handle_location_checked_in(&mut self, event: &LocationCheckedIn);
+    fn handle_location_checked_out(&mut self, event: &LocationCheckedOut);
+    fn handle_locations_merged(&mut self, event: &LocationsMerged);
+    fn handle_location_deleted(&mut self, event: &LocationDeleted);
+    fn handle_address_geocoded(&mut self, event: &AddressGeocoded);
+    fn handle_hierarchy_reorganized(&mut self, event: &HierarchyReorganized);
+    fn handle_coordinates_updated(&mut self, event: &CoordinatesUpdated);
+    fn handle_location_restored(&mut self, event: &LocationRestored);
+    fn handle_coordinates_validated(&mut self, event: &CoordinatesValidated);
+    fn handle_location_address_set(&mut self, event: &LocationAddressSet);
+    fn handle_indoor_position_set(&mut self, event: &IndoorPositionSet);
     fn projection_name(&self) -> &'static str;
+
+    /// Dispatch a wrapped domain event to its handler
+    ///
+    /// The match is exhaustive, so a new event variant cannot be added without
+    /// deciding how projections treat it.
+    fn handle_event(&mut self, event: &LocationDomainEvent) {
+        match event {
+            LocationDomainEvent::LocationDefined(e) => self.handle_location_defined(e),
+            LocationDomainEvent::LocationUpdated(e) => self.handle_location_updated(e),
+            LocationDomainEvent::LocationMoved(e) => self.handle_location_moved(e),
+            LocationDomainEvent::ParentLocationSet(e) => self.handle_parent_location_set(e),
+            LocationDomainEvent::ParentLocationRemoved(e) => self.handle_parent_location_removed(e),
+            LocationDomainEvent::LocationMetadataAdded(e) => self.handle_location_metadata_added(e),
+            LocationDomainEvent::LocationMetadataUpdated(e) => {
+                self.handle_location_metadata_updated(e)
+            }
+            LocationDomainEvent::LocationMetadataRemoved(e) => {
+                self.handle_location_metadata_removed(e)
+            }
+            LocationDomainEvent::LocationArchived(e) => self.handle_location_archived(e),
+            LocationDomainEvent::LocationCheckedIn(e) => self.handle_location_checked_in(e),
+            LocationDomainEvent::LocationCheckedOut(e) => self.handle_location_checked_out(e),
+            LocationDomainEvent::LocationsMerged(e) => self.handle_locations_merged(e),
+            LocationDomainEvent::LocationDeleted(e) => self.handle_location_deleted(e),
+            LocationDomainEvent::AddressGeocoded(e) => self.handle_address_geocoded(e),
+            LocationDomainEvent::HierarchyReorganized(e) => self.handle_hierarchy_reorganized(e),
+            LocationDomainEvent::CoordinatesUpdated(e) => self.handle_coordinates_updated(e),
+            LocationDomainEvent::LocationRestored(e) => self.handle_location_restored(e),
+            LocationDomainEvent::CoordinatesValidated(e) => self.handle_coordinates_validated(e),
+            LocationDomainEvent::LocationAddressSet(e) => self.handle_location_address_set(e),
+            LocationDomainEvent::IndoorPositionSet(e) => self.handle_indoor_position_set(e),
+        }
+    }
+}
+
+/// A domain event together with its position in its aggregate's event stream
+///
+/// Sequences start at 1 for the first event of each aggregate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: LocationDomainEvent,
 }
 
 /// Read model for location queries
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LocationReadModel {
     pub locations: HashMap<Uuid, LocationView>,
     pub hierarchy: LocationHierarchy,
     pub spatial_index: SpatialIndex,
+    /// Last sequence applied per aggregate through [`Self::apply_sequenced`]
+    applied_sequences: HashMap<Uuid, u64>,
+    /// Sequenced events that arrived ahead of a missing predecessor
+    pending_events: HashMap<Uuid, BTreeMap<u64, LocationDomainEvent>>,
 }
 
 /// View of a single location
@@ -31,14 +107,58 @@ pub struct LocationView {
     pub id: Uuid,
     pub name: String,
     pub location_type: LocationType,
+    /// Addresses by role, including the physical address
+    #[serde(default)]
+    pub addresses: HashMap<AddressRole, Address>,
     pub coordinates: Option<GeoCoordinates>,
+    /// Building, floor and room for indoor locations
+    #[serde(default)]
+    pub indoor_position: Option<IndoorPosition>,
     pub parent_id: Option<Uuid>,
     pub children_ids: Vec<Uuid>,
     pub attributes: HashMap<String, String>,
+    /// Number of users currently checked in
+    #[serde(default)]
+    pub occupancy: usize,
+}
+
+impl LocationView {
+    /// Convert this view into a GeoJSON `Feature`
+    ///
+    /// GeoJSON positions are `[longitude, latitude]` (plus altitude when known).
+    /// Locations without coordinates get a `null` geometry.
+    pub fn to_geojson_feature(&self) -> Value {
+        let geometry = match &self.coordinates {
+            Some(coords) => {
+                let mut position = vec![coords.longitude, coords.latitude];
+                if let Some(altitude) = coords.altitude {
+                    position.push(altitude);
+                }
+                json!({ "type": "Point", "coordinates": position })
+            }
+            None => Value::Null,
+        };
+
+        let mut properties: Map<String, Value> = self
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect();
+        properties.insert("name".to_string(), json!(self.name));
+        properties.insert("location_type".to_string(), json!(self.location_type));
+        properties.insert("parent_id".to_string(), json!(self.parent_id));
+
+        json!({
+            "type": "Feature",
+            "id": self.id.to_string(),
+            "geometry": geometry,
+            "properties": properties,
+        })
+    }
 }
 
 /// Hierarchical view of locations
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LocationHierarchy {
     pub roots: Vec<Uuid>,
     pub parent_child_map: HashMap<Uuid, Vec<Uuid>>,
@@ -46,30 +166,364 @@ pub struct LocationHierarchy {
 }
 
 /// Spatial index for proximity queries
+///
+/// Points are stored in an R-tree keyed by `[longitude, latitude]`, so radius
+/// and bounding box queries only visit candidates near the query area.
+/// Nearest-neighbour queries use a k-d tree over positions on the unit sphere
+/// instead: degrees of longitude shrink by `cos(latitude)` towards the poles,
+/// which would skew nearest-first ordering in the R-tree's planar space.
+///
+/// Only the indexed positions are serialized; both trees are rebuilt from them
+/// on deserialization.
 #[derive(Debug, Clone, Default)]
 pub struct SpatialIndex {
-    // In a real implementation, this would use an R-tree or similar
-    pub locations_by_coordinates: Vec<(Uuid, GeoCoordinates)>,
+    tree: RTree<IndexedPoint>,
+    nearest: KdTree,
+    positions: HashMap<Uuid, GeoCoordinates>,
+}
+
+type IndexedPoint = GeomWithData<[f64; 2], Uuid>;
+
+impl SpatialIndex {
+    /// Create an empty spatial index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or move a location in the index
+    pub fn insert(&mut self, location_id: Uuid, coordinates: GeoCoordinates) {
+        self.remove(location_id);
+        self.tree.insert(Self::point(location_id, &coordinates));
+        self.nearest.insert(location_id, &coordinates);
+        self.positions.insert(location_id, coordinates);
+    }
+
+    /// Remove a location from the index, returning its last known coordinates
+    pub fn remove(&mut self, location_id: Uuid) -> Option<GeoCoordinates> {
+        let coordinates = self.positions.remove(&location_id)?;
+        self.tree.remove(&Self::point(location_id, &coordinates));
+        self.nearest.remove(location_id);
+        Some(coordinates)
+    }
+
+    /// Get the indexed coordinates of a location
+    pub fn get(&self, location_id: Uuid) -> Option<&GeoCoordinates> {
+        self.positions.get(&location_id)
+    }
+
+    /// Number of indexed locations
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Find all locations inside a bounding box
+    pub fn query_bbox(&self, bbox: &BoundingBox) -> Vec<Uuid> {
+        bbox.split_at_antimeridian()
+            .iter()
+            .flat_map(|part| {
+                let envelope =
+                    AABB::from_corners([part.min_lon, part.min_lat], [part.max_lon, part.max_lat]);
+                self.tree.locate_in_envelope(&envelope).map(|p| p.data)
+            })
+            .collect()
+    }
+
+    /// Find all locations within `radius_meters` of `center`
+    pub fn query_radius(&self, center: &GeoCoordinates, radius_meters: f64) -> Vec<Uuid> {
+        self.query_bbox(&center.bounding_box(radius_meters))
+            .into_iter()
+            .filter(|id| {
+                self.positions
+                    .get(id)
+                    .is_some_and(|coords| center.distance_to(coords) <= radius_meters)
+            })
+            .collect()
+    }
+
+    /// Find the `k` locations closest to `center`, nearest first
+    ///
+    /// Each location comes with its great-circle distance in meters.
+    pub fn nearest_k(&self, center: &GeoCoordinates, k: usize) -> Vec<(Uuid, f64)> {
+        self.nearest.nearest_k(center, k)
+    }
+
+    fn point(location_id: Uuid, coordinates: &GeoCoordinates) -> IndexedPoint {
+        GeomWithData::new([coordinates.longitude, coordinates.latitude], location_id)
+    }
+}
+
+impl Serialize for SpatialIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.positions.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpatialIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let positions = HashMap::<Uuid, GeoCoordinates>::deserialize(deserializer)?;
+        let points = positions
+            .iter()
+            .map(|(id, coordinates)| Self::point(*id, coordinates))
+            .collect();
+        let mut nearest = KdTree::default();
+        for (id, coordinates) in &positions {
+            nearest.insert(*id, coordinates);
+        }
+
+        Ok(Self {
+            tree: RTree::bulk_load(points),
+            nearest,
+            positions,
+        })
+    }
+}
+
+impl LocationReadModel {
+    /// Rebuild a read model from a full event history
+    ///
+    /// Events are applied in order. Events referring to locations the model has
+    /// not seen are ignored by the individual handlers rather than failing the
+    /// replay.
+    pub fn replay(events: impl IntoIterator<Item = LocationDomainEvent>) -> Self {
+        let mut model = Self::default();
+        for event in events {
+            model.handle_event(&event);
+        }
+        model
+    }
+
+    /// Rebuild the read model as it stood at `as_of`
+    ///
+    /// Only events whose metadata timestamp is at or before `as_of` are applied,
+    /// in the order given. Later events are skipped rather than ending the
+    /// replay, since the streams of different aggregates may interleave.
+    pub fn at(events: &[CimMessage<LocationDomainEvent>], as_of: DateTime<Utc>) -> Self {
+        let as_of = SystemTime::from(as_of);
+        Self::replay(
+            events
+                .iter()
+                .filter(|message| message.metadata.timestamp <= as_of)
+                .map(|message| message.payload.clone()),
+        )
+    }
+
+    /// Serialize the whole read model, including sequencing state, to a compact
+    /// MessagePack blob
+    ///
+    /// Reloading with [`Self::from_bytes`] avoids replaying the event history on
+    /// start-up.
+    pub fn to_bytes(&self) -> DomainResult<Vec<u8>> {
+        rmp_serde::to_vec_named(self)
+            .map_err(|e| DomainError::InternalError(format!("Failed to serialize read model: {e}")))
+    }
+
+    /// Restore a read model written by [`Self::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> DomainResult<Self> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid read model blob: {e}")))
+    }
+
+    /// Apply an event in its aggregate's sequence order
+    ///
+    /// An event ahead of the next expected sequence is held back until the
+    /// events before it arrive; events at or below the last applied sequence are
+    /// duplicates and are dropped. Returns how many events were applied.
+    pub fn apply_sequenced(&mut self, event: SequencedEvent) -> usize {
+        let aggregate_id = event.event.aggregate_id();
+        let next = self.next_sequence(aggregate_id);
+
+        if event.sequence < next {
+            return 0;
+        }
+        if event.sequence > next {
+            self.pending_events
+                .entry(aggregate_id)
+                .or_default()
+                .insert(event.sequence, event.event);
+            return 0;
+        }
+
+        self.handle_event(&event.event);
+        let mut last = event.sequence;
+        let mut applied = 1;
+
+        if let Some(pending) = self.pending_events.get_mut(&aggregate_id) {
+            let mut ready = Vec::new();
+            while let Some(buffered) = pending.remove(&(last + 1)) {
+                ready.push(buffered);
+                last += 1;
+            }
+            if pending.is_empty() {
+                self.pending_events.remove(&aggregate_id);
+            }
+            for buffered in &ready {
+                self.handle_event(buffered);
+            }
+            applied += ready.len();
+        }
+
+        self.applied_sequences.insert(aggregate_id, last);
+        applied
+    }
+
+    /// Aggregates with held-back events, mapped to the sequence they are waiting for
+    pub fn pending_gaps(&self) -> HashMap<Uuid, u64> {
+        self.pending_events
+            .keys()
+            .map(|aggregate_id| (*aggregate_id, self.next_sequence(*aggregate_id)))
+            .collect()
+    }
+
+    fn next_sequence(&self, aggregate_id: Uuid) -> u64 {
+        self.applied_sequences
+            .get(&aggregate_id)
+            .map_or(1, |last| last + 1)
+    }
+
+    /// Convert all locations into a GeoJSON `FeatureCollection`
+    pub fn to_geojson_feature_collection(&self) -> Value {
+        let mut views: Vec<&LocationView> = self.locations.values().collect();
+        views.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+        json!({
+            "type": "FeatureCollection",
+            "features": views
+                .into_iter()
+                .map(LocationView::to_geojson_feature)
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Locations removed by deleting `location_id`
+    ///
+    /// Returns the location itself followed by all of its descendants. Fails if
+    /// the location has children and `cascade` is not set.
+    pub fn deletion_set(&self, location_id: Uuid, cascade: bool) -> DomainResult<Vec<Uuid>> {
+        if !self.locations.contains_key(&location_id) {
+            return Err(DomainError::ValidationError(format!(
+                "Location {location_id} not found"
+            )));
+        }
+
+        let has_children = self
+            .hierarchy
+            .parent_child_map
+            .get(&location_id)
+            .is_some_and(|children| !children.is_empty());
+        if has_children && !cascade {
+            return Err(DomainError::ValidationError(
+                "Cannot delete a location with children without cascade".to_string(),
+            ));
+        }
+
+        let mut ids = vec![location_id];
+        let mut next = 0;
+        while next < ids.len() {
+            let children = self
+                .hierarchy
+                .parent_child_map
+                .get(&ids[next])
+                .cloned()
+                .unwrap_or_default();
+            for child_id in children {
+                if !ids.contains(&child_id) {
+                    ids.push(child_id);
+                }
+            }
+            next += 1;
+        }
+        Ok(ids)
+    }
+
+    fn remove_location(&mut self, location_id: Uuid) {
+        self.locations.remove(&location_id);
+        self.spatial_index.remove(location_id);
+
+        if let Some(parent_id) = self.hierarchy.child_parent_map.remove(&location_id) {
+            if let Some(siblings) = self.hierarchy.parent_child_map.get_mut(&parent_id) {
+                siblings.retain(|id| *id != location_id);
+            }
+            if let Some(parent) = self.locations.get_mut(&parent_id) {
+                parent.children_ids.retain(|id| *id != location_id);
+            }
+        }
+        self.hierarchy.roots.retain(|id| *id != location_id);
+
+        // Children left behind by a non-cascading delete become roots
+        for child_id in self
+            .hierarchy
+            .parent_child_map
+            .remove(&location_id)
+            .unwrap_or_default()
+        {
+            self.hierarchy.child_parent_map.remove(&child_id);
+            if let Some(child) = self.locations.get_mut(&child_id) {
+                child.parent_id = None;
+            }
+        }
+    }
+
+    /// Record `location_id` as a child of `parent_id` in the hierarchy maps
+    ///
+    /// A re-parented location leaves its old parent's children, and linking
+    /// the same pair twice leaves a single entry.
+    fn link_parent(&mut self, location_id: Uuid, parent_id: Uuid) {
+        if let Some(old_parent) = self
+            .hierarchy
+            .child_parent_map
+            .insert(location_id, parent_id)
+        {
+            if let Some(children) = self.hierarchy.parent_child_map.get_mut(&old_parent) {
+                children.retain(|id| *id != location_id);
+            }
+        }
+        let children = self
+            .hierarchy
+            .parent_child_map
+            .entry(parent_id)
+            .or_default();
+        if !children.contains(&location_id) {
+            children.push(location_id);
+        }
+    }
 }
 
 impl LocationProjection for LocationReadModel {
     fn handle_location_defined(&mut self, event: &LocationDefined) {
+        let mut addresses = event.addresses.clone();
+        addresses.remove(&AddressRole::Physical);
+        if let Some(address) = &event.address {
+            addresses.insert(AddressRole::Physical, address.clone());
+        }
+
         let view = LocationView {
             id: event.location_id,
             name: event.name.clone(),
             location_type: event.location_type.clone(),
+            addresses,
             coordinates: event.coordinates.clone(),
+            indoor_position: None,
             parent_id: event.parent_id,
             children_ids: Vec::new(),
             attributes: HashMap::new(),
+            occupancy: 0,
         };
 
         self.locations.insert(event.location_id, view);
 
+        // A location defined under a parent is its child just as after a
+        // `ParentLocationSet`
+        if let Some(parent_id) = event.parent_id {
+            self.link_parent(event.location_id, parent_id);
+        }
+
         if let Some(coords) = &event.coordinates {
-            self.spatial_index
-                .locations_by_coordinates
-                .push((event.location_id, coords.clone()));
+            self.spatial_index.insert(event.location_id, coords.clone());
         }
     }
 
@@ -78,9 +532,23 @@ impl LocationProjection for LocationReadModel {
             if let Some(name) = &event.name {
                 location.name = name.clone();
             }
-            if event.coordinates.is_some() {
-                location.coordinates = event.coordinates.clone();
+            if let Some(address) = &event.address {
+                location
+                    .addresses
+                    .insert(AddressRole::Physical, address.clone());
             }
+            if let Some(coords) = &event.coordinates {
+                location.coordinates = Some(coords.clone());
+                self.spatial_index.insert(event.location_id, coords.clone());
+            }
+        }
+    }
+
+    fn handle_location_moved(&mut self, event: &LocationMoved) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.coordinates = Some(event.coordinates.clone());
+            self.spatial_index
+                .insert(event.location_id, event.coordinates.clone());
         }
     }
 
@@ -90,14 +558,7 @@ impl LocationProjection for LocationReadModel {
             location.parent_id = Some(event.parent_id);
         }
 
-        self.hierarchy
-            .child_parent_map
-            .insert(event.location_id, event.parent_id);
-        self.hierarchy
-            .parent_child_map
-            .entry(event.parent_id)
-            .or_default()
-            .push(event.location_id);
+        self.link_parent(event.location_id, event.parent_id);
     }
 
     fn handle_parent_location_removed(&mut self, event: &ParentLocationRemoved) {
@@ -121,11 +582,922 @@ impl LocationProjection for LocationReadModel {
         }
     }
 
-    fn handle_location_archived(&mut self, _event: &LocationArchived) {
-        // Could mark as archived in the view or remove from active locations
+    fn handle_location_metadata_updated(&mut self, event: &LocationMetadataUpdated) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.attributes = event.current_metadata.clone();
+        }
+    }
+
+    fn handle_location_metadata_removed(&mut self, event: &LocationMetadataRemoved) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.attributes.remove(&event.key);
+        }
+    }
+
+    fn handle_location_archived(&mut self, event: &LocationArchived) {
+        // Archived locations no longer take part in proximity queries
+        self.spatial_index.remove(event.location_id);
+    }
+
+    fn handle_location_restored(&mut self, event: &LocationRestored) {
+        // Restored locations take part in proximity queries again
+        if let Some(coords) = self
+            .locations
+            .get(&event.location_id)
+            .and_then(|location| location.coordinates.clone())
+        {
+            self.spatial_index.insert(event.location_id, coords);
+        }
+    }
+
+    fn handle_location_checked_in(&mut self, event: &LocationCheckedIn) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.occupancy = event.occupancy;
+        }
+    }
+
+    fn handle_location_checked_out(&mut self, event: &LocationCheckedOut) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.occupancy = event.occupancy;
+        }
+    }
+
+    fn handle_locations_merged(&mut self, _event: &LocationsMerged) {
+        // The accompanying parent, metadata and archive events update the views
+    }
+
+    fn handle_location_deleted(&mut self, event: &LocationDeleted) {
+        let ids = if event.cascade {
+            self.deletion_set(event.location_id, true)
+                .unwrap_or_else(|_| vec![event.location_id])
+        } else {
+            vec![event.location_id]
+        };
+
+        // Remove descendants before their ancestors
+        for id in ids.into_iter().rev() {
+            self.remove_location(id);
+        }
+    }
+
+    fn handle_address_geocoded(&mut self, event: &AddressGeocoded) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.coordinates = Some(event.coordinates.clone());
+            self.spatial_index
+                .insert(event.location_id, event.coordinates.clone());
+        }
+    }
+
+    fn handle_hierarchy_reorganized(&mut self, event: &HierarchyReorganized) {
+        // Detach every affected location before attaching any, so the result
+        // does not depend on the order of the changes
+        for (location_id, _, _) in &event.affected {
+            if let Some(old_parent) = self.hierarchy.child_parent_map.remove(location_id) {
+                if let Some(children) = self.hierarchy.parent_child_map.get_mut(&old_parent) {
+                    children.retain(|id| id != location_id);
+                }
+            }
+        }
+
+        for (location_id, _, new_parent) in &event.affected {
+            if let Some(location) = self.locations.get_mut(location_id) {
+                location.parent_id = *new_parent;
+            }
+
+            if let Some(parent_id) = new_parent {
+                self.hierarchy
+                    .child_parent_map
+                    .insert(*location_id, *parent_id);
+                self.hierarchy
+                    .parent_child_map
+                    .entry(*parent_id)
+                    .or_default()
+                    .push(*location_id);
+            }
+        }
+    }
+
+    fn handle_coordinates_updated(&mut self, event: &CoordinatesUpdated) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.coordinates = Some(event.coordinates.clone());
+            self.spatial_index
+                .insert(event.location_id, event.coordinates.clone());
+        }
+    }
+
+    fn handle_coordinates_validated(&mut self, _event: &CoordinatesValidated) {
+        // Validation leaves the stored coordinates as they are
+    }
+
+    fn handle_location_address_set(&mut self, event: &LocationAddressSet) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.addresses.insert(event.role, event.address.clone());
+        }
+    }
+
+    fn handle_indoor_position_set(&mut self, event: &IndoorPositionSet) {
+        if let Some(location) = self.locations.get_mut(&event.location_id) {
+            location.indoor_position = Some(event.position.clone());
+        }
     }
 
     fn projection_name(&self) -> &'static str {
         "LocationReadModel"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spatial_index_radius_query_matches_linear_scan() {
+        let mut index = SpatialIndex::new();
+        let mut points = Vec::new();
+
+        // 100 x 100 grid around San Francisco, roughly 110m apart
+        for i in 0..100 {
+            for j in 0..100 {
+                let id = Uuid::new_v4();
+                let coords =
+                    GeoCoordinates::new(37.70 + i as f64 * 0.001, -122.50 + j as f64 * 0.001);
+                index.insert(id, coords.clone());
+                points.push((id, coords));
+            }
+        }
+        assert_eq!(index.len(), 10_000);
+
+        let center = GeoCoordinates::new(37.75, -122.45);
+        let radius = 1_500.0;
+
+        let mut expected: Vec<Uuid> = points
+            .iter()
+            .filter(|(_, coords)| center.distance_to(coords) <= radius)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut found = index.query_radius(&center, radius);
+
+        expected.sort();
+        found.sort();
+        assert!(!found.is_empty());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_spatial_index_insert_moves_and_remove() {
+        let mut index = SpatialIndex::new();
+        let id = Uuid::new_v4();
+
+        index.insert(id, GeoCoordinates::new(0.0, 0.0));
+        index.insert(id, GeoCoordinates::new(10.0, 10.0));
+        assert_eq!(index.len(), 1);
+        assert!(index
+            .query_radius(&GeoCoordinates::new(0.0, 0.0), 1_000.0)
+            .is_empty());
+        assert_eq!(
+            index.query_radius(&GeoCoordinates::new(10.0, 10.0), 1_000.0),
+            vec![id]
+        );
+
+        assert!(index.remove(id).is_some());
+        assert!(index.is_empty());
+        assert!(index
+            .query_radius(&GeoCoordinates::new(10.0, 10.0), 1_000.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_spatial_index_bbox_across_antimeridian() {
+        let mut index = SpatialIndex::new();
+        let east = Uuid::new_v4();
+        let west = Uuid::new_v4();
+        index.insert(east, GeoCoordinates::new(-17.7, 179.99));
+        index.insert(west, GeoCoordinates::new(-17.7, -179.99));
+
+        let mut found = index.query_radius(&GeoCoordinates::new(-17.7, 180.0), 5_000.0);
+        found.sort();
+        let mut expected = vec![east, west];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_spatial_index_nearest_k_follows_updates() {
+        let mut index = SpatialIndex::new();
+        let near = Uuid::new_v4();
+        let far = Uuid::new_v4();
+        let center = GeoCoordinates::new(51.5, -0.1);
+        index.insert(near, GeoCoordinates::new(51.6, -0.1));
+        index.insert(far, GeoCoordinates::new(48.9, 2.35));
+
+        let ids = |nearest: Vec<(Uuid, f64)>| nearest.into_iter().map(|(id, _)| id).collect();
+        let ordered: Vec<Uuid> = ids(index.nearest_k(&center, 2));
+        assert_eq!(ordered, vec![near, far]);
+
+        index.insert(near, GeoCoordinates::new(40.7, -74.0));
+        let ordered: Vec<Uuid> = ids(index.nearest_k(&center, 2));
+        assert_eq!(ordered, vec![far, near]);
+
+        let restored: SpatialIndex =
+            serde_json::from_str(&serde_json::to_string(&index).unwrap()).unwrap();
+        let ordered: Vec<Uuid> = ids(restored.nearest_k(&center, 2));
+        assert_eq!(ordered, vec![far, near]);
+
+        index.remove(far);
+        let ordered: Vec<Uuid> = ids(index.nearest_k(&center, 2));
+        assert_eq!(ordered, vec![near]);
+    }
+
+    #[test]
+    fn test_read_model_keeps_spatial_index_in_sync() {
+        let mut model = LocationReadModel::default();
+        let id = Uuid::new_v4();
+
+        model.handle_location_defined(&LocationDefined {
+            location_id: id,
+            name: "Depot".to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: Some(GeoCoordinates::new(51.5, -0.12)),
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+        assert_eq!(
+            model
+                .spatial_index
+                .query_radius(&GeoCoordinates::new(51.5, -0.12), 100.0),
+            vec![id]
+        );
+
+        model.handle_location_updated(&LocationUpdated {
+            location_id: id,
+            previous_name: None,
+            name: None,
+            previous_address: None,
+            address: None,
+            previous_coordinates: Some(GeoCoordinates::new(51.5, -0.12)),
+            coordinates: Some(GeoCoordinates::new(48.85, 2.35)),
+            previous_virtual_location: None,
+            virtual_location: None,
+            reason: "Relocated".to_string(),
+            actor: None,
+        });
+        assert!(model
+            .spatial_index
+            .query_radius(&GeoCoordinates::new(51.5, -0.12), 100.0)
+            .is_empty());
+        assert_eq!(
+            model
+                .spatial_index
+                .query_radius(&GeoCoordinates::new(48.85, 2.35), 100.0),
+            vec![id]
+        );
+
+        model.handle_location_archived(&LocationArchived {
+            location_id: id,
+            name: "Depot".to_string(),
+            location_type: LocationType::Physical,
+            reason: "Closed".to_string(),
+            actor: None,
+        });
+        assert!(model.spatial_index.is_empty());
+
+        model.handle_location_restored(&LocationRestored {
+            location_id: id,
+            name: "Depot".to_string(),
+            location_type: LocationType::Physical,
+            reason: "Reopened".to_string(),
+            actor: None,
+        });
+        assert_eq!(
+            model
+                .spatial_index
+                .query_radius(&GeoCoordinates::new(48.85, 2.35), 100.0),
+            vec![id]
+        );
+    }
+
+    #[test]
+    fn test_address_geocoded_attaches_coordinates() {
+        let mut model = LocationReadModel::default();
+        let id = Uuid::new_v4();
+        let address = crate::value_objects::Address::new(
+            "221B Baker St".to_string(),
+            "London".to_string(),
+            "England".to_string(),
+            "UK".to_string(),
+            "NW1 6XE".to_string(),
+        );
+
+        model.handle_location_defined(&LocationDefined {
+            location_id: id,
+            name: "Lodgings".to_string(),
+            location_type: LocationType::Physical,
+            address: Some(address.clone()),
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+        assert!(model.spatial_index.is_empty());
+
+        let coordinates = GeoCoordinates::new(51.5238, -0.1586);
+        model.handle_event(&LocationDomainEvent::AddressGeocoded(AddressGeocoded {
+            location_id: id,
+            address,
+            coordinates: coordinates.clone(),
+            confidence_score: 0.9,
+            provider: "MockProvider".to_string(),
+        }));
+
+        assert_eq!(model.locations[&id].coordinates, Some(coordinates.clone()));
+        assert_eq!(
+            model.spatial_index.query_radius(&coordinates, 100.0),
+            vec![id]
+        );
+    }
+
+    fn define(model: &mut LocationReadModel, name: &str, coords: GeoCoordinates) -> Uuid {
+        let id = Uuid::new_v4();
+        model.handle_location_defined(&LocationDefined {
+            location_id: id,
+            name: name.to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: Some(coords),
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+        id
+    }
+
+    fn set_parent(model: &mut LocationReadModel, child: Uuid, parent: Uuid) {
+        model.handle_parent_location_set(&ParentLocationSet {
+            location_id: child,
+            parent_id: parent,
+            previous_parent_id: None,
+            reason: "Test".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_hierarchy_reorganized_reparents_atomically() {
+        let mut model = LocationReadModel::default();
+        let north = define(&mut model, "North", GeoCoordinates::new(45.0, -93.0));
+        let south = define(&mut model, "South", GeoCoordinates::new(30.0, -90.0));
+        let a = define(&mut model, "A", GeoCoordinates::new(44.0, -93.0));
+        let b = define(&mut model, "B", GeoCoordinates::new(44.5, -93.0));
+        let c = define(&mut model, "C", GeoCoordinates::new(31.0, -90.0));
+        let untouched = define(&mut model, "D", GeoCoordinates::new(45.5, -93.0));
+        set_parent(&mut model, a, north);
+        set_parent(&mut model, b, north);
+        set_parent(&mut model, untouched, north);
+
+        model.handle_event(&LocationDomainEvent::HierarchyReorganized(
+            HierarchyReorganized {
+                location_id: north,
+                affected: vec![
+                    (a, Some(north), Some(south)),
+                    (b, Some(north), None),
+                    (c, None, Some(north)),
+                ],
+                reason: "Regional restructure".to_string(),
+            },
+        ));
+
+        let hierarchy = &model.hierarchy;
+        assert_eq!(hierarchy.child_parent_map.get(&a), Some(&south));
+        assert_eq!(hierarchy.child_parent_map.get(&b), None);
+        assert_eq!(hierarchy.child_parent_map.get(&c), Some(&north));
+        assert_eq!(hierarchy.child_parent_map.get(&untouched), Some(&north));
+        assert_eq!(hierarchy.child_parent_map.len(), 3);
+
+        let mut north_children = hierarchy.parent_child_map[&north].clone();
+        north_children.sort();
+        let mut expected = vec![untouched, c];
+        expected.sort();
+        assert_eq!(north_children, expected);
+        assert_eq!(hierarchy.parent_child_map[&south], vec![a]);
+
+        assert_eq!(model.locations[&a].parent_id, Some(south));
+        assert_eq!(model.locations[&b].parent_id, None);
+        assert_eq!(model.locations[&c].parent_id, Some(north));
+    }
+
+    /// The reorganization workflow publishes each parent change before the summary
+    #[test]
+    fn test_parent_changes_followed_by_reorganization_apply_once() {
+        let mut model = LocationReadModel::default();
+        let north = define(&mut model, "North", GeoCoordinates::new(45.0, -93.0));
+        let south = define(&mut model, "South", GeoCoordinates::new(30.0, -90.0));
+        let a = define(&mut model, "A", GeoCoordinates::new(44.0, -93.0));
+        set_parent(&mut model, a, north);
+
+        model.handle_event(&LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+            location_id: a,
+            parent_id: south,
+            previous_parent_id: Some(north),
+            reason: "Regional restructure".to_string(),
+        }));
+        model.handle_event(&LocationDomainEvent::HierarchyReorganized(
+            HierarchyReorganized {
+                location_id: north,
+                affected: vec![(a, Some(north), Some(south))],
+                reason: "Regional restructure".to_string(),
+            },
+        ));
+
+        let hierarchy = &model.hierarchy;
+        assert_eq!(hierarchy.child_parent_map.get(&a), Some(&south));
+        assert!(hierarchy.parent_child_map[&north].is_empty());
+        assert_eq!(hierarchy.parent_child_map[&south], vec![a]);
+    }
+
+    #[test]
+    fn test_replay_rebuilds_final_state() {
+        let campus = Uuid::new_v4();
+        let office = Uuid::new_v4();
+        let defined = |id: Uuid, name: &str, coords: GeoCoordinates| {
+            LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id: id,
+                name: name.to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(coords),
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            })
+        };
+
+        let history = vec![
+            defined(campus, "Campus", GeoCoordinates::new(40.0, -74.0)),
+            defined(office, "Office", GeoCoordinates::new(40.001, -74.0)),
+            LocationDomainEvent::LocationUpdated(LocationUpdated {
+                location_id: office,
+                previous_name: Some("Office".to_string()),
+                name: Some("Head Office".to_string()),
+                previous_address: None,
+                address: None,
+                previous_coordinates: None,
+                coordinates: None,
+                previous_virtual_location: None,
+                virtual_location: None,
+                reason: "Renamed".to_string(),
+                actor: None,
+            }),
+            LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                location_id: office,
+                parent_id: campus,
+                previous_parent_id: None,
+                reason: "On campus".to_string(),
+            }),
+            LocationDomainEvent::LocationArchived(LocationArchived {
+                location_id: office,
+                name: "Head Office".to_string(),
+                location_type: LocationType::Physical,
+                reason: "Closed".to_string(),
+                actor: None,
+            }),
+        ];
+
+        let model = LocationReadModel::replay(history);
+
+        let view = &model.locations[&office];
+        assert_eq!(view.name, "Head Office");
+        assert_eq!(view.parent_id, Some(campus));
+        assert_eq!(model.hierarchy.child_parent_map[&office], campus);
+        assert_eq!(model.hierarchy.parent_child_map[&campus], vec![office]);
+        assert_eq!(model.locations.len(), 2);
+        assert_eq!(model.spatial_index.len(), 1);
+        assert!(model.spatial_index.get(office).is_none());
+    }
+
+    #[test]
+    fn test_point_in_time_model_ignores_later_events() {
+        use crate::nats::EventMetadata;
+        use chrono::TimeZone;
+
+        let campus = Uuid::new_v4();
+        let office = Uuid::new_v4();
+        let on_day = |day: u32, event: LocationDomainEvent| CimMessage {
+            metadata: EventMetadata {
+                timestamp: Utc.with_ymd_and_hms(2023, 1, day, 12, 0, 0).unwrap().into(),
+                ..EventMetadata::new_root(None)
+            },
+            payload: event,
+        };
+        let defined = |id: Uuid, name: &str| {
+            LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id: id,
+                name: name.to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(GeoCoordinates::new(40.0, -74.0)),
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            })
+        };
+        let history = vec![
+            on_day(1, defined(campus, "Campus")),
+            on_day(1, defined(office, "Office")),
+            on_day(
+                2,
+                LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                    location_id: office,
+                    parent_id: campus,
+                    previous_parent_id: None,
+                    reason: "On campus".to_string(),
+                }),
+            ),
+            on_day(
+                5,
+                LocationDomainEvent::LocationArchived(LocationArchived {
+                    location_id: office,
+                    name: "Office".to_string(),
+                    location_type: LocationType::Physical,
+                    reason: "Closed".to_string(),
+                    actor: None,
+                }),
+            ),
+            on_day(
+                6,
+                LocationDomainEvent::ParentLocationRemoved(ParentLocationRemoved {
+                    location_id: office,
+                    previous_parent_id: campus,
+                    reason: "Closed".to_string(),
+                }),
+            ),
+        ];
+        let cutoff = Utc.with_ymd_and_hms(2023, 1, 3, 0, 0, 0).unwrap();
+
+        let then = LocationReadModel::at(&history, cutoff);
+        assert!(then.spatial_index.get(office).is_some());
+        assert_eq!(then.locations[&office].parent_id, Some(campus));
+        assert_eq!(then.hierarchy.child_parent_map[&office], campus);
+
+        let now =
+            LocationReadModel::at(&history, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert!(now.spatial_index.get(office).is_none());
+        assert_eq!(now.locations[&office].parent_id, None);
+
+        // Nothing had happened yet before the first event
+        let before = LocationReadModel::at(
+            &history,
+            Utc.with_ymd_and_hms(2022, 12, 31, 0, 0, 0).unwrap(),
+        );
+        assert!(before.locations.is_empty());
+    }
+
+    #[test]
+    fn test_read_model_bytes_round_trip() {
+        let mut model = LocationReadModel::default();
+        let campus = define(&mut model, "Campus", GeoCoordinates::new(40.0, -74.0));
+        let building = define(&mut model, "Building", GeoCoordinates::new(40.001, -74.0));
+        let room = define(&mut model, "Room", GeoCoordinates::new(40.001, -74.001));
+        let closed = define(&mut model, "Closed", GeoCoordinates::new(40.0005, -74.0));
+        define(&mut model, "Remote", GeoCoordinates::new(10.0, 10.0));
+        set_parent(&mut model, building, campus);
+        set_parent(&mut model, room, building);
+        model.handle_location_archived(&LocationArchived {
+            location_id: closed,
+            name: "Closed".to_string(),
+            location_type: LocationType::Physical,
+            reason: "Closed".to_string(),
+            actor: None,
+        });
+
+        let restored = LocationReadModel::from_bytes(&model.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(restored.locations.len(), model.locations.len());
+        assert_eq!(
+            restored.hierarchy.child_parent_map,
+            model.hierarchy.child_parent_map
+        );
+        assert_eq!(
+            restored.hierarchy.parent_child_map,
+            model.hierarchy.parent_child_map
+        );
+        assert_eq!(restored.spatial_index.len(), model.spatial_index.len());
+        assert!(restored.spatial_index.get(closed).is_none());
+
+        let center = GeoCoordinates::new(40.0, -74.0);
+        for radius in [50.0, 500.0, 5_000_000.0] {
+            let mut expected = model.spatial_index.query_radius(&center, radius);
+            let mut actual = restored.spatial_index.query_radius(&center, radius);
+            expected.sort();
+            actual.sort();
+            assert_eq!(actual, expected);
+        }
+
+        assert!(LocationReadModel::from_bytes(b"not a read model").is_err());
+    }
+
+    #[test]
+    fn test_sequenced_events_apply_once_gaps_fill() {
+        let office = Uuid::new_v4();
+        let renamed = |sequence: u64, name: &str| SequencedEvent {
+            sequence,
+            event: LocationDomainEvent::LocationUpdated(LocationUpdated {
+                location_id: office,
+                previous_name: None,
+                name: Some(name.to_string()),
+                previous_address: None,
+                address: None,
+                previous_coordinates: None,
+                coordinates: None,
+                previous_virtual_location: None,
+                virtual_location: None,
+                reason: "Renamed".to_string(),
+                actor: None,
+            }),
+        };
+        let defined = SequencedEvent {
+            sequence: 1,
+            event: LocationDomainEvent::LocationDefined(LocationDefined {
+                location_id: office,
+                name: "Office".to_string(),
+                location_type: LocationType::Physical,
+                address: None,
+                addresses: HashMap::new(),
+                coordinates: Some(GeoCoordinates::new(40.0, -74.0)),
+                virtual_location: None,
+                parent_id: None,
+                actor: None,
+            }),
+        };
+
+        let mut model = LocationReadModel::default();
+
+        // Updates arrive before the definition they depend on
+        assert_eq!(model.apply_sequenced(renamed(3, "Head Office")), 0);
+        assert_eq!(model.apply_sequenced(renamed(2, "Main Office")), 0);
+        assert!(model.locations.is_empty());
+        assert_eq!(model.pending_gaps(), HashMap::from([(office, 1)]));
+
+        assert_eq!(model.apply_sequenced(defined.clone()), 3);
+        assert_eq!(model.locations[&office].name, "Head Office");
+        assert!(model.pending_gaps().is_empty());
+
+        // A later gap is reported from the next missing sequence
+        assert_eq!(model.apply_sequenced(renamed(5, "Annex")), 0);
+        assert_eq!(model.pending_gaps(), HashMap::from([(office, 4)]));
+        assert_eq!(model.apply_sequenced(renamed(4, "Old Annex")), 2);
+        assert_eq!(model.locations[&office].name, "Annex");
+
+        // Redelivered events are dropped
+        assert_eq!(model.apply_sequenced(defined), 0);
+        assert_eq!(model.locations[&office].name, "Annex");
+        assert!(model.pending_gaps().is_empty());
+    }
+
+    #[test]
+    fn test_replay_ignores_events_for_unknown_locations() {
+        let model = LocationReadModel::replay(vec![LocationDomainEvent::LocationArchived(
+            LocationArchived {
+                location_id: Uuid::new_v4(),
+                name: "Ghost".to_string(),
+                location_type: LocationType::Physical,
+                reason: "Never defined".to_string(),
+                actor: None,
+            },
+        )]);
+
+        assert!(model.locations.is_empty());
+        assert!(model.spatial_index.is_empty());
+    }
+
+    #[test]
+    fn test_delete_requires_cascade_for_children() {
+        let mut model = LocationReadModel::default();
+        let campus = define(&mut model, "Campus", GeoCoordinates::new(40.0, -74.0));
+        let building = define(&mut model, "Building", GeoCoordinates::new(40.001, -74.0));
+        let room = define(&mut model, "Room", GeoCoordinates::new(40.001, -74.001));
+        set_parent(&mut model, building, campus);
+        set_parent(&mut model, room, building);
+
+        assert!(model.deletion_set(campus, false).is_err());
+        assert!(model.deletion_set(Uuid::new_v4(), true).is_err());
+        assert_eq!(model.deletion_set(room, false).unwrap(), vec![room]);
+        assert_eq!(
+            model.deletion_set(campus, true).unwrap(),
+            vec![campus, building, room]
+        );
+    }
+
+    #[test]
+    fn test_delete_requires_cascade_for_children_defined_under_parent() {
+        let mut model = LocationReadModel::default();
+        let campus = define(&mut model, "Campus", GeoCoordinates::new(40.0, -74.0));
+        let building = Uuid::new_v4();
+        model.handle_location_defined(&LocationDefined {
+            location_id: building,
+            name: "Building".to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: Some(GeoCoordinates::new(40.001, -74.0)),
+            virtual_location: None,
+            parent_id: Some(campus),
+            actor: None,
+        });
+
+        assert_eq!(
+            model.hierarchy.parent_child_map.get(&campus),
+            Some(&vec![building])
+        );
+        assert!(model.deletion_set(campus, false).is_err());
+        assert_eq!(
+            model.deletion_set(campus, true).unwrap(),
+            vec![campus, building]
+        );
+    }
+
+    #[test]
+    fn test_location_deleted_removes_view_hierarchy_and_index() {
+        let mut model = LocationReadModel::default();
+        let campus = define(&mut model, "Campus", GeoCoordinates::new(40.0, -74.0));
+        let building = define(&mut model, "Building", GeoCoordinates::new(40.001, -74.0));
+        let room = define(&mut model, "Room", GeoCoordinates::new(40.001, -74.001));
+        let other = define(&mut model, "Other", GeoCoordinates::new(10.0, 10.0));
+        set_parent(&mut model, building, campus);
+        set_parent(&mut model, room, building);
+
+        model.handle_location_deleted(&LocationDeleted {
+            location_id: building,
+            location_type: LocationType::Physical,
+            reason: "Erasure request".to_string(),
+            cascade: true,
+        });
+
+        assert!(!model.locations.contains_key(&building));
+        assert!(!model.locations.contains_key(&room));
+        assert!(model.locations.contains_key(&campus));
+        assert!(model.locations.contains_key(&other));
+        assert_eq!(model.spatial_index.len(), 2);
+        assert!(model.spatial_index.get(room).is_none());
+        assert!(!model.hierarchy.child_parent_map.contains_key(&building));
+        assert!(!model.hierarchy.child_parent_map.contains_key(&room));
+        assert!(!model.hierarchy.parent_child_map.contains_key(&building));
+        assert!(model.hierarchy.parent_child_map[&campus].is_empty());
+    }
+
+    #[test]
+    fn test_metadata_update_and_removal_reflected_in_view() {
+        let mut model = LocationReadModel::default();
+        let id = define(&mut model, "Depot", GeoCoordinates::new(40.0, -74.0));
+        model.handle_location_metadata_added(&LocationMetadataAdded {
+            location_id: id,
+            added_metadata: HashMap::from([
+                ("dock".to_string(), "3".to_string()),
+                ("shift".to_string(), "night".to_string()),
+            ]),
+            current_metadata: HashMap::from([
+                ("dock".to_string(), "3".to_string()),
+                ("shift".to_string(), "night".to_string()),
+            ]),
+            reason: "Test".to_string(),
+        });
+
+        model.handle_location_metadata_updated(&LocationMetadataUpdated {
+            location_id: id,
+            previous_metadata: HashMap::from([("dock".to_string(), "3".to_string())]),
+            updated_metadata: HashMap::from([("dock".to_string(), "4".to_string())]),
+            current_metadata: HashMap::from([
+                ("dock".to_string(), "4".to_string()),
+                ("shift".to_string(), "night".to_string()),
+            ]),
+            reason: "Test".to_string(),
+        });
+        assert_eq!(model.locations[&id].attributes["dock"], "4");
+
+        model.handle_location_metadata_removed(&LocationMetadataRemoved {
+            location_id: id,
+            key: "shift".to_string(),
+            previous_value: "night".to_string(),
+            current_metadata: HashMap::from([("dock".to_string(), "4".to_string())]),
+            reason: "Test".to_string(),
+        });
+        let attributes = &model.locations[&id].attributes;
+        assert_eq!(attributes.len(), 1);
+        assert!(!attributes.contains_key("shift"));
+    }
+
+    #[test]
+    fn test_geojson_feature_uses_lng_lat_ordering() {
+        let mut model = LocationReadModel::default();
+        let id = define(
+            &mut model,
+            "Ferry Building",
+            GeoCoordinates::new(37.7955, -122.3937),
+        );
+        model.handle_location_metadata_added(&LocationMetadataAdded {
+            location_id: id,
+            added_metadata: HashMap::from([("kind".to_string(), "landmark".to_string())]),
+            current_metadata: HashMap::from([("kind".to_string(), "landmark".to_string())]),
+            reason: "Test".to_string(),
+        });
+
+        let feature = model.locations[&id].to_geojson_feature();
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["id"], id.to_string());
+        assert_eq!(feature["geometry"]["type"], "Point");
+        assert_eq!(
+            feature["geometry"]["coordinates"],
+            json!([-122.3937, 37.7955])
+        );
+        assert_eq!(feature["properties"]["name"], "Ferry Building");
+        assert_eq!(feature["properties"]["location_type"], "Physical");
+        assert_eq!(feature["properties"]["kind"], "landmark");
+    }
+
+    #[test]
+    fn test_geojson_feature_collection() {
+        let mut model = LocationReadModel::default();
+        define(&mut model, "Depot", GeoCoordinates::new(51.5, -0.12));
+        let virtual_id = Uuid::new_v4();
+        model.handle_location_defined(&LocationDefined {
+            location_id: virtual_id,
+            name: "Chat Room".to_string(),
+            location_type: LocationType::Virtual,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+
+        let collection = model.to_geojson_feature_collection();
+        assert_eq!(collection["type"], "FeatureCollection");
+
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0]["properties"]["name"], "Chat Room");
+        assert!(features[0]["geometry"].is_null());
+        assert_eq!(features[1]["geometry"]["coordinates"], json!([-0.12, 51.5]));
+    }
+
+    #[test]
+    fn test_location_view_keeps_addresses_by_role() {
+        let mut model = LocationReadModel::default();
+        let id = Uuid::new_v4();
+        let address = |street: &str| {
+            Address::new(
+                street.to_string(),
+                "Oakland".to_string(),
+                "CA".to_string(),
+                "USA".to_string(),
+                "94607".to_string(),
+            )
+        };
+
+        model.handle_location_defined(&LocationDefined {
+            location_id: id,
+            name: "Depot".to_string(),
+            location_type: LocationType::Physical,
+            address: Some(address("1 Warehouse Way")),
+            addresses: HashMap::from([(AddressRole::Billing, address("PO Box 12"))]),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: None,
+            actor: None,
+        });
+
+        let view = &model.locations[&id];
+        assert_eq!(
+            view.addresses[&AddressRole::Physical].street1,
+            "1 Warehouse Way"
+        );
+        assert_eq!(view.addresses[&AddressRole::Billing].street1, "PO Box 12");
+        assert!(!view.addresses.contains_key(&AddressRole::Mailing));
+
+        model.handle_location_updated(&LocationUpdated {
+            location_id: id,
+            previous_name: None,
+            name: None,
+            previous_address: Some(address("1 Warehouse Way")),
+            address: Some(address("2 Dock Road")),
+            previous_coordinates: None,
+            coordinates: None,
+            previous_virtual_location: None,
+            virtual_location: None,
+            reason: "Moved".to_string(),
+            actor: None,
+        });
+        let view = &model.locations[&id];
+        assert_eq!(
+            view.addresses[&AddressRole::Physical].street1,
+            "2 Dock Road"
+        );
+        assert_eq!(view.addresses[&AddressRole::Billing].street1, "PO Box 12");
+    }
+}
@@ -0,0 +1,321 @@
+//! Inverted index from tags to the locations carrying them
+//!
+//! Tags are stored on locations as metadata keys prefixed with
+//! [`TagLocationsInRegion::TAG_PREFIX`], so the index follows the metadata
+//! events rather than a dedicated tagging event.
+
+use super::LocationProjection;
+use crate::events::*;
+use crate::TagLocationsInRegion;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Projection answering "which locations have this tag" without a full scan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagIndex {
+    locations_by_tag: HashMap<String, HashSet<Uuid>>,
+    tags_by_location: HashMap<Uuid, HashSet<String>>,
+    /// Parent of each location, to find the descendants of a cascading delete
+    parents: HashMap<Uuid, Uuid>,
+}
+
+impl TagIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locations tagged with `tag`, in ascending ID order
+    pub fn locations_with_tag(&self, tag: &str) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self
+            .locations_by_tag
+            .get(tag)
+            .map(|ids| ids.iter().copied().collect())
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    }
+
+    /// Locations tagged with every one of `tags`, in ascending ID order
+    ///
+    /// An empty list of tags matches no locations.
+    pub fn locations_with_all_tags(&self, tags: &[&str]) -> Vec<Uuid> {
+        let mut sets = Vec::with_capacity(tags.len());
+        for tag in tags {
+            match self.locations_by_tag.get(*tag) {
+                Some(ids) => sets.push(ids),
+                None => return Vec::new(),
+            }
+        }
+
+        // Walk the smallest set and probe the others
+        sets.sort_by_key(|ids| ids.len());
+        let Some((smallest, rest)) = sets.split_first() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<Uuid> = smallest
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.contains(id)))
+            .copied()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Tags of a location, sorted
+    pub fn tags_of(&self, location_id: Uuid) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .tags_by_location
+            .get(&location_id)
+            .map(|tags| tags.iter().cloned().collect())
+            .unwrap_or_default();
+        tags.sort();
+        tags
+    }
+
+    fn tag(&mut self, location_id: Uuid, tag: &str) {
+        self.locations_by_tag
+            .entry(tag.to_string())
+            .or_default()
+            .insert(location_id);
+        self.tags_by_location
+            .entry(location_id)
+            .or_default()
+            .insert(tag.to_string());
+    }
+
+    fn untag(&mut self, location_id: Uuid, tag: &str) {
+        if let Some(ids) = self.locations_by_tag.get_mut(tag) {
+            ids.remove(&location_id);
+            if ids.is_empty() {
+                self.locations_by_tag.remove(tag);
+            }
+        }
+        if let Some(tags) = self.tags_by_location.get_mut(&location_id) {
+            tags.remove(tag);
+            if tags.is_empty() {
+                self.tags_by_location.remove(&location_id);
+            }
+        }
+    }
+
+    fn remove_location(&mut self, location_id: Uuid) {
+        for tag in self.tags_of(location_id) {
+            self.untag(location_id, &tag);
+        }
+        self.parents.remove(&location_id);
+    }
+
+    /// A location followed by all of its descendants
+    fn with_descendants(&self, location_id: Uuid) -> Vec<Uuid> {
+        let mut ids = vec![location_id];
+        let mut seen = HashSet::from([location_id]);
+        let mut next = 0;
+        while next < ids.len() {
+            let parent_id = ids[next];
+            for (child_id, _) in self.parents.iter().filter(|(_, p)| **p == parent_id) {
+                if seen.insert(*child_id) {
+                    ids.push(*child_id);
+                }
+            }
+            next += 1;
+        }
+        ids
+    }
+}
+
+/// The tag named by a metadata key, if the key is a tag
+fn tag_name(key: &str) -> Option<&str> {
+    key.strip_prefix(TagLocationsInRegion::TAG_PREFIX)
+}
+
+impl LocationProjection for TagIndex {
+    fn handle_location_defined(&mut self, event: &LocationDefined) {
+        if let Some(parent_id) = event.parent_id {
+            self.parents.insert(event.location_id, parent_id);
+        }
+    }
+
+    fn handle_location_updated(&mut self, _event: &LocationUpdated) {}
+
+    fn handle_location_moved(&mut self, _event: &LocationMoved) {}
+
+    fn handle_parent_location_set(&mut self, event: &ParentLocationSet) {
+        self.parents.insert(event.location_id, event.parent_id);
+    }
+
+    fn handle_parent_location_removed(&mut self, event: &ParentLocationRemoved) {
+        self.parents.remove(&event.location_id);
+    }
+
+    fn handle_location_metadata_added(&mut self, event: &LocationMetadataAdded) {
+        for tag in event.added_metadata.keys().filter_map(|key| tag_name(key)) {
+            self.tag(event.location_id, tag);
+        }
+    }
+
+    fn handle_location_metadata_updated(&mut self, _event: &LocationMetadataUpdated) {
+        // A new value under an existing key does not change which tags are set
+    }
+
+    fn handle_location_metadata_removed(&mut self, event: &LocationMetadataRemoved) {
+        if let Some(tag) = tag_name(&event.key) {
+            self.untag(event.location_id, tag);
+        }
+    }
+
+    fn handle_location_archived(&mut self, _event: &LocationArchived) {
+        // Archived locations keep their tags, as they keep their metadata
+    }
+
+    fn handle_location_restored(&mut self, _event: &LocationRestored) {}
+
+    fn handle_location_checked_in(&mut self, _event: &LocationCheckedIn) {}
+
+    fn handle_location_checked_out(&mut self, _event: &LocationCheckedOut) {}
+
+    fn handle_locations_merged(&mut self, _event: &LocationsMerged) {
+        // Tags copied onto the target arrive as their own metadata events
+    }
+
+    fn handle_location_deleted(&mut self, event: &LocationDeleted) {
+        let ids = if event.cascade {
+            self.with_descendants(event.location_id)
+        } else {
+            vec![event.location_id]
+        };
+        for id in ids {
+            self.remove_location(id);
+        }
+
+        // Children left behind by a non-cascading delete become roots
+        self.parents
+            .retain(|_, parent_id| *parent_id != event.location_id);
+    }
+
+    fn handle_address_geocoded(&mut self, _event: &AddressGeocoded) {}
+
+    fn handle_hierarchy_reorganized(&mut self, event: &HierarchyReorganized) {
+        for (location_id, _, new_parent) in &event.affected {
+            match new_parent {
+                Some(parent_id) => self.parents.insert(*location_id, *parent_id),
+                None => self.parents.remove(location_id),
+            };
+        }
+    }
+
+    fn handle_coordinates_updated(&mut self, _event: &CoordinatesUpdated) {}
+
+    fn handle_coordinates_validated(&mut self, _event: &CoordinatesValidated) {}
+
+    fn handle_location_address_set(&mut self, _event: &LocationAddressSet) {}
+
+    fn handle_indoor_position_set(&mut self, _event: &IndoorPositionSet) {}
+
+    fn projection_name(&self) -> &'static str {
+        "TagIndex"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::LocationType;
+    use crate::LocationDomainEvent;
+
+    fn tagged(location_id: Uuid, tags: &[&str]) -> LocationDomainEvent {
+        let added: HashMap<String, String> = tags
+            .iter()
+            .map(|tag| (TagLocationsInRegion::metadata_key(tag), "true".to_string()))
+            .collect();
+        LocationDomainEvent::LocationMetadataAdded(LocationMetadataAdded {
+            location_id,
+            current_metadata: added.clone(),
+            added_metadata: added,
+            reason: "Tagged".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_single_and_multi_tag_queries() {
+        let mut index = TagIndex::new();
+        let mut ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        ids.sort();
+        let [cafe, library, depot] = ids;
+
+        index.handle_event(&tagged(cafe, &["wifi", "food", "public"]));
+        index.handle_event(&tagged(library, &["wifi", "public"]));
+        index.handle_event(&tagged(depot, &["loading-dock"]));
+        // Plain metadata is not a tag
+        index.handle_event(&LocationDomainEvent::LocationMetadataAdded(
+            LocationMetadataAdded {
+                location_id: depot,
+                added_metadata: HashMap::from([("wifi".to_string(), "no".to_string())]),
+                current_metadata: HashMap::new(),
+                reason: "Survey".to_string(),
+            },
+        ));
+
+        assert_eq!(index.locations_with_tag("wifi"), vec![cafe, library]);
+        assert_eq!(index.locations_with_tag("loading-dock"), vec![depot]);
+        assert!(index.locations_with_tag("parking").is_empty());
+
+        assert_eq!(
+            index.locations_with_all_tags(&["wifi", "public"]),
+            vec![cafe, library]
+        );
+        assert_eq!(
+            index.locations_with_all_tags(&["public", "food"]),
+            vec![cafe]
+        );
+        assert!(index
+            .locations_with_all_tags(&["wifi", "loading-dock"])
+            .is_empty());
+        assert!(index
+            .locations_with_all_tags(&["wifi", "parking"])
+            .is_empty());
+        assert!(index.locations_with_all_tags(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_removed_tags_and_deleted_locations_leave_the_index() {
+        let mut index = TagIndex::new();
+        let campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+
+        index.handle_event(&LocationDomainEvent::LocationDefined(LocationDefined {
+            location_id: building,
+            name: "Building".to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates: None,
+            virtual_location: None,
+            parent_id: Some(campus),
+            actor: None,
+        }));
+        index.handle_event(&tagged(campus, &["wifi", "public"]));
+        index.handle_event(&tagged(building, &["wifi"]));
+
+        index.handle_event(&LocationDomainEvent::LocationMetadataRemoved(
+            LocationMetadataRemoved {
+                location_id: campus,
+                key: TagLocationsInRegion::metadata_key("public"),
+                previous_value: "true".to_string(),
+                current_metadata: HashMap::new(),
+                reason: "Closed to visitors".to_string(),
+            },
+        ));
+        assert!(index.locations_with_tag("public").is_empty());
+        assert_eq!(index.tags_of(campus), vec!["wifi".to_string()]);
+
+        index.handle_event(&LocationDomainEvent::LocationDeleted(LocationDeleted {
+            location_id: campus,
+            location_type: LocationType::Physical,
+            reason: "Sold".to_string(),
+            cascade: true,
+        }));
+        assert!(index.locations_with_tag("wifi").is_empty());
+        assert!(index.tags_of(building).is_empty());
+    }
+}
@@ -1,14 +1,38 @@
 //! Location Domain Queries
 
-use crate::value_objects::{GeoCoordinates, LocationType};
+use crate::nats::{LocationAggregate, LocationSubject, QueryType};
+use crate::projections::{LocationReadModel, LocationView};
+use crate::value_objects::{Distance, GeoCoordinates, LocationType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// Base trait for location queries
+///
+/// Each query is requested on its own subject of the subject algebra and is
+/// answered from a [`LocationReadModel`].
 pub trait LocationQuery: Send + Sync {
     type Result;
 
-    fn query_type(&self) -> &'static str;
+    /// Query type under which the query is requested
+    const QUERY_TYPE: QueryType;
+    /// Aggregate whose view answers the query
+    const AGGREGATE: LocationAggregate;
+
+    fn query_type(&self) -> &'static str {
+        Self::QUERY_TYPE.as_str()
+    }
+
+    /// Subject the query is requested on, e.g. `queries.location.location.get`
+    fn subject() -> LocationSubject
+    where
+        Self: Sized,
+    {
+        LocationSubject::query(Self::AGGREGATE, Self::QUERY_TYPE, None)
+    }
+
+    /// Answer the query from a read model
+    fn execute(&self, model: &LocationReadModel) -> Self::Result;
 }
 
 /// Query to get a specific location
@@ -23,8 +47,35 @@ pub struct GetLocation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FindNearbyLocations {
     pub center: GeoCoordinates,
-    pub radius_km: f64,
+    pub radius: Distance,
     pub location_types: Option<Vec<LocationType>>,
+    /// Return at most this many locations, the nearest ones
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+impl FindNearbyLocations {
+    /// The location as a match of this query, if it is one
+    fn matching(&self, model: &LocationReadModel, location_id: Uuid) -> Option<NearbyLocation> {
+        let location = model.locations.get(&location_id)?;
+        if let Some(types) = &self.location_types {
+            if !types.contains(&location.location_type) {
+                return None;
+            }
+        }
+
+        let distance_meters = self.center.distance_to(location.coordinates.as_ref()?);
+        (distance_meters <= self.radius.as_meters()).then(|| NearbyLocation {
+            location: location.clone(),
+            distance_meters,
+        })
+    }
+}
+
+fn by_distance(a: &NearbyLocation, b: &NearbyLocation) -> std::cmp::Ordering {
+    a.distance_meters
+        .total_cmp(&b.distance_meters)
+        .then_with(|| a.location.id.cmp(&b.location.id))
 }
 
 /// Query to get location hierarchy
@@ -34,6 +85,170 @@ pub struct GetLocationHierarchy {
     pub max_depth: Option<u32>,
 }
 
+/// A location with its children and ancestors, as requested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationDetails {
+    pub location: LocationView,
+    /// Direct children ordered by name; empty unless requested
+    pub children: Vec<LocationView>,
+    /// Ancestors from the direct parent up to the root; empty unless requested
+    pub ancestors: Vec<LocationView>,
+}
+
+/// A location found near the query center
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearbyLocation {
+    pub location: LocationView,
+    pub distance_meters: f64,
+}
+
+/// A location together with its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationTree {
+    pub location: LocationView,
+    /// Child subtrees ordered by name
+    pub children: Vec<LocationTree>,
+}
+
+impl LocationQuery for GetLocation {
+    type Result = Option<LocationDetails>;
+
+    const QUERY_TYPE: QueryType = QueryType::Get;
+    const AGGREGATE: LocationAggregate = LocationAggregate::Location;
+
+    fn execute(&self, model: &LocationReadModel) -> Self::Result {
+        let location = model.locations.get(&self.location_id)?.clone();
+
+        let children = if self.include_children {
+            children_of(model, self.location_id)
+        } else {
+            Vec::new()
+        };
+
+        // The visited set guards against a corrupt hierarchy containing a cycle
+        let mut ancestors = Vec::new();
+        if self.include_ancestors {
+            let mut visited = HashSet::from([self.location_id]);
+            let mut parent_id = location.parent_id;
+            while let Some(parent) = parent_id.and_then(|id| model.locations.get(&id)) {
+                if !visited.insert(parent.id) {
+                    break;
+                }
+                ancestors.push(parent.clone());
+                parent_id = parent.parent_id;
+            }
+        }
+
+        Some(LocationDetails {
+            location,
+            children,
+            ancestors,
+        })
+    }
+}
+
+impl LocationQuery for FindNearbyLocations {
+    type Result = Vec<NearbyLocation>;
+
+    const QUERY_TYPE: QueryType = QueryType::FindNearby;
+    const AGGREGATE: LocationAggregate = LocationAggregate::Coordinates;
+
+    /// Nearest first; archived locations are not in the spatial index and never match
+    ///
+    /// With `max_results` set, candidates come nearest first from the spatial
+    /// index's k-d tree, so only the closest locations are examined rather
+    /// than every location in the radius.
+    fn execute(&self, model: &LocationReadModel) -> Self::Result {
+        let Some(max_results) = self.max_results else {
+            let mut nearby: Vec<NearbyLocation> = model
+                .spatial_index
+                .query_radius(&self.center, self.radius.as_meters())
+                .into_iter()
+                .filter_map(|id| self.matching(model, id))
+                .collect();
+            nearby.sort_by(by_distance);
+            return nearby;
+        };
+
+        // Widen the search until enough candidates match, the index runs out
+        // or the candidates leave the radius
+        let mut k = max_results;
+        loop {
+            let candidates = model.spatial_index.nearest_k(&self.center, k);
+            let exhausted = candidates.len() < k
+                || candidates
+                    .last()
+                    .is_some_and(|(_, distance)| *distance > self.radius.as_meters());
+
+            let mut nearby: Vec<NearbyLocation> = candidates
+                .into_iter()
+                .filter_map(|(id, _)| self.matching(model, id))
+                .collect();
+            if nearby.len() >= max_results || exhausted {
+                nearby.sort_by(by_distance);
+                nearby.truncate(max_results);
+                return nearby;
+            }
+            k *= 2;
+        }
+    }
+}
+
+impl LocationQuery for GetLocationHierarchy {
+    type Result = Option<LocationTree>;
+
+    const QUERY_TYPE: QueryType = QueryType::GetHierarchy;
+    const AGGREGATE: LocationAggregate = LocationAggregate::Hierarchy;
+
+    /// A `max_depth` of 0 returns the root alone; `None` returns the whole subtree
+    fn execute(&self, model: &LocationReadModel) -> Self::Result {
+        let root = model.locations.get(&self.root_location_id)?;
+        let mut visited = HashSet::new();
+        Some(build_tree(model, root, self.max_depth, &mut visited))
+    }
+}
+
+/// Direct children of a location, ordered by name
+fn children_of(model: &LocationReadModel, parent_id: Uuid) -> Vec<LocationView> {
+    let mut children: Vec<LocationView> = model
+        .locations
+        .values()
+        .filter(|location| location.parent_id == Some(parent_id))
+        .cloned()
+        .collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    children
+}
+
+fn build_tree(
+    model: &LocationReadModel,
+    location: &LocationView,
+    remaining_depth: Option<u32>,
+    visited: &mut HashSet<Uuid>,
+) -> LocationTree {
+    visited.insert(location.id);
+
+    let mut children = Vec::new();
+    if remaining_depth != Some(0) {
+        // Children already in the tree would only be reached through a cycle
+        for child in children_of(model, location.id) {
+            if !visited.contains(&child.id) {
+                children.push(build_tree(
+                    model,
+                    &child,
+                    remaining_depth.map(|d| d - 1),
+                    visited,
+                ));
+            }
+        }
+    }
+
+    LocationTree {
+        location: location.clone(),
+        children,
+    }
+}
+
 /// Query handler for location queries
 pub struct LocationQueryHandler {
     // Read model would be injected here
@@ -52,3 +267,201 @@ impl LocationQueryHandler {
 
     // Query handling methods would be implemented here
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projections::LocationProjection;
+    use crate::LocationDefined;
+    use std::collections::HashMap;
+
+    fn define(
+        model: &mut LocationReadModel,
+        name: &str,
+        coordinates: Option<GeoCoordinates>,
+        parent_id: Option<Uuid>,
+    ) -> Uuid {
+        let location_id = Uuid::new_v4();
+        model.handle_location_defined(&LocationDefined {
+            location_id,
+            name: name.to_string(),
+            location_type: LocationType::Physical,
+            address: None,
+            addresses: HashMap::new(),
+            coordinates,
+            virtual_location: None,
+            parent_id,
+            actor: None,
+        });
+        location_id
+    }
+
+    #[test]
+    fn test_query_subjects() {
+        assert_eq!(
+            GetLocation::subject().to_subject(),
+            "queries.location.location.get"
+        );
+        assert_eq!(
+            FindNearbyLocations::subject().to_subject(),
+            "queries.location.coordinates.find_nearby"
+        );
+        assert_eq!(
+            GetLocationHierarchy::subject().to_subject(),
+            "queries.location.hierarchy.get_hierarchy"
+        );
+    }
+
+    #[test]
+    fn test_find_nearby_orders_by_distance() {
+        let mut model = LocationReadModel::default();
+        let center = GeoCoordinates::new(51.5007, -0.1246);
+        let far = define(
+            &mut model,
+            "Tower",
+            Some(GeoCoordinates::new(51.5081, -0.0759)),
+            None,
+        );
+        let near = define(
+            &mut model,
+            "Abbey",
+            Some(GeoCoordinates::new(51.4993, -0.1273)),
+            None,
+        );
+        define(
+            &mut model,
+            "Paris",
+            Some(GeoCoordinates::new(48.8584, 2.2945)),
+            None,
+        );
+        define(&mut model, "Nowhere", None, None);
+
+        let query = FindNearbyLocations {
+            center,
+            radius: Distance::from_kilometers(5.0),
+            location_types: None,
+            max_results: None,
+        };
+        let ids: Vec<Uuid> = query
+            .execute(&model)
+            .iter()
+            .map(|n| n.location.id)
+            .collect();
+        assert_eq!(ids, vec![near, far]);
+
+        let query = FindNearbyLocations {
+            location_types: Some(vec![LocationType::Virtual]),
+            ..query
+        };
+        assert!(query.execute(&model).is_empty());
+    }
+
+    #[test]
+    fn test_find_nearby_with_max_results_matches_radius_scan() {
+        let mut model = LocationReadModel::default();
+        let center = GeoCoordinates::new(40.7128, -74.0060);
+        for step in 1..=20 {
+            let offset = f64::from(step) * 0.002;
+            define(
+                &mut model,
+                &format!("Stop {step}"),
+                Some(GeoCoordinates::new(40.7128 + offset, -74.0060 - offset)),
+                None,
+            );
+        }
+        define(
+            &mut model,
+            "Boston",
+            Some(GeoCoordinates::new(42.3601, -71.0589)),
+            None,
+        );
+
+        let scan = FindNearbyLocations {
+            center,
+            radius: Distance::from_kilometers(50.0),
+            location_types: None,
+            max_results: None,
+        };
+        let all = scan.execute(&model);
+        assert_eq!(all.len(), 20);
+
+        for max_results in [0, 1, 5, 20, 50] {
+            let nearest = FindNearbyLocations {
+                max_results: Some(max_results),
+                ..scan.clone()
+            }
+            .execute(&model);
+            let expected: Vec<Uuid> = all
+                .iter()
+                .take(max_results)
+                .map(|n| n.location.id)
+                .collect();
+            let ids: Vec<Uuid> = nearest.iter().map(|n| n.location.id).collect();
+            assert_eq!(ids, expected);
+        }
+
+        // Candidates rejected by the type filter widen the search
+        let virtual_only = FindNearbyLocations {
+            location_types: Some(vec![LocationType::Virtual]),
+            max_results: Some(3),
+            ..scan
+        };
+        assert!(virtual_only.execute(&model).is_empty());
+    }
+
+    #[test]
+    fn test_get_location_and_hierarchy() {
+        let mut model = LocationReadModel::default();
+        let campus = define(&mut model, "Campus", None, None);
+        let building = define(&mut model, "Building", None, Some(campus));
+        let floor = define(&mut model, "Floor", None, Some(building));
+        let annex = define(&mut model, "Annex", None, Some(campus));
+
+        let details = GetLocation {
+            location_id: building,
+            include_children: true,
+            include_ancestors: true,
+        }
+        .execute(&model)
+        .unwrap();
+        assert_eq!(details.location.id, building);
+        assert_eq!(details.children.len(), 1);
+        assert_eq!(details.children[0].id, floor);
+        assert_eq!(details.ancestors.len(), 1);
+        assert_eq!(details.ancestors[0].id, campus);
+
+        let bare = GetLocation {
+            location_id: building,
+            include_children: false,
+            include_ancestors: false,
+        }
+        .execute(&model)
+        .unwrap();
+        assert!(bare.children.is_empty() && bare.ancestors.is_empty());
+
+        let tree = GetLocationHierarchy {
+            root_location_id: campus,
+            max_depth: None,
+        }
+        .execute(&model)
+        .unwrap();
+        let children: Vec<Uuid> = tree.children.iter().map(|c| c.location.id).collect();
+        assert_eq!(children, vec![annex, building]);
+        assert_eq!(tree.children[1].children[0].location.id, floor);
+
+        let shallow = GetLocationHierarchy {
+            root_location_id: campus,
+            max_depth: Some(1),
+        }
+        .execute(&model)
+        .unwrap();
+        assert!(shallow.children.iter().all(|c| c.children.is_empty()));
+
+        assert!(GetLocationHierarchy {
+            root_location_id: Uuid::new_v4(),
+            max_depth: None,
+        }
+        .execute(&model)
+        .is_none());
+    }
+}
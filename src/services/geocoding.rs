@@ -2,6 +2,10 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 use uuid::Uuid;
 use crate::value_objects::{Address, Coordinates};
 use thiserror::Error;
@@ -16,7 +20,10 @@ pub trait GeocodingService: Send + Sync {
     async fn reverse_geocode(&self, coordinates: &Coordinates) -> Result<ReverseGeocodeResult, GeocodingError>;
     
     /// Batch geocode multiple addresses
-    async fn batch_geocode(&self, addresses: &[Address]) -> Result<Vec<GeocodeResult>, GeocodingError>;
+    ///
+    /// Each address gets its own result, in input order, so one failure does not
+    /// discard the others.
+    async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>>;
     
     /// Validate an address without geocoding
     async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError>;
@@ -43,6 +50,21 @@ pub struct ReverseGeocodeResult {
     pub confidence_score: f64,
     pub precision_level: PrecisionLevel,
     pub additional_info: GeocodeInfo,
+    /// Alternative matches as `(address, precision, confidence)`
+    #[serde(default)]
+    pub candidates: Vec<(Address, PrecisionLevel, f64)>,
+}
+
+impl ReverseGeocodeResult {
+    /// The most precise candidate, ties broken by higher confidence
+    ///
+    /// Falls back to `address` when the provider returned no candidates.
+    pub fn best_candidate(&self) -> &Address {
+        self.candidates
+            .iter()
+            .min_by(|a, b| a.1.cmp(&b.1).then(b.2.total_cmp(&a.2)))
+            .map_or(&self.address, |(address, _, _)| address)
+    }
 }
 
 /// Address validation result
@@ -57,7 +79,9 @@ pub struct AddressValidationResult {
 }
 
 /// Precision level of geocoding result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Variants are ordered from most to least precise.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum PrecisionLevel {
     /// Exact address match
     Exact,
@@ -169,10 +193,18 @@ pub enum GeocodingError {
     ProviderError(String),
 }
 
+impl GeocodingError {
+    /// Whether the failure is temporary and the request may succeed if retried
+    pub fn is_transient(&self) -> bool {
+        matches!(self, GeocodingError::ServiceUnavailable(_) | GeocodingError::Timeout)
+    }
+}
+
 /// Mock geocoding service for testing
 pub struct MockGeocodingService {
     pub fail_rate: f64,
     pub response_delay_ms: u64,
+    pub confidence_score: f64,
 }
 
 impl MockGeocodingService {
@@ -180,6 +212,7 @@ impl MockGeocodingService {
         Self {
             fail_rate: 0.0,
             response_delay_ms: 100,
+            confidence_score: 0.95,
         }
     }
     
@@ -192,6 +225,11 @@ impl MockGeocodingService {
         self.response_delay_ms = delay_ms;
         self
     }
+    
+    pub fn with_confidence(mut self, confidence_score: f64) -> Self {
+        self.confidence_score = confidence_score;
+        self
+    }
 }
 
 impl Default for MockGeocodingService {
@@ -216,7 +254,7 @@ impl GeocodingService for MockGeocodingService {
             request_id: Uuid::new_v4(),
             input_address: address.clone(),
             coordinates: Coordinates::new(37.7749, -122.4194), // San Francisco
-            confidence_score: 0.95,
+            confidence_score: self.confidence_score,
             precision_level: PrecisionLevel::Street,
             formatted_address: address.clone(),
             additional_info: GeocodeInfo {
@@ -247,10 +285,18 @@ impl GeocodingService for MockGeocodingService {
             "94102".to_string(),
         );
         
+        let city_address = Address::new(
+            String::new(),
+            "San Francisco".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            String::new(),
+        );
+        
         Ok(ReverseGeocodeResult {
             request_id: Uuid::new_v4(),
             input_coordinates: coordinates.clone(),
-            address: mock_address,
+            address: mock_address.clone(),
             confidence_score: 0.90,
             precision_level: PrecisionLevel::Street,
             additional_info: GeocodeInfo {
@@ -260,20 +306,21 @@ impl GeocodingService for MockGeocodingService {
                 geocoding_method: GeocodingMethod::RealTime,
                 data_sources: vec!["mock_database".to_string()],
             },
+            candidates: vec![
+                (city_address, PrecisionLevel::City, 0.99),
+                (mock_address, PrecisionLevel::Street, 0.90),
+            ],
         })
     }
     
-    async fn batch_geocode(&self, addresses: &[Address]) -> Result<Vec<GeocodeResult>, GeocodingError> {
-        let mut results = Vec::new();
+    async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>> {
+        let mut results = Vec::with_capacity(addresses.len());
         
         for address in addresses {
-            match self.geocode(address).await {
-                Ok(result) => results.push(result),
-                Err(e) => return Err(e),
-            }
+            results.push(self.geocode(address).await);
         }
         
-        Ok(results)
+        results
     }
     
     async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError> {
@@ -304,10 +351,379 @@ impl GeocodingService for MockGeocodingService {
     }
 }
 
+/// Caching wrapper around any geocoding service
+///
+/// Reverse lookups are keyed on coordinates rounded to `precision` decimal
+/// places, forward lookups on the normalized single-line address. Cache hits
+/// are served without calling the inner service, so they leave its rate limit
+/// untouched.
+pub struct CachingGeocodingService<G: GeocodingService> {
+    inner: G,
+    precision: u32,
+    forward_cache: Mutex<HashMap<String, GeocodeResult>>,
+    reverse_cache: Mutex<HashMap<(i64, i64), ReverseGeocodeResult>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<G: GeocodingService> CachingGeocodingService<G> {
+    /// Default rounding precision, roughly one meter at the equator
+    pub const DEFAULT_PRECISION: u32 = 5;
+
+    pub fn new(inner: G) -> Self {
+        Self::with_precision(inner, Self::DEFAULT_PRECISION)
+    }
+    
+    pub fn with_precision(inner: G, precision: u32) -> Self {
+        Self {
+            inner,
+            precision,
+            forward_cache: Mutex::new(HashMap::new()),
+            reverse_cache: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+    
+    /// Cache statistics as `(hits, misses)`
+    pub fn cache_stats(&self) -> (u64, u64) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+    
+    /// Drop all cached results
+    pub fn clear(&self) {
+        self.forward_cache.lock().unwrap().clear();
+        self.reverse_cache.lock().unwrap().clear();
+    }
+    
+    fn coordinate_key(&self, coordinates: &Coordinates) -> (i64, i64) {
+        let scale = 10f64.powi(self.precision as i32);
+        (
+            (coordinates.latitude * scale).round() as i64,
+            (coordinates.longitude * scale).round() as i64,
+        )
+    }
+    
+    fn address_key(address: &Address) -> String {
+        address
+            .format_single_line()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[async_trait]
+impl<G: GeocodingService> GeocodingService for CachingGeocodingService<G> {
+    async fn geocode(&self, address: &Address) -> Result<GeocodeResult, GeocodingError> {
+        let key = Self::address_key(address);
+        
+        if let Some(cached) = self.forward_cache.lock().unwrap().get(&key).cloned() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(GeocodeResult {
+                request_id: Uuid::new_v4(),
+                input_address: address.clone(),
+                additional_info: GeocodeInfo {
+                    response_time_ms: 0,
+                    geocoding_method: GeocodingMethod::Cached,
+                    ..cached.additional_info
+                },
+                ..cached
+            });
+        }
+        
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = self.inner.geocode(address).await?;
+        self.forward_cache.lock().unwrap().insert(key, result.clone());
+        Ok(result)
+    }
+    
+    async fn reverse_geocode(&self, coordinates: &Coordinates) -> Result<ReverseGeocodeResult, GeocodingError> {
+        let key = self.coordinate_key(coordinates);
+        
+        if let Some(cached) = self.reverse_cache.lock().unwrap().get(&key).cloned() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(ReverseGeocodeResult {
+                request_id: Uuid::new_v4(),
+                input_coordinates: coordinates.clone(),
+                additional_info: GeocodeInfo {
+                    response_time_ms: 0,
+                    geocoding_method: GeocodingMethod::Cached,
+                    ..cached.additional_info
+                },
+                ..cached
+            });
+        }
+        
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = self.inner.reverse_geocode(coordinates).await?;
+        self.reverse_cache.lock().unwrap().insert(key, result.clone());
+        Ok(result)
+    }
+    
+    async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>> {
+        let mut results = Vec::with_capacity(addresses.len());
+        
+        for address in addresses {
+            results.push(self.geocode(address).await);
+        }
+        
+        results
+    }
+    
+    async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError> {
+        self.inner.validate_address(address).await
+    }
+}
+
+/// Retrying wrapper around any geocoding service
+///
+/// Transient failures (`ServiceUnavailable`, `Timeout`) are retried up to
+/// `max_retries` times, waiting `initial_backoff` before the first retry and
+/// doubling the wait after each one up to `max_backoff`. Every other error is
+/// returned immediately.
+pub struct RetryingGeocodingService<G: GeocodingService> {
+    inner: G,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<G: GeocodingService> RetryingGeocodingService<G> {
+    /// Default number of retries after the first attempt
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+    /// Default wait before the first retry
+    pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+    /// Default upper bound on a single wait
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    pub fn new(inner: G) -> Self {
+        Self {
+            inner,
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+        }
+    }
+    
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+    
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+    
+    /// Wait before retry number `retry` (starting at 0)
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+    
+    async fn with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T, GeocodingError>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: std::future::Future<Output = Result<T, GeocodingError>> + Send,
+        T: Send,
+    {
+        let mut retry = 0;
+        loop {
+            match operation().await {
+                Err(e) if e.is_transient() && retry < self.max_retries => {
+                    tokio::time::sleep(self.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<G: GeocodingService> GeocodingService for RetryingGeocodingService<G> {
+    async fn geocode(&self, address: &Address) -> Result<GeocodeResult, GeocodingError> {
+        self.with_retry(|| self.inner.geocode(address)).await
+    }
+    
+    async fn reverse_geocode(&self, coordinates: &Coordinates) -> Result<ReverseGeocodeResult, GeocodingError> {
+        self.with_retry(|| self.inner.reverse_geocode(coordinates)).await
+    }
+    
+    async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>> {
+        let mut results = Vec::with_capacity(addresses.len());
+        
+        for address in addresses {
+            results.push(self.geocode(address).await);
+        }
+        
+        results
+    }
+    
+    async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError> {
+        self.with_retry(|| self.inner.validate_address(address)).await
+    }
+}
+
+/// Rate-limited wrapper around any geocoding service
+///
+/// Requests draw from a token bucket holding up to `burst` tokens and refilled
+/// at `requests_per_second`. When the bucket is empty a request waits for the
+/// next token instead of being sent, and waiting requests are served in order.
+/// Results report the tokens left afterwards in
+/// [`GeocodeInfo::rate_limit_remaining`].
+pub struct RateLimitedGeocodingService<G: GeocodingService> {
+    inner: G,
+    requests_per_second: f64,
+    burst: u32,
+    bucket: tokio::sync::Mutex<TokenBucket>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: tokio::time::Instant,
+}
+
+impl<G: GeocodingService> RateLimitedGeocodingService<G> {
+    /// Create a limiter that starts with a full bucket
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not positive.
+    pub fn new(inner: G, requests_per_second: f64, burst: u32) -> Self {
+        assert!(requests_per_second > 0.0, "requests_per_second must be positive");
+        let burst = burst.max(1);
+        Self {
+            inner,
+            requests_per_second,
+            burst,
+            bucket: tokio::sync::Mutex::new(TokenBucket {
+                tokens: burst as f64,
+                refilled_at: tokio::time::Instant::now(),
+            }),
+        }
+    }
+    
+    /// Wait for a token and take it, returning the whole tokens left
+    async fn acquire(&self) -> u32 {
+        let mut bucket = self.bucket.lock().await;
+        loop {
+            let now = tokio::time::Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst as f64);
+            bucket.refilled_at = now;
+            
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                return bucket.tokens.floor() as u32;
+            }
+            
+            let wait = (1.0 - bucket.tokens) / self.requests_per_second;
+            tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<G: GeocodingService> GeocodingService for RateLimitedGeocodingService<G> {
+    async fn geocode(&self, address: &Address) -> Result<GeocodeResult, GeocodingError> {
+        let remaining = self.acquire().await;
+        let mut result = self.inner.geocode(address).await?;
+        result.additional_info.rate_limit_remaining = Some(remaining);
+        Ok(result)
+    }
+    
+    async fn reverse_geocode(&self, coordinates: &Coordinates) -> Result<ReverseGeocodeResult, GeocodingError> {
+        let remaining = self.acquire().await;
+        let mut result = self.inner.reverse_geocode(coordinates).await?;
+        result.additional_info.rate_limit_remaining = Some(remaining);
+        Ok(result)
+    }
+    
+    async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>> {
+        let mut results = Vec::with_capacity(addresses.len());
+        
+        for address in addresses {
+            results.push(self.geocode(address).await);
+        }
+        
+        results
+    }
+    
+    async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError> {
+        self.acquire().await;
+        self.inner.validate_address(address).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::value_objects::Coordinates;
+    use std::sync::atomic::AtomicU32;
+
+    /// Fails with `error` for the first `failures` geocode calls, then succeeds
+    struct FlakyGeocodingService {
+        failures: u32,
+        error: fn() -> GeocodingError,
+        calls: AtomicU32,
+        inner: MockGeocodingService,
+    }
+
+    impl FlakyGeocodingService {
+        fn new(failures: u32, error: fn() -> GeocodingError) -> Self {
+            Self {
+                failures,
+                error,
+                calls: AtomicU32::new(0),
+                inner: MockGeocodingService::new().with_delay(0),
+            }
+        }
+        
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl GeocodingService for FlakyGeocodingService {
+        async fn geocode(&self, address: &Address) -> Result<GeocodeResult, GeocodingError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)());
+            }
+            self.inner.geocode(address).await
+        }
+        
+        async fn reverse_geocode(&self, coordinates: &Coordinates) -> Result<ReverseGeocodeResult, GeocodingError> {
+            self.inner.reverse_geocode(coordinates).await
+        }
+        
+        async fn batch_geocode(&self, addresses: &[Address]) -> Vec<Result<GeocodeResult, GeocodingError>> {
+            let mut results = Vec::new();
+            for address in addresses {
+                results.push(self.geocode(address).await);
+            }
+            results
+        }
+        
+        async fn validate_address(&self, address: &Address) -> Result<AddressValidationResult, GeocodingError> {
+            self.inner.validate_address(address).await
+        }
+    }
+
+    fn test_address(street: &str) -> Address {
+        Address::new(
+            street.to_string(),
+            "Test City".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            "12345".to_string(),
+        )
+    }
 
     #[tokio::test]
     async fn test_mock_geocoding_service() {
@@ -338,6 +754,25 @@ mod tests {
         assert!(result.confidence_score > 0.0);
         assert!(!result.address.street1.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_results_carry_the_address_value_object() {
+        let service = MockGeocodingService::new().with_delay(0);
+        let address = test_address("1 Market Street").with_street2("Suite 200".to_string());
+        assert!(address.validate().is_ok());
+
+        let result = service.geocode(&address).await.unwrap();
+        assert_eq!(result.input_address.street2.as_deref(), Some("Suite 200"));
+        assert!(result.formatted_address.semantically_equals(&address));
+
+        // Reverse geocoded addresses are complete addresses, not partial fields
+        let reverse = service.reverse_geocode(&result.coordinates).await.unwrap();
+        let best = reverse.best_candidate();
+        assert!(best.validate().is_ok());
+        assert_eq!(best.street1, "123 Mock Street");
+        assert_eq!(best.locality, "San Francisco");
+        assert_eq!(best.street2, None);
+    }
     
     #[tokio::test]
     async fn test_address_validation() {
@@ -390,11 +825,11 @@ mod tests {
             ),
         ];
         
-        let results = service.batch_geocode(&addresses).await.unwrap();
+        let results = service.batch_geocode(&addresses).await;
         
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].input_address, addresses[0]);
-        assert_eq!(results[1].input_address, addresses[1]);
+        assert_eq!(results[0].as_ref().unwrap().input_address, addresses[0]);
+        assert_eq!(results[1].as_ref().unwrap().input_address, addresses[1]);
     }
     
     #[tokio::test]
@@ -416,4 +851,197 @@ mod tests {
             _ => panic!("Expected ServiceUnavailable error"),
         }
     }
+    
+    #[tokio::test]
+    async fn test_cached_reverse_geocode_nearby_hit() {
+        let service = CachingGeocodingService::new(MockGeocodingService::new().with_delay(0));
+        
+        let first = service.reverse_geocode(&Coordinates::new(37.774901, -122.419401)).await.unwrap();
+        assert!(matches!(first.additional_info.geocoding_method, GeocodingMethod::RealTime));
+        
+        // Rounds to the same 5-decimal key
+        let nearby = Coordinates::new(37.774903, -122.419398);
+        let second = service.reverse_geocode(&nearby).await.unwrap();
+        
+        assert!(matches!(second.additional_info.geocoding_method, GeocodingMethod::Cached));
+        assert_eq!(second.input_coordinates, nearby);
+        assert_eq!(second.address, first.address);
+        assert_eq!(
+            second.additional_info.rate_limit_remaining,
+            first.additional_info.rate_limit_remaining
+        );
+        assert_eq!(service.cache_stats(), (1, 1));
+    }
+    
+    #[tokio::test]
+    async fn test_cached_reverse_geocode_far_miss() {
+        let service = CachingGeocodingService::new(MockGeocodingService::new().with_delay(0));
+        
+        service.reverse_geocode(&Coordinates::new(37.7749, -122.4194)).await.unwrap();
+        let far = service.reverse_geocode(&Coordinates::new(40.7128, -74.0060)).await.unwrap();
+        
+        assert!(matches!(far.additional_info.geocoding_method, GeocodingMethod::RealTime));
+        assert_eq!(service.cache_stats(), (0, 2));
+    }
+    
+    #[tokio::test]
+    async fn test_cached_geocode_normalizes_address() {
+        let service = CachingGeocodingService::new(MockGeocodingService::new().with_delay(0));
+        let address = Address::new(
+            "123 Test Street".to_string(),
+            "Test City".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            "12345".to_string(),
+        );
+        let shouting = Address::new(
+            "123  TEST STREET".to_string(),
+            "test city".to_string(),
+            "CA".to_string(),
+            "US".to_string(),
+            "12345".to_string(),
+        );
+        
+        service.geocode(&address).await.unwrap();
+        let cached = service.geocode(&shouting).await.unwrap();
+        
+        assert!(matches!(cached.additional_info.geocoding_method, GeocodingMethod::Cached));
+        assert_eq!(cached.input_address, shouting);
+        assert_eq!(service.cache_stats(), (1, 1));
+    }
+    
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_failures() {
+        let service = RetryingGeocodingService::new(FlakyGeocodingService::new(2, || {
+            GeocodingError::ServiceUnavailable("Flaky".to_string())
+        }))
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+        
+        let result = service.geocode(&test_address("123 Test Street")).await;
+        
+        assert!(result.is_ok());
+        assert_eq!(service.inner.calls(), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_retries() {
+        let service = RetryingGeocodingService::new(FlakyGeocodingService::new(5, || GeocodingError::Timeout))
+            .with_max_retries(2)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+        
+        let result = service.geocode(&test_address("123 Test Street")).await;
+        
+        assert!(matches!(result, Err(GeocodingError::Timeout)));
+        assert_eq!(service.inner.calls(), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_retry_skips_permanent_errors() {
+        let service = RetryingGeocodingService::new(FlakyGeocodingService::new(1, || GeocodingError::NoResults))
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+        
+        let result = service.geocode(&test_address("123 Test Street")).await;
+        
+        assert!(matches!(result, Err(GeocodingError::NoResults)));
+        assert_eq!(service.inner.calls(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_batch_geocode_keeps_partial_success() {
+        // Only the first call fails, and without retries it stays failed
+        let service = FlakyGeocodingService::new(1, || GeocodingError::InvalidApiKey);
+        let addresses = vec![test_address("1 First Street"), test_address("2 Second Street")];
+        
+        let results = service.batch_geocode(&addresses).await;
+        
+        assert!(matches!(results[0], Err(GeocodingError::InvalidApiKey)));
+        assert_eq!(results[1].as_ref().unwrap().input_address, addresses[1]);
+    }
+    
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let service = RetryingGeocodingService::new(MockGeocodingService::new())
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(350));
+        
+        assert_eq!(service.backoff(0), Duration::from_millis(100));
+        assert_eq!(service.backoff(1), Duration::from_millis(200));
+        assert_eq!(service.backoff(2), Duration::from_millis(350));
+        assert_eq!(service.backoff(40), Duration::from_millis(350));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_delays_requests_beyond_burst() {
+        // 20 requests per second: every request past the burst waits 50ms
+        let service = RateLimitedGeocodingService::new(MockGeocodingService::new().with_delay(0), 20.0, 2);
+        let address = test_address("1 Burst Way");
+        
+        let start = tokio::time::Instant::now();
+        for _ in 0..5 {
+            service.geocode(&address).await.unwrap();
+        }
+        
+        assert!(start.elapsed() >= Duration::from_millis(145));
+    }
+    
+    #[tokio::test]
+    async fn test_rate_limiter_passes_steady_requests() {
+        let service = RateLimitedGeocodingService::new(MockGeocodingService::new().with_delay(0), 10.0, 3);
+        let address = test_address("2 Steady St");
+        
+        let start = tokio::time::Instant::now();
+        let mut remaining = Vec::new();
+        for _ in 0..3 {
+            let result = service.geocode(&address).await.unwrap();
+            remaining.push(result.additional_info.rate_limit_remaining);
+        }
+        assert_eq!(remaining, vec![Some(2), Some(1), Some(0)]);
+        assert!(start.elapsed() < Duration::from_millis(50));
+        
+        // A request arriving after a token has been refilled is not held back
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        let start = tokio::time::Instant::now();
+        service.geocode(&address).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+    
+    fn reverse_result(candidates: Vec<(Address, PrecisionLevel, f64)>) -> ReverseGeocodeResult {
+        ReverseGeocodeResult {
+            request_id: Uuid::new_v4(),
+            input_coordinates: Coordinates::new(37.7749, -122.4194),
+            address: test_address("Primary"),
+            confidence_score: 0.5,
+            precision_level: PrecisionLevel::Approximate,
+            additional_info: GeocodeInfo {
+                provider: "Test".to_string(),
+                response_time_ms: 0,
+                rate_limit_remaining: None,
+                geocoding_method: GeocodingMethod::RealTime,
+                data_sources: vec![],
+            },
+            candidates,
+        }
+    }
+    
+    #[test]
+    fn test_best_candidate_prefers_precision_then_confidence() {
+        let result = reverse_result(vec![
+            (test_address("City"), PrecisionLevel::City, 0.99),
+            (test_address("Exact low"), PrecisionLevel::Exact, 0.60),
+            (test_address("Exact high"), PrecisionLevel::Exact, 0.85),
+            (test_address("Street"), PrecisionLevel::Street, 0.95),
+        ]);
+        assert_eq!(result.best_candidate(), &test_address("Exact high"));
+        
+        // Without candidates the primary address is used
+        assert_eq!(reverse_result(vec![]).best_candidate(), &test_address("Primary"));
+    }
+    
+    #[tokio::test]
+    async fn test_mock_reverse_geocode_candidates() {
+        let service = MockGeocodingService::new().with_delay(0);
+        let result = service.reverse_geocode(&Coordinates::new(37.7749, -122.4194)).await.unwrap();
+        
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.best_candidate(), &result.address);
+    }
 }
\ No newline at end of file
@@ -1,27 +1,54 @@
 //! Hierarchy management services for location relationships
 
+use crate::domain_events::LocationDomainEvent;
+use crate::events::{ParentLocationRemoved, ParentLocationSet};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
+use uuid::Uuid;
 
 /// Hierarchy management service trait
 #[async_trait]
 pub trait HierarchyManagementService: Send + Sync {
     /// Build a hierarchy tree for a location
     async fn build_hierarchy_tree(&self, root_id: &Uuid) -> Result<HierarchyTree, HierarchyError>;
-    
+
     /// Validate hierarchy operations (prevent cycles, etc.)
-    async fn validate_hierarchy_operation(&self, operation: &HierarchyOperation) -> Result<ValidationResult, HierarchyError>;
-    
+    async fn validate_hierarchy_operation(
+        &self,
+        operation: &HierarchyOperation,
+    ) -> Result<ValidationResult, HierarchyError>;
+
     /// Reorganize a branch of the hierarchy
-    async fn reorganize_branch(&self, branch_root: &Uuid, new_structure: &HierarchyStructure) -> Result<ReorganizationResult, HierarchyError>;
-    
+    async fn reorganize_branch(
+        &self,
+        branch_root: &Uuid,
+        new_structure: &HierarchyStructure,
+    ) -> Result<ReorganizationResult, HierarchyError>;
+
     /// Find all ancestors of a location
-    async fn find_ancestors(&self, location_id: &Uuid) -> Result<Vec<HierarchyNode>, HierarchyError>;
-    
+    async fn find_ancestors(
+        &self,
+        location_id: &Uuid,
+    ) -> Result<Vec<HierarchyNode>, HierarchyError>;
+
     /// Find all descendants of a location
-    async fn find_descendants(&self, location_id: &Uuid, max_depth: Option<u32>) -> Result<Vec<HierarchyNode>, HierarchyError>;
+    async fn find_descendants(
+        &self,
+        location_id: &Uuid,
+        max_depth: Option<u32>,
+    ) -> Result<Vec<HierarchyNode>, HierarchyError>;
+
+    /// Re-parent `node` together with its whole subtree
+    ///
+    /// Only the moved node changes parent, so the returned events cover that node alone.
+    /// Nothing is applied; the caller persists the events.
+    async fn move_subtree(
+        &self,
+        node: Uuid,
+        new_parent: Option<Uuid>,
+    ) -> Result<Vec<LocationDomainEvent>, HierarchyError>;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,23 +144,50 @@ pub struct ReorganizationResult {
 pub enum HierarchyError {
     #[error("Circular reference detected: {0}")]
     CircularReference(String),
-    
+
     #[error("Location not found: {0}")]
     LocationNotFound(Uuid),
-    
+
     #[error("Invalid hierarchy operation: {0}")]
     InvalidOperation(String),
-    
+
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
 }
 
 /// Mock hierarchy management service
-pub struct MockHierarchyManagementService;
+#[derive(Default)]
+pub struct MockHierarchyManagementService {
+    /// Known parent links, keyed by child location
+    pub parent_links: HashMap<Uuid, Uuid>,
+}
+
+impl MockHierarchyManagementService {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl Default for MockHierarchyManagementService {
-    fn default() -> Self {
-        Self
+    pub fn with_parent(mut self, child: Uuid, parent: Uuid) -> Self {
+        self.parent_links.insert(child, parent);
+        self
+    }
+
+    /// Whether `ancestor` appears on the parent chain of `location` (or is `location` itself)
+    fn is_ancestor_or_self(&self, ancestor: Uuid, location: Uuid) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(location);
+
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            if !visited.insert(id) {
+                return false;
+            }
+            current = self.parent_links.get(&id).copied();
+        }
+
+        false
     }
 }
 
@@ -143,31 +197,27 @@ impl HierarchyManagementService for MockHierarchyManagementService {
         // Mock tree structure
         let child_id = Uuid::new_v4();
         let grandchild_id = Uuid::new_v4();
-        
+
         let root = HierarchyNode {
             location_id: *root_id,
             name: Some("Root Location".to_string()),
             level: 0,
-            children: vec![
-                HierarchyNode {
-                    location_id: child_id,
-                    name: Some("Child Location".to_string()),
-                    level: 1,
-                    children: vec![
-                        HierarchyNode {
-                            location_id: grandchild_id,
-                            name: Some("Grandchild Location".to_string()),
-                            level: 2,
-                            children: vec![],
-                            node_type: HierarchyNodeType::Leaf,
-                        }
-                    ],
-                    node_type: HierarchyNodeType::Branch,
-                }
-            ],
+            children: vec![HierarchyNode {
+                location_id: child_id,
+                name: Some("Child Location".to_string()),
+                level: 1,
+                children: vec![HierarchyNode {
+                    location_id: grandchild_id,
+                    name: Some("Grandchild Location".to_string()),
+                    level: 2,
+                    children: vec![],
+                    node_type: HierarchyNodeType::Leaf,
+                }],
+                node_type: HierarchyNodeType::Branch,
+            }],
             node_type: HierarchyNodeType::Root,
         };
-        
+
         Ok(HierarchyTree {
             root,
             total_nodes: 3,
@@ -179,11 +229,14 @@ impl HierarchyManagementService for MockHierarchyManagementService {
             },
         })
     }
-    
-    async fn validate_hierarchy_operation(&self, operation: &HierarchyOperation) -> Result<ValidationResult, HierarchyError> {
+
+    async fn validate_hierarchy_operation(
+        &self,
+        operation: &HierarchyOperation,
+    ) -> Result<ValidationResult, HierarchyError> {
         let mut issues = Vec::new();
         let mut warnings = Vec::new();
-        
+
         // Mock validation - check for self-parenting
         if let Some(parent_id) = operation.parent_location {
             if parent_id == operation.target_location {
@@ -194,18 +247,22 @@ impl HierarchyManagementService for MockHierarchyManagementService {
                 });
             }
         }
-        
+
         // Mock warning for deep hierarchies
         warnings.push("This operation may create a deep hierarchy".to_string());
-        
+
         Ok(ValidationResult {
             is_valid: issues.is_empty(),
             issues,
             warnings,
         })
     }
-    
-    async fn reorganize_branch(&self, branch_root: &Uuid, _new_structure: &HierarchyStructure) -> Result<ReorganizationResult, HierarchyError> {
+
+    async fn reorganize_branch(
+        &self,
+        branch_root: &Uuid,
+        _new_structure: &HierarchyStructure,
+    ) -> Result<ReorganizationResult, HierarchyError> {
         Ok(ReorganizationResult {
             success: true,
             affected_locations: vec![*branch_root],
@@ -213,8 +270,11 @@ impl HierarchyManagementService for MockHierarchyManagementService {
             execution_time_ms: 100,
         })
     }
-    
-    async fn find_ancestors(&self, location_id: &Uuid) -> Result<Vec<HierarchyNode>, HierarchyError> {
+
+    async fn find_ancestors(
+        &self,
+        location_id: &Uuid,
+    ) -> Result<Vec<HierarchyNode>, HierarchyError> {
         // Mock ancestor chain
         Ok(vec![
             HierarchyNode {
@@ -233,8 +293,12 @@ impl HierarchyManagementService for MockHierarchyManagementService {
             },
         ])
     }
-    
-    async fn find_descendants(&self, _location_id: &Uuid, _max_depth: Option<u32>) -> Result<Vec<HierarchyNode>, HierarchyError> {
+
+    async fn find_descendants(
+        &self,
+        _location_id: &Uuid,
+        _max_depth: Option<u32>,
+    ) -> Result<Vec<HierarchyNode>, HierarchyError> {
         // Mock descendant list
         Ok(vec![
             HierarchyNode {
@@ -253,6 +317,44 @@ impl HierarchyManagementService for MockHierarchyManagementService {
             },
         ])
     }
+
+    async fn move_subtree(
+        &self,
+        node: Uuid,
+        new_parent: Option<Uuid>,
+    ) -> Result<Vec<LocationDomainEvent>, HierarchyError> {
+        let previous_parent = self.parent_links.get(&node).copied();
+
+        let event = match (new_parent, previous_parent) {
+            // Already in place
+            (Some(parent_id), Some(current)) if parent_id == current => return Ok(Vec::new()),
+            (None, None) => return Ok(Vec::new()),
+            (Some(parent_id), _) => {
+                if self.is_ancestor_or_self(node, parent_id) {
+                    return Err(HierarchyError::CircularReference(format!(
+                        "cannot move {} under {}: the new parent is within its own subtree",
+                        node, parent_id
+                    )));
+                }
+
+                LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                    location_id: node,
+                    parent_id,
+                    previous_parent_id: previous_parent,
+                    reason: "Subtree moved".to_string(),
+                })
+            }
+            (None, Some(previous_parent_id)) => {
+                LocationDomainEvent::ParentLocationRemoved(ParentLocationRemoved {
+                    location_id: node,
+                    previous_parent_id,
+                    reason: "Subtree moved to top level".to_string(),
+                })
+            }
+        };
+
+        Ok(vec![event])
+    }
 }
 
 #[cfg(test)]
@@ -261,21 +363,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_build_hierarchy_tree() {
-        let service = MockHierarchyManagementService;
+        let service = MockHierarchyManagementService::default();
         let root_id = Uuid::new_v4();
-        
+
         let tree = service.build_hierarchy_tree(&root_id).await.unwrap();
-        
+
         assert_eq!(tree.root.location_id, root_id);
         assert_eq!(tree.total_nodes, 3);
         assert_eq!(tree.max_depth, 2);
     }
-    
+
     #[tokio::test]
     async fn test_validate_hierarchy_operation() {
-        let service = MockHierarchyManagementService;
+        let service = MockHierarchyManagementService::default();
         let location_id = Uuid::new_v4();
-        
+
         // Valid operation
         let valid_operation = HierarchyOperation {
             operation_type: HierarchyOperationType::SetParent,
@@ -283,10 +385,13 @@ mod tests {
             parent_location: Some(Uuid::new_v4()),
             new_parent_location: None,
         };
-        
-        let result = service.validate_hierarchy_operation(&valid_operation).await.unwrap();
+
+        let result = service
+            .validate_hierarchy_operation(&valid_operation)
+            .await
+            .unwrap();
         assert!(result.is_valid);
-        
+
         // Invalid operation (self-parenting)
         let invalid_operation = HierarchyOperation {
             operation_type: HierarchyOperationType::SetParent,
@@ -294,9 +399,69 @@ mod tests {
             parent_location: Some(location_id), // Same as target
             new_parent_location: None,
         };
-        
-        let result = service.validate_hierarchy_operation(&invalid_operation).await.unwrap();
+
+        let result = service
+            .validate_hierarchy_operation(&invalid_operation)
+            .await
+            .unwrap();
         assert!(!result.is_valid);
         assert!(!result.issues.is_empty());
     }
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_move_building_between_campuses() {
+        let north_campus = Uuid::new_v4();
+        let south_campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+        let floor = Uuid::new_v4();
+        let service = MockHierarchyManagementService::new()
+            .with_parent(building, north_campus)
+            .with_parent(floor, building);
+
+        let events = service
+            .move_subtree(building, Some(south_campus))
+            .await
+            .unwrap();
+
+        // Only the building is re-parented; the floor follows it implicitly
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            LocationDomainEvent::ParentLocationSet(event) => {
+                assert_eq!(event.location_id, building);
+                assert_eq!(event.parent_id, south_campus);
+                assert_eq!(event.previous_parent_id, Some(north_campus));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_move_subtree_to_top_level() {
+        let campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+        let service = MockHierarchyManagementService::new().with_parent(building, campus);
+
+        let events = service.move_subtree(building, None).await.unwrap();
+
+        assert!(matches!(
+            &events[..],
+            [LocationDomainEvent::ParentLocationRemoved(event)] if event.previous_parent_id == campus
+        ));
+        assert!(service.move_subtree(campus, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_move_subtree_rejects_own_descendant() {
+        let campus = Uuid::new_v4();
+        let building = Uuid::new_v4();
+        let room = Uuid::new_v4();
+        let service = MockHierarchyManagementService::new()
+            .with_parent(building, campus)
+            .with_parent(room, building);
+
+        let result = service.move_subtree(campus, Some(room)).await;
+        assert!(matches!(result, Err(HierarchyError::CircularReference(_))));
+
+        let result = service.move_subtree(campus, Some(campus)).await;
+        assert!(matches!(result, Err(HierarchyError::CircularReference(_))));
+    }
+}
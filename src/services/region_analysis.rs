@@ -2,8 +2,10 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
-use crate::value_objects::Coordinates;
+use crate::services::spatial_search::SpatialHotspot;
+use crate::value_objects::{Boundary, Coordinates};
 
 #[async_trait]
 pub trait RegionAnalysisService: Send + Sync {
@@ -42,4 +44,203 @@ impl RegionAnalysisService for MockRegionAnalysisService {
             center_point: Coordinates::new(37.7749, -122.4194),
         })
     }
-}
\ No newline at end of file
+}
+/// DBSCAN label assigned to each input point
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PointLabel {
+    Unvisited,
+    Noise,
+    Cluster(usize),
+}
+
+/// Group locations into density clusters using DBSCAN
+///
+/// A point is a core point when at least `min_points` locations (itself included)
+/// lie within `eps_meters` of it. Clusters grow from core points; points reachable
+/// from no core point are noise and belong to no hotspot. Each hotspot is centred on
+/// its cluster centroid, with a radius reaching the furthest member, and its
+/// `density_score` is the share of all input points that fell into the cluster.
+pub fn cluster_locations(
+    points: &[(Uuid, Coordinates)],
+    eps_meters: f64,
+    min_points: usize,
+) -> Vec<SpatialHotspot> {
+    let neighbors = |index: usize| -> Vec<usize> {
+        let origin = &points[index].1;
+        (0..points.len())
+            .filter(|&other| origin.distance_to(&points[other].1) <= eps_meters)
+            .collect()
+    };
+
+    let mut labels = vec![PointLabel::Unvisited; points.len()];
+    let mut cluster_count = 0;
+
+    for index in 0..points.len() {
+        if labels[index] != PointLabel::Unvisited {
+            continue;
+        }
+
+        let seeds = neighbors(index);
+        if seeds.len() < min_points {
+            labels[index] = PointLabel::Noise;
+            continue;
+        }
+
+        let cluster = cluster_count;
+        cluster_count += 1;
+        labels[index] = PointLabel::Cluster(cluster);
+
+        let mut queue = seeds;
+        while let Some(candidate) = queue.pop() {
+            match labels[candidate] {
+                // Border point: reachable from a core point but not dense itself
+                PointLabel::Noise => labels[candidate] = PointLabel::Cluster(cluster),
+                PointLabel::Unvisited => {
+                    labels[candidate] = PointLabel::Cluster(cluster);
+                    let expansion = neighbors(candidate);
+                    if expansion.len() >= min_points {
+                        queue.extend(expansion);
+                    }
+                }
+                PointLabel::Cluster(_) => {}
+            }
+        }
+    }
+
+    (0..cluster_count)
+        .map(|cluster| {
+            let members: Vec<&Coordinates> = points
+                .iter()
+                .zip(&labels)
+                .filter(|(_, label)| **label == PointLabel::Cluster(cluster))
+                .map(|((_, coordinates), _)| coordinates)
+                .collect();
+
+            let count = members.len() as f64;
+            let center = Coordinates::new(
+                members.iter().map(|c| c.latitude).sum::<f64>() / count,
+                members.iter().map(|c| c.longitude).sum::<f64>() / count,
+            );
+            let radius_meters = members
+                .iter()
+                .map(|c| center.distance_to(c))
+                .fold(0.0, f64::max);
+
+            SpatialHotspot {
+                center,
+                radius_meters,
+                location_count: members.len() as u64,
+                density_score: count / points.len() as f64,
+                dominant_categories: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Assign locations to the regions whose boundary contains them
+///
+/// Every region appears in the result, with its locations in input order.
+/// Regions may overlap, so a location can be listed under several regions;
+/// a location on a region's edge counts as inside it.
+pub fn spatial_join(
+    regions: &[(Uuid, Boundary)],
+    locations: &[(Uuid, Coordinates)],
+) -> HashMap<Uuid, Vec<Uuid>> {
+    regions
+        .iter()
+        .map(|(region_id, boundary)| {
+            let members = locations
+                .iter()
+                .filter(|(_, coordinates)| boundary.contains(coordinates))
+                .map(|(location_id, _)| *location_id)
+                .collect();
+            (*region_id, members)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scatter(center: (f64, f64), count: usize) -> Vec<(Uuid, Coordinates)> {
+        // Roughly 10m apart along a short diagonal
+        (0..count)
+            .map(|i| {
+                let offset = i as f64 * 0.0001;
+                (Uuid::new_v4(), Coordinates::new(center.0 + offset, center.1 + offset))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cluster_locations_finds_separated_clusters() {
+        let mut points = scatter((37.7749, -122.4194), 6); // San Francisco
+        points.extend(scatter((40.7128, -74.0060), 4)); // New York
+        // Isolated noise
+        points.push((Uuid::new_v4(), Coordinates::new(51.5074, -0.1278)));
+        points.push((Uuid::new_v4(), Coordinates::new(35.6762, 139.6503)));
+
+        let mut hotspots = cluster_locations(&points, 100.0, 3);
+        hotspots.sort_by_key(|h| std::cmp::Reverse(h.location_count));
+
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].location_count, 6);
+        assert_eq!(hotspots[1].location_count, 4);
+        assert!(hotspots[0].center.distance_to(&Coordinates::new(37.7749, -122.4194)) < 100.0);
+        assert!(hotspots[1].center.distance_to(&Coordinates::new(40.7128, -74.0060)) < 100.0);
+        assert!(hotspots.iter().all(|h| h.radius_meters > 0.0 && h.radius_meters < 100.0));
+        assert!((hotspots[0].density_score - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cluster_locations_all_noise() {
+        let points = vec![
+            (Uuid::new_v4(), Coordinates::new(37.7749, -122.4194)),
+            (Uuid::new_v4(), Coordinates::new(40.7128, -74.0060)),
+        ];
+
+        assert!(cluster_locations(&points, 1000.0, 2).is_empty());
+        assert!(cluster_locations(&[], 1000.0, 2).is_empty());
+    }
+
+    fn square(south: f64, west: f64, north: f64, east: f64) -> Boundary {
+        Boundary::from_vertices(vec![
+            Coordinates::new(south, west),
+            Coordinates::new(south, east),
+            Coordinates::new(north, east),
+            Coordinates::new(north, west),
+        ])
+    }
+
+    #[test]
+    fn test_spatial_join_with_overlapping_regions() {
+        let west_region = Uuid::new_v4();
+        let east_region = Uuid::new_v4();
+        let empty_region = Uuid::new_v4();
+        let regions = vec![
+            (west_region, square(0.0, 0.0, 2.0, 2.0)),
+            (east_region, square(0.0, 1.0, 2.0, 3.0)),
+            (empty_region, square(10.0, 10.0, 11.0, 11.0)),
+        ];
+
+        let west_only = Uuid::new_v4();
+        let both = Uuid::new_v4();
+        let east_only = Uuid::new_v4();
+        let neither = Uuid::new_v4();
+        let locations = vec![
+            (west_only, Coordinates::new(1.0, 0.5)),
+            (both, Coordinates::new(1.0, 1.5)),
+            (east_only, Coordinates::new(1.0, 2.5)),
+            (neither, Coordinates::new(5.0, 5.0)),
+        ];
+
+        let joined = spatial_join(&regions, &locations);
+
+        assert_eq!(joined.len(), 3);
+        assert_eq!(joined[&west_region], vec![west_only, both]);
+        assert_eq!(joined[&east_region], vec![both, east_only]);
+        assert!(joined[&empty_region].is_empty());
+        assert!(joined.values().all(|members| !members.contains(&neither)));
+    }
+}
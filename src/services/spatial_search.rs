@@ -1,9 +1,11 @@
 //! Spatial search services for location-based queries
 
 use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::value_objects::{Coordinates, LocationTypes};
+use crate::value_objects::{polygon_contains, BoundingBox, Coordinates, LocationTypes};
 use thiserror::Error;
 
 /// Spatial search service trait for location-based queries
@@ -26,6 +28,8 @@ pub trait SpatialSearchService: Send + Sync {
     ) -> Result<SpatialSearchResult, SpatialSearchError>;
     
     /// Find locations along a route/path
+    ///
+    /// Matches lie within `corridor_width_meters` of the route polyline.
     async fn find_along_route(
         &self,
         route_points: &[Coordinates],
@@ -34,12 +38,16 @@ pub trait SpatialSearchService: Send + Sync {
     ) -> Result<SpatialSearchResult, SpatialSearchError>;
     
     /// Find nearest locations to a point
+    ///
+    /// Results are ordered by distance, then location id. Pass the previous
+    /// result's `next_page_token` as `page_token` to continue after its last match.
     async fn find_nearest(
         &self,
         point: &Coordinates,
         max_results: u32,
         max_distance_meters: Option<f64>,
         filters: Option<SpatialSearchFilters>,
+        page_token: Option<&str>,
     ) -> Result<SpatialSearchResult, SpatialSearchError>;
     
     /// Get spatial statistics for a region
@@ -64,12 +72,39 @@ pub struct SpatialSearchFilters {
     /// Filter by creation date range
     pub created_after: Option<chrono::DateTime<chrono::Utc>>,
     pub created_before: Option<chrono::DateTime<chrono::Utc>>,
-    /// Filter by activity level
+    /// Filter by activity level, as scored by [`ActivityTracker`](crate::services::ActivityTracker)
     pub min_activity_score: Option<f64>,
     /// Filter by verification status
     pub verified_only: Option<bool>,
     /// Custom metadata filters
     pub metadata_filters: Option<serde_json::Value>,
+    /// Exclude locations whose horizontal accuracy is worse than this (or unknown)
+    #[serde(default)]
+    pub max_accuracy_meters: Option<f64>,
+}
+
+impl SpatialSearchFilters {
+    /// Whether a location passes the type, tag and accuracy filters
+    fn matches(&self, location: &SpatialLocationMatch) -> bool {
+        if let Some(ref types) = self.location_types {
+            if !types.contains(&location.location_type) {
+                return false;
+            }
+        }
+        if let Some(ref tags) = self.tags {
+            if !tags.iter().any(|tag| location.tags.contains(tag)) {
+                return false;
+            }
+        }
+        if let Some(max_accuracy) = self.max_accuracy_meters {
+            // A fix without a reported accuracy cannot be shown to meet the threshold
+            let accuracy = location.coordinates.accuracy_meters;
+            if !accuracy.is_some_and(|accuracy| accuracy <= max_accuracy) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 /// Spatial search result
@@ -85,6 +120,53 @@ pub struct SpatialSearchResult {
     pub search_metadata: SpatialSearchMetadata,
 }
 
+/// Cursor for resuming a nearest-location search
+///
+/// Encoded as URL-safe base64 of its JSON form. A page resumes with the first
+/// match ordered strictly after `(distance_meters, location_id)`, so matches
+/// with equal distances are neither repeated nor skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageToken {
+    /// Last location returned on the previous page
+    pub location_id: Uuid,
+    /// Distance of that location from the search point
+    pub distance_meters: f64,
+}
+
+impl PageToken {
+    /// Create a token pointing after the given match
+    pub fn after(location: &SpatialLocationMatch) -> Self {
+        Self {
+            location_id: location.location_id,
+            distance_meters: location.distance_meters.unwrap_or(f64::INFINITY),
+        }
+    }
+    
+    /// Encode the token as an opaque string
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("page token serializes to JSON");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+    
+    /// Decode a token produced by [`PageToken::encode`]
+    pub fn decode(token: &str) -> Result<Self, SpatialSearchError> {
+        let json = URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|e| SpatialSearchError::InvalidPageToken(e.to_string()))?;
+        serde_json::from_slice(&json).map_err(|e| SpatialSearchError::InvalidPageToken(e.to_string()))
+    }
+    
+    /// Whether `location` comes after this cursor in nearest-first order
+    fn precedes(&self, location: &SpatialLocationMatch) -> bool {
+        let distance = location.distance_meters.unwrap_or(f64::INFINITY);
+        match distance.total_cmp(&self.distance_meters) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => location.location_id > self.location_id,
+            std::cmp::Ordering::Less => false,
+        }
+    }
+}
+
 /// Spatial search query information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpatialQuery {
@@ -146,10 +228,39 @@ pub enum SpatialRegion {
     },
     RouteCorRidor {
         route_points: Vec<Coordinates>,
+        /// Greatest distance from the route polyline, so the corridor spans
+        /// twice this across
         corridor_width_meters: f64,
     },
 }
 
+impl SpatialRegion {
+    /// Check whether a point lies inside the region
+    ///
+    /// Polygons are tested in the longitude/latitude plane; points on an edge
+    /// or vertex count as inside. A route corridor contains every point within
+    /// `corridor_width_meters` of the route polyline, as in
+    /// [`SpatialSearchService::find_along_route`].
+    pub fn contains(&self, point: &Coordinates) -> bool {
+        match self {
+            SpatialRegion::Circle { center, radius_meters } => {
+                center.distance_to(point) <= *radius_meters
+            }
+            SpatialRegion::BoundingBox { southwest, northeast } => BoundingBox {
+                min_lat: southwest.latitude,
+                max_lat: northeast.latitude,
+                min_lon: southwest.longitude,
+                max_lon: northeast.longitude,
+            }
+            .contains(point),
+            SpatialRegion::Polygon { vertices } => polygon_contains(vertices, point),
+            SpatialRegion::RouteCorRidor { route_points, corridor_width_meters } => point
+                .distance_to_polyline(route_points)
+                .is_some_and(|distance| distance <= *corridor_width_meters),
+        }
+    }
+}
+
 /// Spatial statistics for a region
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpatialStatistics {
@@ -245,11 +356,24 @@ impl MockSpatialSearchService {
         self
     }
     
+    /// Annotate mock locations with their real distance and bearing from `origin`
+    fn measured_from(&self, origin: &Coordinates) -> Vec<SpatialLocationMatch> {
+        self.mock_locations
+            .iter()
+            .map(|loc| {
+                let mut measured = loc.clone();
+                measured.distance_meters = Some(origin.distance_to(&loc.coordinates));
+                measured.bearing_degrees = Some(origin.bearing_to(&loc.coordinates));
+                measured
+            })
+            .collect()
+    }
+
     fn generate_mock_locations() -> Vec<SpatialLocationMatch> {
         vec![
             SpatialLocationMatch {
                 location_id: Uuid::new_v4(),
-                coordinates: Coordinates::new(37.7749, -122.4194), // San Francisco
+                coordinates: Coordinates::new(37.7749, -122.4194).with_accuracy(5.0), // San Francisco
                 distance_meters: Some(100.0),
                 bearing_degrees: Some(45.0),
                 location_type: LocationTypes::Physical,
@@ -263,7 +387,7 @@ impl MockSpatialSearchService {
             },
             SpatialLocationMatch {
                 location_id: Uuid::new_v4(),
-                coordinates: Coordinates::new(37.7849, -122.4094),
+                coordinates: Coordinates::new(37.7849, -122.4094).with_accuracy(250.0),
                 distance_meters: Some(500.0),
                 bearing_degrees: Some(90.0),
                 location_type: LocationTypes::Physical,
@@ -299,9 +423,9 @@ impl SpatialSearchService for MockSpatialSearchService {
             return Err(SpatialSearchError::InvalidRadius(radius_meters));
         }
         
-        // Filter mock locations by distance (simplified)
-        let filtered_locations: Vec<SpatialLocationMatch> = self.mock_locations
-            .iter()
+        // Filter mock locations by their distance from the center
+        let filtered_locations: Vec<SpatialLocationMatch> = self.measured_from(center)
+            .into_iter()
             .filter(|loc| {
                 if let Some(distance) = loc.distance_meters {
                     distance <= radius_meters
@@ -309,23 +433,11 @@ impl SpatialSearchService for MockSpatialSearchService {
                     true // Include if distance not calculated
                 }
             })
-            .filter(|loc| {
-                // Apply additional filters if provided
-                if let Some(ref filters) = filters {
-                    if let Some(ref types) = filters.location_types {
-                        if !types.contains(&loc.location_type) {
-                            return false;
-                        }
-                    }
-                    if let Some(ref tags) = filters.tags {
-                        if !tags.iter().any(|tag| loc.tags.contains(tag)) {
-                            return false;
-                        }
-                    }
-                }
-                true
+            // Apply additional filters if provided
+            .filter(|loc| match &filters {
+                Some(filters) => filters.matches(loc),
+                None => true,
             })
-            .cloned()
             .collect();
         
         Ok(SpatialSearchResult {
@@ -375,7 +487,16 @@ impl SpatialSearchService for MockSpatialSearchService {
             ));
         }
         
-        // Mock implementation - return all locations for simplicity
+        // Mock implementation - return every location passing the filters
+        let filtered_locations: Vec<SpatialLocationMatch> = self.mock_locations
+            .iter()
+            .filter(|loc| match &filters {
+                Some(filters) => filters.matches(loc),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        
         Ok(SpatialSearchResult {
             request_id: Uuid::new_v4(),
             query: SpatialQuery {
@@ -387,8 +508,8 @@ impl SpatialSearchService for MockSpatialSearchService {
                 filters: filters.clone(),
                 timestamp: chrono::Utc::now(),
             },
-            locations: self.mock_locations.clone(),
-            total_count: self.mock_locations.len() as u64,
+            total_count: filtered_locations.len() as u64,
+            locations: filtered_locations,
             search_time_ms: self.response_delay_ms,
             has_more_results: false,
             next_page_token: None,
@@ -411,23 +532,58 @@ impl SpatialSearchService for MockSpatialSearchService {
     
     async fn find_along_route(
         &self,
-        _route_points: &[Coordinates],
-        _corridor_width_meters: f64,
+        route_points: &[Coordinates],
+        corridor_width_meters: f64,
         filters: Option<SpatialSearchFilters>,
     ) -> Result<SpatialSearchResult, SpatialSearchError> {
         tokio::time::sleep(tokio::time::Duration::from_millis(self.response_delay_ms)).await;
         
-        // Mock implementation
+        if route_points.is_empty() {
+            return Err(SpatialSearchError::InvalidCoordinates(
+                "Route must contain at least one point".to_string()
+            ));
+        }
+        if corridor_width_meters <= 0.0 {
+            return Err(SpatialSearchError::InvalidRadius(corridor_width_meters));
+        }
+        
+        // Keep locations whose distance to the route fits in the corridor
+        let mut filtered_locations: Vec<SpatialLocationMatch> = self.mock_locations
+            .iter()
+            .filter_map(|loc| {
+                let distance = loc.coordinates.distance_to_polyline(route_points)?;
+                if distance > corridor_width_meters {
+                    return None;
+                }
+                let mut measured = loc.clone();
+                measured.distance_meters = Some(distance);
+                measured.bearing_degrees = None;
+                Some(measured)
+            })
+            .filter(|loc| match &filters {
+                Some(filters) => filters.matches(loc),
+                None => true,
+            })
+            .collect();
+        filtered_locations.sort_by(|a, b| {
+            a.distance_meters
+                .partial_cmp(&b.distance_meters)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        
         Ok(SpatialSearchResult {
             request_id: Uuid::new_v4(),
             query: SpatialQuery {
                 query_type: SpatialQueryType::AlongRoute,
-                parameters: serde_json::json!({}),
+                parameters: serde_json::json!({
+                    "route_points": route_points,
+                    "corridor_width_meters": corridor_width_meters
+                }),
                 filters: filters.clone(),
                 timestamp: chrono::Utc::now(),
             },
-            locations: self.mock_locations.clone(),
-            total_count: self.mock_locations.len() as u64,
+            total_count: filtered_locations.len() as u64,
+            locations: filtered_locations,
             search_time_ms: self.response_delay_ms,
             has_more_results: false,
             next_page_token: None,
@@ -450,29 +606,62 @@ impl SpatialSearchService for MockSpatialSearchService {
     
     async fn find_nearest(
         &self,
-        _point: &Coordinates,
+        point: &Coordinates,
         max_results: u32,
-        _max_distance_meters: Option<f64>,
+        max_distance_meters: Option<f64>,
         filters: Option<SpatialSearchFilters>,
+        page_token: Option<&str>,
     ) -> Result<SpatialSearchResult, SpatialSearchError> {
+        let cursor = page_token.map(PageToken::decode).transpose()?;
+        
         tokio::time::sleep(tokio::time::Duration::from_millis(self.response_delay_ms)).await;
         
-        let mut locations = self.mock_locations.clone();
+        let mut locations: Vec<SpatialLocationMatch> = self.measured_from(point)
+            .into_iter()
+            .filter(|loc| match (max_distance_meters, loc.distance_meters) {
+                (Some(max_distance), Some(distance)) => distance <= max_distance,
+                _ => true,
+            })
+            .filter(|loc| match &filters {
+                Some(filters) => filters.matches(loc),
+                None => true,
+            })
+            .collect();
+        locations.sort_by(|a, b| {
+            a.distance_meters
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&b.distance_meters.unwrap_or(f64::INFINITY))
+                .then(a.location_id.cmp(&b.location_id))
+        });
+        let total_count = locations.len() as u64;
+        if let Some(ref cursor) = cursor {
+            locations.retain(|location| cursor.precedes(location));
+        }
+        
+        let has_more_results = locations.len() > max_results as usize;
         locations.truncate(max_results as usize);
+        let next_page_token = if has_more_results {
+            locations.last().map(|last| PageToken::after(last).encode())
+        } else {
+            None
+        };
         
         Ok(SpatialSearchResult {
             request_id: Uuid::new_v4(),
             query: SpatialQuery {
                 query_type: SpatialQueryType::Nearest,
-                parameters: serde_json::json!({"max_results": max_results}),
+                parameters: serde_json::json!({
+                    "max_results": max_results,
+                    "max_distance_meters": max_distance_meters
+                }),
                 filters: filters.clone(),
                 timestamp: chrono::Utc::now(),
             },
             locations,
-            total_count: max_results.min(self.mock_locations.len() as u32) as u64,
+            total_count,
             search_time_ms: self.response_delay_ms,
-            has_more_results: self.mock_locations.len() > max_results as usize,
-            next_page_token: None,
+            has_more_results,
+            next_page_token,
             search_metadata: SpatialSearchMetadata {
                 index_version: "1.0".to_string(),
                 search_algorithm: "mock_spatial_index".to_string(),
@@ -500,7 +689,13 @@ impl SpatialSearchService for MockSpatialSearchService {
         let mut type_breakdown = std::collections::HashMap::new();
         let mut category_breakdown = std::collections::HashMap::new();
         
-        for location in &self.mock_locations {
+        let contained: Vec<&SpatialLocationMatch> = self
+            .mock_locations
+            .iter()
+            .filter(|location| region.contains(&location.coordinates))
+            .collect();
+        
+        for location in &contained {
             *type_breakdown.entry(location.location_type.clone()).or_insert(0) += 1;
             for category in &location.categories {
                 *category_breakdown.entry(category.clone()).or_insert(0) += 1;
@@ -509,7 +704,7 @@ impl SpatialSearchService for MockSpatialSearchService {
         
         Ok(SpatialStatistics {
             region: region.clone(),
-            total_locations: self.mock_locations.len() as u64,
+            total_locations: contained.len() as u64,
             density_per_km2: 50.0, // Mock density
             location_type_breakdown: type_breakdown,
             category_breakdown,
@@ -591,12 +786,86 @@ mod tests {
         let service = MockSpatialSearchService::new();
         let point = Coordinates::new(37.7749, -122.4194);
         
-        let result = service.find_nearest(&point, 1, None, None).await.unwrap();
+        let result = service.find_nearest(&point, 1, None, None, None).await.unwrap();
 
         assert_eq!(result.locations.len(), 1);
         assert_eq!(result.query.query_type, SpatialQueryType::Nearest);
     }
     
+    #[test]
+    fn test_page_token_round_trip() {
+        let token = PageToken {
+            location_id: Uuid::new_v4(),
+            distance_meters: 1234.5,
+        };
+        
+        let encoded = token.encode();
+        assert!(!encoded.contains('{'));
+        assert_eq!(PageToken::decode(&encoded).unwrap(), token);
+        
+        for malformed in ["not base64!", "bm90IGpzb24", ""] {
+            assert!(matches!(
+                PageToken::decode(malformed),
+                Err(SpatialSearchError::InvalidPageToken(_))
+            ));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_find_nearest_pages_through_all_locations() {
+        let mut service = MockSpatialSearchService::new().with_delay(0);
+        // A duplicate position ties on distance, so ordering falls back to the id
+        let mut twin = service.mock_locations[1].clone();
+        twin.location_id = Uuid::new_v4();
+        service.mock_locations.push(twin);
+        let point = Coordinates::new(37.7749, -122.4194);
+        
+        let all = service.find_nearest(&point, 10, None, None, None).await.unwrap();
+        assert_eq!(all.locations.len(), 3);
+        assert!(all.next_page_token.is_none());
+        
+        let mut paged = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let page = service
+                .find_nearest(&point, 1, None, None, token.as_deref())
+                .await
+                .unwrap();
+            paged.extend(page.locations.iter().map(|l| l.location_id));
+            // The count covers every match, not just this page
+            assert_eq!(page.total_count, 3);
+            assert_eq!(page.has_more_results, page.next_page_token.is_some());
+            match page.next_page_token {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+        
+        let expected: Vec<_> = all.locations.iter().map(|l| l.location_id).collect();
+        assert_eq!(paged, expected);
+        
+        let result = service.find_nearest(&point, 1, None, None, Some("garbage")).await;
+        assert!(matches!(result, Err(SpatialSearchError::InvalidPageToken(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_matches_carry_real_bearing() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        // Due south of "Mock Location 2"
+        let point = Coordinates::new(37.7749, -122.4094);
+        
+        let result = service.find_nearest(&point, 2, None, None, None).await.unwrap();
+        let north = result
+            .locations
+            .iter()
+            .find(|loc| loc.name.as_deref() == Some("Mock Location 2"))
+            .unwrap();
+        
+        let bearing = north.bearing_degrees.unwrap();
+        assert!(bearing < 1.0 || bearing > 359.0, "bearing {bearing} should point north");
+        assert!((north.distance_meters.unwrap() - 1_112.0).abs() < 5.0);
+    }
+    
     #[tokio::test]
     async fn test_spatial_statistics() {
         let service = MockSpatialSearchService::new();
@@ -626,6 +895,7 @@ mod tests {
             min_activity_score: None,
             verified_only: None,
             metadata_filters: None,
+            max_accuracy_meters: None,
         };
         
         let result = service.find_within_radius(&center, 1000.0, Some(filters)).await.unwrap();
@@ -636,4 +906,187 @@ mod tests {
             assert!(location.tags.contains(&"test".to_string()));
         }
     }
+    
+    fn square() -> SpatialRegion {
+        SpatialRegion::Polygon {
+            vertices: vec![
+                Coordinates::new(0.0, 0.0),
+                Coordinates::new(0.0, 1.0),
+                Coordinates::new(1.0, 1.0),
+                Coordinates::new(1.0, 0.0),
+            ],
+        }
+    }
+    
+    #[test]
+    fn test_polygon_contains_inside_point() {
+        assert!(square().contains(&Coordinates::new(0.5, 0.5)));
+        assert!(square().contains(&Coordinates::new(0.1, 0.9)));
+    }
+    
+    #[test]
+    fn test_polygon_excludes_outside_point() {
+        assert!(!square().contains(&Coordinates::new(1.5, 0.5)));
+        assert!(!square().contains(&Coordinates::new(0.5, -0.1)));
+        assert!(!square().contains(&Coordinates::new(-1.0, -1.0)));
+    }
+    
+    #[test]
+    fn test_polygon_boundary_counts_as_inside() {
+        assert!(square().contains(&Coordinates::new(0.0, 0.5)));
+        assert!(square().contains(&Coordinates::new(1.0, 0.25)));
+        assert!(square().contains(&Coordinates::new(1.0, 1.0)));
+    }
+    
+    #[test]
+    fn test_route_corridor_contains() {
+        let corridor = SpatialRegion::RouteCorRidor {
+            route_points: vec![Coordinates::new(0.0, 0.0), Coordinates::new(0.0, 1.0)],
+            corridor_width_meters: 1_000.0,
+        };
+        
+        // ~555m north of the route's midpoint
+        assert!(corridor.contains(&Coordinates::new(0.005, 0.5)));
+        // ~1.1km north, beyond the corridor width
+        assert!(!corridor.contains(&Coordinates::new(0.01, 0.5)));
+        // Past the end of the route
+        assert!(!corridor.contains(&Coordinates::new(0.0, 1.1)));
+    }
+    
+    #[tokio::test]
+    async fn test_find_along_route_filters_by_corridor() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        // West to east through "Mock Location 1"; "Mock Location 2" is ~1.1km north
+        let route = vec![
+            Coordinates::new(37.7749, -122.4400),
+            Coordinates::new(37.7749, -122.4300),
+            Coordinates::new(37.7752, -122.4000),
+        ];
+        
+        let result = service.find_along_route(&route, 500.0, None).await.unwrap();
+        
+        assert_eq!(result.query.query_type, SpatialQueryType::AlongRoute);
+        assert_eq!(result.locations.len(), 1);
+        assert_eq!(result.locations[0].name.as_deref(), Some("Mock Location 1"));
+        assert!(result.locations[0].distance_meters.unwrap() < 50.0);
+        
+        let wide = service.find_along_route(&route, 2_000.0, None).await.unwrap();
+        assert_eq!(wide.total_count, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_find_along_route_rejects_empty_route() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        
+        let result = service.find_along_route(&[], 500.0, None).await;
+        
+        assert!(matches!(result, Err(SpatialSearchError::InvalidCoordinates(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_spatial_statistics_counts_contained_locations() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        let around_first = SpatialRegion::Circle {
+            center: Coordinates::new(37.7749, -122.4194),
+            radius_meters: 500.0,
+        };
+        let elsewhere = SpatialRegion::BoundingBox {
+            southwest: Coordinates::new(40.0, -75.0),
+            northeast: Coordinates::new(41.0, -73.0),
+        };
+        
+        let result = service.get_spatial_statistics(&around_first, None).await.unwrap();
+        assert_eq!(result.total_locations, 1);
+        
+        let result = service.get_spatial_statistics(&elsewhere, None).await.unwrap();
+        assert_eq!(result.total_locations, 0);
+        assert!(result.location_type_breakdown.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_search_excludes_low_accuracy_fixes() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        let center = Coordinates::new(37.7749, -122.4194);
+        let filters = SpatialSearchFilters {
+            location_types: None,
+            tags: None,
+            categories: None,
+            owner_id: None,
+            created_after: None,
+            created_before: None,
+            min_activity_score: None,
+            verified_only: None,
+            metadata_filters: None,
+            max_accuracy_meters: Some(50.0),
+        };
+        
+        let unfiltered = service.find_within_radius(&center, 5000.0, None).await.unwrap();
+        assert_eq!(unfiltered.locations.len(), 2);
+        
+        let result = service.find_within_radius(&center, 5000.0, Some(filters)).await.unwrap();
+        assert_eq!(result.locations.len(), 1);
+        assert_eq!(result.locations[0].coordinates.accuracy_meters, Some(5.0));
+    }
+    
+    #[tokio::test]
+    async fn test_find_nearest_and_bounds_apply_filters() {
+        let service = MockSpatialSearchService::new().with_delay(0);
+        let point = Coordinates::new(37.7749, -122.4194);
+        let filters = SpatialSearchFilters {
+            location_types: None,
+            tags: None,
+            categories: None,
+            owner_id: None,
+            created_after: None,
+            created_before: None,
+            min_activity_score: None,
+            verified_only: None,
+            metadata_filters: None,
+            max_accuracy_meters: Some(50.0),
+        };
+        
+        let nearest = service
+            .find_nearest(&point, 1, None, Some(filters.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(nearest.locations.len(), 1);
+        assert_eq!(nearest.locations[0].coordinates.accuracy_meters, Some(5.0));
+        // The count covers only the matches passing the filters
+        assert_eq!(nearest.total_count, 1);
+        assert!(!nearest.has_more_results);
+        
+        // "Mock Location 2" is about 1.4 km away
+        let close = service.find_nearest(&point, 10, Some(500.0), None, None).await.unwrap();
+        assert_eq!(close.locations.len(), 1);
+        assert_eq!(close.total_count, 1);
+        assert!(close.locations[0].distance_meters.unwrap() <= 500.0);
+        
+        let southwest = Coordinates::new(37.7, -122.5);
+        let northeast = Coordinates::new(37.8, -122.4);
+        let bounded = service
+            .find_within_bounds(&southwest, &northeast, Some(filters))
+            .await
+            .unwrap();
+        assert_eq!(bounded.locations.len(), 1);
+        assert_eq!(bounded.total_count, 1);
+        assert_eq!(bounded.locations[0].coordinates.accuracy_meters, Some(5.0));
+    }
+    
+    #[test]
+    fn test_filters_without_accuracy_threshold_deserialize() {
+        let filters: SpatialSearchFilters = serde_json::from_value(serde_json::json!({
+            "location_types": null,
+            "tags": null,
+            "categories": null,
+            "owner_id": null,
+            "created_after": null,
+            "created_before": null,
+            "min_activity_score": null,
+            "verified_only": null,
+            "metadata_filters": null
+        }))
+        .unwrap();
+        
+        assert_eq!(filters.max_accuracy_meters, None);
+    }
 }
\ No newline at end of file
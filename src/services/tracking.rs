@@ -1,9 +1,10 @@
 //! Location tracking services
 
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::value_objects::Coordinates;
+use crate::value_objects::{BoundingBox, Coordinates, GeoCoordinates};
 
 #[async_trait]
 pub trait LocationTrackingService: Send + Sync {
@@ -28,6 +29,152 @@ pub struct VisitRecord {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Summary of a movement trail recorded as timestamped positions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrailSummary {
+    pub point_count: usize,
+    /// Sum of the distances between consecutive positions
+    pub total_distance_meters: f64,
+    /// Time covered by the trail, counting only forward time steps
+    pub duration_seconds: f64,
+    pub average_speed_mps: f64,
+    /// Fastest speed between two consecutive positions
+    pub max_speed_mps: f64,
+    /// Smallest box containing every position, `None` for an empty trail
+    pub bounds: Option<BoundingBox>,
+    /// Number of pauses: runs of consecutive positions within [`TrailSummary::STOP_RADIUS_METERS`]
+    pub stop_count: usize,
+}
+
+impl TrailSummary {
+    /// Consecutive positions closer than this are treated as stationary
+    pub const STOP_RADIUS_METERS: f64 = 25.0;
+
+    /// Summarize positions given in recording order
+    ///
+    /// Steps whose timestamp does not advance still add distance but are left out
+    /// of duration and speed, so duplicate or out-of-order fixes cannot produce
+    /// infinite or negative speeds.
+    pub fn from_positions(positions: &[(GeoCoordinates, DateTime<Utc>)]) -> Self {
+        let mut total_distance_meters = 0.0;
+        let mut duration_seconds = 0.0;
+        let mut max_speed_mps: f64 = 0.0;
+        let mut stop_count = 0;
+        let mut stationary = false;
+
+        for pair in positions.windows(2) {
+            let ((from, from_time), (to, to_time)) = (&pair[0], &pair[1]);
+            let distance = from.distance_to(to);
+            total_distance_meters += distance;
+
+            let seconds = (*to_time - *from_time).num_milliseconds() as f64 / 1000.0;
+            if seconds > 0.0 {
+                duration_seconds += seconds;
+                max_speed_mps = max_speed_mps.max(distance / seconds);
+            }
+
+            let within_stop_radius = distance <= Self::STOP_RADIUS_METERS;
+            if within_stop_radius && !stationary {
+                stop_count += 1;
+            }
+            stationary = within_stop_radius;
+        }
+
+        let average_speed_mps = if duration_seconds > 0.0 {
+            total_distance_meters / duration_seconds
+        } else {
+            0.0
+        };
+
+        Self {
+            point_count: positions.len(),
+            total_distance_meters,
+            duration_seconds,
+            average_speed_mps,
+            max_speed_mps,
+            bounds: trail_bounds(positions.iter().map(|(coordinates, _)| coordinates)),
+            stop_count,
+        }
+    }
+}
+
+fn trail_bounds<'a>(mut points: impl Iterator<Item = &'a GeoCoordinates>) -> Option<BoundingBox> {
+    let first = points.next()?;
+    let mut bounds = BoundingBox {
+        min_lat: first.latitude,
+        max_lat: first.latitude,
+        min_lon: first.longitude,
+        max_lon: first.longitude,
+    };
+    for point in points {
+        bounds.min_lat = bounds.min_lat.min(point.latitude);
+        bounds.max_lat = bounds.max_lat.max(point.latitude);
+        bounds.min_lon = bounds.min_lon.min(point.longitude);
+        bounds.max_lon = bounds.max_lon.max(point.longitude);
+    }
+    Some(bounds)
+}
+
+/// Time-decayed activity score of a location
+///
+/// Every check-in or visit contributes a weight of 1 that halves with each
+/// `half_life` elapsed since it happened, so recent activity dominates the
+/// score and a location nobody visits decays toward zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityTracker {
+    half_life: Duration,
+    visits: Vec<DateTime<Utc>>,
+}
+
+impl ActivityTracker {
+    /// Half-life used by [`ActivityTracker::default`]
+    pub const DEFAULT_HALF_LIFE_DAYS: i64 = 7;
+
+    /// Create a tracker whose activity halves in weight every `half_life`
+    ///
+    /// Half-lives shorter than one second are raised to one second.
+    pub fn new(half_life: Duration) -> Self {
+        Self {
+            half_life: half_life.max(Duration::seconds(1)),
+            visits: Vec::new(),
+        }
+    }
+
+    pub fn half_life(&self) -> Duration {
+        self.half_life
+    }
+
+    /// Record a check-in or visit at `at`
+    pub fn record_visit(&mut self, at: DateTime<Utc>) {
+        self.visits.push(at);
+    }
+
+    pub fn visit_count(&self) -> usize {
+        self.visits.len()
+    }
+
+    /// Activity score as of `now`
+    ///
+    /// Visits recorded after `now` are not counted.
+    pub fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        let half_life_seconds = self.half_life.num_milliseconds() as f64 / 1000.0;
+        self.visits
+            .iter()
+            .filter(|at| **at <= now)
+            .map(|at| {
+                let age_seconds = (now - *at).num_milliseconds() as f64 / 1000.0;
+                0.5_f64.powf(age_seconds / half_life_seconds)
+            })
+            .sum()
+    }
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self::new(Duration::days(Self::DEFAULT_HALF_LIFE_DAYS))
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum TrackingError {
     #[error("Tracking service unavailable")]
@@ -62,4 +209,122 @@ impl LocationTrackingService for MockLocationTrackingService {
             timestamp: chrono::Utc::now(),
         })
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    /// Eastward along the equator, 0.001° (~111 m) every 10 seconds
+    fn straight_trail(points: i64) -> Vec<(GeoCoordinates, DateTime<Utc>)> {
+        (0..points)
+            .map(|i| {
+                (
+                    GeoCoordinates::new(0.0, i as f64 * 0.001),
+                    start() + Duration::seconds(i * 10),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_straight_line_trail_distance_and_speed() {
+        let summary = TrailSummary::from_positions(&straight_trail(11));
+
+        // 0.01° of arc on the equator
+        let expected = 0.01_f64.to_radians() * 6_371_000.0;
+        assert_eq!(summary.point_count, 11);
+        assert!((summary.total_distance_meters - expected).abs() < 0.5);
+        assert_eq!(summary.duration_seconds, 100.0);
+        assert!((summary.average_speed_mps - expected / 100.0).abs() < 0.01);
+        assert!((summary.max_speed_mps - summary.average_speed_mps).abs() < 0.01);
+        assert_eq!(summary.stop_count, 0);
+
+        let bounds = summary.bounds.unwrap();
+        assert_eq!((bounds.min_lon, bounds.max_lon), (0.0, 0.01));
+        assert_eq!((bounds.min_lat, bounds.max_lat), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_trail_with_pause_counts_one_stop() {
+        let mut trail = straight_trail(3);
+        let parked = trail[2].0.clone();
+        for i in 1..=3 {
+            trail.push((parked.clone(), trail[2].1 + Duration::seconds(i * 60)));
+        }
+        let resume_at = trail.last().unwrap().1;
+        trail.push((
+            GeoCoordinates::new(0.0, 0.003),
+            resume_at + Duration::seconds(10),
+        ));
+
+        let summary = TrailSummary::from_positions(&trail);
+
+        assert_eq!(summary.stop_count, 1);
+        assert!(summary.average_speed_mps < summary.max_speed_mps);
+    }
+
+    #[test]
+    fn test_trail_ignores_non_advancing_timestamps() {
+        let mut trail = straight_trail(2);
+        // Same instant, and then a step back in time
+        trail.push((GeoCoordinates::new(0.0, 0.002), trail[1].1));
+        trail.push((GeoCoordinates::new(0.0, 0.003), start()));
+
+        let summary = TrailSummary::from_positions(&trail);
+
+        assert!(summary.max_speed_mps.is_finite());
+        assert_eq!(summary.duration_seconds, 10.0);
+        assert!((summary.total_distance_meters - 3.0 * 111.195).abs() < 1.0);
+
+        let empty = TrailSummary::from_positions(&[]);
+        assert_eq!(empty.point_count, 0);
+        assert_eq!(empty.average_speed_mps, 0.0);
+        assert!(empty.bounds.is_none());
+    }
+
+    #[test]
+    fn test_activity_score_weights_recent_visits_higher() {
+        let now = start() + Duration::days(30);
+        let mut recent = ActivityTracker::default();
+        let mut older = ActivityTracker::default();
+        for hour in 0..5 {
+            recent.record_visit(now - Duration::days(1) + Duration::hours(hour));
+            older.record_visit(now - Duration::days(20) + Duration::hours(hour));
+        }
+
+        assert_eq!(recent.visit_count(), older.visit_count());
+        assert!(recent.score_at(now) > older.score_at(now));
+        assert!(recent.score_at(now) < 5.0);
+
+        // A visit one half-life old counts for half
+        let mut single = ActivityTracker::new(Duration::hours(1));
+        single.record_visit(now - Duration::hours(1));
+        assert!((single.score_at(now) - 0.5).abs() < 1e-9);
+        // Visits after the scoring time do not count yet
+        assert_eq!(single.score_at(now - Duration::hours(2)), 0.0);
+    }
+
+    #[test]
+    fn test_activity_score_decays_toward_zero() {
+        let mut tracker = ActivityTracker::new(Duration::days(1));
+        for minute in 0..10 {
+            tracker.record_visit(start() + Duration::minutes(minute));
+        }
+
+        let last_visit = start() + Duration::minutes(9);
+        let scores: Vec<f64> = (0..=10)
+            .map(|days| tracker.score_at(last_visit + Duration::days(days)))
+            .collect();
+
+        assert!(scores.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!((scores[1] / scores[0] - 0.5).abs() < 0.01);
+        assert!(scores[10] < 10.0 / 1000.0);
+        assert_eq!(ActivityTracker::default().score_at(start()), 0.0);
+    }
 }
\ No newline at end of file
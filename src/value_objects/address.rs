@@ -1,7 +1,10 @@
 //! Physical address value object
 
 use cim_domain::{DomainError, DomainResult};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
 
 /// Physical address value object
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -25,6 +28,17 @@ pub struct Address {
     pub postal_code: String,
 }
 
+/// Purpose an address serves for a location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AddressRole {
+    /// Where the location physically is
+    Physical,
+    /// Where post for the location is delivered
+    Mailing,
+    /// Where invoices for the location are sent
+    Billing,
+}
+
 impl Address {
     /// Create a new address
     pub fn new(
@@ -44,6 +58,22 @@ impl Address {
         }
     }
 
+    /// Create a new address, validating it strictly
+    ///
+    /// In addition to the checks in [`Address::validate`], the postal code must
+    /// match the format for the address's country.
+    pub fn new_validated(
+        street1: String,
+        locality: String,
+        region: String,
+        country: String,
+        postal_code: String,
+    ) -> DomainResult<Self> {
+        let address = Self::new(street1, locality, region, country, postal_code);
+        address.validate_strict()?;
+        Ok(address)
+    }
+
     /// Add second street line
     pub fn with_street2(mut self, street2: String) -> Self {
         self.street2 = Some(street2);
@@ -83,13 +113,34 @@ impl Address {
         }
 
         // Additional validation could include:
-        // - Country-specific postal code formats
         // - Valid country codes
         // - Region validation based on country
 
         Ok(())
     }
 
+    /// Validate address invariants including the country-specific postal code format
+    pub fn validate_strict(&self) -> DomainResult<()> {
+        self.validate()?;
+        self.validate_postal_code()
+    }
+
+    /// Validate the postal code against the format used by the address's country
+    ///
+    /// Countries without a known format only need a plausible alphanumeric code.
+    pub fn validate_postal_code(&self) -> DomainResult<()> {
+        let format = PostalCodeFormat::for_country(&self.country);
+
+        if !format.regex().is_match(self.postal_code.trim()) {
+            return Err(DomainError::ValidationError(format!(
+                "Invalid postal code for {}: {}",
+                self.country, self.postal_code
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Format as single-line string
     pub fn format_single_line(&self) -> String {
         let mut parts = vec![self.street1.clone()];
@@ -104,6 +155,59 @@ impl Address {
         parts.join(", ")
     }
 
+    /// Parse an address produced by [`Address::format_single_line`]
+    ///
+    /// Expects `street1[, street2], locality, region postal_code, country`. The
+    /// postal code is taken to be the trailing words of the region group that
+    /// contain a digit, so `"New York 10001"` and `"BC V6B 1A1"` both split correctly.
+    pub fn parse_single_line(input: &str) -> DomainResult<Address> {
+        let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+
+        let (street1, street2, rest) = match parts.as_slice() {
+            [street1, rest @ ..] if rest.len() == 3 => (*street1, None, rest),
+            [street1, street2, rest @ ..] if rest.len() == 3 => (*street1, Some(*street2), rest),
+            _ => {
+                return Err(DomainError::ValidationError(format!(
+                    "Expected 'street, [street2,] city, region postal code, country' but found {} components in '{input}'",
+                    parts.len()
+                )));
+            }
+        };
+        let (locality, region_postal, country) = (rest[0], rest[1], rest[2]);
+
+        let words: Vec<&str> = region_postal.split_whitespace().collect();
+        let postal_start = words
+            .iter()
+            .rposition(|word| !word.chars().any(|c| c.is_ascii_digit()))
+            .map_or(0, |i| i + 1);
+        let (region, postal_code) = words.split_at(postal_start);
+
+        if postal_code.is_empty() {
+            return Err(DomainError::ValidationError(format!(
+                "Could not find a postal code in '{region_postal}'"
+            )));
+        }
+        if region.is_empty() {
+            return Err(DomainError::ValidationError(format!(
+                "Could not find a region before the postal code in '{region_postal}'"
+            )));
+        }
+
+        let mut address = Address::new(
+            street1.to_string(),
+            locality.to_string(),
+            region.join(" "),
+            country.to_string(),
+            postal_code.join(" "),
+        );
+        if let Some(street2) = street2.filter(|s| !s.is_empty()) {
+            address = address.with_street2(street2.to_string());
+        }
+
+        address.validate()?;
+        Ok(address)
+    }
+
     /// Format as multi-line string
     pub fn format_multi_line(&self) -> String {
         let mut lines = vec![self.street1.clone()];
@@ -117,4 +221,419 @@ impl Address {
 
         lines.join("\n")
     }
+
+    /// Canonical form of this address for comparison
+    ///
+    /// Whitespace is trimmed and collapsed, common US street suffixes and
+    /// directionals are abbreviated (`Street` → `St`, `North` → `N`), the locality
+    /// is title-cased and short region/country codes and postal codes are uppercased.
+    pub fn normalized(&self) -> Address {
+        Address {
+            street1: normalize_street(&self.street1),
+            street2: self
+                .street2
+                .as_deref()
+                .map(normalize_street)
+                .filter(|street2| !street2.is_empty()),
+            locality: title_case(&collapse_whitespace(&self.locality)),
+            region: normalize_code(&self.region),
+            country: normalize_code(&self.country),
+            postal_code: collapse_whitespace(&self.postal_code).to_uppercase(),
+        }
+    }
+
+    /// Stable key identifying this address for deduplication
+    ///
+    /// A hex SHA-256 of the case-folded normalized form, so differently written
+    /// versions of the same address share a fingerprint across processes.
+    pub fn fingerprint(&self) -> String {
+        hex::encode(Sha256::digest(self.canonical_form().as_bytes()))
+    }
+
+    /// Check whether two addresses denote the same place
+    ///
+    /// Compares the normalized forms case-insensitively, so street-suffix
+    /// abbreviations and spacing do not matter while street numbers, localities
+    /// and the other fields still must agree.
+    pub fn semantically_equals(&self, other: &Address) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+
+    /// Case-folded normalized fields joined by a unit separator
+    fn canonical_form(&self) -> String {
+        let normalized = self.normalized();
+        [
+            normalized.street1.as_str(),
+            normalized.street2.as_deref().unwrap_or(""),
+            normalized.locality.as_str(),
+            normalized.region.as_str(),
+            normalized.country.as_str(),
+            normalized.postal_code.as_str(),
+        ]
+        .join("\u{1f}")
+        .to_lowercase()
+    }
+}
+
+/// Abbreviations for US street suffixes, unit designators and directionals
+const STREET_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("street", "St"),
+    ("st", "St"),
+    ("avenue", "Ave"),
+    ("av", "Ave"),
+    ("ave", "Ave"),
+    ("boulevard", "Blvd"),
+    ("blvd", "Blvd"),
+    ("road", "Rd"),
+    ("rd", "Rd"),
+    ("drive", "Dr"),
+    ("dr", "Dr"),
+    ("lane", "Ln"),
+    ("ln", "Ln"),
+    ("court", "Ct"),
+    ("ct", "Ct"),
+    ("place", "Pl"),
+    ("pl", "Pl"),
+    ("terrace", "Ter"),
+    ("ter", "Ter"),
+    ("highway", "Hwy"),
+    ("hwy", "Hwy"),
+    ("parkway", "Pkwy"),
+    ("pkwy", "Pkwy"),
+    ("circle", "Cir"),
+    ("cir", "Cir"),
+    ("square", "Sq"),
+    ("sq", "Sq"),
+    ("suite", "Ste"),
+    ("ste", "Ste"),
+    ("apartment", "Apt"),
+    ("apt", "Apt"),
+    ("north", "N"),
+    ("south", "S"),
+    ("east", "E"),
+    ("west", "W"),
+    ("northeast", "NE"),
+    ("northwest", "NW"),
+    ("southeast", "SE"),
+    ("southwest", "SW"),
+    ("n", "N"),
+    ("s", "S"),
+    ("e", "E"),
+    ("w", "W"),
+    ("ne", "NE"),
+    ("nw", "NW"),
+    ("se", "SE"),
+    ("sw", "SW"),
+];
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Abbreviate known street words, dropping trailing periods and commas
+fn normalize_street(street: &str) -> String {
+    street
+        .split_whitespace()
+        .map(|word| {
+            let bare = word.trim_end_matches(['.', ',']);
+            let lower = bare.to_lowercase();
+            STREET_ABBREVIATIONS
+                .iter()
+                .find(|(long, _)| *long == lower)
+                .map_or_else(|| bare.to_string(), |(_, short)| short.to_string())
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Title-case each word, including hyphenated parts (`winston-salem` → `Winston-Salem`)
+fn title_case(value: &str) -> String {
+    value
+        .split(' ')
+        .map(|word| {
+            word.split('-')
+                .map(|part| {
+                    let mut chars = part.chars();
+                    match chars.next() {
+                        Some(first) => first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Uppercase short alphabetic codes (`il` → `IL`, `usa` → `USA`), title-case names
+fn normalize_code(value: &str) -> String {
+    let value = collapse_whitespace(value);
+    if value.len() <= 3 && value.chars().all(|c| c.is_ascii_alphabetic()) {
+        value.to_ascii_uppercase()
+    } else {
+        title_case(&value)
+    }
+}
+
+/// Postal code formats with country-specific rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PostalCodeFormat {
+    /// US ZIP or ZIP+4, e.g. `20500` or `20500-0003`
+    UnitedStates,
+    /// Canadian postal code, e.g. `K1A 0B1`
+    Canada,
+    /// UK postcode, e.g. `SW1A 1AA`
+    UnitedKingdom,
+    /// Any plausible alphanumeric code
+    Other,
+}
+
+impl PostalCodeFormat {
+    fn for_country(country: &str) -> Self {
+        match country.trim().to_uppercase().as_str() {
+            "US" | "USA" | "UNITED STATES" | "UNITED STATES OF AMERICA" => Self::UnitedStates,
+            "CA" | "CAN" | "CANADA" => Self::Canada,
+            "GB" | "GBR" | "UK" | "UNITED KINGDOM" | "GREAT BRITAIN" => Self::UnitedKingdom,
+            _ => Self::Other,
+        }
+    }
+
+    fn regex(self) -> &'static Regex {
+        static US: OnceLock<Regex> = OnceLock::new();
+        static CANADA: OnceLock<Regex> = OnceLock::new();
+        static UK: OnceLock<Regex> = OnceLock::new();
+        static OTHER: OnceLock<Regex> = OnceLock::new();
+
+        let (cell, pattern) = match self {
+            Self::UnitedStates => (&US, r"^\d{5}(-\d{4})?$"),
+            Self::Canada => (
+                &CANADA,
+                r"(?i)^[ABCEGHJ-NPRSTVXY]\d[ABCEGHJ-NPRSTV-Z] ?\d[ABCEGHJ-NPRSTV-Z]\d$",
+            ),
+            Self::UnitedKingdom => (&UK, r"(?i)^[A-Z]{1,2}\d[A-Z\d]? ?\d[A-Z]{2}$"),
+            Self::Other => (&OTHER, r"^[A-Za-z0-9][A-Za-z0-9 \-]{1,9}$"),
+        };
+
+        cell.get_or_init(|| Regex::new(pattern).expect("postal code pattern is valid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(country: &str, postal_code: &str) -> Address {
+        Address::new(
+            "1 Test Street".to_string(),
+            "Test City".to_string(),
+            "Test Region".to_string(),
+            country.to_string(),
+            postal_code.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_us_postal_codes() {
+        assert!(address("US", "20500").validate_postal_code().is_ok());
+        assert!(address("USA", "20500-0003").validate_postal_code().is_ok());
+        assert!(address("US", "ABC").validate_postal_code().is_err());
+        assert!(address("US", "2050").validate_postal_code().is_err());
+        assert!(address("USA", "20500-03").validate_postal_code().is_err());
+    }
+
+    #[test]
+    fn test_canadian_postal_codes() {
+        assert!(address("Canada", "K1A 0B1").validate_postal_code().is_ok());
+        assert!(address("CA", "k1a0b1").validate_postal_code().is_ok());
+        assert!(address("Canada", "12345").validate_postal_code().is_err());
+        // D, F, I, O, Q and U are never used
+        assert!(address("Canada", "D1A 0B1").validate_postal_code().is_err());
+    }
+
+    #[test]
+    fn test_uk_postal_codes() {
+        assert!(address("UK", "SW1A 1AA").validate_postal_code().is_ok());
+        assert!(address("GB", "M1 1AE").validate_postal_code().is_ok());
+        assert!(address("United Kingdom", "B338TH").validate_postal_code().is_ok());
+        assert!(address("UK", "12345").validate_postal_code().is_err());
+    }
+
+    #[test]
+    fn test_unknown_country_is_permissive() {
+        assert!(address("Germany", "10115").validate_postal_code().is_ok());
+        assert!(address("Pastland", "99999").validate_postal_code().is_ok());
+        assert!(address("Netherlands", "1012 AB").validate_postal_code().is_ok());
+        assert!(address("Pastland", "!").validate_postal_code().is_err());
+    }
+
+    #[test]
+    fn test_strict_validation_is_opt_in() {
+        // Lenient validation keeps accepting placeholder codes
+        assert!(address("US", "ABC").validate().is_ok());
+        assert!(address("US", "ABC").validate_strict().is_err());
+
+        assert!(Address::new_validated(
+            "1600 Pennsylvania Avenue NW".to_string(),
+            "Washington".to_string(),
+            "DC".to_string(),
+            "USA".to_string(),
+            "20500".to_string(),
+        )
+        .is_ok());
+        assert!(Address::new_validated(
+            "1600 Pennsylvania Avenue NW".to_string(),
+            "Washington".to_string(),
+            "DC".to_string(),
+            "USA".to_string(),
+            "ABC".to_string(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_single_line() {
+        let address = Address::parse_single_line("123 Main St, Springfield, IL 62701, USA").unwrap();
+
+        assert_eq!(address.street1, "123 Main St");
+        assert_eq!(address.street2, None);
+        assert_eq!(address.locality, "Springfield");
+        assert_eq!(address.region, "IL");
+        assert_eq!(address.postal_code, "62701");
+        assert_eq!(address.country, "USA");
+    }
+
+    #[test]
+    fn test_parse_single_line_round_trip() {
+        let addresses = vec![
+            Address::new(
+                "1600 Pennsylvania Avenue NW".to_string(),
+                "Washington".to_string(),
+                "DC".to_string(),
+                "USA".to_string(),
+                "20500-0003".to_string(),
+            ),
+            Address::new(
+                "350 Fifth Avenue".to_string(),
+                "New York".to_string(),
+                "New York".to_string(),
+                "US".to_string(),
+                "10118".to_string(),
+            )
+            .with_street2("Suite 3200".to_string()),
+            Address::new(
+                "10 Downing Street".to_string(),
+                "London".to_string(),
+                "Greater London".to_string(),
+                "UK".to_string(),
+                "SW1A 2AA".to_string(),
+            ),
+            Address::new(
+                "800 Robson St".to_string(),
+                "Vancouver".to_string(),
+                "British Columbia".to_string(),
+                "Canada".to_string(),
+                "V6Z 3B7".to_string(),
+            )
+            .with_street2("Unit 4".to_string()),
+        ];
+
+        for address in addresses {
+            let parsed = Address::parse_single_line(&address.format_single_line()).unwrap();
+            assert_eq!(parsed, address);
+        }
+    }
+
+    #[test]
+    fn test_parse_single_line_errors() {
+        // Too few components
+        let err = Address::parse_single_line("123 Main St, Springfield").unwrap_err();
+        assert!(err.to_string().contains("components"));
+
+        // No postal code
+        let err = Address::parse_single_line("123 Main St, Springfield, IL, USA").unwrap_err();
+        assert!(err.to_string().contains("postal code"));
+
+        // No region
+        let err = Address::parse_single_line("123 Main St, Springfield, 62701, USA").unwrap_err();
+        assert!(err.to_string().contains("region"));
+
+        // Missing street
+        assert!(Address::parse_single_line(", Springfield, IL 62701, USA").is_err());
+    }
+
+    #[test]
+    fn test_normalized_canonicalizes_fields() {
+        let address = Address::new(
+            "  1600  pennsylvania avenue northwest ".to_string(),
+            "winston-salem".to_string(),
+            " nc".to_string(),
+            "usa".to_string(),
+            " 27101 ".to_string(),
+        )
+        .with_street2("suite 200".to_string())
+        .normalized();
+
+        assert_eq!(address.street1, "1600 pennsylvania Ave NW");
+        assert_eq!(address.street2.as_deref(), Some("Ste 200"));
+        assert_eq!(address.locality, "Winston-Salem");
+        assert_eq!(address.region, "NC");
+        assert_eq!(address.country, "USA");
+        assert_eq!(address.postal_code, "27101");
+    }
+
+    #[test]
+    fn test_fingerprint_matches_equivalent_addresses() {
+        let formal = Address::new(
+            "123 Main Street".to_string(),
+            "Springfield".to_string(),
+            "IL".to_string(),
+            "USA".to_string(),
+            "62701".to_string(),
+        );
+        let informal = Address::new(
+            " 123   MAIN st. ".to_string(),
+            "springfield".to_string(),
+            "il".to_string(),
+            "usa".to_string(),
+            "62701".to_string(),
+        );
+        let different = Address::new(
+            "125 Main Street".to_string(),
+            "Springfield".to_string(),
+            "IL".to_string(),
+            "USA".to_string(),
+            "62701".to_string(),
+        );
+
+        assert_eq!(formal.fingerprint(), informal.fingerprint());
+        assert_eq!(formal.fingerprint(), formal.normalized().fingerprint());
+        assert_ne!(formal.fingerprint(), different.fingerprint());
+        assert_eq!(formal.fingerprint().len(), 64);
+    }
+
+    #[test]
+    fn test_semantically_equals_ignores_formatting() {
+        let address = |street: &str, locality: &str| {
+            Address::new(
+                street.to_string(),
+                locality.to_string(),
+                "IL".to_string(),
+                "USA".to_string(),
+                "62701".to_string(),
+            )
+        };
+        let main_st = address("123 Main St", "Springfield");
+
+        assert!(main_st.semantically_equals(&address("123 MAIN STREET", "Springfield")));
+        assert!(main_st.semantically_equals(&address("  123  main st ", "SPRINGFIELD")));
+        assert_ne!(main_st, address("123 MAIN STREET", "Springfield"));
+
+        assert!(!main_st.semantically_equals(&address("124 Main St", "Springfield")));
+        assert!(!main_st.semantically_equals(&address("123 Main St", "Shelbyville")));
+    }
 }
@@ -0,0 +1,476 @@
+//! Region boundary value object
+
+use super::GeoCoordinates;
+use cim_domain::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius used for the local projection
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Polygon boundary of a region, stored as a closed ring of coordinates
+///
+/// The first and last points of a valid ring are equal. Area and centroid are
+/// computed on a local equirectangular projection, which is accurate for regions
+/// up to a few hundred kilometers across that do not cross the antimeridian.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Boundary {
+    pub ring: Vec<GeoCoordinates>,
+}
+
+impl Boundary {
+    /// Create a boundary from a ring of points
+    pub fn new(ring: Vec<GeoCoordinates>) -> Self {
+        Self { ring }
+    }
+
+    /// Create a boundary from polygon vertices, closing the ring if needed
+    pub fn from_vertices(mut vertices: Vec<GeoCoordinates>) -> Self {
+        if let (Some(first), Some(last)) = (vertices.first(), vertices.last()) {
+            if !same_point(first, last) {
+                vertices.push(first.clone());
+            }
+        }
+        Self::new(vertices)
+    }
+
+    /// Validate the ring
+    ///
+    /// The ring must be closed, have at least three distinct vertices, contain
+    /// only valid coordinates and not intersect itself.
+    pub fn validate(&self) -> DomainResult<()> {
+        for point in &self.ring {
+            point.validate()?;
+        }
+
+        match (self.ring.first(), self.ring.last()) {
+            (Some(first), Some(last)) if self.ring.len() > 1 && same_point(first, last) => {}
+            _ => {
+                return Err(DomainError::ValidationError(
+                    "Boundary ring must be closed (first point equal to last)".to_string(),
+                ))
+            }
+        }
+
+        let vertices = self.vertices();
+        if vertices.len() < 3 {
+            return Err(DomainError::ValidationError(format!(
+                "Boundary needs at least 3 vertices, found {}",
+                vertices.len()
+            )));
+        }
+
+        if self.is_self_intersecting() {
+            return Err(DomainError::ValidationError(
+                "Boundary ring intersects itself".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Polygon vertices, without the closing point
+    pub fn vertices(&self) -> &[GeoCoordinates] {
+        match (self.ring.first(), self.ring.last()) {
+            (Some(first), Some(last)) if self.ring.len() > 1 && same_point(first, last) => {
+                &self.ring[..self.ring.len() - 1]
+            }
+            _ => &self.ring,
+        }
+    }
+
+    /// Check whether a point lies inside the boundary
+    ///
+    /// Points on an edge or vertex count as inside.
+    pub fn contains(&self, point: &GeoCoordinates) -> bool {
+        polygon_contains(self.vertices(), point)
+    }
+
+    /// Enclosed area in square meters
+    pub fn area_sq_meters(&self) -> f64 {
+        self.signed_projected_area().abs()
+    }
+
+    /// Area-weighted centroid of the enclosed region
+    ///
+    /// Degenerate rings with no area fall back to the mean of their vertices.
+    pub fn centroid(&self) -> Option<GeoCoordinates> {
+        let vertices = self.vertices();
+        let origin = vertices.first()?;
+        let projected = project(vertices, origin);
+        let area = self.signed_projected_area();
+
+        if area.abs() < f64::EPSILON {
+            let n = vertices.len() as f64;
+            let latitude = vertices.iter().map(|v| v.latitude).sum::<f64>() / n;
+            let longitude = vertices.iter().map(|v| v.longitude).sum::<f64>() / n;
+            return Some(GeoCoordinates::new(latitude, longitude));
+        }
+
+        let (mut cx, mut cy) = (0.0, 0.0);
+        for (i, &(x1, y1)) in projected.iter().enumerate() {
+            let (x2, y2) = projected[(i + 1) % projected.len()];
+            let cross = x1 * y2 - x2 * y1;
+            cx += (x1 + x2) * cross;
+            cy += (y1 + y2) * cross;
+        }
+        let (x, y) = (cx / (6.0 * area), cy / (6.0 * area));
+
+        Some(GeoCoordinates::new(
+            origin.latitude + (y / EARTH_RADIUS_M).to_degrees(),
+            origin.longitude
+                + (x / (EARTH_RADIUS_M * origin.latitude.to_radians().cos())).to_degrees(),
+        ))
+    }
+
+    /// Reduce the number of vertices with the Ramer–Douglas–Peucker algorithm
+    ///
+    /// Vertices within `tolerance_meters` of the simplified outline are
+    /// dropped, measuring perpendicular distances on the local projection. The
+    /// ring is split at the vertex farthest from the first one and both halves
+    /// are simplified as open polylines, so the result stays closed and keeps
+    /// at least three vertices.
+    pub fn simplify(&self, tolerance_meters: f64) -> Boundary {
+        let vertices = self.vertices();
+        let n = vertices.len();
+        if n <= 3 {
+            return self.clone();
+        }
+
+        // The closing point is appended so the second half ends on index `n`
+        let mut projected = project(vertices, &vertices[0]);
+        projected.push(projected[0]);
+        let far = (1..n)
+            .max_by(|&a, &b| {
+                let (ax, ay) = projected[a];
+                let (bx, by) = projected[b];
+                ax.hypot(ay).total_cmp(&bx.hypot(by))
+            })
+            .unwrap_or(1);
+
+        let mut keep = vec![false; n + 1];
+        keep[0] = true;
+        keep[far] = true;
+        keep[n] = true;
+        let mut spans = vec![(0, far), (far, n)];
+        while let Some((start, end)) = spans.pop() {
+            if let Some((index, distance)) = farthest_from_segment(&projected, start, end) {
+                if distance > tolerance_meters {
+                    keep[index] = true;
+                    spans.push((start, index));
+                    spans.push((index, end));
+                }
+            }
+        }
+
+        // A tolerance wider than the region would collapse it to a line
+        if keep[..n].iter().filter(|&&kept| kept).count() < 3 {
+            let halves = [
+                farthest_from_segment(&projected, 0, far),
+                farthest_from_segment(&projected, far, n),
+            ];
+            if let Some((index, _)) = halves
+                .into_iter()
+                .flatten()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+            {
+                keep[index] = true;
+            }
+        }
+
+        Boundary::from_vertices(
+            (0..n)
+                .filter(|&i| keep[i])
+                .map(|i| vertices[i].clone())
+                .collect(),
+        )
+    }
+
+    /// Shoelace area on the local projection; positive for counter-clockwise rings
+    fn signed_projected_area(&self) -> f64 {
+        let vertices = self.vertices();
+        let Some(origin) = vertices.first() else {
+            return 0.0;
+        };
+        let projected = project(vertices, origin);
+
+        projected
+            .iter()
+            .enumerate()
+            .map(|(i, &(x1, y1))| {
+                let (x2, y2) = projected[(i + 1) % projected.len()];
+                x1 * y2 - x2 * y1
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// Check whether any two non-adjacent edges cross
+    fn is_self_intersecting(&self) -> bool {
+        let vertices = self.vertices();
+        let n = vertices.len();
+        let edge = |i: usize| (&vertices[i], &vertices[(i + 1) % n]);
+
+        (0..n).any(|i| {
+            (i + 1..n)
+                // Adjacent edges share a vertex, including the last and first edge
+                .filter(|&j| j != i + 1 && !(i == 0 && j == n - 1))
+                .any(|j| segments_intersect(edge(i), edge(j)))
+        })
+    }
+}
+
+/// Ray-casting point-in-polygon test, treating the boundary as inside
+///
+/// Polygons are tested in the longitude/latitude plane.
+pub(crate) fn polygon_contains(vertices: &[GeoCoordinates], point: &GeoCoordinates) -> bool {
+    const EPSILON: f64 = 1e-12;
+
+    if vertices.len() < 3 {
+        return false;
+    }
+
+    let (x, y) = (point.longitude, point.latitude);
+    let mut inside = false;
+
+    for i in 0..vertices.len() {
+        let a = &vertices[i];
+        let b = &vertices[(i + 1) % vertices.len()];
+        let (x1, y1, x2, y2) = (a.longitude, a.latitude, b.longitude, b.latitude);
+
+        // On the edge: collinear and within the segment's extent
+        let cross = (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1);
+        if cross.abs() <= EPSILON
+            && x >= x1.min(x2) - EPSILON
+            && x <= x1.max(x2) + EPSILON
+            && y >= y1.min(y2) - EPSILON
+            && y <= y1.max(y2) + EPSILON
+        {
+            return true;
+        }
+
+        if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+fn same_point(a: &GeoCoordinates, b: &GeoCoordinates) -> bool {
+    a.latitude == b.latitude && a.longitude == b.longitude
+}
+
+/// Project points onto a plane in meters centred on `origin`
+fn project(points: &[GeoCoordinates], origin: &GeoCoordinates) -> Vec<(f64, f64)> {
+    let cos_lat = origin.latitude.to_radians().cos();
+    points
+        .iter()
+        .map(|p| {
+            (
+                (p.longitude - origin.longitude).to_radians() * cos_lat * EARTH_RADIUS_M,
+                (p.latitude - origin.latitude).to_radians() * EARTH_RADIUS_M,
+            )
+        })
+        .collect()
+}
+
+/// Interior point of `points[start..=end]` farthest from the segment between its ends
+fn farthest_from_segment(points: &[(f64, f64)], start: usize, end: usize) -> Option<(usize, f64)> {
+    (start + 1..end)
+        .map(|i| (i, segment_distance(points[i], points[start], points[end])))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Distance in the plane from `p` to the segment `a`-`b`
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+/// Proper or touching intersection of two segments in the longitude/latitude plane
+fn segments_intersect(
+    (a1, a2): (&GeoCoordinates, &GeoCoordinates),
+    (b1, b2): (&GeoCoordinates, &GeoCoordinates),
+) -> bool {
+    let orientation = |p: &GeoCoordinates, q: &GeoCoordinates, r: &GeoCoordinates| {
+        (q.longitude - p.longitude) * (r.latitude - p.latitude)
+            - (q.latitude - p.latitude) * (r.longitude - p.longitude)
+    };
+    let on_segment = |p: &GeoCoordinates, q: &GeoCoordinates, r: &GeoCoordinates| {
+        r.longitude >= p.longitude.min(q.longitude)
+            && r.longitude <= p.longitude.max(q.longitude)
+            && r.latitude >= p.latitude.min(q.latitude)
+            && r.latitude <= p.latitude.max(q.latitude)
+    };
+
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(b1, b2, a1))
+        || (d2 == 0.0 && on_segment(b1, b2, a2))
+        || (d3 == 0.0 && on_segment(a1, a2, b1))
+        || (d4 == 0.0 && on_segment(a1, a2, b2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 0.01° square with its south-west corner at (lat, lon)
+    fn square(lat: f64, lon: f64) -> Boundary {
+        Boundary::from_vertices(vec![
+            GeoCoordinates::new(lat, lon),
+            GeoCoordinates::new(lat, lon + 0.01),
+            GeoCoordinates::new(lat + 0.01, lon + 0.01),
+            GeoCoordinates::new(lat + 0.01, lon),
+        ])
+    }
+
+    #[test]
+    fn test_square_contains_inside_points_only() {
+        let boundary = square(0.0, 0.0);
+        assert!(boundary.validate().is_ok());
+        assert_eq!(boundary.ring.len(), 5);
+
+        assert!(boundary.contains(&GeoCoordinates::new(0.005, 0.005)));
+        assert!(boundary.contains(&GeoCoordinates::new(0.0, 0.005))); // on an edge
+        assert!(!boundary.contains(&GeoCoordinates::new(0.02, 0.005)));
+        assert!(!boundary.contains(&GeoCoordinates::new(0.005, -0.001)));
+    }
+
+    #[test]
+    fn test_square_area_and_centroid() {
+        // 0.01° of arc is ~1111.95 m on the sphere
+        let side = 0.01_f64.to_radians() * EARTH_RADIUS_M;
+
+        let equatorial = square(0.0, 0.0);
+        let expected = side * side;
+        assert!((equatorial.area_sq_meters() - expected).abs() / expected < 0.001);
+
+        // Meridians converge, so the same square in degrees shrinks with latitude
+        let northern = square(45.0, 7.0);
+        let expected = side * side * 45.005_f64.to_radians().cos();
+        assert!((northern.area_sq_meters() - expected).abs() / expected < 0.001);
+
+        let centroid = northern.centroid().unwrap();
+        assert!((centroid.latitude - 45.005).abs() < 1e-4);
+        assert!((centroid.longitude - 7.005).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_rings() {
+        let open = Boundary::new(square(0.0, 0.0).vertices().to_vec());
+        assert!(open.validate().is_err());
+
+        let too_small = Boundary::from_vertices(vec![
+            GeoCoordinates::new(0.0, 0.0),
+            GeoCoordinates::new(0.0, 1.0),
+        ]);
+        assert!(too_small.validate().is_err());
+
+        // Bow tie: the first and third edges cross
+        let bow_tie = Boundary::from_vertices(vec![
+            GeoCoordinates::new(0.0, 0.0),
+            GeoCoordinates::new(1.0, 1.0),
+            GeoCoordinates::new(1.0, 0.0),
+            GeoCoordinates::new(0.0, 1.0),
+        ]);
+        assert!(bow_tie.validate().is_err());
+    }
+
+    #[test]
+    fn test_simplify_dense_square_to_corners() {
+        // 100 points per side, each nudged up to ~0.1 m off the straight edge
+        let corners = [(0.0, 0.0), (0.0, 0.01), (0.01, 0.01), (0.01, 0.0)];
+        let mut vertices = Vec::new();
+        for (side, &(lat1, lon1)) in corners.iter().enumerate() {
+            let (lat2, lon2) = corners[(side + 1) % corners.len()];
+            for step in 0..100 {
+                let t = step as f64 / 100.0;
+                let jitter = if step == 0 {
+                    0.0
+                } else {
+                    (step % 3) as f64 * 1e-6 - 1e-6
+                };
+                vertices.push(GeoCoordinates::new(
+                    lat1 + (lat2 - lat1) * t + jitter,
+                    lon1 + (lon2 - lon1) * t + jitter,
+                ));
+            }
+        }
+        let dense = Boundary::from_vertices(vertices);
+        assert_eq!(dense.vertices().len(), 400);
+
+        let simplified = dense.simplify(1.0);
+
+        assert!(simplified.validate().is_ok());
+        assert_eq!(simplified.vertices().len(), 4);
+        for (lat, lon) in corners {
+            assert!(simplified
+                .vertices()
+                .iter()
+                .any(|v| v.latitude == lat && v.longitude == lon));
+        }
+        assert!((simplified.area_sq_meters() - dense.area_sq_meters()).abs() < 1_000.0);
+    }
+
+    #[test]
+    fn test_simplify_keeps_containment_away_from_edges() {
+        // A 1 km radius circle drawn with 2000 vertices
+        let center = GeoCoordinates::new(51.5, -0.12);
+        let offset = |radius: f64, angle: f64| {
+            GeoCoordinates::new(
+                center.latitude + (radius * angle.cos() / EARTH_RADIUS_M).to_degrees(),
+                center.longitude
+                    + (radius * angle.sin()
+                        / (EARTH_RADIUS_M * center.latitude.to_radians().cos()))
+                    .to_degrees(),
+            )
+        };
+        let dense = Boundary::from_vertices(
+            (0..2000)
+                .map(|i| offset(1_000.0, i as f64 / 2000.0 * std::f64::consts::TAU))
+                .collect(),
+        );
+
+        let tolerance = 5.0;
+        let simplified = dense.simplify(tolerance);
+
+        assert!(simplified.validate().is_ok());
+        assert!(simplified.vertices().len() < 100);
+        for i in 0..36 {
+            let angle = i as f64 / 36.0 * std::f64::consts::TAU;
+            for radius in [
+                0.0,
+                500.0,
+                1_000.0 - 2.0 * tolerance,
+                1_000.0 + 2.0 * tolerance,
+            ] {
+                let point = offset(radius, angle);
+                assert_eq!(simplified.contains(&point), dense.contains(&point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_simplify_never_collapses_below_a_triangle() {
+        let simplified = square(0.0, 0.0).simplify(1_000_000.0);
+
+        assert!(simplified.validate().is_ok());
+        assert_eq!(simplified.vertices().len(), 3);
+    }
+}
@@ -1,7 +1,11 @@
 //! Geographic coordinates value object
 
+use chrono::{DateTime, Utc};
 use cim_domain::{DomainError, DomainResult};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 /// Geographic coordinates value object
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -17,6 +21,10 @@ pub struct GeoCoordinates {
 
     /// Coordinate system (default: WGS84)
     pub coordinate_system: String,
+
+    /// Horizontal accuracy radius in meters, as reported by GPS fixes (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accuracy_meters: Option<f64>,
 }
 
 impl GeoCoordinates {
@@ -27,6 +35,7 @@ impl GeoCoordinates {
             longitude,
             altitude: None,
             coordinate_system: "WGS84".to_string(),
+            accuracy_meters: None,
         }
     }
 
@@ -36,6 +45,12 @@ impl GeoCoordinates {
         self
     }
 
+    /// Add horizontal accuracy
+    pub fn with_accuracy(mut self, accuracy_meters: f64) -> Self {
+        self.accuracy_meters = Some(accuracy_meters);
+        self
+    }
+
     /// Use different coordinate system
     pub fn with_coordinate_system(mut self, system: String) -> Self {
         self.coordinate_system = system;
@@ -60,9 +75,15 @@ impl GeoCoordinates {
     }
 
     /// Calculate distance to another point (in meters, using Haversine formula)
+    ///
+    /// Uses Earth's mean radius; see [`distance_to_with_model`](Self::distance_to_with_model)
+    /// for other spheres.
     pub fn distance_to(&self, other: &GeoCoordinates) -> f64 {
-        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+        self.distance_to_with_model(other, DistanceModel::default())
+    }
 
+    /// Calculate distance to another point on a sphere of the given model (in meters)
+    pub fn distance_to_with_model(&self, other: &GeoCoordinates, model: DistanceModel) -> f64 {
         let lat1 = self.latitude.to_radians();
         let lat2 = other.latitude.to_radians();
         let delta_lat = (other.latitude - self.latitude).to_radians();
@@ -72,7 +93,141 @@ impl GeoCoordinates {
             + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
         let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
 
-        EARTH_RADIUS_M * c
+        model.radius_meters * c
+    }
+
+    /// Calculate straight-line distance to another point including altitude (in meters)
+    ///
+    /// Combines the [`distance_to`](Self::distance_to) surface distance with the
+    /// altitude difference. A missing altitude is treated as 0.
+    pub fn distance_3d_to(&self, other: &GeoCoordinates) -> f64 {
+        let horizontal = self.distance_to(other);
+        let vertical = other.altitude.unwrap_or(0.0) - self.altitude.unwrap_or(0.0);
+
+        horizontal.hypot(vertical)
+    }
+
+    /// Calculate shortest distance to the segment from `start` to `end` (in meters)
+    ///
+    /// The segment is projected onto a local equirectangular plane centred on this
+    /// point, which is accurate for segments up to a few hundred kilometers.
+    pub fn distance_to_segment(&self, start: &GeoCoordinates, end: &GeoCoordinates) -> f64 {
+        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+        let cos_lat = self.latitude.to_radians().cos();
+        let project = |c: &GeoCoordinates| {
+            (
+                (c.longitude - self.longitude).to_radians() * cos_lat * EARTH_RADIUS_M,
+                (c.latitude - self.latitude).to_radians() * EARTH_RADIUS_M,
+            )
+        };
+
+        let (ax, ay) = project(start);
+        let (bx, by) = project(end);
+        let (dx, dy) = (bx - ax, by - ay);
+        let length_sq = dx * dx + dy * dy;
+
+        // Position of the closest point along the segment, clamped to its ends
+        let t = if length_sq == 0.0 {
+            0.0
+        } else {
+            (-(ax * dx + ay * dy) / length_sq).clamp(0.0, 1.0)
+        };
+
+        (ax + t * dx).hypot(ay + t * dy)
+    }
+
+    /// Calculate shortest distance to a polyline (in meters)
+    ///
+    /// Returns `None` for an empty polyline; a single point is measured directly.
+    pub fn distance_to_polyline(&self, points: &[GeoCoordinates]) -> Option<f64> {
+        match points {
+            [] => None,
+            [only] => Some(self.distance_to(only)),
+            _ => points
+                .windows(2)
+                .map(|segment| self.distance_to_segment(&segment[0], &segment[1]))
+                .reduce(f64::min),
+        }
+    }
+
+    /// Calculate distance to another point (in meters, using Vincenty's formula on WGS-84)
+    ///
+    /// More accurate than [`distance_to`](Self::distance_to) over long distances, but
+    /// iterative and slower. Falls back to the spherical result for nearly antipodal
+    /// points where the iteration does not converge.
+    pub fn distance_to_vincenty(&self, other: &GeoCoordinates) -> f64 {
+        self.vincenty_inverse(other)
+            .unwrap_or_else(|| self.distance_to(other))
+    }
+
+    /// Vincenty's inverse formula, `None` if it fails to converge
+    fn vincenty_inverse(&self, other: &GeoCoordinates) -> Option<f64> {
+        const A: f64 = 6_378_137.0; // WGS-84 semi-major axis
+        const F: f64 = 1.0 / 298.257_223_563; // WGS-84 flattening
+        const B: f64 = (1.0 - F) * A;
+        const MAX_ITERATIONS: usize = 200;
+        const TOLERANCE: f64 = 1e-12;
+
+        let l = (other.longitude - self.longitude).to_radians();
+        let u1 = ((1.0 - F) * self.latitude.to_radians().tan()).atan();
+        let u2 = ((1.0 - F) * other.latitude.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..MAX_ITERATIONS {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                // Coincident points
+                return Some(0.0);
+            }
+
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+            // Equatorial lines have cos²α = 0
+            let cos_2sigma_m = if cos_sq_alpha != 0.0 {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            } else {
+                0.0
+            };
+            let c = F / 16.0 * cos_sq_alpha * (4.0 + F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            if (lambda - previous).abs() < TOLERANCE {
+                let u_sq = cos_sq_alpha * (A * A - B * B) / (B * B);
+                let big_a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                    * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+                return Some(B * big_a * (sigma - delta_sigma));
+            }
+        }
+
+        None
     }
 
     /// Calculate bearing to another point (in degrees, 0-360)
@@ -91,7 +246,32 @@ impl GeoCoordinates {
         (bearing_deg + 360.0) % 360.0
     }
 
-    /// Get a bounding box around this point
+    /// Calculate the geographic midpoint along the great circle to another point
+    pub fn midpoint(&self, other: &GeoCoordinates) -> GeoCoordinates {
+        let lat1 = self.latitude.to_radians();
+        let lon1 = self.longitude.to_radians();
+        let lat2 = other.latitude.to_radians();
+        let delta_lon = (other.longitude - self.longitude).to_radians();
+
+        let bx = lat2.cos() * delta_lon.cos();
+        let by = lat2.cos() * delta_lon.sin();
+
+        let lat_mid =
+            (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by.powi(2)).sqrt());
+        let lon_mid = lon1 + by.atan2(lat1.cos() + bx);
+
+        // Normalize longitude to -180..180
+        let lon_deg = (lon_mid.to_degrees() + 540.0) % 360.0 - 180.0;
+
+        GeoCoordinates::new(lat_mid.to_degrees(), lon_deg)
+            .with_coordinate_system(self.coordinate_system.clone())
+    }
+
+    /// Get the smallest bounding box containing the circle of `radius_meters` around this point
+    ///
+    /// Latitudes are clamped to [-90, 90]. When the circle reaches a pole the box spans
+    /// every longitude. When it crosses the antimeridian the returned box has
+    /// `min_lon > max_lon`; see [`BoundingBox::crosses_antimeridian`].
     pub fn bounding_box(&self, radius_meters: f64) -> BoundingBox {
         const EARTH_RADIUS_M: f64 = 6_371_000.0;
 
@@ -102,22 +282,549 @@ impl GeoCoordinates {
         let min_lat = self.latitude - angular_distance.to_degrees();
         let max_lat = self.latitude + angular_distance.to_degrees();
 
-        // Calculate longitude bounds (accounting for latitude)
+        // A circle that reaches a pole covers every longitude
+        if min_lat <= -90.0 || max_lat >= 90.0 {
+            return BoundingBox {
+                min_lat: min_lat.max(-90.0),
+                max_lat: max_lat.min(90.0),
+                min_lon: -180.0,
+                max_lon: 180.0,
+            };
+        }
+
+        // Calculate longitude bounds (longitude lines converge with cos(latitude))
         let lat_rad = self.latitude.to_radians();
-        let delta_lon = (angular_distance / lat_rad.cos()).to_degrees();
-        let min_lon = self.longitude - delta_lon;
-        let max_lon = self.longitude + delta_lon;
+        let ratio = angular_distance.sin() / lat_rad.cos();
+        if ratio >= 1.0 {
+            return BoundingBox {
+                min_lat,
+                max_lat,
+                min_lon: -180.0,
+                max_lon: 180.0,
+            };
+        }
+
+        let delta_lon = ratio.asin().to_degrees();
+        if delta_lon >= 180.0 {
+            return BoundingBox {
+                min_lat,
+                max_lat,
+                min_lon: -180.0,
+                max_lon: 180.0,
+            };
+        }
 
         BoundingBox {
             min_lat,
             max_lat,
-            min_lon,
-            max_lon,
+            min_lon: normalize_longitude(self.longitude - delta_lon),
+            max_lon: normalize_longitude(self.longitude + delta_lon),
+        }
+    }
+
+    /// Hide the exact position by moving it to a random point within `radius_meters`
+    ///
+    /// The bearing is uniform and the offset is uniform over the area of the circle,
+    /// so the true position is not more likely to be near the center. Altitude and
+    /// accuracy are dropped, as either could narrow the position down again.
+    pub fn fuzz(&self, radius_meters: f64, rng: &mut impl Rng) -> GeoCoordinates {
+        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+        let bearing = rng.gen_range(0.0..std::f64::consts::TAU);
+        let distance = radius_meters.max(0.0) * rng.gen::<f64>().sqrt();
+        let angular_distance = distance / EARTH_RADIUS_M;
+
+        let lat1 = self.latitude.to_radians();
+        let lon1 = self.longitude.to_radians();
+        let lat2 = (lat1.sin() * angular_distance.cos()
+            + lat1.cos() * angular_distance.sin() * bearing.cos())
+        .clamp(-1.0, 1.0)
+        .asin();
+        let lon2 = lon1
+            + (bearing.sin() * angular_distance.sin() * lat1.cos())
+                .atan2(angular_distance.cos() - lat1.sin() * lat2.sin());
+
+        GeoCoordinates::new(lat2.to_degrees(), normalize_longitude(lon2.to_degrees()))
+            .with_coordinate_system(self.coordinate_system.clone())
+    }
+
+    /// Coarsen the position to the center of a grid cell roughly `precision_meters` wide
+    ///
+    /// Cells are rows of equal latitude height, each split into columns of about the
+    /// same width in meters, so every point in a cell snaps to the same coordinates and
+    /// snapping again changes nothing. Like [`fuzz`](Self::fuzz), altitude and accuracy
+    /// are dropped. A non-positive precision leaves the position unchanged.
+    pub fn snap_to_grid(&self, precision_meters: f64) -> GeoCoordinates {
+        const METERS_PER_DEGREE: f64 = 6_371_000.0 * std::f64::consts::PI / 180.0;
+
+        let (latitude, longitude) = if precision_meters > 0.0 {
+            let lat_step = (precision_meters / METERS_PER_DEGREE).min(180.0);
+            let latitude = grid_cell_center(self.latitude + 90.0, lat_step, 180.0) - 90.0;
+
+            // Columns widen toward the poles to stay about `precision_meters` across
+            let lon_step = (lat_step / latitude.to_radians().cos()).min(360.0);
+            let offset = (self.longitude + 180.0).rem_euclid(360.0);
+            let longitude = grid_cell_center(offset, lon_step, 360.0) - 180.0;
+            (latitude, longitude)
+        } else {
+            (self.latitude, self.longitude)
+        };
+
+        GeoCoordinates::new(latitude, longitude)
+            .with_coordinate_system(self.coordinate_system.clone())
+    }
+
+    /// Encode these coordinates as an Open Location Code ("plus code")
+    ///
+    /// `length` is the number of code digits: 2 to 15, with lengths below 10 rounded up
+    /// to an even number. Ten digits identify a cell of roughly 14m x 14m; codes shorter
+    /// than 8 digits are padded with `0`, e.g. `7FG49Q00+`.
+    pub fn to_plus_code(&self, length: usize) -> String {
+        let length = match length.clamp(2, OLC_MAX_DIGITS) {
+            n if n < OLC_PAIR_CODE_LENGTH && n % 2 == 1 => n + 1,
+            n => n,
+        };
+
+        // Work in integers at the finest grid resolution to avoid floating point drift
+        let lat_units = ((self.latitude + 90.0) * OLC_LAT_MULTIPLIER as f64 * 1e6).round() / 1e6;
+        let lng_units = ((self.longitude + 180.0) * OLC_LNG_MULTIPLIER as f64 * 1e6).round() / 1e6;
+        let mut lat = (lat_units.floor() as i64).clamp(0, 180 * OLC_LAT_MULTIPLIER - 1);
+        let mut lng = (lng_units.floor() as i64).rem_euclid(360 * OLC_LNG_MULTIPLIER);
+
+        // Digits are produced least significant first
+        let mut digits = Vec::with_capacity(OLC_MAX_DIGITS);
+        if length > OLC_PAIR_CODE_LENGTH {
+            for _ in 0..OLC_MAX_DIGITS - OLC_PAIR_CODE_LENGTH {
+                let index = (lat % OLC_GRID_ROWS) * OLC_GRID_COLUMNS + lng % OLC_GRID_COLUMNS;
+                digits.push(OLC_ALPHABET[index as usize]);
+                lat /= OLC_GRID_ROWS;
+                lng /= OLC_GRID_COLUMNS;
+            }
+        } else {
+            lat /= OLC_GRID_ROWS.pow(5);
+            lng /= OLC_GRID_COLUMNS.pow(5);
+        }
+        for _ in 0..OLC_PAIR_CODE_LENGTH / 2 {
+            digits.push(OLC_ALPHABET[(lng % OLC_BASE) as usize]);
+            digits.push(OLC_ALPHABET[(lat % OLC_BASE) as usize]);
+            lat /= OLC_BASE;
+            lng /= OLC_BASE;
+        }
+
+        let mut code: String = digits
+            .iter()
+            .rev()
+            .take(length)
+            .map(|&d| d as char)
+            .collect();
+        while code.len() < OLC_SEPARATOR_POSITION {
+            code.push(OLC_PADDING);
+        }
+        code.insert(OLC_SEPARATOR_POSITION, OLC_SEPARATOR);
+        code
+    }
+
+    /// Decode a full Open Location Code to the center of the cell it identifies
+    ///
+    /// Codes are case-insensitive. Short codes (which need a reference location) are
+    /// rejected, as are codes with invalid characters, separator placement or padding.
+    pub fn from_plus_code(code: &str) -> DomainResult<GeoCoordinates> {
+        let code = code.trim().to_ascii_uppercase();
+        validate_plus_code(&code)?;
+
+        let digits: Vec<i64> = code
+            .chars()
+            .filter(|&c| c != OLC_SEPARATOR && c != OLC_PADDING)
+            .take(OLC_MAX_DIGITS)
+            .filter_map(olc_digit)
+            .collect();
+
+        // Pair section: alternating latitude/longitude digits in base 20
+        let mut south = -90 * OLC_PAIR_PRECISION;
+        let mut west = -180 * OLC_PAIR_PRECISION;
+        let mut place_value = OLC_BASE.pow(4);
+        let pair_digits = digits.len().min(OLC_PAIR_CODE_LENGTH);
+        for i in (0..pair_digits).step_by(2) {
+            south += digits[i] * place_value;
+            west += digits[i + 1] * place_value;
+            if i + 2 < pair_digits {
+                place_value /= OLC_BASE;
+            }
+        }
+        let mut lat_size = place_value as f64 / OLC_PAIR_PRECISION as f64;
+        let mut lng_size = place_value as f64 / OLC_PAIR_PRECISION as f64;
+        let mut latitude = south as f64 / OLC_PAIR_PRECISION as f64;
+        let mut longitude = west as f64 / OLC_PAIR_PRECISION as f64;
+
+        // Grid section: each digit splits the cell into 5 rows x 4 columns
+        if digits.len() > OLC_PAIR_CODE_LENGTH {
+            let (mut row_value, mut column_value) = (OLC_GRID_ROWS.pow(4), OLC_GRID_COLUMNS.pow(4));
+            let (mut grid_lat, mut grid_lng) = (0, 0);
+            for (i, digit) in digits.iter().enumerate().skip(OLC_PAIR_CODE_LENGTH) {
+                grid_lat += digit / OLC_GRID_COLUMNS * row_value;
+                grid_lng += digit % OLC_GRID_COLUMNS * column_value;
+                if i + 1 < digits.len() {
+                    row_value /= OLC_GRID_ROWS;
+                    column_value /= OLC_GRID_COLUMNS;
+                }
+            }
+            lat_size = row_value as f64 / OLC_LAT_MULTIPLIER as f64;
+            lng_size = column_value as f64 / OLC_LNG_MULTIPLIER as f64;
+            latitude += grid_lat as f64 / OLC_LAT_MULTIPLIER as f64;
+            longitude += grid_lng as f64 / OLC_LNG_MULTIPLIER as f64;
         }
+
+        Ok(GeoCoordinates::new(
+            (latitude + lat_size / 2.0).min(90.0),
+            (longitude + lng_size / 2.0).min(180.0),
+        ))
+    }
+
+    /// Encode these coordinates as a geohash of `precision` characters (1 to 12)
+    ///
+    /// Each character narrows the cell by 5 bits, alternating longitude and latitude,
+    /// so points sharing a geohash prefix lie in the same cell at that precision.
+    /// Five characters give cells of roughly 5km x 5km, nine roughly 5m x 5m.
+    pub fn geohash(&self, precision: usize) -> String {
+        let precision = precision.clamp(1, GEOHASH_MAX_PRECISION);
+        let (mut lat_range, mut lng_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let mut hash = String::with_capacity(precision);
+        let mut even_bit = true;
+
+        while hash.len() < precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (range, value) = if even_bit {
+                    (&mut lng_range, self.longitude)
+                } else {
+                    (&mut lat_range, self.latitude)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                index <<= 1;
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even_bit = !even_bit;
+            }
+            hash.push(GEOHASH_ALPHABET[index] as char);
+        }
+
+        hash
+    }
+
+    /// Project these coordinates onto their UTM zone
+    ///
+    /// The zone is the standard 6° band containing the longitude, without the
+    /// Norway and Svalbard exceptions. Accuracy is sub-millimeter within a zone
+    /// but degrades outside UTM's intended latitude range of 80°S to 84°N.
+    pub fn to_utm(&self) -> UtmCoordinate {
+        let zone = ((((self.longitude + 180.0) / 6.0).floor() as i32) + 1).clamp(1, 60) as u8;
+        let (sin_phi, cos_phi) = self.latitude.to_radians().sin_cos();
+        let tan_phi = sin_phi / cos_phi;
+
+        let n = UTM_A / (1.0 - UTM_E2 * sin_phi * sin_phi).sqrt();
+        let t = tan_phi * tan_phi;
+        let c = UTM_EP2 * cos_phi * cos_phi;
+        let a = cos_phi * (self.longitude.to_radians() - utm_central_meridian(zone));
+        let m = utm_meridian_arc(self.latitude.to_radians());
+
+        let easting = UTM_K0
+            * n
+            * (a + (1.0 - t + c) * a.powi(3) / 6.0
+                + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * UTM_EP2) * a.powi(5) / 120.0)
+            + UTM_FALSE_EASTING;
+        let mut northing = UTM_K0
+            * (m + n
+                * tan_phi
+                * (a * a / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * UTM_EP2) * a.powi(6) / 720.0));
+
+        let hemisphere = if self.latitude < 0.0 {
+            northing += UTM_FALSE_NORTHING_SOUTH;
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        };
+
+        UtmCoordinate {
+            zone,
+            hemisphere,
+            easting,
+            northing,
+        }
+    }
+
+    /// Convert UTM coordinates back to WGS84 latitude and longitude
+    pub fn from_utm(utm: UtmCoordinate) -> DomainResult<GeoCoordinates> {
+        if !(1..=60).contains(&utm.zone) {
+            return Err(DomainError::ValidationError(format!(
+                "UTM zone {} is out of range [1, 60]",
+                utm.zone
+            )));
+        }
+        if !utm.easting.is_finite() || !utm.northing.is_finite() {
+            return Err(DomainError::ValidationError(
+                "UTM easting and northing must be finite".to_string(),
+            ));
+        }
+
+        let x = utm.easting - UTM_FALSE_EASTING;
+        let y = match utm.hemisphere {
+            Hemisphere::North => utm.northing,
+            Hemisphere::South => utm.northing - UTM_FALSE_NORTHING_SOUTH,
+        };
+
+        // Footpoint latitude: the latitude whose meridian arc equals the northing
+        let e2 = UTM_E2;
+        let mu = y
+            / UTM_K0
+            / (UTM_A * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2.powi(3) / 256.0));
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+            + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+        let (sin_phi1, cos_phi1) = phi1.sin_cos();
+        let tan_phi1 = sin_phi1 / cos_phi1;
+        let n1 = UTM_A / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+        let t1 = tan_phi1 * tan_phi1;
+        let c1 = UTM_EP2 * cos_phi1 * cos_phi1;
+        let r1 = UTM_A * (1.0 - e2) / (1.0 - e2 * sin_phi1 * sin_phi1).powf(1.5);
+        let d = x / (n1 * UTM_K0);
+
+        let latitude = phi1
+            - (n1 * tan_phi1 / r1)
+                * (d * d / 2.0
+                    - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * UTM_EP2) * d.powi(4)
+                        / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1
+                        - 252.0 * UTM_EP2
+                        - 3.0 * c1 * c1)
+                        * d.powi(6)
+                        / 720.0);
+        let longitude = utm_central_meridian(utm.zone)
+            + (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+                + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * UTM_EP2 + 24.0 * t1 * t1)
+                    * d.powi(5)
+                    / 120.0)
+                / cos_phi1;
+
+        let coordinates = GeoCoordinates::new(latitude.to_degrees(), longitude.to_degrees());
+        coordinates.validate()?;
+        Ok(coordinates)
     }
 }
 
+impl fmt::Display for GeoCoordinates {
+    /// Terse `lat,lng` form, with `,alt` appended when the altitude is known
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)?;
+        if let Some(altitude) = self.altitude {
+            write!(f, ",{}", altitude)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GeoCoordinates {
+    type Err = DomainError;
+
+    /// Parse `lat,lng` or `lat,lng,alt` as produced by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<f64> = s
+            .split(',')
+            .map(|part| part.trim().parse::<f64>().ok().filter(|value| value.is_finite()))
+            .collect::<Option<_>>()
+            .ok_or_else(|| DomainError::ValidationError(format!("Malformed coordinates: {s}")))?;
+
+        let coordinates = match parts[..] {
+            [latitude, longitude] => GeoCoordinates::new(latitude, longitude),
+            [latitude, longitude, altitude] => {
+                GeoCoordinates::new(latitude, longitude).with_altitude(altitude)
+            }
+            _ => {
+                return Err(DomainError::ValidationError(format!(
+                    "Expected lat,lng or lat,lng,alt but got: {s}"
+                )))
+            }
+        };
+        coordinates.validate()?;
+        Ok(coordinates)
+    }
+}
+
+/// WGS-84 semi-major axis in meters
+const UTM_A: f64 = 6_378_137.0;
+/// WGS-84 flattening
+const UTM_F: f64 = 1.0 / 298.257_223_563;
+/// WGS-84 first eccentricity squared
+const UTM_E2: f64 = UTM_F * (2.0 - UTM_F);
+/// WGS-84 second eccentricity squared, `e² / (1 - e²)`
+const UTM_EP2: f64 = UTM_E2 / (1.0 - UTM_E2);
+/// UTM scale factor on the central meridian
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+/// Added to southern hemisphere northings to keep them positive
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// Central meridian of a UTM zone, in radians
+fn utm_central_meridian(zone: u8) -> f64 {
+    (f64::from(zone) * 6.0 - 183.0).to_radians()
+}
+
+/// Distance along the meridian from the equator to latitude `phi` (radians)
+fn utm_meridian_arc(phi: f64) -> f64 {
+    let e2 = UTM_E2;
+    let (e4, e6) = (e2 * e2, e2.powi(3));
+    UTM_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
+
+/// Open Location Code digit alphabet (base 20, no vowels or ambiguous characters)
+const OLC_ALPHABET: &[u8; 20] = b"23456789CFGHJMPQRVWX";
+const OLC_SEPARATOR: char = '+';
+const OLC_SEPARATOR_POSITION: usize = 8;
+const OLC_PADDING: char = '0';
+const OLC_BASE: i64 = 20;
+/// Digits encoded as latitude/longitude pairs; any further digits use the 5x4 grid
+const OLC_PAIR_CODE_LENGTH: usize = 10;
+const OLC_MAX_DIGITS: usize = 15;
+const OLC_GRID_ROWS: i64 = 5;
+const OLC_GRID_COLUMNS: i64 = 4;
+/// Units per degree after the pair section
+const OLC_PAIR_PRECISION: i64 = 8_000;
+/// Units per degree after the full grid section
+const OLC_LAT_MULTIPLIER: i64 = OLC_PAIR_PRECISION * 3_125;
+const OLC_LNG_MULTIPLIER: i64 = OLC_PAIR_PRECISION * 1_024;
+
+/// Geohash base-32 alphabet (no a, i, l or o)
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest geohash supported, finer than the precision of an f64 coordinate fix
+const GEOHASH_MAX_PRECISION: usize = 12;
+
+/// Value of an Open Location Code digit
+fn olc_digit(c: char) -> Option<i64> {
+    OLC_ALPHABET
+        .iter()
+        .position(|&d| d as char == c)
+        .map(|index| index as i64)
+}
+
+/// Check that an upper-cased code is a well-formed full Open Location Code
+fn validate_plus_code(code: &str) -> DomainResult<()> {
+    let invalid = |reason: &str| -> DomainResult<()> {
+        Err(DomainError::ValidationError(format!(
+            "Invalid plus code '{code}': {reason}"
+        )))
+    };
+
+    if code.matches(OLC_SEPARATOR).count() != 1 {
+        return invalid("expected exactly one '+' separator");
+    }
+    if code.find(OLC_SEPARATOR) != Some(OLC_SEPARATOR_POSITION) {
+        return invalid("only full codes with 8 digits before '+' are supported");
+    }
+
+    let (head, tail) = (
+        &code[..OLC_SEPARATOR_POSITION],
+        &code[OLC_SEPARATOR_POSITION + 1..],
+    );
+    if tail.chars().count() == 1 {
+        return invalid("a single digit after '+' is not allowed");
+    }
+
+    if let Some(padding) = head.find(OLC_PADDING) {
+        if padding == 0 || padding % 2 == 1 {
+            return invalid("padding must start at an even position after the first pair");
+        }
+        if !head[padding..].chars().all(|c| c == OLC_PADDING) {
+            return invalid("padding must be contiguous up to the separator");
+        }
+        if !tail.is_empty() {
+            return invalid("padded codes cannot have digits after '+'");
+        }
+    }
+
+    if let Some(c) = head
+        .chars()
+        .filter(|&c| c != OLC_PADDING)
+        .chain(tail.chars())
+        .find(|&c| olc_digit(c).is_none())
+    {
+        return invalid(&format!("'{c}' is not a plus code digit"));
+    }
+
+    // The first pair cannot exceed 180 degrees of latitude or 360 of longitude
+    let mut first_pair = code.chars().filter_map(olc_digit);
+    if first_pair.next().is_some_and(|lat| lat * OLC_BASE >= 180)
+        || first_pair.next().is_some_and(|lng| lng * OLC_BASE >= 360)
+    {
+        return invalid("first pair is outside the valid latitude/longitude range");
+    }
+
+    Ok(())
+}
+
+/// Center of the grid cell containing `offset`, for cells of `step` starting at 0
+///
+/// The last cell is cut short at `limit` and centered on what remains of it.
+fn grid_cell_center(offset: f64, step: f64, limit: f64) -> f64 {
+    let start = ((offset / step).floor() * step).min(limit);
+    let end = (start + step).min(limit);
+    (start + end) / 2.0
+}
+
+/// Wrap a longitude into the [-180, 180] range
+fn normalize_longitude(longitude: f64) -> f64 {
+    if (-180.0..=180.0).contains(&longitude) {
+        longitude
+    } else {
+        (longitude + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+/// A position observed at a point in time, e.g. a GPS fix of a tracked asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionFix {
+    pub coordinates: GeoCoordinates,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Hemisphere of a UTM coordinate, which decides the false northing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Hemisphere {
+    North,
+    South,
+}
+
+/// Planar position in the Universal Transverse Mercator system
+///
+/// Easting and northing are in meters within the zone, so distances and areas
+/// between points in the same zone can be computed with plane geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UtmCoordinate {
+    /// Zone number (1 to 60), each spanning 6° of longitude
+    pub zone: u8,
+    pub hemisphere: Hemisphere,
+    /// Meters east, with the zone's central meridian at 500 000
+    pub easting: f64,
+    /// Meters north of the equator (offset by 10 000 000 in the south)
+    pub northing: f64,
+}
+
 /// Geographic bounding box
+///
+/// A box that crosses the antimeridian is represented with `min_lon > max_lon`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BoundingBox {
     pub min_lat: f64,
@@ -129,18 +836,218 @@ pub struct BoundingBox {
 impl BoundingBox {
     /// Check if a point is within this bounding box
     pub fn contains(&self, coords: &GeoCoordinates) -> bool {
-        coords.latitude >= self.min_lat
-            && coords.latitude <= self.max_lat
-            && coords.longitude >= self.min_lon
-            && coords.longitude <= self.max_lon
+        if coords.latitude < self.min_lat || coords.latitude > self.max_lat {
+            return false;
+        }
+
+        if self.crosses_antimeridian() {
+            coords.longitude >= self.min_lon || coords.longitude <= self.max_lon
+        } else {
+            coords.longitude >= self.min_lon && coords.longitude <= self.max_lon
+        }
     }
 
     /// Calculate the center of the bounding box
     pub fn center(&self) -> GeoCoordinates {
-        GeoCoordinates::new(
-            (self.min_lat + self.max_lat) / 2.0,
-            (self.min_lon + self.max_lon) / 2.0,
-        )
+        let center_lon = if self.crosses_antimeridian() {
+            normalize_longitude((self.min_lon + self.max_lon + 360.0) / 2.0)
+        } else {
+            (self.min_lon + self.max_lon) / 2.0
+        };
+
+        GeoCoordinates::new((self.min_lat + self.max_lat) / 2.0, center_lon)
+    }
+
+    /// Southwest corner of the box
+    pub fn southwest(&self) -> GeoCoordinates {
+        GeoCoordinates::new(self.min_lat, self.min_lon)
+    }
+
+    /// Northeast corner of the box
+    pub fn northeast(&self) -> GeoCoordinates {
+        GeoCoordinates::new(self.max_lat, self.max_lon)
+    }
+
+    /// Southwest and northeast corners of the box
+    pub fn corners(&self) -> (GeoCoordinates, GeoCoordinates) {
+        (self.southwest(), self.northeast())
+    }
+
+    /// Check if this box wraps around the antimeridian (180° meridian)
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.min_lon > self.max_lon
+    }
+
+    /// Longitude span of the box in degrees
+    pub fn longitude_span(&self) -> f64 {
+        if self.crosses_antimeridian() {
+            360.0 - self.min_lon + self.max_lon
+        } else {
+            self.max_lon - self.min_lon
+        }
+    }
+
+    /// Split into boxes that do not cross the antimeridian
+    ///
+    /// Returns a single box when no split is needed, otherwise the eastern and
+    /// western halves.
+    pub fn split_at_antimeridian(&self) -> Vec<BoundingBox> {
+        if !self.crosses_antimeridian() {
+            return vec![self.clone()];
+        }
+
+        vec![
+            BoundingBox {
+                min_lat: self.min_lat,
+                max_lat: self.max_lat,
+                min_lon: self.min_lon,
+                max_lon: 180.0,
+            },
+            BoundingBox {
+                min_lat: self.min_lat,
+                max_lat: self.max_lat,
+                min_lon: -180.0,
+                max_lon: self.max_lon,
+            },
+        ]
+    }
+}
+
+/// Query bounds with explicit antimeridian handling
+///
+/// The longitude range runs eastwards from `west` to `east`. When `west` lies
+/// east of `east` the range wraps across ±180° and covers two intervals,
+/// `[west, 180]` and `[-180, east]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoBounds {
+    pub south: f64,
+    pub north: f64,
+    pub west: f64,
+    pub east: f64,
+    pub crosses_antimeridian: bool,
+}
+
+impl GeoBounds {
+    /// Bounds spanning eastwards from the southwest to the northeast corner
+    pub fn from_corners(southwest: &GeoCoordinates, northeast: &GeoCoordinates) -> Self {
+        Self {
+            south: southwest.latitude,
+            north: northeast.latitude,
+            west: southwest.longitude,
+            east: northeast.longitude,
+            crosses_antimeridian: southwest.longitude > northeast.longitude,
+        }
+    }
+
+    /// Longitude intervals covered, one per side of the antimeridian
+    pub fn longitude_intervals(&self) -> Vec<(f64, f64)> {
+        if self.crosses_antimeridian {
+            vec![(self.west, 180.0), (-180.0, self.east)]
+        } else {
+            vec![(self.west, self.east)]
+        }
+    }
+
+    /// Check whether a point lies within the bounds, edges included
+    pub fn contains(&self, point: &GeoCoordinates) -> bool {
+        if point.latitude < self.south || point.latitude > self.north {
+            return false;
+        }
+
+        self.longitude_intervals()
+            .into_iter()
+            .any(|(west, east)| point.longitude >= west && point.longitude <= east)
+    }
+}
+
+impl From<&BoundingBox> for GeoBounds {
+    fn from(bbox: &BoundingBox) -> Self {
+        Self::from_corners(&bbox.southwest(), &bbox.northeast())
+    }
+}
+
+/// Sphere that great-circle distances are measured on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DistanceModel {
+    /// Radius of the sphere in meters
+    pub radius_meters: f64,
+}
+
+impl DistanceModel {
+    /// Earth's mean radius, the default for all distance calculations
+    pub const EARTH_MEAN: Self = Self {
+        radius_meters: 6_371_000.0,
+    };
+
+    /// Earth's equatorial radius (WGS 84 semi-major axis)
+    pub const EARTH_EQUATORIAL: Self = Self {
+        radius_meters: 6_378_137.0,
+    };
+
+    /// Mars' mean radius
+    pub const MARS_MEAN: Self = Self {
+        radius_meters: 3_389_500.0,
+    };
+
+    /// Create a model for a sphere of the given radius
+    pub fn new(radius_meters: f64) -> Self {
+        Self { radius_meters }
+    }
+}
+
+impl Default for DistanceModel {
+    fn default() -> Self {
+        Self::EARTH_MEAN
+    }
+}
+
+/// A distance with an explicit unit, stored in meters
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Distance(f64);
+
+impl Distance {
+    const METERS_PER_KILOMETER: f64 = 1_000.0;
+    const METERS_PER_MILE: f64 = 1_609.344;
+
+    /// Create a distance from meters
+    pub fn from_meters(meters: f64) -> Self {
+        Self(meters)
+    }
+
+    /// Create a distance from kilometers
+    pub fn from_kilometers(kilometers: f64) -> Self {
+        Self(kilometers * Self::METERS_PER_KILOMETER)
+    }
+
+    /// Create a distance from international miles
+    pub fn from_miles(miles: f64) -> Self {
+        Self(miles * Self::METERS_PER_MILE)
+    }
+
+    /// Distance in meters
+    pub fn as_meters(&self) -> f64 {
+        self.0
+    }
+
+    /// Distance in kilometers
+    pub fn as_kilometers(&self) -> f64 {
+        self.0 / Self::METERS_PER_KILOMETER
+    }
+
+    /// Distance in international miles
+    pub fn as_miles(&self) -> f64 {
+        self.0 / Self::METERS_PER_MILE
+    }
+}
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.abs() >= Self::METERS_PER_KILOMETER {
+            write!(f, "{:.2} km", self.as_kilometers())
+        } else {
+            write!(f, "{:.1} m", self.0)
+        }
     }
 }
 
@@ -161,6 +1068,31 @@ mod tests {
         assert!((distance - 3_935_000.0).abs() < 10_000.0); // Within 10km accuracy
     }
 
+    #[test]
+    fn test_distance_with_model() {
+        let nyc = GeoCoordinates::new(40.7128, -74.0060);
+        let la = GeoCoordinates::new(34.0522, -118.2437);
+
+        let earth = nyc.distance_to_with_model(&la, DistanceModel::EARTH_MEAN);
+        assert_eq!(earth, nyc.distance_to(&la));
+
+        // Same angular separation on a smaller sphere scales with the radius
+        let mars = nyc.distance_to_with_model(&la, DistanceModel::MARS_MEAN);
+        let ratio =
+            DistanceModel::MARS_MEAN.radius_meters / DistanceModel::EARTH_MEAN.radius_meters;
+        assert!((mars - earth * ratio).abs() < 1e-6);
+        assert!(mars < earth);
+
+        let equatorial = nyc.distance_to_with_model(&la, DistanceModel::EARTH_EQUATORIAL);
+        assert!(equatorial > earth);
+
+        // One degree of latitude on a unit sphere is one degree in radians
+        let unit = DistanceModel::new(1.0);
+        let one_degree = GeoCoordinates::new(0.0, 0.0)
+            .distance_to_with_model(&GeoCoordinates::new(1.0, 0.0), unit);
+        assert!((one_degree - 1.0_f64.to_radians()).abs() < 1e-12);
+    }
+
     #[test]
     fn test_bearing_calculation() {
         let start = GeoCoordinates::new(0.0, 0.0);
@@ -186,4 +1118,500 @@ mod tests {
             bearing_east
         );
     }
+
+    #[test]
+    fn test_bounding_box_longitude_compression() {
+        let equator = GeoCoordinates::new(0.0, 10.0);
+        let north = GeoCoordinates::new(60.0, 10.0);
+
+        let equator_box = equator.bounding_box(1_000.0);
+        let north_box = north.bounding_box(1_000.0);
+
+        // Latitude span is independent of latitude
+        let equator_lat_span = equator_box.max_lat - equator_box.min_lat;
+        let north_lat_span = north_box.max_lat - north_box.min_lat;
+        assert!((equator_lat_span - north_lat_span).abs() < 1e-9);
+
+        // cos(60°) = 0.5, so the longitude span should roughly double
+        let ratio = north_box.longitude_span() / equator_box.longitude_span();
+        assert!((ratio - 2.0).abs() < 0.01, "ratio was {ratio}");
+
+        // The circle's edge points must lie inside the box
+        assert!(equator_box.contains(&GeoCoordinates::new(0.0, 10.0089)));
+        assert!(!equator_box.contains(&GeoCoordinates::new(0.0, 10.02)));
+    }
+
+    #[test]
+    fn test_bounding_box_clamps_at_poles() {
+        let near_pole = GeoCoordinates::new(89.99, 0.0);
+        let bbox = near_pole.bounding_box(10_000.0);
+
+        assert_eq!(bbox.max_lat, 90.0);
+        assert_eq!(bbox.min_lon, -180.0);
+        assert_eq!(bbox.max_lon, 180.0);
+        assert!(!bbox.crosses_antimeridian());
+    }
+
+    #[test]
+    fn test_bounding_box_antimeridian() {
+        let fiji = GeoCoordinates::new(-17.7, 179.99);
+        let bbox = fiji.bounding_box(5_000.0);
+
+        assert!(bbox.crosses_antimeridian());
+        assert!(bbox.min_lon > 179.0);
+        assert!(bbox.max_lon < -179.0);
+        assert!(bbox.contains(&GeoCoordinates::new(-17.7, -179.99)));
+        assert!(bbox.contains(&GeoCoordinates::new(-17.7, 179.98)));
+        assert!(!bbox.contains(&GeoCoordinates::new(-17.7, 0.0)));
+
+        let halves = bbox.split_at_antimeridian();
+        assert_eq!(halves.len(), 2);
+        assert_eq!(halves[0].max_lon, 180.0);
+        assert_eq!(halves[1].min_lon, -180.0);
+        assert!(halves.iter().all(|b| !b.crosses_antimeridian()));
+    }
+
+    #[test]
+    fn test_geo_bounds_across_antimeridian() {
+        let bounds = GeoBounds::from_corners(
+            &GeoCoordinates::new(-30.0, 170.0),
+            &GeoCoordinates::new(10.0, -170.0),
+        );
+
+        assert!(bounds.crosses_antimeridian);
+        assert_eq!(bounds.longitude_intervals().len(), 2);
+        assert!(bounds.contains(&GeoCoordinates::new(0.0, 179.0)));
+        assert!(bounds.contains(&GeoCoordinates::new(0.0, -179.0)));
+        assert!(bounds.contains(&GeoCoordinates::new(0.0, 180.0)));
+        assert!(!bounds.contains(&GeoCoordinates::new(0.0, 0.0)));
+        assert!(!bounds.contains(&GeoCoordinates::new(0.0, 169.0)));
+        assert!(!bounds.contains(&GeoCoordinates::new(20.0, 179.0)));
+
+        // The same corners the other way round span the rest of the globe
+        let complement = GeoBounds::from_corners(
+            &GeoCoordinates::new(-30.0, -170.0),
+            &GeoCoordinates::new(10.0, 170.0),
+        );
+        assert!(!complement.crosses_antimeridian);
+        assert!(complement.contains(&GeoCoordinates::new(0.0, 0.0)));
+        assert!(!complement.contains(&GeoCoordinates::new(0.0, 179.0)));
+
+        // Distances take the short way across the antimeridian
+        let east = GeoCoordinates::new(0.0, 179.0);
+        let west = GeoCoordinates::new(0.0, -179.0);
+        assert!((east.distance_to(&west) - 222_390.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn test_bearing_nyc_to_la() {
+        let nyc = GeoCoordinates::new(40.7128, -74.0060);
+        let la = GeoCoordinates::new(34.0522, -118.2437);
+
+        // Initial great-circle bearing heads just north of due west
+        let bearing = nyc.bearing_to(&la);
+        assert!(
+            (265.0..300.0).contains(&bearing),
+            "NYC to LA bearing {bearing} should be roughly west-northwest"
+        );
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let a = GeoCoordinates::new(0.0, 0.0);
+        let b = GeoCoordinates::new(0.0, 90.0);
+
+        let mid = a.midpoint(&b);
+        assert!(mid.latitude.abs() < 1e-9);
+        assert!((mid.longitude - 45.0).abs() < 1e-9);
+
+        // Midpoint is equidistant from both ends
+        let nyc = GeoCoordinates::new(40.7128, -74.0060);
+        let la = GeoCoordinates::new(34.0522, -118.2437);
+        let mid = nyc.midpoint(&la);
+        let half = nyc.distance_to(&la) / 2.0;
+        assert!((nyc.distance_to(&mid) - half).abs() < 1.0);
+        assert!((la.distance_to(&mid) - half).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_vincenty_distance() {
+        let nyc = GeoCoordinates::new(40.7128, -74.0060);
+        let la = GeoCoordinates::new(34.0522, -118.2437);
+
+        let spherical = nyc.distance_to(&la);
+        let ellipsoidal = nyc.distance_to_vincenty(&la);
+
+        // ~3944 km on the ellipsoid, a few km more than the spherical estimate
+        assert!((ellipsoidal - 3_944_422.0).abs() < 10.0);
+        let difference = ellipsoidal - spherical;
+        assert!(difference > 2_000.0 && difference < 20_000.0);
+
+        assert_eq!(nyc.distance_to_vincenty(&nyc), 0.0);
+    }
+
+    #[test]
+    fn test_distance_3d() {
+        // ~100 m north of the origin, one floor stack higher
+        let north = (100.0_f64 / 6_371_000.0).to_degrees();
+        let ground = GeoCoordinates::new(0.0, 0.0).with_altitude(10.0);
+        let roof = GeoCoordinates::new(north, 0.0).with_altitude(110.0);
+
+        assert!((ground.distance_to(&roof) - 100.0).abs() < 0.01);
+        assert!((ground.distance_3d_to(&roof) - 141.42).abs() < 0.01);
+
+        // Same spot, different floors
+        let lobby = GeoCoordinates::new(37.7749, -122.4194).with_altitude(5.0);
+        let floor_3 = GeoCoordinates::new(37.7749, -122.4194).with_altitude(17.0);
+        assert_eq!(lobby.distance_to(&floor_3), 0.0);
+        assert!((lobby.distance_3d_to(&floor_3) - 12.0).abs() < 1e-9);
+
+        // Missing altitude counts as sea level
+        let sea_level = GeoCoordinates::new(37.7749, -122.4194);
+        assert!((sea_level.distance_3d_to(&lobby) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_distance_3d_matches_surface_distance_at_equal_altitude() {
+        let nyc = GeoCoordinates::new(40.7128, -74.0060).with_altitude(30.0);
+        let la = GeoCoordinates::new(34.0522, -118.2437).with_altitude(30.0);
+
+        assert_eq!(nyc.distance_3d_to(&la), nyc.distance_to(&la));
+    }
+
+    #[test]
+    fn test_distance_to_segment() {
+        let start = GeoCoordinates::new(0.0, 0.0);
+        let end = GeoCoordinates::new(0.0, 1.0);
+        let meters_per_degree = 6_371_000.0_f64.to_radians();
+
+        // Beside the middle of the segment
+        let beside = GeoCoordinates::new(0.01, 0.5);
+        assert!((beside.distance_to_segment(&start, &end) - 0.01 * meters_per_degree).abs() < 1.0);
+
+        // Past the end, measured to the end point
+        let past = GeoCoordinates::new(0.0, 1.01);
+        assert!((past.distance_to_segment(&start, &end) - past.distance_to(&end)).abs() < 1.0);
+
+        // Degenerate segment
+        assert!(
+            (beside.distance_to_segment(&start, &start) - beside.distance_to(&start)).abs() < 1.0
+        );
+
+        assert_eq!(beside.distance_to_polyline(&[]), None);
+        assert_eq!(
+            beside.distance_to_polyline(&[start.clone()]),
+            Some(beside.distance_to(&start))
+        );
+    }
+
+    #[test]
+    fn test_vincenty_near_antipodal() {
+        let origin = GeoCoordinates::new(0.0, 0.0);
+
+        // Close to antipodal but still converges
+        let near = GeoCoordinates::new(-1.0, 179.0);
+        let distance = origin.vincenty_inverse(&near).unwrap();
+        assert!((distance - 19_860_509.0).abs() < 10.0);
+
+        // Nearly antipodal points fall back to the spherical result
+        let antipodal = GeoCoordinates::new(0.5, 179.7);
+        assert!(origin.vincenty_inverse(&antipodal).is_none());
+        assert_eq!(
+            origin.distance_to_vincenty(&antipodal),
+            origin.distance_to(&antipodal)
+        );
+    }
+
+    #[test]
+    fn test_distance_conversions() {
+        let mile = Distance::from_miles(1.0);
+        assert!((mile.as_meters() - 1_609.344).abs() < 1e-9);
+        assert!((mile.as_kilometers() - 1.609344).abs() < 1e-12);
+
+        let marathon = Distance::from_kilometers(42.195);
+        assert!((marathon.as_meters() - 42_195.0).abs() < 1e-9);
+        assert!((marathon.as_miles() - 26.218_757).abs() < 1e-6);
+
+        assert_eq!(Distance::from_meters(1_609.344), mile);
+        assert!(Distance::from_meters(1_000.0) < mile);
+        assert!(Distance::from_kilometers(2.0) > mile);
+    }
+
+    #[test]
+    fn test_distance_display() {
+        assert_eq!(Distance::from_meters(250.0).to_string(), "250.0 m");
+        assert_eq!(Distance::from_kilometers(3.5).to_string(), "3.50 km");
+        assert_eq!(
+            serde_json::to_string(&Distance::from_kilometers(1.5)).unwrap(),
+            "1500.0"
+        );
+    }
+
+    #[test]
+    fn test_plus_code_encoding_matches_reference() {
+        // Reference values from the Open Location Code test data
+        let cases = [
+            (20.375, 2.775, 6, "7FG49Q00+"),
+            (20.3700625, 2.7821875, 10, "7FG49QCJ+2V"),
+            (20.3701125, 2.782234375, 11, "7FG49QCJ+2VX"),
+            (20.3701135, 2.78223535156, 13, "7FG49QCJ+2VXGJ"),
+            (47.0000625, 8.0000625, 10, "8FVC2222+22"),
+            (-41.2730625, 174.7859375, 10, "4VCPPQGP+Q9"),
+            (-89.5, -179.5, 4, "22220000+"),
+            (90.0, 1.0, 4, "CFX30000+"),
+            (1.0, 180.0, 4, "62H20000+"),
+        ];
+
+        for (lat, lng, length, expected) in cases {
+            assert_eq!(GeoCoordinates::new(lat, lng).to_plus_code(length), expected);
+        }
+    }
+
+    #[test]
+    fn test_plus_code_decodes_to_cell_center() {
+        let cases = [
+            ("7FG49Q00+", 20.375, 2.775),
+            ("7FG49QCJ+2V", 20.3700625, 2.7821875),
+            ("8fvc2222+22", 47.0000625, 8.0000625),
+            ("4VCPPQGP+Q9", -41.2730625, 174.7859375),
+        ];
+
+        for (code, lat, lng) in cases {
+            let decoded = GeoCoordinates::from_plus_code(code).unwrap();
+            assert!((decoded.latitude - lat).abs() < 1e-9, "{code}: {decoded:?}");
+            assert!(
+                (decoded.longitude - lng).abs() < 1e-9,
+                "{code}: {decoded:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_plus_code_round_trip() {
+        let sf = GeoCoordinates::new(37.7749, -122.4194);
+
+        for length in [10, 11, 15] {
+            let code = sf.to_plus_code(length);
+            let decoded = GeoCoordinates::from_plus_code(&code).unwrap();
+            // A 10-digit cell is ~14m across, so its center is within ~10m
+            assert!(sf.distance_to(&decoded) < 10.0, "{code}: {decoded:?}");
+            assert_eq!(decoded.to_plus_code(length), code);
+        }
+    }
+
+    #[test]
+    fn test_geohash_matches_reference() {
+        let aalborg = GeoCoordinates::new(57.64911, 10.40744);
+        assert_eq!(aalborg.geohash(11), "u4pruydqqvj");
+
+        let sf = GeoCoordinates::new(37.7749, -122.4194);
+        assert_eq!(sf.geohash(9), "9q8yyk8yt");
+        assert_eq!(sf.geohash(0), "9");
+        assert_eq!(sf.geohash(20).len(), 12);
+    }
+
+    #[test]
+    fn test_geohash_prefix_tracks_proximity() {
+        let sf = GeoCoordinates::new(37.7749, -122.4194);
+        let half_km_north = GeoCoordinates::new(37.7793, -122.4193);
+        let oakland = GeoCoordinates::new(37.8044, -122.2712);
+
+        // Nearby points share cells at low precision ...
+        assert_eq!(sf.geohash(5), half_km_north.geohash(5));
+        assert_eq!(sf.geohash(2), oakland.geohash(2));
+
+        // ... and separate as the cells shrink
+        assert_ne!(sf.geohash(6), half_km_north.geohash(6));
+        assert_ne!(sf.geohash(3), oakland.geohash(3));
+    }
+
+    #[test]
+    fn test_plus_code_rejects_malformed_codes() {
+        for code in [
+            "",
+            "7FG49QCJ2V",   // missing separator
+            "7FG4+9QCJ2V",  // short code / misplaced separator
+            "7FG49QCJ+2V+", // two separators
+            "7FG49QCJ+2",   // single digit after separator
+            "7FG49QCA+2V",  // 'A' is not in the alphabet
+            "7FG490CJ+",    // padding at an odd position
+            "7F000Q00+",    // non-contiguous padding
+            "7FG49Q00+2V",  // digits after padding
+            "WFG49QCJ+2V",  // latitude out of range
+        ] {
+            assert!(
+                GeoCoordinates::from_plus_code(code).is_err(),
+                "{code} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_accuracy() {
+        let fix = GeoCoordinates::new(37.7749, -122.4194).with_accuracy(12.5);
+        assert_eq!(fix.accuracy_meters, Some(12.5));
+        assert_eq!(GeoCoordinates::new(37.7749, -122.4194).accuracy_meters, None);
+
+        let json = serde_json::to_string(&fix).unwrap();
+        let restored: GeoCoordinates = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, fix);
+    }
+
+    #[test]
+    fn test_accuracy_is_backward_compatible() {
+        // Payloads written before accuracy existed still deserialize
+        let legacy = r#"{"latitude":37.7749,"longitude":-122.4194,"altitude":null,"coordinate_system":"WGS84"}"#;
+        let coords: GeoCoordinates = serde_json::from_str(legacy).unwrap();
+        assert_eq!(coords.accuracy_meters, None);
+
+        // and coordinates without accuracy serialize as before
+        let json = serde_json::to_value(GeoCoordinates::new(37.7749, -122.4194)).unwrap();
+        assert!(json.get("accuracy_meters").is_none());
+    }
+
+    #[test]
+    fn test_utm_matches_reference_points() {
+        // CN Tower, Toronto: 17T 630084 4833438
+        let utm = GeoCoordinates::new(43.642567, -79.387139).to_utm();
+        assert_eq!(utm.zone, 17);
+        assert_eq!(utm.hemisphere, Hemisphere::North);
+        assert!((utm.easting - 630_084.0).abs() < 1.0);
+        assert!((utm.northing - 4_833_438.0).abs() < 1.0);
+
+        // Sydney Opera House: 56H 334901 6252289
+        let utm = GeoCoordinates::new(-33.8568, 151.2153).to_utm();
+        assert_eq!(utm.zone, 56);
+        assert_eq!(utm.hemisphere, Hemisphere::South);
+        assert!((utm.easting - 334_901.0).abs() < 1.0);
+        assert!((utm.northing - 6_252_289.0).abs() < 1.0);
+
+        // The equator on a central meridian is the zone's false origin
+        let utm = GeoCoordinates::new(0.0, 3.0).to_utm();
+        assert_eq!(utm.zone, 31);
+        assert!((utm.easting - 500_000.0).abs() < 1e-6);
+        assert!(utm.northing.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_utm_round_trip() {
+        for (lat, lon) in [
+            (43.642567, -79.387139),
+            (-33.8568, 151.2153),
+            (51.5007, -0.1246),
+            (64.1466, -21.9426),
+            (-54.8019, -68.3030),
+            (80.0, 179.9),
+        ] {
+            let original = GeoCoordinates::new(lat, lon);
+            let restored = GeoCoordinates::from_utm(original.to_utm()).unwrap();
+            assert!(
+                original.distance_to(&restored) < 0.01,
+                "({lat}, {lon}) round-tripped to ({}, {})",
+                restored.latitude,
+                restored.longitude
+            );
+        }
+    }
+
+    #[test]
+    fn test_utm_zone_boundaries() {
+        assert_eq!(GeoCoordinates::new(37.0, 5.999_999).to_utm().zone, 31);
+        assert_eq!(GeoCoordinates::new(37.0, 6.0).to_utm().zone, 32);
+        assert_eq!(GeoCoordinates::new(0.0, -180.0).to_utm().zone, 1);
+        assert_eq!(GeoCoordinates::new(0.0, 180.0).to_utm().zone, 60);
+
+        // Points at the edge of a zone are furthest from its central meridian
+        let edge = GeoCoordinates::new(37.0, 5.999_999);
+        let restored = GeoCoordinates::from_utm(edge.to_utm()).unwrap();
+        assert!(edge.distance_to(&restored) < 0.01);
+
+        let invalid = UtmCoordinate {
+            zone: 61,
+            ..edge.to_utm()
+        };
+        assert!(GeoCoordinates::from_utm(invalid).is_err());
+    }
+
+    #[test]
+    fn test_coordinates_round_trip_through_str() {
+        let plain = GeoCoordinates::new(37.7749, -122.4194);
+        assert_eq!(plain.to_string(), "37.7749,-122.4194");
+        assert_eq!("37.7749,-122.4194".parse::<GeoCoordinates>().unwrap(), plain);
+
+        let elevated = GeoCoordinates::new(27.9881, 86.925).with_altitude(8848.86);
+        assert_eq!(elevated.to_string(), "27.9881,86.925,8848.86");
+        assert_eq!(elevated.to_string().parse::<GeoCoordinates>().unwrap(), elevated);
+
+        let spaced: GeoCoordinates = " 0.5 , -0.25 ".parse().unwrap();
+        assert_eq!((spaced.latitude, spaced.longitude), (0.5, -0.25));
+    }
+
+    #[test]
+    fn test_coordinates_from_str_rejects_bad_input() {
+        for input in ["91,0", "0,181", "37.7749", "north,west", "1,2,3,4", "NaN,0", ""] {
+            assert!(
+                matches!(input.parse::<GeoCoordinates>(), Err(DomainError::ValidationError(_))),
+                "{input} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fuzz_stays_within_radius() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let origins = [
+            GeoCoordinates::new(37.7749, -122.4194).with_altitude(16.0),
+            GeoCoordinates::new(89.9999, 0.0),
+            GeoCoordinates::new(-12.5, 179.9999),
+        ];
+
+        for origin in &origins {
+            let fuzzed: Vec<GeoCoordinates> =
+                (0..500).map(|_| origin.fuzz(1_000.0, &mut rng)).collect();
+
+            for point in &fuzzed {
+                assert!(point.validate().is_ok());
+                assert!(origin.distance_to(point) <= 1_000.0 + 1e-3);
+                assert_eq!(point.altitude, None);
+            }
+            // Points spread over the circle rather than clustering at its center
+            assert!(fuzzed.iter().any(|point| origin.distance_to(point) > 900.0));
+        }
+
+        let exact = origins[0].fuzz(0.0, &mut rng);
+        assert!(origins[0].distance_to(&exact) < 1e-6);
+    }
+
+    #[test]
+    fn test_snap_to_grid_is_idempotent_and_groups_nearby_points() {
+        let original = GeoCoordinates::new(37.7749, -122.4194);
+        let snapped = original.snap_to_grid(1_000.0);
+
+        assert_eq!(snapped.snap_to_grid(1_000.0), snapped);
+        assert!(original.distance_to(&snapped) <= 1_000.0);
+
+        // Points a few meters from the cell center fall in the same cell
+        let neighbors = [
+            GeoCoordinates::new(snapped.latitude + 0.0001, snapped.longitude - 0.0001),
+            GeoCoordinates::new(snapped.latitude - 0.0001, snapped.longitude + 0.0001),
+        ];
+        for neighbor in &neighbors {
+            assert_eq!(neighbor.snap_to_grid(1_000.0), snapped);
+        }
+
+        let across_town = GeoCoordinates::new(37.8044, -122.2712).snap_to_grid(1_000.0);
+        assert_ne!(across_town, snapped);
+
+        // Cells near the poles and the antimeridian are stable too
+        for point in [GeoCoordinates::new(89.999, 45.0), GeoCoordinates::new(0.0, 180.0)] {
+            let snapped = point.snap_to_grid(5_000.0);
+            assert!(snapped.validate().is_ok());
+            assert_eq!(snapped.snap_to_grid(5_000.0), snapped);
+        }
+
+        assert_eq!(original.snap_to_grid(0.0), original);
+    }
 }
@@ -0,0 +1,115 @@
+//! Indoor position value object: building, floor and room
+
+use cim_domain::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+
+/// Position inside a building
+///
+/// Floors are numbered as the building numbers them, with negative floors
+/// below ground. `x` and `y` are optional local coordinates on the floor plan,
+/// in meters from the plan's origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndoorPosition {
+    /// Identifier or name of the building
+    pub building: String,
+    /// Floor number, negative below ground
+    pub floor: i32,
+    /// Room on the floor, if known
+    pub room: Option<String>,
+    /// Floor-plan x coordinate in meters
+    pub x: Option<f64>,
+    /// Floor-plan y coordinate in meters
+    pub y: Option<f64>,
+}
+
+impl IndoorPosition {
+    /// Create a position on a floor of a building
+    pub fn new(building: impl Into<String>, floor: i32) -> Self {
+        Self {
+            building: building.into(),
+            floor,
+            room: None,
+            x: None,
+            y: None,
+        }
+    }
+
+    /// Set the room
+    pub fn with_room(mut self, room: impl Into<String>) -> Self {
+        self.room = Some(room.into());
+        self
+    }
+
+    /// Set the floor-plan coordinates
+    pub fn with_plan_coordinates(mut self, x: f64, y: f64) -> Self {
+        self.x = Some(x);
+        self.y = Some(y);
+        self
+    }
+
+    /// Validate the position
+    pub fn validate(&self) -> DomainResult<()> {
+        if self.building.trim().is_empty() {
+            return Err(DomainError::ValidationError(
+                "Indoor position requires a building".to_string(),
+            ));
+        }
+
+        if self
+            .room
+            .as_ref()
+            .is_some_and(|room| room.trim().is_empty())
+        {
+            return Err(DomainError::ValidationError(
+                "Room cannot be blank".to_string(),
+            ));
+        }
+
+        if [self.x, self.y].iter().flatten().any(|v| !v.is_finite()) {
+            return Err(DomainError::ValidationError(
+                "Floor-plan coordinates must be finite".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether both positions are on the same floor of the same building
+    pub fn same_floor(&self, other: &IndoorPosition) -> bool {
+        self.building == other.building && self.floor == other.floor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_floor() {
+        let reception = IndoorPosition::new("HQ", 2).with_room("201");
+        let meeting_room = IndoorPosition::new("HQ", 2)
+            .with_room("214")
+            .with_plan_coordinates(12.5, 4.0);
+        let canteen = IndoorPosition::new("HQ", -1).with_room("B04");
+        let annex = IndoorPosition::new("Annex", 2).with_room("201");
+
+        assert!(reception.same_floor(&meeting_room));
+        assert!(meeting_room.same_floor(&reception));
+        assert!(!reception.same_floor(&canteen));
+        assert!(!reception.same_floor(&annex));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(IndoorPosition::new("HQ", 0).validate().is_ok());
+        assert!(IndoorPosition::new(" ", 0).validate().is_err());
+        assert!(IndoorPosition::new("HQ", 0)
+            .with_room("")
+            .validate()
+            .is_err());
+        assert!(IndoorPosition::new("HQ", 0)
+            .with_plan_coordinates(f64::NAN, 1.0)
+            .validate()
+            .is_err());
+    }
+}
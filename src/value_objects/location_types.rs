@@ -1,7 +1,9 @@
 //! Location type definitions
 
+use cim_domain::DomainError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 /// Types of locations
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -14,12 +16,37 @@ pub enum LocationType {
     Logical,
     /// Hybrid location with both physical and virtual aspects
     Hybrid,
+    /// Mobile location whose position changes over time (e.g., vehicle, tracked asset)
+    Mobile,
 }
 
 impl LocationType {
+    /// Every location type, in declaration order
+    pub const ALL: [LocationType; 5] = [
+        LocationType::Physical,
+        LocationType::Virtual,
+        LocationType::Logical,
+        LocationType::Hybrid,
+        LocationType::Mobile,
+    ];
+
+    /// Stable lowercase name, suitable for subjects and storage columns
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationType::Physical => "physical",
+            LocationType::Virtual => "virtual",
+            LocationType::Logical => "logical",
+            LocationType::Hybrid => "hybrid",
+            LocationType::Mobile => "mobile",
+        }
+    }
+
     /// Check if location can have physical attributes
     pub fn can_have_physical_attributes(&self) -> bool {
-        matches!(self, LocationType::Physical | LocationType::Hybrid)
+        matches!(
+            self,
+            LocationType::Physical | LocationType::Hybrid | LocationType::Mobile
+        )
     }
 
     /// Check if location can have virtual attributes
@@ -35,6 +62,43 @@ impl fmt::Display for LocationType {
             LocationType::Virtual => write!(f, "Virtual"),
             LocationType::Logical => write!(f, "Logical"),
             LocationType::Hybrid => write!(f, "Hybrid"),
+            LocationType::Mobile => write!(f, "Mobile"),
         }
     }
 }
+
+impl FromStr for LocationType {
+    type Err = DomainError;
+
+    /// Parse a name produced by [`LocationType::as_str`], ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LocationType::ALL
+            .into_iter()
+            .find(|location_type| location_type.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| DomainError::ValidationError(format!("Unknown location type: {s}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_type_round_trips_through_str() {
+        for location_type in LocationType::ALL {
+            let parsed: LocationType = location_type.as_str().parse().unwrap();
+            assert_eq!(parsed, location_type);
+
+            // Display names parse as well
+            let parsed: LocationType = location_type.to_string().parse().unwrap();
+            assert_eq!(parsed, location_type);
+        }
+    }
+
+    #[test]
+    fn test_location_type_rejects_unknown_names() {
+        assert!("".parse::<LocationType>().is_err());
+        assert!("building".parse::<LocationType>().is_err());
+        assert!("physical-ish".parse::<LocationType>().is_err());
+    }
+}
@@ -4,12 +4,16 @@
 // This module is reserved for future value object extractions
 
 mod address;
+mod boundary;
 mod coordinates;
+mod indoor_position;
 mod location_types;
 mod virtual_location;
 
 pub use address::*;
+pub use boundary::*;
 pub use coordinates::*;
+pub use indoor_position::*;
 pub use location_types::*;
 pub use virtual_location::*;
 
@@ -255,9 +255,7 @@ impl VirtualLocation {
 
     /// Add a URL to this virtual location
     pub fn add_url(&mut self, url: VirtualUrl) -> DomainResult<()> {
-        // Validate URL
-        Url::parse(&url.url)
-            .map_err(|e| DomainError::ValidationError(format!("Invalid URL: {e}")))?;
+        url.validate_for_type()?;
 
         self.urls.push(url);
         Ok(())
@@ -303,6 +301,34 @@ impl VirtualLocation {
     pub fn active_ips(&self) -> Vec<&IpAddress> {
         self.ip_addresses.iter().filter(|ip| ip.is_active).collect()
     }
+
+    /// Mark every entry for `url` active or inactive, e.g. after a reachability check
+    ///
+    /// Returns whether the URL belongs to this location.
+    pub fn set_url_active(&mut self, url: &str, active: bool) -> bool {
+        let mut found = false;
+        for entry in self.urls.iter_mut().filter(|u| u.url == url) {
+            entry.is_active = active;
+            found = true;
+        }
+        found
+    }
+
+    /// Mark every entry for `address` active or inactive
+    ///
+    /// Returns whether the address belongs to this location.
+    pub fn set_ip_active(&mut self, address: &IpAddr, active: bool) -> bool {
+        let mut found = false;
+        for entry in self
+            .ip_addresses
+            .iter_mut()
+            .filter(|ip| &ip.address == address)
+        {
+            entry.is_active = active;
+            found = true;
+        }
+        found
+    }
 }
 
 impl VirtualUrl {
@@ -331,6 +357,41 @@ impl VirtualUrl {
     pub fn is_secure(&self) -> bool {
         self.url.starts_with("https://") || self.url.starts_with("wss://")
     }
+
+    /// Check that the URL parses and its scheme suits its declared type
+    pub fn validate_for_type(&self) -> DomainResult<()> {
+        let parsed = Url::parse(&self.url)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid URL: {e}")))?;
+
+        match self.url_type.allowed_schemes() {
+            Some(schemes) if !schemes.contains(&parsed.scheme()) => {
+                Err(DomainError::ValidationError(format!(
+                    "{} URL must use one of the schemes {}, got '{}'",
+                    self.url_type,
+                    schemes.join(", "),
+                    parsed.scheme()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl UrlType {
+    /// URL schemes accepted for this type, or `None` when any scheme is fine
+    pub fn allowed_schemes(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Primary
+            | Self::Api
+            | Self::Documentation
+            | Self::Support
+            | Self::Status
+            | Self::Webhook
+            | Self::Cdn => Some(&["http", "https"]),
+            Self::Mirror => Some(&["http", "https", "ftp", "ftps", "rsync"]),
+            Self::Development | Self::Custom(_) => None,
+        }
+    }
 }
 
 impl IpAddress {
@@ -354,10 +415,54 @@ impl IpAddress {
     }
 
     /// Check if this is a private IP address
+    ///
+    /// IPv4 uses the RFC 1918 ranges; IPv6 counts unique local and link-local
+    /// addresses as private.
     pub fn is_private(&self) -> bool {
         match self.address {
             IpAddr::V4(ipv4) => ipv4.is_private(),
-            IpAddr::V6(ipv6) => ipv6.is_loopback() || ipv6.is_multicast(),
+            IpAddr::V6(_) => self.is_unique_local() || self.is_link_local(),
+        }
+    }
+
+    /// Check if this is a link-local address (`169.254.0.0/16` or `fe80::/10`)
+    pub fn is_link_local(&self) -> bool {
+        match self.address {
+            IpAddr::V4(ipv4) => ipv4.is_link_local(),
+            IpAddr::V6(ipv6) => ipv6.segments()[0] & 0xffc0 == 0xfe80,
+        }
+    }
+
+    /// Check if this is an IPv6 unique local address (`fc00::/7`)
+    pub fn is_unique_local(&self) -> bool {
+        match self.address {
+            IpAddr::V4(_) => false,
+            IpAddr::V6(ipv6) => ipv6.segments()[0] & 0xfe00 == 0xfc00,
+        }
+    }
+
+    /// Check if this address has global scope
+    ///
+    /// Only the scope is considered: private, link-local, loopback, unspecified,
+    /// broadcast and multicast addresses are excluded, while reserved global
+    /// ranges such as documentation prefixes still count as global.
+    pub fn is_global(&self) -> bool {
+        match self.address {
+            IpAddr::V4(ipv4) => {
+                !(ipv4.is_private()
+                    || ipv4.is_loopback()
+                    || ipv4.is_link_local()
+                    || ipv4.is_unspecified()
+                    || ipv4.is_broadcast()
+                    || ipv4.is_multicast())
+            }
+            IpAddr::V6(ipv6) => {
+                !(ipv6.is_loopback()
+                    || ipv6.is_unspecified()
+                    || ipv6.is_multicast()
+                    || self.is_link_local()
+                    || self.is_unique_local())
+            }
         }
     }
 
@@ -473,6 +578,32 @@ mod tests {
         assert_eq!(ip6.address, IpAddr::V6("2001:db8::1".parse().unwrap()));
     }
 
+    #[test]
+    fn test_ipv6_scope_classification() {
+        let link_local = IpAddress::new("fe80::1", IpAddressType::Internal).unwrap();
+        assert!(link_local.is_link_local());
+        assert!(!link_local.is_unique_local());
+        assert!(link_local.is_private());
+        assert!(!link_local.is_global());
+
+        let unique_local = IpAddress::new("fc00::1", IpAddressType::Internal).unwrap();
+        assert!(unique_local.is_unique_local());
+        assert!(!unique_local.is_link_local());
+        assert!(unique_local.is_private());
+        assert!(!unique_local.is_global());
+
+        let documentation = IpAddress::new("2001:db8::1", IpAddressType::Primary).unwrap();
+        assert!(!documentation.is_link_local());
+        assert!(!documentation.is_unique_local());
+        assert!(!documentation.is_private());
+        assert!(documentation.is_global());
+
+        let loopback = IpAddress::new("::1", IpAddressType::Internal).unwrap();
+        assert!(loopback.is_loopback());
+        assert!(!loopback.is_private());
+        assert!(!loopback.is_global());
+    }
+
     #[test]
     fn test_url_validation() {
         let url = VirtualUrl::new("https://api.example.com/v1".to_string(), UrlType::Api).unwrap();
@@ -481,6 +612,40 @@ mod tests {
         assert_eq!(url.domain(), Some("api.example.com".to_string()));
     }
 
+    #[test]
+    fn test_url_scheme_must_match_type() {
+        let mut location =
+            VirtualLocation::website("https://example.com", "Example Website".to_string()).unwrap();
+
+        let https_webhook = VirtualUrl::new(
+            "https://example.com/hooks/orders".to_string(),
+            UrlType::Webhook,
+        )
+        .unwrap();
+        assert!(https_webhook.validate_for_type().is_ok());
+        assert!(location.add_url(https_webhook).is_ok());
+
+        let ftp_webhook =
+            VirtualUrl::new("ftp://example.com/hooks".to_string(), UrlType::Webhook).unwrap();
+        assert!(ftp_webhook.validate_for_type().is_err());
+        assert!(location.add_url(ftp_webhook).is_err());
+        assert_eq!(location.urls.len(), 2);
+
+        let ftp_mirror =
+            VirtualUrl::new("ftp://mirror.example.com/pub".to_string(), UrlType::Mirror).unwrap();
+        assert!(ftp_mirror.validate_for_type().is_ok());
+
+        for url in [
+            "ftp://example.com/x",
+            "s3://bucket/key",
+            "ssh://git@example.com/repo",
+        ] {
+            let custom =
+                VirtualUrl::new(url.to_string(), UrlType::Custom("Artifacts".to_string())).unwrap();
+            assert!(custom.validate_for_type().is_ok());
+        }
+    }
+
     #[test]
     fn test_cloud_service_location() {
         let cloud = VirtualLocation::cloud_service(
@@ -0,0 +1,103 @@
+//! Execution of workflow node actions
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use super::{WorkflowAction, WorkflowContext, WorkflowError, WorkflowResult};
+
+/// Runs a workflow action against the instance context
+#[async_trait]
+pub trait ActionExecutor: Send + Sync {
+    /// Execute the action, recording any results in the context
+    async fn execute(&self, action: &WorkflowAction, ctx: &mut WorkflowContext) -> WorkflowResult<()>;
+}
+
+/// Maps action types to the executors that run them
+#[derive(Clone, Default)]
+pub struct ActionRegistry {
+    executors: HashMap<String, Arc<dyn ActionExecutor>>,
+}
+
+impl ActionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the executor for an action type, replacing any previous one
+    pub fn register(&mut self, action_type: impl Into<String>, executor: Arc<dyn ActionExecutor>) {
+        self.executors.insert(action_type.into(), executor);
+    }
+
+    /// Check whether an executor is registered for the action type
+    pub fn is_registered(&self, action_type: &str) -> bool {
+        self.executors.contains_key(action_type)
+    }
+
+    /// Execute a single action with its registered executor
+    pub async fn execute(&self, action: &WorkflowAction, ctx: &mut WorkflowContext) -> WorkflowResult<()> {
+        let executor = self.executors.get(&action.action_type).ok_or_else(|| WorkflowError::EngineError {
+            message: format!("No executor registered for action type '{}'", action.action_type),
+        })?;
+        executor.execute(action, ctx).await
+    }
+
+    /// Execute actions in order, stopping at the first failure
+    pub async fn execute_all(&self, actions: &[WorkflowAction], ctx: &mut WorkflowContext) -> WorkflowResult<()> {
+        for action in actions {
+            self.execute(action, ctx).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies every action parameter into the context variables
+    struct SetVariables;
+
+    #[async_trait]
+    impl ActionExecutor for SetVariables {
+        async fn execute(&self, action: &WorkflowAction, ctx: &mut WorkflowContext) -> WorkflowResult<()> {
+            for (key, value) in &action.parameters {
+                ctx.set_variable(key.clone(), value.clone());
+            }
+            Ok(())
+        }
+    }
+
+    fn action(action_type: &str) -> WorkflowAction {
+        WorkflowAction {
+            action_type: action_type.to_string(),
+            parameters: HashMap::from([("geocoded".to_string(), serde_json::json!(true))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_action_mutates_context() {
+        let mut registry = ActionRegistry::new();
+        registry.register("geocode_address", Arc::new(SetVariables));
+        let mut ctx = WorkflowContext::new();
+
+        assert!(registry.is_registered("geocode_address"));
+        registry.execute(&action("geocode_address"), &mut ctx).await.unwrap();
+
+        assert_eq!(ctx.get_variable("geocoded"), Some(&serde_json::json!(true)));
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_action_is_engine_error() {
+        let mut registry = ActionRegistry::new();
+        registry.register("geocode_address", Arc::new(SetVariables));
+        let mut ctx = WorkflowContext::new();
+
+        let result = registry
+            .execute_all(&[action("notify_reviewers"), action("geocode_address")], &mut ctx)
+            .await;
+
+        assert!(matches!(result, Err(WorkflowError::EngineError { message }) if message.contains("notify_reviewers")));
+        // Later actions do not run after a failure
+        assert!(ctx.get_variable("geocoded").is_none());
+    }
+}
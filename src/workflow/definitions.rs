@@ -1,10 +1,11 @@
 //! Workflow definitions for location workflows
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use super::{WorkflowId, NodeId, WorkflowResult, WorkflowError};
+use super::{WorkflowId, NodeId, WorkflowContext, WorkflowResult, WorkflowError};
 
 /// Workflow definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +47,9 @@ pub struct WorkflowNode {
     pub actions: Vec<WorkflowAction>,
     /// Required permissions to complete this node
     pub required_permissions: Vec<String>,
+    /// How long the node may stay active before the instance fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Duration>,
 }
 
 /// Types of workflow nodes
@@ -100,6 +104,10 @@ pub struct WorkflowAction {
 
 impl WorkflowDefinition {
     /// Validate workflow definition
+    ///
+    /// Beyond checking that the start and end nodes exist, every problem in the
+    /// node graph is reported at once: transitions to unknown nodes, end nodes
+    /// with outgoing transitions, and nodes unreachable from the start node.
     pub fn validate(&self) -> WorkflowResult<()> {
         // Check that start node exists
         if !self.nodes.contains_key(&self.start_node) {
@@ -117,22 +125,76 @@ impl WorkflowDefinition {
             }
         }
         
+        let mut problems = Vec::new();
+        
         // Check that all transition targets exist
-        for (node_id, node) in &self.nodes {
-            for transition in &node.transitions {
-                if !self.nodes.contains_key(&transition.to_node) {
-                    return Err(WorkflowError::InvalidDefinition {
-                        reason: format!(
-                            "Node '{}' has transition to non-existent node '{}'",
-                            node_id.as_str(),
-                            transition.to_node.as_str()
-                        ),
-                    });
+        let mut dangling: Vec<String> = self.nodes
+            .iter()
+            .flat_map(|(node_id, node)| {
+                node.transitions
+                    .iter()
+                    .filter(|t| !self.nodes.contains_key(&t.to_node))
+                    .map(move |t| format!("'{}' -> '{}'", node_id.as_str(), t.to_node.as_str()))
+            })
+            .collect();
+        if !dangling.is_empty() {
+            dangling.sort();
+            problems.push(format!("transitions to non-existent nodes: {}", dangling.join(", ")));
+        }
+        
+        // End nodes are terminal
+        let mut terminal_with_exits: Vec<&str> = self.nodes
+            .values()
+            .filter(|node| matches!(node.node_type, NodeType::End) || self.end_nodes.contains(&node.id))
+            .filter(|node| !node.transitions.is_empty())
+            .map(|node| node.id.as_str())
+            .collect();
+        if !terminal_with_exits.is_empty() {
+            terminal_with_exits.sort();
+            problems.push(format!(
+                "end nodes with outgoing transitions: {}",
+                terminal_with_exits.join(", ")
+            ));
+        }
+        
+        let reachable = self.reachable_nodes();
+        let mut unreachable: Vec<&str> = self.nodes
+            .keys()
+            .filter(|node_id| !reachable.contains(node_id))
+            .map(|node_id| node_id.as_str())
+            .collect();
+        if !unreachable.is_empty() {
+            unreachable.sort();
+            problems.push(format!(
+                "nodes unreachable from '{}': {}",
+                self.start_node.as_str(),
+                unreachable.join(", ")
+            ));
+        }
+        
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(WorkflowError::InvalidDefinition {
+                reason: problems.join("; "),
+            })
+        }
+    }
+    
+    /// Nodes reachable from the start node by following transitions
+    fn reachable_nodes(&self) -> HashSet<&NodeId> {
+        let mut reachable = HashSet::from([&self.start_node]);
+        let mut queue = VecDeque::from([&self.start_node]);
+        
+        while let Some(node_id) = queue.pop_front() {
+            for next in self.get_next_nodes(node_id) {
+                if self.nodes.contains_key(next) && reachable.insert(next) {
+                    queue.push_back(next);
                 }
             }
         }
         
-        Ok(())
+        reachable
     }
     
     /// Get node by ID
@@ -164,11 +226,11 @@ impl WorkflowNode {
 
 impl TransitionCondition {
     /// Evaluate condition against workflow context
-    pub fn evaluate(&self, context: &serde_json::Value) -> bool {
+    pub fn evaluate(&self, context: &WorkflowContext) -> bool {
         match self {
             TransitionCondition::Always => true,
             TransitionCondition::VariableEquals { name, value } => {
-                context.get(name).map_or(false, |v| v == value)
+                context.get_variable(name) == Some(value)
             },
             TransitionCondition::HasPermission { .. } => {
                 // Mock implementation - would check user permissions
@@ -185,7 +247,7 @@ impl TransitionCondition {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::workflow::{WorkflowId, NodeId};
+    use crate::workflow::{WorkflowId, NodeId, WorkflowContext};
 
     #[test]
     fn test_workflow_definition_validation() {
@@ -206,6 +268,7 @@ mod tests {
             }],
             actions: vec![],
             required_permissions: vec![],
+            timeout: None,
         });
         nodes.insert(end_node_id.clone(), WorkflowNode {
             id: end_node_id.clone(),
@@ -215,6 +278,7 @@ mod tests {
             transitions: vec![],
             actions: vec![],
             required_permissions: vec![],
+            timeout: None,
         });
         
         let workflow = WorkflowDefinition {
@@ -232,6 +296,95 @@ mod tests {
         assert!(workflow.validate().is_ok());
     }
     
+    fn node(id: &str, node_type: NodeType, targets: &[&str]) -> WorkflowNode {
+        WorkflowNode {
+            id: NodeId::from(id),
+            name: id.to_string(),
+            description: None,
+            node_type,
+            transitions: targets
+                .iter()
+                .map(|target| NodeTransition {
+                    to_node: NodeId::from(*target),
+                    condition: Some(TransitionCondition::Always),
+                    label: None,
+                })
+                .collect(),
+            actions: vec![],
+            required_permissions: vec![],
+            timeout: None,
+        }
+    }
+    
+    fn definition(nodes: Vec<WorkflowNode>, end_nodes: &[&str]) -> WorkflowDefinition {
+        WorkflowDefinition {
+            id: WorkflowId::new(),
+            name: "Test Workflow".to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            nodes: nodes.into_iter().map(|n| (n.id.clone(), n)).collect(),
+            start_node: NodeId::from("start"),
+            end_nodes: end_nodes.iter().map(|id| NodeId::from(*id)).collect(),
+            created_at: chrono::Utc::now(),
+            created_by: Uuid::new_v4(),
+        }
+    }
+    
+    fn invalid_reason(workflow: &WorkflowDefinition) -> String {
+        match workflow.validate() {
+            Err(WorkflowError::InvalidDefinition { reason }) => reason,
+            other => panic!("Expected InvalidDefinition, got {other:?}"),
+        }
+    }
+    
+    #[test]
+    fn test_validation_accepts_branching_workflow() {
+        let workflow = definition(
+            vec![
+                node("start", NodeType::Start, &["review"]),
+                node("review", NodeType::Decision, &["approved", "rejected", "start"]),
+                node("approved", NodeType::End, &[]),
+                node("rejected", NodeType::End, &[]),
+            ],
+            &["approved", "rejected"],
+        );
+        
+        assert!(workflow.validate().is_ok());
+    }
+    
+    #[test]
+    fn test_validation_reports_orphan_nodes() {
+        let workflow = definition(
+            vec![
+                node("start", NodeType::Start, &["end"]),
+                node("orphan", NodeType::Task, &["end"]),
+                node("end", NodeType::End, &[]),
+            ],
+            &["end"],
+        );
+        
+        let reason = invalid_reason(&workflow);
+        assert!(reason.contains("unreachable"));
+        assert!(reason.contains("orphan"));
+    }
+    
+    #[test]
+    fn test_validation_reports_dangling_transitions_and_exits_from_end_nodes() {
+        let workflow = definition(
+            vec![
+                node("start", NodeType::Start, &["task"]),
+                node("task", NodeType::Task, &["end", "missing"]),
+                node("end", NodeType::End, &["task"]),
+            ],
+            &["end"],
+        );
+        
+        let reason = invalid_reason(&workflow);
+        assert!(reason.contains("'task' -> 'missing'"));
+        assert!(reason.contains("end nodes with outgoing transitions: end"));
+        assert!(!reason.contains("unreachable"));
+    }
+    
     #[test]
     fn test_transition_condition_evaluation() {
         let condition = TransitionCondition::VariableEquals {
@@ -239,16 +392,16 @@ mod tests {
             value: serde_json::json!("approved"),
         };
         
-        let context = serde_json::json!({
-            "status": "approved"
-        });
+        let mut context = WorkflowContext::new();
+        context.set_variable("status".into(), serde_json::json!("approved"));
         
         assert!(condition.evaluate(&context));
         
-        let wrong_context = serde_json::json!({
-            "status": "pending"
-        });
+        let mut wrong_context = WorkflowContext::new();
+        wrong_context.set_variable("status".into(), serde_json::json!("pending"));
         
         assert!(!condition.evaluate(&wrong_context));
+        assert!(!condition.evaluate(&WorkflowContext::new()));
+        assert!(TransitionCondition::Always.evaluate(&WorkflowContext::new()));
     }
 }
\ No newline at end of file
@@ -1,13 +1,26 @@
 //! Predefined location-specific workflows
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 use chrono::Utc;
+use crate::domain_events::LocationDomainEvent;
+use crate::events::{HierarchyReorganized, ParentLocationRemoved, ParentLocationSet};
+use crate::ports::EventPublisher;
+use crate::projections::LocationReadModel;
 use super::{
     WorkflowId, NodeId, WorkflowDefinition, WorkflowNode, NodeType,
     NodeTransition, TransitionCondition, WorkflowAction,
+    ActionExecutor, WorkflowContext, WorkflowError, WorkflowResult,
 };
 
+/// Context variable holding the planned `(location_id, old_parent, new_parent)` changes
+pub const REORGANIZATION_PLAN_VARIABLE: &str = "reorganization_plan";
+/// Context variable holding the reason recorded on the reorganization event
+pub const REORGANIZATION_REASON_VARIABLE: &str = "reorganization_reason";
+
 /// Create location verification workflow
 pub fn create_location_verification_workflow() -> WorkflowDefinition {
     let workflow_id = WorkflowId::new_named("location_verification");
@@ -38,6 +51,7 @@ pub fn create_location_verification_workflow() -> WorkflowDefinition {
             }
         ],
         required_permissions: vec!["location.submit".to_string()],
+        timeout: None,
     });
     
     // Review node
@@ -66,6 +80,7 @@ pub fn create_location_verification_workflow() -> WorkflowDefinition {
         ],
         actions: vec![],
         required_permissions: vec!["location.review".to_string()],
+        timeout: None,
     });
     
     // Verify node
@@ -103,6 +118,7 @@ pub fn create_location_verification_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec!["location.verify".to_string()],
+        timeout: None,
     });
     
     // Approved node
@@ -123,6 +139,7 @@ pub fn create_location_verification_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec![],
+        timeout: None,
     });
     
     // Rejected node
@@ -139,6 +156,7 @@ pub fn create_location_verification_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec![],
+        timeout: None,
     });
     
     WorkflowDefinition {
@@ -179,6 +197,7 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
         }],
         actions: vec![],
         required_permissions: vec!["hierarchy.plan".to_string()],
+        timeout: None,
     });
     
     // Validate reorganization
@@ -212,6 +231,7 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec![],
+        timeout: None,
     });
     
     // Execute reorganization
@@ -249,6 +269,7 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec!["hierarchy.execute".to_string()],
+        timeout: None,
     });
     
     // Completed node
@@ -265,6 +286,7 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec![],
+        timeout: None,
     });
     
     // Failed node
@@ -285,6 +307,7 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
             },
         ],
         required_permissions: vec![],
+        timeout: None,
     });
     
     WorkflowDefinition {
@@ -300,6 +323,121 @@ pub fn create_hierarchy_reorganization_workflow() -> WorkflowDefinition {
     }
 }
 
+/// Executes the `update_parent_child_relationships` step of the hierarchy
+/// reorganization workflow
+///
+/// Publishes a `ParentLocationSet` or `ParentLocationRemoved` event for every
+/// planned parent change, so each location's own history records it, followed
+/// by a `HierarchyReorganized` event summarizing the whole plan. All of them go
+/// out as one batch. A plan that would make a location its own ancestor is
+/// rejected before anything is published.
+pub struct HierarchyReorganizationExecutor {
+    publisher: Arc<dyn EventPublisher>,
+    /// Current hierarchy, used to find cycles through locations the plan leaves alone
+    read_model: Option<Arc<RwLock<LocationReadModel>>>,
+}
+
+impl HierarchyReorganizationExecutor {
+    pub fn new(publisher: Arc<dyn EventPublisher>) -> Self {
+        Self {
+            publisher,
+            read_model: None,
+        }
+    }
+
+    /// Check plans against the current hierarchy of this read model
+    ///
+    /// Without one, only cycles formed by the planned changes themselves are
+    /// detected.
+    pub fn with_read_model(mut self, read_model: Arc<RwLock<LocationReadModel>>) -> Self {
+        self.read_model = Some(read_model);
+        self
+    }
+
+    /// Find a planned location that would become its own ancestor
+    fn find_cycle(
+        affected: &[(Uuid, Option<Uuid>, Option<Uuid>)],
+        current_parents: &HashMap<Uuid, Uuid>,
+    ) -> Option<Uuid> {
+        let planned: HashMap<Uuid, Option<Uuid>> = affected
+            .iter()
+            .map(|(location_id, _, new_parent)| (*location_id, *new_parent))
+            .collect();
+        let parent_of = |id: Uuid| match planned.get(&id) {
+            Some(new_parent) => *new_parent,
+            None => current_parents.get(&id).copied(),
+        };
+
+        affected.iter().map(|(location_id, _, _)| *location_id).find(|location_id| {
+            let mut seen = HashSet::new();
+            let mut current = Some(*location_id);
+            while let Some(id) = current {
+                if !seen.insert(id) {
+                    return true;
+                }
+                current = parent_of(id);
+            }
+            false
+        })
+    }
+}
+
+#[async_trait]
+impl ActionExecutor for HierarchyReorganizationExecutor {
+    async fn execute(&self, _action: &WorkflowAction, ctx: &mut WorkflowContext) -> WorkflowResult<()> {
+        let location_id = ctx.location_id.ok_or_else(|| WorkflowError::EngineError {
+            message: "Hierarchy reorganization requires a location".to_string(),
+        })?;
+        let affected = ctx
+            .get_variable_as::<Vec<(Uuid, Option<Uuid>, Option<Uuid>)>>(REORGANIZATION_PLAN_VARIABLE)
+            .ok_or_else(|| WorkflowError::EngineError {
+                message: format!("Missing or invalid '{REORGANIZATION_PLAN_VARIABLE}' variable"),
+            })?;
+        let reason = ctx
+            .get_variable_as::<String>(REORGANIZATION_REASON_VARIABLE)
+            .unwrap_or_else(|| "Hierarchy reorganization".to_string());
+        
+        let current_parents = match &self.read_model {
+            Some(read_model) => read_model.read().await.hierarchy.child_parent_map.clone(),
+            None => HashMap::new(),
+        };
+        if let Some(location_id) = Self::find_cycle(&affected, &current_parents) {
+            return Err(WorkflowError::EngineError {
+                message: format!("Reorganization would make {location_id} its own ancestor"),
+            });
+        }
+        
+        let mut events: Vec<LocationDomainEvent> = affected
+            .iter()
+            .filter_map(|(location_id, old_parent, new_parent)| match (new_parent, old_parent) {
+                (Some(parent_id), _) => Some(LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                    location_id: *location_id,
+                    parent_id: *parent_id,
+                    previous_parent_id: *old_parent,
+                    reason: reason.clone(),
+                })),
+                (None, Some(previous_parent_id)) => Some(LocationDomainEvent::ParentLocationRemoved(ParentLocationRemoved {
+                    location_id: *location_id,
+                    previous_parent_id: *previous_parent_id,
+                    reason: reason.clone(),
+                })),
+                (None, None) => None,
+            })
+            .collect();
+        events.push(LocationDomainEvent::HierarchyReorganized(HierarchyReorganized {
+            location_id,
+            affected,
+            reason,
+        }));
+        self.publisher.publish_batch(&events).await.map_err(|e| WorkflowError::EngineError {
+            message: e.to_string(),
+        })?;
+        
+        ctx.set_variable("execution_result".to_string(), serde_json::json!("success"));
+        Ok(())
+    }
+}
+
 /// Get all predefined location workflows
 pub fn get_predefined_workflows() -> Vec<WorkflowDefinition> {
     vec![
@@ -311,6 +449,7 @@ pub fn get_predefined_workflows() -> Vec<WorkflowDefinition> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ports::{PublishError, QueryError};
 
     #[test]
     fn test_location_verification_workflow() {
@@ -344,6 +483,123 @@ mod tests {
         assert!(workflow.nodes.contains_key(&NodeId::from("failed")));
     }
     
+    #[derive(Default)]
+    struct RecordingPublisher {
+        events: std::sync::Mutex<Vec<LocationDomainEvent>>,
+    }
+    
+    #[async_trait]
+    impl EventPublisher for RecordingPublisher {
+        async fn publish(&self, event: &LocationDomainEvent) -> Result<(), PublishError> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+        
+        async fn publish_batch(&self, events: &[LocationDomainEvent]) -> Result<(), PublishError> {
+            self.events.lock().unwrap().extend_from_slice(events);
+            Ok(())
+        }
+        
+        async fn query_by_correlation(&self, _correlation_id: Uuid) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+        
+        async fn query_by_aggregate(&self, _aggregate_id: Uuid) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+        
+        async fn query_by_time_range(
+            &self,
+            _start: chrono::DateTime<Utc>,
+            _end: chrono::DateTime<Utc>,
+        ) -> Result<Vec<LocationDomainEvent>, QueryError> {
+            Ok(Vec::new())
+        }
+    }
+    
+    fn reorganize_action() -> WorkflowAction {
+        WorkflowAction {
+            action_type: "update_parent_child_relationships".to_string(),
+            parameters: HashMap::new(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_reorganization_executor_publishes_plan() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let executor = HierarchyReorganizationExecutor::new(publisher.clone());
+        let root = Uuid::new_v4();
+        let child = Uuid::new_v4();
+        let orphan = Uuid::new_v4();
+        let plan = vec![(child, None::<Uuid>, Some(root)), (orphan, Some(root), None)];
+        
+        let mut ctx = WorkflowContext::new().with_location(root);
+        ctx.set_variable(REORGANIZATION_PLAN_VARIABLE.to_string(), serde_json::json!(plan));
+        executor.execute(&reorganize_action(), &mut ctx).await.unwrap();
+        
+        assert_eq!(ctx.get_variable_as::<String>("execution_result"), Some("success".to_string()));
+        let events = publisher.events.lock().unwrap();
+        match events.as_slice() {
+            [
+                LocationDomainEvent::ParentLocationSet(set),
+                LocationDomainEvent::ParentLocationRemoved(removed),
+                LocationDomainEvent::HierarchyReorganized(e),
+            ] => {
+                assert_eq!((set.location_id, set.parent_id, set.previous_parent_id), (child, root, None));
+                assert_eq!((removed.location_id, removed.previous_parent_id), (orphan, root));
+                assert_eq!(e.location_id, root);
+                assert_eq!(e.affected, plan);
+                assert_eq!(e.reason, "Hierarchy reorganization");
+            }
+            other => panic!("Expected per-location events and a HierarchyReorganized event, got {other:?}"),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_reorganization_executor_rejects_cycles() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        
+        // The planned changes alone form a cycle
+        let executor = HierarchyReorganizationExecutor::new(publisher.clone());
+        let mut ctx = WorkflowContext::new().with_location(a);
+        let plan = vec![(a, None::<Uuid>, Some(b)), (b, None, Some(a))];
+        ctx.set_variable(REORGANIZATION_PLAN_VARIABLE.to_string(), serde_json::json!(plan));
+        let result = executor.execute(&reorganize_action(), &mut ctx).await;
+        assert!(matches!(result, Err(WorkflowError::EngineError { .. })));
+        
+        // The cycle runs through c, which the plan leaves under a
+        let read_model = LocationReadModel::replay([
+            LocationDomainEvent::ParentLocationSet(ParentLocationSet {
+                location_id: c,
+                parent_id: a,
+                previous_parent_id: None,
+                reason: "test".to_string(),
+            }),
+        ]);
+        let executor = HierarchyReorganizationExecutor::new(publisher.clone())
+            .with_read_model(Arc::new(RwLock::new(read_model)));
+        let mut ctx = WorkflowContext::new().with_location(a);
+        let plan = vec![(b, None::<Uuid>, Some(c)), (a, None, Some(b))];
+        ctx.set_variable(REORGANIZATION_PLAN_VARIABLE.to_string(), serde_json::json!(plan));
+        let result = executor.execute(&reorganize_action(), &mut ctx).await;
+        assert!(matches!(result, Err(WorkflowError::EngineError { .. })));
+        
+        assert!(publisher.events.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_reorganization_executor_requires_plan() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let executor = HierarchyReorganizationExecutor::new(publisher.clone());
+        let mut ctx = WorkflowContext::new().with_location(Uuid::new_v4());
+        
+        let result = executor.execute(&reorganize_action(), &mut ctx).await;
+        
+        assert!(matches!(result, Err(WorkflowError::EngineError { .. })));
+        assert!(publisher.events.lock().unwrap().is_empty());
+    }
+    
     #[test]
     fn test_predefined_workflows() {
         let workflows = get_predefined_workflows();
@@ -10,7 +10,7 @@ use chrono::{DateTime, Utc};
 use super::{
     WorkflowId, WorkflowInstanceId, NodeId, WorkflowStatus, WorkflowContext, 
     WorkflowTransition, NodeStatus, WorkflowResult, WorkflowError,
-    WorkflowDefinition,
+    WorkflowDefinition, WorkflowNode, ActionRegistry,
 };
 
 /// Workflow manager trait
@@ -51,6 +51,23 @@ pub trait WorkflowManager: Send + Sync {
     
     /// Get workflow history
     async fn get_history(&self, instance_id: &WorkflowInstanceId) -> WorkflowResult<Vec<WorkflowTransition>>;
+    
+    /// List workflow instances, optionally only those with the given status
+    ///
+    /// A `Failed` filter matches every failed instance, whatever its reason.
+    async fn list_instances(&self, filter: Option<WorkflowStatus>) -> WorkflowResult<Vec<WorkflowInstance>>;
+    
+    /// Count workflow instances per status
+    ///
+    /// Failed instances are counted together under `WorkflowStatus::Failed`
+    /// with an empty reason.
+    async fn count_by_status(&self) -> WorkflowResult<HashMap<WorkflowStatus, usize>> {
+        let mut counts = HashMap::new();
+        for instance in self.list_instances(None).await? {
+            *counts.entry(instance.status.bucket()).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
 }
 
 /// Workflow instance
@@ -126,6 +143,7 @@ pub struct MockWorkflowManager {
     definitions: Arc<RwLock<HashMap<WorkflowId, WorkflowDefinition>>>,
     instances: Arc<RwLock<HashMap<WorkflowInstanceId, WorkflowInstance>>>,
     transitions: Arc<RwLock<HashMap<WorkflowInstanceId, Vec<WorkflowTransition>>>>,
+    actions: Option<ActionRegistry>,
 }
 
 impl MockWorkflowManager {
@@ -134,14 +152,97 @@ impl MockWorkflowManager {
             definitions: Arc::new(RwLock::new(HashMap::new())),
             instances: Arc::new(RwLock::new(HashMap::new())),
             transitions: Arc::new(RwLock::new(HashMap::new())),
+            actions: None,
         }
     }
     
+    /// Run node actions with the given registry whenever a node is entered
+    ///
+    /// Without a registry, node actions are not executed. With one, an action type
+    /// that has no registered executor fails the node entry.
+    pub fn with_actions(mut self, actions: ActionRegistry) -> Self {
+        self.actions = Some(actions);
+        self
+    }
+    
     pub async fn add_definition(&self, definition: WorkflowDefinition) {
         let mut definitions = self.definitions.write().await;
         definitions.insert(definition.id.clone(), definition);
     }
 
+    /// Fail running instances whose current node has been active past its timeout
+    ///
+    /// A node is considered entered at the last transition into it, or at instance
+    /// creation for the start node. Returns the IDs of the instances that were failed.
+    pub async fn check_timeouts(&self, now: DateTime<Utc>) -> Vec<WorkflowInstanceId> {
+        let definitions = self.definitions.read().await;
+        let mut instances = self.instances.write().await;
+        let mut transitions = self.transitions.write().await;
+        let mut timed_out = Vec::new();
+
+        for instance in instances.values_mut() {
+            if !matches!(instance.status, WorkflowStatus::Running | WorkflowStatus::Waiting) {
+                continue;
+            }
+            if instance.get_node_status(&instance.current_node) != NodeStatus::Active {
+                continue;
+            }
+
+            let timeout = definitions
+                .get(&instance.workflow_id)
+                .and_then(|definition| definition.get_node(&instance.current_node))
+                .and_then(|node| node.timeout)
+                .and_then(|timeout| chrono::Duration::from_std(timeout).ok());
+            let timeout = match timeout {
+                Some(timeout) => timeout,
+                None => continue,
+            };
+
+            let history = transitions.entry(instance.id).or_default();
+            let entered_at = history
+                .iter()
+                .rev()
+                .find(|t| t.to_node == instance.current_node)
+                .map_or(instance.created_at, |t| t.transitioned_at);
+
+            if now - entered_at <= timeout {
+                continue;
+            }
+
+            let reason = format!(
+                "Node {} timed out after {}s",
+                instance.current_node.as_str(),
+                timeout.num_seconds()
+            );
+
+            history.push(WorkflowTransition {
+                id: Uuid::new_v4(),
+                from_node: instance.current_node.clone(),
+                to_node: instance.current_node.clone(),
+                transitioned_at: now,
+                transitioned_by: None,
+                reason: Some(reason.clone()),
+                data: HashMap::new(),
+            });
+
+            instance.set_node_status(instance.current_node.clone(), NodeStatus::Failed(reason.clone()));
+            instance.status = WorkflowStatus::Failed(reason);
+            instance.completed_at = Some(now);
+            instance.updated_at = now;
+            timed_out.push(instance.id);
+        }
+
+        timed_out
+    }
+
+    /// Execute the entry actions of a node against the context
+    async fn enter_node(&self, node: &WorkflowNode, context: &mut WorkflowContext) -> WorkflowResult<()> {
+        match &self.actions {
+            Some(actions) => actions.execute_all(&node.actions, context).await,
+            None => Ok(()),
+        }
+    }
+
     async fn get_definition(&self, workflow_id: &WorkflowId) -> WorkflowResult<WorkflowDefinition> {
         let definitions = self.definitions.read().await;
         definitions.get(workflow_id).cloned().ok_or_else(|| WorkflowError::WorkflowNotFound {
@@ -161,10 +262,14 @@ impl WorkflowManager for MockWorkflowManager {
     async fn start_workflow(
         &self,
         workflow_id: &WorkflowId,
-        context: WorkflowContext,
+        mut context: WorkflowContext,
     ) -> WorkflowResult<WorkflowInstance> {
         let definition = self.get_definition(workflow_id).await?;
 
+        if let Some(start_node) = definition.get_node(&definition.start_node) {
+            self.enter_node(start_node, &mut context).await?;
+        }
+
         let mut instance = WorkflowInstance::new(
             workflow_id.clone(),
             definition.start_node.clone(),
@@ -237,6 +342,11 @@ impl WorkflowManager for MockWorkflowManager {
             instance.context = new_context;
         }
         
+        // Run the target node's entry actions; a failure leaves the stored instance untouched
+        if let Some(target) = definition.get_node(target_node) {
+            self.enter_node(target, &mut instance.context).await?;
+        }
+        
         // Check if workflow is complete
         if definition.end_nodes.contains(target_node) {
             instance.status = WorkflowStatus::Completed;
@@ -256,11 +366,16 @@ impl WorkflowManager for MockWorkflowManager {
         &self,
         instance_id: &WorkflowInstanceId,
         _user_id: Option<Uuid>,
-        _completion_data: Option<serde_json::Value>,
+        completion_data: Option<serde_json::Value>,
     ) -> WorkflowResult<WorkflowInstance> {
-        let instance = self.get_instance(instance_id).await?;
+        let mut instance = self.get_instance(instance_id).await?;
         let definition = self.get_definition(&instance.workflow_id).await?;
         
+        // Completion data fields become context variables for condition evaluation
+        if let Some(serde_json::Value::Object(data)) = completion_data {
+            instance.context.variables.extend(data);
+        }
+        
         // Find next node based on transitions
         let current_node = definition.get_node(&instance.current_node)
             .ok_or_else(|| WorkflowError::InvalidTransition {
@@ -269,9 +384,18 @@ impl WorkflowManager for MockWorkflowManager {
                 reason: "Current node not found".to_string(),
             })?;
         
-        // For simplicity, take first available transition
-        if let Some(transition) = current_node.transitions.first() {
-            self.advance_workflow(instance_id, &transition.to_node, None).await
+        if !current_node.transitions.is_empty() {
+            // Take the first transition whose condition holds; no condition means always
+            let transition = current_node.transitions.iter()
+                .find(|t| t.condition.as_ref().map_or(true, |c| c.evaluate(&instance.context)))
+                .ok_or_else(|| WorkflowError::InvalidTransition {
+                    from: instance.current_node.as_str().to_string(),
+                    to: "unknown".to_string(),
+                    reason: "No transition condition matched".to_string(),
+                })?;
+            
+            let context = instance.context.clone();
+            self.advance_workflow(instance_id, &transition.to_node, Some(context)).await
         } else {
             // No transitions available, mark as completed
             let mut updated_instance = instance;
@@ -306,16 +430,34 @@ impl WorkflowManager for MockWorkflowManager {
         let transitions = self.transitions.read().await;
         Ok(transitions.get(instance_id).cloned().unwrap_or_default())
     }
+    
+    async fn list_instances(&self, filter: Option<WorkflowStatus>) -> WorkflowResult<Vec<WorkflowInstance>> {
+        let filter = filter.map(|status| status.bucket());
+        let instances = self.instances.read().await;
+        let mut listed: Vec<WorkflowInstance> = instances
+            .values()
+            .filter(|instance| match &filter {
+                Some(status) => instance.status.bucket() == *status,
+                None => true,
+            })
+            .cloned()
+            .collect();
+        listed.sort_by_key(|instance| instance.created_at);
+        Ok(listed)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::workflow::{WorkflowDefinition, WorkflowNode, NodeType, NodeTransition, TransitionCondition};
+    use crate::workflow::{
+        WorkflowDefinition, WorkflowNode, NodeType, NodeTransition, TransitionCondition,
+        ActionExecutor, WorkflowAction,
+    };
 
     #[tokio::test]
     async fn test_workflow_execution() {
-        let mut manager = MockWorkflowManager::new();
+        let manager = MockWorkflowManager::new();
         
         // Create simple workflow definition
         let workflow_id = WorkflowId::new();
@@ -335,6 +477,7 @@ mod tests {
             }],
             actions: vec![],
             required_permissions: vec![],
+            timeout: None,
         });
         
         nodes.insert(end_node.clone(), WorkflowNode {
@@ -345,6 +488,7 @@ mod tests {
             transitions: vec![],
             actions: vec![],
             required_permissions: vec![],
+            timeout: None,
         });
         
         let definition = WorkflowDefinition {
@@ -359,7 +503,7 @@ mod tests {
             created_by: Uuid::new_v4(),
         };
         
-        manager.add_definition(definition);
+        manager.add_definition(definition).await;
         
         // Start workflow
         let context = WorkflowContext::new();
@@ -375,4 +519,232 @@ mod tests {
         assert_eq!(completed_instance.current_node, end_node);
         assert!(completed_instance.completed_at.is_some());
     }
-}
\ No newline at end of file
+    
+    async fn start_verification(manager: &MockWorkflowManager) -> WorkflowInstance {
+        let definition = crate::workflow::create_location_verification_workflow();
+        let workflow_id = definition.id.clone();
+        manager.add_definition(definition).await;
+        
+        let instance = manager.start_workflow(&workflow_id, WorkflowContext::new()).await.unwrap();
+        // Submit always moves on to review
+        let instance = manager.complete_node(&instance.id, None, None).await.unwrap();
+        assert_eq!(instance.current_node, NodeId::from("review"));
+        instance
+    }
+    
+    #[tokio::test]
+    async fn test_complete_node_follows_approved_branch() {
+        let manager = MockWorkflowManager::new();
+        let instance = start_verification(&manager).await;
+        
+        let instance = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "review_result": "approved" })))
+            .await
+            .unwrap();
+        assert_eq!(instance.current_node, NodeId::from("verify"));
+        
+        let instance = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "verification_result": "verified" })))
+            .await
+            .unwrap();
+        assert_eq!(instance.current_node, NodeId::from("approved"));
+        assert_eq!(instance.context.get_variable("review_result"), Some(&serde_json::json!("approved")));
+    }
+    
+    #[tokio::test]
+    async fn test_complete_node_follows_rejected_branch() {
+        let manager = MockWorkflowManager::new();
+        let instance = start_verification(&manager).await;
+        
+        let instance = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "review_result": "rejected" })))
+            .await
+            .unwrap();
+        assert_eq!(instance.current_node, NodeId::from("rejected"));
+    }
+    
+    #[tokio::test]
+    async fn test_complete_node_without_matching_condition() {
+        let manager = MockWorkflowManager::new();
+        let instance = start_verification(&manager).await;
+        
+        let result = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "review_result": "maybe" })))
+            .await;
+        assert!(matches!(result, Err(WorkflowError::InvalidTransition { .. })));
+        
+        // The instance stays on the review node
+        let instance = manager.get_instance(&instance.id).await.unwrap();
+        assert_eq!(instance.current_node, NodeId::from("review"));
+    }
+    
+    async fn start_timed_review(manager: &MockWorkflowManager) -> WorkflowInstance {
+        let mut definition = crate::workflow::create_location_verification_workflow();
+        definition.nodes.get_mut(&NodeId::from("review")).unwrap().timeout =
+            Some(std::time::Duration::from_secs(60 * 60));
+        let workflow_id = definition.id.clone();
+        manager.add_definition(definition).await;
+        
+        let instance = manager.start_workflow(&workflow_id, WorkflowContext::new()).await.unwrap();
+        manager.complete_node(&instance.id, None, None).await.unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_check_timeouts_fails_overdue_instance() {
+        let manager = MockWorkflowManager::new();
+        let instance = start_timed_review(&manager).await;
+        
+        let timed_out = manager.check_timeouts(Utc::now() + chrono::Duration::hours(2)).await;
+        assert_eq!(timed_out, vec![instance.id]);
+        
+        let instance = manager.get_instance(&instance.id).await.unwrap();
+        assert!(matches!(&instance.status, WorkflowStatus::Failed(reason) if reason.contains("timed out")));
+        assert!(matches!(
+            instance.get_node_status(&NodeId::from("review")),
+            NodeStatus::Failed(_)
+        ));
+        
+        let history = manager.get_history(&instance.id).await.unwrap();
+        let last = history.last().unwrap();
+        assert_eq!(last.from_node, NodeId::from("review"));
+        assert!(last.reason.as_ref().unwrap().contains("timed out"));
+        
+        // Failed instances are not reported again
+        assert!(manager.check_timeouts(Utc::now() + chrono::Duration::hours(3)).await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_check_timeouts_leaves_instance_within_sla() {
+        let manager = MockWorkflowManager::new();
+        let instance = start_timed_review(&manager).await;
+        let history_len = manager.get_history(&instance.id).await.unwrap().len();
+        
+        let timed_out = manager.check_timeouts(Utc::now() + chrono::Duration::minutes(30)).await;
+        assert!(timed_out.is_empty());
+        
+        let instance = manager.get_instance(&instance.id).await.unwrap();
+        assert_eq!(instance.status, WorkflowStatus::Running);
+        assert_eq!(instance.get_node_status(&NodeId::from("review")), NodeStatus::Active);
+        assert_eq!(manager.get_history(&instance.id).await.unwrap().len(), history_len);
+    }
+    
+    /// Marks each executed action type as a context variable
+    struct RecordAction;
+    
+    #[async_trait]
+    impl ActionExecutor for RecordAction {
+        async fn execute(&self, action: &WorkflowAction, ctx: &mut WorkflowContext) -> WorkflowResult<()> {
+            ctx.set_variable(action.action_type.clone(), serde_json::json!(true));
+            Ok(())
+        }
+    }
+    
+    fn registry(action_types: &[&str]) -> ActionRegistry {
+        let mut registry = ActionRegistry::new();
+        for action_type in action_types {
+            registry.register(*action_type, Arc::new(RecordAction));
+        }
+        registry
+    }
+    
+    #[tokio::test]
+    async fn test_node_entry_runs_registered_actions() {
+        let manager = MockWorkflowManager::new().with_actions(registry(&[
+            "notify_reviewers",
+            "geocode_address",
+            "validate_coordinates",
+        ]));
+        let definition = crate::workflow::create_location_verification_workflow();
+        let workflow_id = definition.id.clone();
+        manager.add_definition(definition).await;
+        
+        let instance = manager.start_workflow(&workflow_id, WorkflowContext::new()).await.unwrap();
+        assert_eq!(instance.context.get_variable("notify_reviewers"), Some(&serde_json::json!(true)));
+        assert!(instance.context.get_variable("geocode_address").is_none());
+        
+        let instance = manager.complete_node(&instance.id, None, None).await.unwrap();
+        let instance = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "review_result": "approved" })))
+            .await
+            .unwrap();
+        assert_eq!(instance.current_node, NodeId::from("verify"));
+        assert_eq!(instance.context.get_variable("geocode_address"), Some(&serde_json::json!(true)));
+        assert_eq!(instance.context.get_variable("validate_coordinates"), Some(&serde_json::json!(true)));
+    }
+    
+    #[tokio::test]
+    async fn test_node_entry_fails_on_unregistered_action() {
+        let manager = MockWorkflowManager::new().with_actions(registry(&["notify_reviewers"]));
+        let definition = crate::workflow::create_location_verification_workflow();
+        let workflow_id = definition.id.clone();
+        manager.add_definition(definition).await;
+        
+        let instance = manager.start_workflow(&workflow_id, WorkflowContext::new()).await.unwrap();
+        let instance = manager.complete_node(&instance.id, None, None).await.unwrap();
+        
+        let result = manager
+            .complete_node(&instance.id, None, Some(serde_json::json!({ "review_result": "approved" })))
+            .await;
+        assert!(matches!(result, Err(WorkflowError::EngineError { message }) if message.contains("geocode_address")));
+        
+        // The failed entry is not committed
+        let instance = manager.get_instance(&instance.id).await.unwrap();
+        assert_eq!(instance.current_node, NodeId::from("review"));
+    }
+    
+    #[tokio::test]
+    async fn test_list_and_count_instances_by_status() {
+        let manager = MockWorkflowManager::new();
+        let mut definition = crate::workflow::create_location_verification_workflow();
+        definition.nodes.get_mut(&NodeId::from("review")).unwrap().timeout =
+            Some(std::time::Duration::from_secs(3600));
+        let workflow_id = definition.id.clone();
+        manager.add_definition(definition).await;
+        
+        let mut ids = Vec::new();
+        for _ in 0..5 {
+            let instance = manager.start_workflow(&workflow_id, WorkflowContext::new()).await.unwrap();
+            ids.push(instance.id);
+        }
+        
+        // Approve the first instance all the way to completion
+        manager.complete_node(&ids[0], None, None).await.unwrap();
+        manager
+            .complete_node(&ids[0], None, Some(serde_json::json!({ "review_result": "approved" })))
+            .await
+            .unwrap();
+        let completed = manager
+            .complete_node(&ids[0], None, Some(serde_json::json!({ "verification_result": "verified" })))
+            .await
+            .unwrap();
+        assert_eq!(completed.status, WorkflowStatus::Completed);
+        
+        // Two instances time out in review, one is cancelled, one stays running
+        manager.complete_node(&ids[1], None, None).await.unwrap();
+        manager.complete_node(&ids[2], None, None).await.unwrap();
+        manager.cancel_workflow(&ids[3], None).await.unwrap();
+        let failed = manager.check_timeouts(Utc::now() + chrono::Duration::hours(2)).await;
+        assert_eq!(failed.len(), 2);
+        
+        let counts = manager.count_by_status().await.unwrap();
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts[&WorkflowStatus::Completed], 1);
+        assert_eq!(counts[&WorkflowStatus::Failed(String::new())], 2);
+        assert_eq!(counts[&WorkflowStatus::Cancelled], 1);
+        assert_eq!(counts[&WorkflowStatus::Running], 1);
+        assert!(!counts.contains_key(&WorkflowStatus::Waiting));
+        
+        assert_eq!(manager.list_instances(None).await.unwrap().len(), 5);
+        let running = manager.list_instances(Some(WorkflowStatus::Running)).await.unwrap();
+        assert_eq!(running.iter().map(|i| i.id).collect::<Vec<_>>(), vec![ids[4]]);
+        let failed_ids: Vec<_> = manager
+            .list_instances(Some(WorkflowStatus::Failed("any reason".to_string())))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(failed_ids.len(), 2);
+        assert!(failed_ids.contains(&ids[1]) && failed_ids.contains(&ids[2]));
+    }
+}
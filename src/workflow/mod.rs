@@ -3,16 +3,18 @@
 //! This module implements workflow state machines for location-based processes
 //! such as location verification, approval workflows, and hierarchical reorganization.
 
+pub mod actions;
 pub mod definitions;
 pub mod manager;
 pub mod location_workflows;
 
+pub use actions::*;
 pub use definitions::*;
 pub use manager::*;
 pub use location_workflows::*;
 
 use uuid::Uuid;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
@@ -106,7 +108,7 @@ impl From<&str> for NodeId {
 }
 
 /// Workflow execution status
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WorkflowStatus {
     /// Workflow is actively running
     Running,
@@ -120,6 +122,16 @@ pub enum WorkflowStatus {
     Cancelled,
 }
 
+impl WorkflowStatus {
+    /// The status with any failure reason dropped, so all failures group together
+    pub fn bucket(&self) -> WorkflowStatus {
+        match self {
+            Self::Failed(_) => Self::Failed(String::new()),
+            other => other.clone(),
+        }
+    }
+}
+
 /// Workflow execution context containing runtime data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowContext {
@@ -160,6 +172,22 @@ impl WorkflowContext {
     pub fn get_variable(&self, key: &str) -> Option<&serde_json::Value> {
         self.variables.get(key)
     }
+    
+    /// Store a variable by serializing it to JSON
+    pub fn set_typed<T: Serialize>(&mut self, key: &str, value: &T) -> WorkflowResult<()> {
+        let value = serde_json::to_value(value).map_err(|e| WorkflowError::EngineError {
+            message: format!("Failed to serialize variable {key}: {e}"),
+        })?;
+        self.variables.insert(key.to_string(), value);
+        Ok(())
+    }
+    
+    /// Read a variable back as `T`, or `None` if it is missing or has a different shape
+    pub fn get_variable_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.variables
+            .get(key)
+            .and_then(|value| T::deserialize(value).ok())
+    }
 }
 
 impl Default for WorkflowContext {
@@ -259,4 +287,39 @@ mod tests {
         assert_eq!(context.initiated_by, Some(user_id));
         assert_eq!(context.get_variable("test_var"), Some(&serde_json::json!("test_value")));
     }
-}
\ No newline at end of file
+    
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ReviewOutcome {
+        reviewer: String,
+        approved: bool,
+        score: u8,
+    }
+    
+    #[test]
+    fn test_typed_variables_round_trip() {
+        let mut context = WorkflowContext::new();
+        let outcome = ReviewOutcome {
+            reviewer: "alice".to_string(),
+            approved: true,
+            score: 9,
+        };
+        
+        context.set_typed("review", &outcome).unwrap();
+        context.set_typed("attempts", &3u32).unwrap();
+        
+        assert_eq!(context.get_variable_as::<ReviewOutcome>("review"), Some(outcome));
+        assert_eq!(context.get_variable_as::<u32>("attempts"), Some(3));
+        assert_eq!(context.get_variable_as::<u32>("missing"), None);
+    }
+    
+    #[test]
+    fn test_typed_variable_mismatch_is_none() {
+        let mut context = WorkflowContext::new();
+        context.set_variable("review".into(), serde_json::json!("approved"));
+        context.set_typed("attempts", &-1i32).unwrap();
+        
+        assert_eq!(context.get_variable_as::<ReviewOutcome>("review"), None);
+        assert_eq!(context.get_variable_as::<u32>("attempts"), None);
+        assert_eq!(context.get_variable_as::<String>("review"), Some("approved".to_string()));
+    }
+}
@@ -176,7 +176,9 @@ fn test_l5_metadata_management() {
             .unwrap();
 
     // Add single metadata
-    location.add_metadata("building_code".to_string(), "B-123".to_string());
+    location
+        .add_metadata("building_code".to_string(), "B-123".to_string())
+        .unwrap();
     assert_eq!(
         location.get_metadata().get("building_code"),
         Some(&"B-123".to_string())
@@ -187,7 +189,7 @@ fn test_l5_metadata_management() {
     bulk_metadata.insert("floor".to_string(), "3".to_string());
     bulk_metadata.insert("capacity".to_string(), "50".to_string());
 
-    location.add_metadata_bulk(bulk_metadata);
+    location.add_metadata_bulk(bulk_metadata).unwrap();
 
     assert_eq!(location.get_metadata().get("floor"), Some(&"3".to_string()));
     assert_eq!(
@@ -277,7 +279,7 @@ async fn test_l7_query_handler() {
         location_type: None,
         within_distance_of: None,
         parent_id: None,
-        metadata_filters: HashMap::new(),
+        metadata_filters: Vec::new(),
         include_archived: false,
         limit: None,
         offset: None,
@@ -293,7 +295,7 @@ async fn test_l7_query_handler() {
         location_type: Some(LocationType::Physical),
         within_distance_of: None,
         parent_id: None,
-        metadata_filters: HashMap::new(),
+        metadata_filters: Vec::new(),
         include_archived: false,
         limit: None,
         offset: None,
@@ -304,7 +306,7 @@ async fn test_l7_query_handler() {
 
     // Test geographic query (within 10km of SF office)
     let sf_center = GeoCoordinates::new(37.7749, -122.4194);
-    let nearby = query_handler.find_nearby(sf_center, 10_000.0).unwrap();
+    let nearby = query_handler.find_nearby(sf_center, Distance::from_kilometers(10.0)).unwrap();
     assert_eq!(nearby.len(), 1); // Only SF office should be within 10km
 }
 